@@ -64,6 +64,7 @@ impl ZellijPlugin for State {
             EventType::SessionUpdate,
             EventType::Key,
             EventType::RunCommandResult,
+            EventType::SessionSnapshots,
         ]);
     }
 
@@ -114,6 +115,11 @@ impl ZellijPlugin for State {
                 self.update_session_infos(session_infos);
                 should_render = true;
             },
+            Event::SessionSnapshots(session_name, snapshots) => {
+                self.resurrectable_sessions
+                    .update_snapshots(session_name, snapshots);
+                should_render = true;
+            },
             _ => (),
         };
         should_render
@@ -344,6 +350,9 @@ impl State {
     }
     fn handle_resurrect_session_key(&mut self, key: Key) -> bool {
         let mut should_render = false;
+        if self.resurrectable_sessions.snapshot_browser.is_some() {
+            return self.handle_snapshot_browser_key(key);
+        }
         if let Key::Down = key {
             self.resurrectable_sessions.move_selection_down();
             should_render = true;
@@ -373,6 +382,11 @@ impl State {
             self.resurrectable_sessions
                 .show_delete_all_sessions_warning();
             should_render = true;
+        } else if let Key::Ctrl('s') = key {
+            if !self.resurrectable_sessions.open_snapshot_browser() {
+                self.show_error("Must select session to browse its snapshots.");
+            }
+            should_render = true;
         } else if let Key::Esc = key {
             if !self.is_welcome_screen {
                 hide_self();
@@ -380,6 +394,21 @@ impl State {
         }
         should_render
     }
+    fn handle_snapshot_browser_key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Down => self.resurrectable_sessions.move_selection_down(),
+            Key::Up => self.resurrectable_sessions.move_selection_up(),
+            Key::Char('\n') => {
+                if let Some(session_name) = self.resurrectable_sessions.restore_selected_snapshot()
+                {
+                    switch_session(Some(&session_name));
+                }
+            },
+            Key::Esc | Key::Ctrl('c') => self.resurrectable_sessions.close_snapshot_browser(),
+            _ => return false,
+        }
+        true
+    }
     fn handle_selection(&mut self) {
         match self.active_screen {
             ActiveScreen::NewSession => {
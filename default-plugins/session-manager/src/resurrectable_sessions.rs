@@ -15,6 +15,15 @@ pub struct ResurrectableSessions {
     pub is_searching: bool,
     pub search_term: String,
     pub delete_all_dead_sessions_warning: bool,
+    pub snapshot_browser: Option<SnapshotBrowser>,
+}
+
+// the snapshots of a single session, which it can be resurrected from instead of its latest state
+#[derive(Debug, Default)]
+pub struct SnapshotBrowser {
+    pub session_name: String,
+    pub snapshots: Vec<(u64, Duration)>, // timestamp, age - newest first
+    pub selected_index: usize,
 }
 
 impl ResurrectableSessions {
@@ -27,6 +36,10 @@ impl ResurrectableSessions {
             self.render_delete_all_sessions_warning(rows, columns, x, y);
             return;
         }
+        if let Some(snapshot_browser) = &self.snapshot_browser {
+            self.render_snapshot_browser(snapshot_browser, rows, columns, x, y);
+            return;
+        }
         let search_indication =
             Text::new(format!("Search: {}_", self.search_term)).color_range(2, ..7);
         let table_rows = rows.saturating_sub(5); // search row, toggle row and some padding
@@ -130,6 +143,52 @@ impl ResurrectableSessions {
             None,
         );
     }
+    fn render_snapshot_browser(
+        &self,
+        snapshot_browser: &SnapshotBrowser,
+        rows: usize,
+        columns: usize,
+        x: usize,
+        y: usize,
+    ) {
+        let title = format!("Snapshots of {}", snapshot_browser.session_name);
+        let title_len = title.chars().count();
+        print_text_with_coordinates(
+            Text::new(title).color_range(0, 13..title_len),
+            x.saturating_sub(1),
+            y + 2,
+            None,
+            None,
+        );
+        let table_rows = rows.saturating_sub(5); // title row, toggle row and some padding
+        let mut table = Table::new().add_row(vec![" ", " "]); // skip the title row
+        if snapshot_browser.snapshots.is_empty() {
+            table = table.add_styled_row(vec![Text::new("No snapshots found"), Text::new(" ")]);
+        }
+        let (first_row_index_to_render, last_row_index_to_render) = self.range_to_render(
+            table_rows,
+            snapshot_browser.snapshots.len(),
+            Some(snapshot_browser.selected_index),
+        );
+        for i in first_row_index_to_render..last_row_index_to_render {
+            if let Some((_timestamp, age)) = snapshot_browser.snapshots.get(i) {
+                let is_selected = i == snapshot_browser.selected_index;
+                let mut table_cells = vec![
+                    self.render_age("Taken", age),
+                    if is_selected {
+                        Text::new(format!("<ENTER> - Resurrect From Snapshot")).color_range(3, 0..7)
+                    } else {
+                        Text::new(" ")
+                    },
+                ];
+                if is_selected {
+                    table_cells = table_cells.drain(..).map(|t| t.selected()).collect();
+                }
+                table = table.add_styled_row(table_cells);
+            }
+        }
+        print_table_with_coordinates(table, x, y + 3, Some(columns), Some(table_rows));
+    }
     fn range_to_render(
         &self,
         table_rows: usize,
@@ -157,7 +216,10 @@ impl ResurrectableSessions {
         }
     }
     fn render_ctime(&self, ctime: &Duration) -> Text {
-        let duration = format_duration(ctime.clone()).to_string();
+        self.render_age("Created", ctime)
+    }
+    fn render_age(&self, prefix: &str, age: &Duration) -> Text {
+        let duration = format_duration(age.clone()).to_string();
         let duration_parts = duration.split_whitespace();
         let mut formatted_duration = String::new();
         for part in duration_parts {
@@ -172,7 +234,9 @@ impl ResurrectableSessions {
             formatted_duration.push_str("<1m");
         }
         let duration_len = formatted_duration.chars().count();
-        Text::new(format!("Created {} ago", formatted_duration)).color_range(2, 8..9 + duration_len)
+        let prefix_len = prefix.chars().count();
+        Text::new(format!("{} {} ago", prefix, formatted_duration))
+            .color_range(2, prefix_len + 1..prefix_len + 2 + duration_len)
     }
     fn render_more_indication_or_enter_as_needed(
         &self,
@@ -199,7 +263,13 @@ impl ResurrectableSessions {
         }
     }
     pub fn move_selection_down(&mut self) {
-        if self.is_searching {
+        if let Some(snapshot_browser) = self.snapshot_browser.as_mut() {
+            if snapshot_browser.selected_index + 1 >= snapshot_browser.snapshots.len() {
+                snapshot_browser.selected_index = 0;
+            } else {
+                snapshot_browser.selected_index += 1;
+            }
+        } else if self.is_searching {
             if let Some(selected_index) = self.selected_search_index.as_mut() {
                 if *selected_index == self.search_results.len().saturating_sub(1) {
                     *selected_index = 0;
@@ -222,7 +292,14 @@ impl ResurrectableSessions {
         }
     }
     pub fn move_selection_up(&mut self) {
-        if self.is_searching {
+        if let Some(snapshot_browser) = self.snapshot_browser.as_mut() {
+            if snapshot_browser.selected_index == 0 {
+                snapshot_browser.selected_index =
+                    snapshot_browser.snapshots.len().saturating_sub(1);
+            } else {
+                snapshot_browser.selected_index -= 1;
+            }
+        } else if self.is_searching {
             if let Some(selected_index) = self.selected_search_index.as_mut() {
                 if *selected_index == 0 {
                     *selected_index = self.search_results.len().saturating_sub(1);
@@ -255,6 +332,43 @@ impl ResurrectableSessions {
                 .map(|session_name_and_creation_time| session_name_and_creation_time.0.clone())
         }
     }
+    pub fn open_snapshot_browser(&mut self) -> bool {
+        match self.get_selected_session_name() {
+            Some(session_name) => {
+                list_session_snapshots(&session_name);
+                self.snapshot_browser = Some(SnapshotBrowser {
+                    session_name,
+                    ..Default::default()
+                });
+                true
+            },
+            None => false,
+        }
+    }
+    pub fn close_snapshot_browser(&mut self) {
+        self.snapshot_browser = None;
+    }
+    pub fn update_snapshots(&mut self, session_name: String, mut snapshots: Vec<(u64, Duration)>) {
+        if let Some(snapshot_browser) = self
+            .snapshot_browser
+            .as_mut()
+            .filter(|s| s.session_name == session_name)
+        {
+            snapshots.sort_by(|a, b| b.0.cmp(&a.0));
+            snapshot_browser.snapshots = snapshots;
+            snapshot_browser.selected_index = 0;
+        }
+    }
+    // places the selected snapshot so that the session will be resurrected from it, returning the
+    // name of the session to resurrect
+    pub fn restore_selected_snapshot(&mut self) -> Option<String> {
+        let snapshot_browser = self.snapshot_browser.take()?;
+        let (timestamp, _age) = snapshot_browser
+            .snapshots
+            .get(snapshot_browser.selected_index)?;
+        restore_session_snapshot(&snapshot_browser.session_name, *timestamp);
+        Some(snapshot_browser.session_name)
+    }
     pub fn delete_selected_session(&mut self) {
         self.selected_index
             .and_then(|i| {
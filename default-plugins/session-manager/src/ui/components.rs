@@ -393,16 +393,25 @@ pub fn build_session_ui_line(session_ui_info: &SessionUiInfo, colors: Colors) ->
             2 + tab_count.width() + 2 + total_pane_count.width() + 3,
         ),
     ]));
-    let connected_users_count = UiSpan::UiSpanTelescope(UiSpanTelescope::new(vec![
-        StringAndLength::new(
-            format!(" [{connected_users_styled} connected users]"),
-            2 + connected_users.width() + 17,
-        ),
-        StringAndLength::new(
-            format!(" [{connected_users_styled}]"),
-            2 + connected_users.width() + 1,
-        ),
-    ]));
+    let mut connected_users_descriptions = vec![];
+    if !session_ui_info.clients.is_empty() {
+        let clients = session_ui_info.clients.join(", ");
+        let clients_styled = colors.orange(&clients);
+        connected_users_descriptions.push(StringAndLength::new(
+            format!(" [{clients_styled}]"),
+            2 + clients.width() + 1,
+        ));
+    }
+    connected_users_descriptions.push(StringAndLength::new(
+        format!(" [{connected_users_styled} connected users]"),
+        2 + connected_users.width() + 17,
+    ));
+    connected_users_descriptions.push(StringAndLength::new(
+        format!(" [{connected_users_styled}]"),
+        2 + connected_users.width() + 1,
+    ));
+    let connected_users_count =
+        UiSpan::UiSpanTelescope(UiSpanTelescope::new(connected_users_descriptions));
     ui_spans.push(session_bullet_span);
     ui_spans.push(session_name_span);
     ui_spans.push(tab_and_pane_count);
@@ -901,11 +910,19 @@ pub fn render_controls_line(
             let del_text = colors.bold("Delete");
             let del_all = colors.magenta("<Ctrl d>");
             let del_all_text = colors.bold("Delete all");
+            let snapshots = colors.magenta("<Ctrl s>");
+            let snapshots_text = colors.bold("Snapshots");
 
-            if max_cols > 83 {
+            if max_cols > 104 {
+                print!(
+                    "\u{1b}[m\u{1b}[{y};{x}HHelp: {arrows} - {navigate}, {enter} - {select}, {del} - {del_text}, {del_all} - {del_all_text}, {snapshots} - {snapshots_text}"
+                );
+            } else if max_cols > 83 {
                 print!(
                     "\u{1b}[m\u{1b}[{y};{x}HHelp: {arrows} - {navigate}, {enter} - {select}, {del} - {del_text}, {del_all} - {del_all_text}"
                 );
+            } else if max_cols >= 38 {
+                print!("\u{1b}[m\u{1b}[{y};{x}H{arrows}/{enter}/{del}/{del_all}/{snapshots}");
             } else if max_cols >= 28 {
                 print!("\u{1b}[m\u{1b}[{y};{x}H{arrows}/{enter}/{del}/{del_all}");
             }
@@ -226,6 +226,7 @@ pub struct SessionUiInfo {
     pub name: String,
     pub tabs: Vec<TabUiInfo>,
     pub connected_users: usize,
+    pub clients: Vec<String>, // eg. "alice" or "bob -> alice" if bob follows alice
     pub is_current_session: bool,
 }
 
@@ -239,6 +240,23 @@ impl SessionUiInfo {
                 .map(|t| TabUiInfo::new(t, &session_info.panes))
                 .collect(),
             connected_users: session_info.connected_clients,
+            clients: session_info
+                .clients
+                .iter()
+                .map(|client| {
+                    let leader = client.following.and_then(|leader| {
+                        session_info
+                            .clients
+                            .iter()
+                            .find(|c| c.client_id == leader)
+                            .map(|c| c.display_name())
+                    });
+                    match leader {
+                        Some(leader) => format!("{} -> {}", client.display_name(), leader),
+                        None => client.display_name(),
+                    }
+                })
+                .collect(),
             is_current_session: session_info.is_current_session,
         }
     }
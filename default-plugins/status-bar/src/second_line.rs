@@ -365,6 +365,19 @@ pub fn text_copied_hint(palette: &Palette, copy_destination: CopyDestination) ->
     }
 }
 
+pub fn read_only_interface_indication(palette: &Palette) -> LinePart {
+    let read_only_text = " -- VIEWING READ-ONLY -- ";
+    let orange_color = palette_match!(palette.orange);
+    LinePart {
+        part: Style::new()
+            .fg(orange_color)
+            .bold()
+            .paint(read_only_text)
+            .to_string(),
+        len: read_only_text.chars().count(),
+    }
+}
+
 pub fn system_clipboard_error(palette: &Palette) -> LinePart {
     let hint = " Error using the system clipboard.";
     let red_color = palette_match!(palette.red);
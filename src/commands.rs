@@ -1,12 +1,20 @@
-use dialoguer::Confirm;
-use std::{fs::File, io::prelude::*, path::PathBuf, process, time::Duration};
+use dialoguer::{Confirm, Select};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::prelude::*,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
 use crate::sessions::{
-    assert_dead_session, assert_session, assert_session_ne, delete_session as delete_session_impl,
-    get_active_session, get_name_generator, get_resurrectable_sessions, get_sessions,
-    get_sessions_sorted_by_mtime, kill_session as kill_session_impl, match_session_name,
-    print_sessions, print_sessions_with_index, resurrection_layout, session_exists, ActiveSession,
-    SessionNameMatch,
+    assert_dead_session, assert_session, assert_session_ne, assert_valid_session_name,
+    delete_session as delete_session_impl, get_active_session, get_name_generator,
+    get_resurrectable_sessions, get_sessions, get_sessions_sorted_by_mtime,
+    kill_session as kill_session_impl, match_session_name, print_session_snapshots, print_sessions,
+    print_sessions_with_index, restore_session_snapshot, resurrection_layout, session_exists,
+    ActiveSession, SessionNameMatch,
 };
 use zellij_client::{
     old_config_converter::{
@@ -18,20 +26,24 @@ use zellij_client::{
 use zellij_server::{os_input_output::get_server_os_input, start_server as start_server_impl};
 use zellij_utils::{
     cli::{CliArgs, Command, SessionCommand, Sessions},
+    consts::session_info_folder_for_session,
     data::{ConnectToSession, LayoutInfo},
     envs,
     input::{
         actions::Action,
+        command::ResurrectionPolicy,
         config::{Config, ConfigError},
         layout::Layout,
         options::Options,
+        project_layout::{find_project_layout, layout_trust_contents, LayoutTrustCache},
     },
     miette::{Report, Result},
     nix,
+    session_archive::SessionArchive,
     setup::{find_default_config_dir, get_layout_dir, Setup},
 };
 
-pub(crate) use crate::sessions::list_sessions;
+pub(crate) use crate::sessions::{list_sessions, list_sessions_as_json};
 
 pub(crate) fn kill_all_sessions(yes: bool) {
     match get_sessions() {
@@ -124,6 +136,137 @@ pub(crate) fn delete_session(target_session: &Option<String>, force: bool) {
     }
 }
 
+pub(crate) fn export_session(session_name: &str, output: Option<PathBuf>, relative_paths: bool) {
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.tar.zst", session_name)));
+    let session_folder = session_info_folder_for_session(session_name);
+    let result = SessionArchive::from_session_folder(session_name, &session_folder)
+        .and_then(|mut archive| {
+            if relative_paths {
+                archive.set_global_cwd(None)?;
+            }
+            Ok(archive)
+        })
+        .and_then(|archive| {
+            let file = std::fs::File::create(&output)?;
+            archive.write(std::io::BufWriter::new(file))
+        });
+    match result {
+        Ok(()) => {
+            println!(
+                "Session {:?} exported to {}.",
+                session_name,
+                output.display()
+            );
+            process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Failed to export session {:?}: {}", session_name, e);
+            process::exit(1);
+        },
+    }
+}
+
+pub(crate) fn import_session(
+    archive_path: &Path,
+    name: Option<String>,
+    cwd: Option<PathBuf>,
+    force: bool,
+) {
+    let mut archive = match std::fs::File::open(archive_path)
+        .and_then(|file| SessionArchive::read(std::io::BufReader::new(file)))
+    {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", archive_path.display(), e);
+            process::exit(1);
+        },
+    };
+    let session_name = name.unwrap_or_else(|| archive.session_name.clone());
+    assert_valid_session_name(&session_name);
+    if force {
+        if session_exists(&session_name).unwrap_or(false) {
+            eprintln!(
+                "Session {:?} is running, it cannot be replaced by an imported session.",
+                session_name
+            );
+            process::exit(1);
+        }
+        let _ = std::fs::remove_dir_all(session_info_folder_for_session(&session_name));
+    }
+    assert_session_ne(&session_name);
+    let cwd = cwd
+        .or_else(|| match archive.global_cwd() {
+            Some(_) => None,
+            None => std::env::current_dir().ok(),
+        })
+        .map(|cwd| std::env::current_dir().unwrap_or_default().join(cwd));
+    if let Some(cwd) = cwd {
+        if let Err(e) = archive.set_global_cwd(Some(&cwd)) {
+            eprintln!("Failed to import session: {}", e);
+            process::exit(1);
+        }
+    }
+    match archive.install(&session_info_folder_for_session(&session_name)) {
+        Ok(()) => {
+            println!(
+                "Session {:?} imported, attach to it to resurrect it.",
+                session_name
+            );
+            process::exit(0);
+        },
+        Err(e) => {
+            eprintln!("Failed to import session {:?}: {}", session_name, e);
+            process::exit(1);
+        },
+    }
+}
+
+pub(crate) fn resurrect_session(
+    mut opts: CliArgs,
+    session_name: String,
+    list_snapshots: bool,
+    snapshot: Option<String>,
+    force_run_commands: bool,
+) {
+    if list_snapshots {
+        print_session_snapshots(&session_name);
+        process::exit(0);
+    }
+    if session_exists(&session_name).unwrap_or(false) {
+        eprintln!(
+            "Session {:?} is running, only dead sessions can be resurrected.",
+            session_name
+        );
+        process::exit(1);
+    }
+    if let Some(snapshot) = snapshot {
+        match restore_session_snapshot(&session_name, &snapshot) {
+            Ok(timestamp) => log::info!(
+                "Resurrecting {:?} from snapshot {}",
+                session_name,
+                timestamp
+            ),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            },
+        }
+    }
+    if resurrection_layout(&session_name).is_none() {
+        eprintln!("No resurrectable session named {:?} found.", session_name);
+        process::exit(1);
+    }
+    opts.command = Some(Command::Sessions(Sessions::Attach {
+        session_name: Some(session_name),
+        create: false,
+        force_run_commands,
+        index: None,
+        options: None,
+        read_only: false,
+    }));
+    start_client(opts);
+}
+
 fn get_os_input<OsInputOutput>(
     fn_get_os_input: fn() -> Result<OsInputOutput, nix::Error>,
 ) -> OsInputOutput {
@@ -382,22 +525,100 @@ fn attach_with_session_name(
     }
 }
 
-pub(crate) fn start_client(opts: CliArgs) {
+fn setup_from_cli_args_or_exit(opts: &CliArgs) -> (Config, Layout, Options, Config, Options) {
+    match Setup::from_cli_args(opts) {
+        Ok(results) => results,
+        Err(e) => {
+            if let ConfigError::KdlError(error) = e {
+                let report: Report = error.into();
+                eprintln!("{:?}", report);
+            } else {
+                eprintln!("{}", e);
+            }
+            process::exit(1);
+        },
+    }
+}
+
+/// Looks for a layout shipped by the project we are starting a new session in, asking the user
+/// whether to trust it if we haven't seen it before (or if it changed since it was trusted)
+fn project_layout_to_start_with(opts: &CliArgs, config_options: &Options) -> Option<PathBuf> {
+    if opts.layout.is_some()
+        || opts.command.is_some()
+        || config_options.project_layouts == Some(false)
+    {
+        return None;
+    }
+    let project_layout = find_project_layout(&std::env::current_dir().ok()?)?;
+    let layout_dir = config_options
+        .layout_dir
+        .clone()
+        .or_else(|| get_layout_dir(opts.config_dir.clone().or_else(find_default_config_dir)));
+    let contents = match layout_trust_contents(&project_layout, layout_dir) {
+        Ok(contents) => contents,
+        Err(e) => {
+            // we never start with a layout we could not fully read, since we could not tell what
+            // the user is trusting
+            if let ConfigError::KdlError(error) = e {
+                let report: Report = error.into();
+                eprintln!("{:?}", report);
+            } else {
+                eprintln!("{}", e);
+            }
+            eprintln!(
+                "Ignoring the project layout at {}",
+                project_layout.display()
+            );
+            return None;
+        },
+    };
+    let mut trust_cache = LayoutTrustCache::from_path_or_default(None);
+    if trust_cache.is_trusted(&project_layout, &contents) {
+        return Some(project_layout);
+    }
+    let choices = [
+        "Yes, this time",
+        "Yes, and trust this layout from now on",
+        "No",
+    ];
+    let choice = Select::new()
+        .with_prompt(format!(
+            "Found a project layout at {}, layouts can run commands. Start the session with it?",
+            project_layout.display()
+        ))
+        .items(&choices)
+        .default(2)
+        .interact()
+        .ok()?;
+    match choice {
+        0 => Some(project_layout),
+        1 => {
+            trust_cache.trust(project_layout.clone(), contents);
+            if let Err(e) = trust_cache.write_to_file() {
+                log::error!("Failed to write trusted layouts file: {}", e);
+            }
+            Some(project_layout)
+        },
+        _ => None,
+    }
+}
+
+pub(crate) fn start_client(mut opts: CliArgs) {
     // look for old YAML config/layout/theme files and convert them to KDL
     convert_old_yaml_files(&opts);
-    let (config, layout, config_options, config_without_layout, config_options_without_layout) =
-        match Setup::from_cli_args(&opts) {
-            Ok(results) => results,
-            Err(e) => {
-                if let ConfigError::KdlError(error) = e {
-                    let report: Report = error.into();
-                    eprintln!("{:?}", report);
-                } else {
-                    eprintln!("{}", e);
-                }
-                process::exit(1);
-            },
-        };
+    let (
+        mut config,
+        mut layout,
+        mut config_options,
+        config_without_layout,
+        config_options_without_layout,
+    ) = setup_from_cli_args_or_exit(&opts);
+    if let Some(project_layout) = project_layout_to_start_with(&opts, &config_options) {
+        // we parse everything again so that the project layout takes the place of the default
+        // layout, including its configuration
+        opts.layout = Some(project_layout);
+        (config, layout, config_options, _, _) = setup_from_cli_args_or_exit(&opts);
+    }
     let mut reconnect_to_session: Option<ConnectToSession> = None;
     let os_input = get_os_input(get_client_os_input);
     loop {
@@ -420,6 +641,7 @@ pub(crate) fn start_client(opts: CliArgs) {
                     force_run_commands: false,
                     index: None,
                     options: None,
+                    read_only: false,
                 }));
             } else {
                 opts.command = None;
@@ -436,11 +658,13 @@ pub(crate) fn start_client(opts: CliArgs) {
                         &PathBuf::from(layout_name),
                         layout_dir.clone(),
                         config_without_layout.clone(),
+                        BTreeMap::new(),
                     ),
                     LayoutInfo::File(layout_name) => Layout::from_path_or_default(
                         Some(&PathBuf::from(layout_name)),
                         layout_dir.clone(),
                         config_without_layout.clone(),
+                        BTreeMap::new(),
                     ),
                 };
                 match new_session_layout {
@@ -479,6 +703,7 @@ pub(crate) fn start_client(opts: CliArgs) {
             force_run_commands,
             index,
             options,
+            read_only,
         })) = opts.command.clone()
         {
             let config_options = match options.as_deref() {
@@ -504,6 +729,17 @@ pub(crate) fn start_client(opts: CliArgs) {
                     (Some(session_name), Some(mut resurrection_layout)) if !session_exists => {
                         if force_run_commands {
                             resurrection_layout.recursively_add_start_suspended(Some(false));
+                        } else {
+                            resurrection_layout.start_on_resurrection(&ResurrectionPolicy::new(
+                                config_options
+                                    .resurrect_run_commands
+                                    .clone()
+                                    .unwrap_or_default(),
+                                config_options
+                                    .resurrect_suspend_commands
+                                    .clone()
+                                    .unwrap_or_default(),
+                            ));
                         }
                         ClientInfo::Resurrect(session_name.clone(), resurrection_layout)
                     },
@@ -511,6 +747,11 @@ pub(crate) fn start_client(opts: CliArgs) {
                 }
             };
 
+            if read_only && !matches!(client, ClientInfo::Attach(..)) {
+                eprintln!("A read-only client can only attach to a running session.");
+                process::exit(1);
+            }
+
             if let Ok(val) = std::env::var(envs::SESSION_NAME_ENV_KEY) {
                 if val == *client.get_session_name() {
                     panic!("You are trying to attach to the current session (\"{}\"). This is not supported.", val);
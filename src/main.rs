@@ -5,7 +5,7 @@ mod tests;
 
 use zellij_utils::{
     clap::Parser,
-    cli::{CliAction, CliArgs, Command, Sessions},
+    cli::{CliAction, CliArgs, Command, SessionListFormat, Sessions},
     input::config::Config,
     logging::*,
 };
@@ -29,6 +29,9 @@ fn main() {
             name,
             close_on_exit,
             start_suspended,
+            env,
+            restart,
+            max_restarts,
             x,
             y,
             width,
@@ -47,6 +50,9 @@ fn main() {
                 name,
                 close_on_exit,
                 start_suspended,
+                env,
+                restart,
+                max_restarts,
                 configuration: None,
                 skip_plugin_cache,
                 x,
@@ -80,6 +86,9 @@ fn main() {
                 name: None,
                 close_on_exit: false,
                 start_suspended: false,
+                env: vec![],
+                restart: None,
+                max_restarts: None,
                 configuration,
                 skip_plugin_cache,
                 x,
@@ -168,9 +177,13 @@ fn main() {
         no_formatting,
         short,
         reverse,
+        format,
     })) = opts.command
     {
-        commands::list_sessions(no_formatting, short, reverse);
+        match format {
+            SessionListFormat::Text => commands::list_sessions(no_formatting, short, reverse),
+            SessionListFormat::Json => commands::list_sessions_as_json(reverse),
+        }
     } else if let Some(Command::Sessions(Sessions::ListAliases)) = opts.command {
         commands::list_aliases(opts);
     } else if let Some(Command::Sessions(Sessions::KillAllSessions { yes })) = opts.command {
@@ -188,6 +201,35 @@ fn main() {
     })) = opts.command
     {
         commands::delete_session(target_session, force);
+    } else if let Some(Command::Sessions(Sessions::Resurrect {
+        session_name,
+        list_snapshots,
+        snapshot,
+        force_run_commands,
+    })) = opts.command.clone()
+    {
+        commands::resurrect_session(
+            opts,
+            session_name,
+            list_snapshots,
+            snapshot,
+            force_run_commands,
+        );
+    } else if let Some(Command::Sessions(Sessions::ExportSession {
+        ref session_name,
+        ref output,
+        relative_paths,
+    })) = opts.command
+    {
+        commands::export_session(session_name, output.clone(), relative_paths);
+    } else if let Some(Command::Sessions(Sessions::ImportSession {
+        ref archive,
+        ref name,
+        ref cwd,
+        force,
+    })) = opts.command
+    {
+        commands::import_session(archive, name.clone(), cwd.clone(), force);
     } else if let Some(path) = opts.server {
         commands::start_server(path, opts.debug);
     } else {
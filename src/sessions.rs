@@ -1,3 +1,4 @@
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::os::unix::fs::FileTypeExt;
 use std::time::{Duration, SystemTime};
@@ -6,14 +7,17 @@ use suggest::Suggest;
 use zellij_utils::{
     anyhow,
     consts::{
-        session_info_folder_for_session, session_layout_cache_file_name,
+        session_info_cache_file_name, session_info_folder_for_session,
+        session_layout_cache_file_name, session_snapshots_folder_for_session,
         ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR,
     },
+    data::SessionInfo,
     envs,
-    humantime::format_duration,
-    input::layout::Layout,
+    humantime::{format_duration, format_rfc3339_seconds},
+    input::layout::{Layout, Run},
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg},
+    session_snapshots::{find_snapshot_at, list_snapshots, parse_snapshot_time, restore_snapshot},
 };
 
 pub(crate) fn get_sessions() -> Result<Vec<(String, Duration)>, io::ErrorKind> {
@@ -291,6 +295,153 @@ pub(crate) fn list_sessions(no_formatting: bool, short: bool, reverse: bool) {
     process::exit(exit_code);
 }
 
+pub(crate) fn list_sessions_as_json(reverse: bool) {
+    let exit_code = match get_sessions() {
+        Ok(running_sessions) => {
+            let curr_session = envs::get_session_name().unwrap_or_else(|_| "".into());
+            let running_sessions = running_sessions
+                .into_iter()
+                .map(|(name, age)| {
+                    let session_info = fs::read_to_string(session_info_cache_file_name(&name))
+                        .ok()
+                        .and_then(|raw_session_info| {
+                            SessionInfo::from_string(&raw_session_info, &curr_session).ok()
+                        });
+                    (name, age, session_info)
+                })
+                .collect();
+            let sessions = sessions_to_json(
+                running_sessions,
+                get_resurrectable_sessions(),
+                &curr_session,
+                SystemTime::now(),
+                reverse,
+            );
+            match serde_json::to_string_pretty(&sessions) {
+                Ok(sessions) => {
+                    println!("{}", sessions);
+                    0
+                },
+                Err(e) => {
+                    eprintln!("Failed to serialize sessions: {}", e);
+                    1
+                },
+            }
+        },
+        Err(e) => {
+            eprintln!("Error occurred: {:?}", e);
+            1
+        },
+    };
+    process::exit(exit_code);
+}
+
+/// Running sessions come with the session info they last cached (if they did already), exited
+/// ones with their resurrection layout - sorted like `print_sessions` sorts them
+fn sessions_to_json(
+    running_sessions: Vec<(String, Duration, Option<SessionInfo>)>,
+    resurrectable_sessions: Vec<(String, Duration, Layout)>,
+    curr_session: &str,
+    now: SystemTime,
+    reverse: bool,
+) -> Value {
+    let created_at = |age: &Duration| {
+        now.checked_sub(*age)
+            .map(|created_at| format_rfc3339_seconds(created_at).to_string())
+    };
+    let mut sessions: Vec<(Duration, Value)> = vec![];
+    for (name, age, session_info) in &running_sessions {
+        let (clients, tabs) = match session_info {
+            Some(session_info) => {
+                let tabs = session_info
+                    .tabs
+                    .iter()
+                    .map(|tab| {
+                        let mut tab_json = json!(tab);
+                        tab_json["panes"] = json!(session_info
+                            .panes
+                            .panes
+                            .get(&tab.position)
+                            .cloned()
+                            .unwrap_or_default());
+                        tab_json
+                    })
+                    .collect();
+                (json!(session_info.clients), Value::Array(tabs))
+            },
+            // the session did not cache its info yet
+            None => (Value::Null, Value::Null),
+        };
+        let session = json!({
+            "name": name,
+            "status": "running",
+            "is_current_session": name == curr_session,
+            "created_at": created_at(age),
+            "age_secs": age.as_secs(),
+            "clients": clients,
+            "tabs": tabs,
+        });
+        sessions.push((*age, session));
+    }
+    for (name, age, layout) in &resurrectable_sessions {
+        if running_sessions
+            .iter()
+            .any(|(running_session, ..)| running_session == name)
+        {
+            continue;
+        }
+        let session = json!({
+            "name": name,
+            "status": "exited",
+            "is_current_session": false,
+            "created_at": created_at(age),
+            "age_secs": age.as_secs(),
+            "layout": layout_summary(layout),
+        });
+        sessions.push((*age, session));
+    }
+    sessions.sort_by(|a, b| {
+        if reverse {
+            a.0.cmp(&b.0)
+        } else {
+            b.0.cmp(&a.0)
+        }
+    });
+    Value::Array(
+        sessions
+            .into_iter()
+            .map(|(_age, session)| session)
+            .collect(),
+    )
+}
+
+/// The tabs of a resurrection layout, with the number of panes and the commands they run
+fn layout_summary(layout: &Layout) -> Value {
+    let tabs: Vec<Value> = layout
+        .tabs
+        .iter()
+        .map(|(tab_name, tiled_panes, floating_panes)| {
+            let runs = tiled_panes
+                .extract_run_instructions()
+                .into_iter()
+                .chain(floating_panes.iter().map(|pane| pane.run.clone()));
+            let commands: Vec<String> = runs
+                .filter_map(|run| match run {
+                    Some(Run::Command(run_command)) => Some(run_command.to_string()),
+                    _ => None,
+                })
+                .collect();
+            json!({
+                "name": tab_name,
+                "tiled_panes": tiled_panes.pane_count(),
+                "floating_panes": floating_panes.len(),
+                "commands": commands,
+            })
+        })
+        .collect();
+    json!({ "tabs": tabs })
+}
+
 #[derive(Debug, Clone)]
 pub enum SessionNameMatch {
     AmbiguousPrefix(Vec<String>),
@@ -344,6 +495,41 @@ pub(crate) fn resurrection_layout(session_name_to_resurrect: &str) -> Option<Lay
         })
 }
 
+pub(crate) fn print_session_snapshots(session_name: &str) {
+    let snapshots = list_snapshots(&session_snapshots_folder_for_session(session_name));
+    if snapshots.is_empty() {
+        eprintln!("No snapshots found for session {:?}.", session_name);
+        process::exit(1);
+    }
+    let now = SystemTime::now();
+    for snapshot in snapshots.iter().rev() {
+        let age = now.duration_since(snapshot.time()).unwrap_or_default();
+        println!(
+            "{} {} [{} ago]",
+            snapshot.timestamp,
+            format_rfc3339_seconds(snapshot.time()),
+            format_duration(Duration::from_secs(age.as_secs()))
+        );
+    }
+}
+
+// places the latest snapshot taken at or before this time in the session folder, so that the
+// session will be resurrected from it
+pub(crate) fn restore_session_snapshot(session_name: &str, time: &str) -> Result<u64, String> {
+    let time = parse_snapshot_time(time, SystemTime::now())?;
+    let snapshots = list_snapshots(&session_snapshots_folder_for_session(session_name));
+    let snapshot = find_snapshot_at(&snapshots, time).ok_or_else(|| {
+        format!(
+            "No snapshot of session {:?} was taken at or before {}.",
+            session_name,
+            format_rfc3339_seconds(time)
+        )
+    })?;
+    restore_snapshot(snapshot, &session_info_folder_for_session(session_name))
+        .map(|_| snapshot.timestamp)
+        .map_err(|e| format!("Failed to restore snapshot: {}", e))
+}
+
 pub(crate) fn assert_session(name: &str) {
     match session_exists(name) {
         Ok(result) => {
@@ -391,7 +577,8 @@ pub(crate) fn assert_dead_session(name: &str, force: bool) {
     process::exit(1);
 }
 
-pub(crate) fn assert_session_ne(name: &str) {
+/// Exits if a session cannot be named this way (eg. since its name is used as a folder name)
+pub(crate) fn assert_valid_session_name(name: &str) {
     if name.trim().is_empty() {
         eprintln!("Session name cannot be empty. Please provide a specific session name.");
         process::exit(1);
@@ -404,6 +591,10 @@ pub(crate) fn assert_session_ne(name: &str) {
         eprintln!("Session name cannot contain '/'.");
         process::exit(1);
     }
+}
+
+pub(crate) fn assert_session_ne(name: &str) {
+    assert_valid_session_name(name);
 
     match session_exists(name) {
         Ok(result) if !result => {
@@ -567,3 +758,102 @@ const NOUNS: &[&'static str] = &[
     "yak",
     "zebra",
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use zellij_utils::data::{ConnectedClientInfo, PaneInfo, TabInfo};
+
+    #[test]
+    fn sessions_are_listed_as_json_with_their_tabs_panes_and_layouts() {
+        let mut session_info = SessionInfo {
+            name: "running".to_owned(),
+            tabs: vec![TabInfo {
+                position: 0,
+                name: "code".to_owned(),
+                active: true,
+                ..Default::default()
+            }],
+            clients: vec![ConnectedClientInfo {
+                client_id: 1,
+                name: Some("laptop".to_owned()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        session_info.panes.panes.insert(
+            0,
+            vec![PaneInfo {
+                id: 1,
+                terminal_command: Some("cargo watch".to_owned()),
+                cwd: Some(PathBuf::from("/path/to/project")),
+                ..Default::default()
+            }],
+        );
+        let layout = Layout::from_kdl(
+            r#"
+                layout {
+                    tab name="logs" {
+                        pane command="tail" {
+                            args "-f" "log.txt"
+                        }
+                        pane
+                    }
+                }
+            "#,
+            "layout.kdl".to_owned(),
+            None,
+            None,
+        )
+        .unwrap();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let sessions = sessions_to_json(
+            vec![(
+                "running".to_owned(),
+                Duration::from_secs(60),
+                Some(session_info),
+            )],
+            vec![
+                (
+                    "running".to_owned(),
+                    Duration::from_secs(60),
+                    layout.clone(),
+                ),
+                ("exited".to_owned(), Duration::from_secs(3600), layout),
+            ],
+            "running",
+            now,
+            false,
+        );
+        assert_eq!(sessions.as_array().unwrap().len(), 2);
+
+        let exited = &sessions[0];
+        assert_eq!(exited["name"], "exited");
+        assert_eq!(exited["status"], "exited");
+        assert_eq!(exited["created_at"], "2001-09-09T00:46:40Z");
+        assert_eq!(exited["age_secs"], 3600);
+        assert_eq!(
+            exited["layout"],
+            json!({ "tabs": [{
+                "name": "logs",
+                "tiled_panes": 2,
+                "floating_panes": 0,
+                "commands": ["tail -f log.txt"],
+            }]})
+        );
+
+        let running = &sessions[1];
+        assert_eq!(running["name"], "running");
+        assert_eq!(running["status"], "running");
+        assert_eq!(running["is_current_session"], true);
+        assert_eq!(running["created_at"], "2001-09-09T01:45:40Z");
+        assert_eq!(running["clients"][0]["name"], "laptop");
+        assert_eq!(running["tabs"][0]["name"], "code");
+        assert_eq!(
+            running["tabs"][0]["panes"][0]["terminal_command"],
+            "cargo watch"
+        );
+        assert_eq!(running["tabs"][0]["panes"][0]["cwd"], "/path/to/project");
+    }
+}
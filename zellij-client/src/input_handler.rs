@@ -45,6 +45,9 @@ struct InputHandler {
     receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
     holding_mouse: Option<HeldMouseButton>,
     mouse_mode_active: bool,
+    /// Whether we attached with `--read-only`, in which case we only send the server actions
+    /// that do not change the session
+    is_read_only: bool,
 }
 
 impl InputHandler {
@@ -57,6 +60,7 @@ impl InputHandler {
         send_client_instructions: SenderWithContext<ClientInstruction>,
         mode: InputMode,
         receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
+        is_read_only: bool,
     ) -> Self {
         InputHandler {
             mode,
@@ -69,6 +73,7 @@ impl InputHandler {
             receive_input_instructions,
             holding_mouse: None,
             mouse_mode_active: false,
+            is_read_only,
         }
     }
 
@@ -284,6 +289,18 @@ impl InputHandler {
     fn dispatch_action(&mut self, action: Action, client_id: Option<ClientId>) -> bool {
         let mut should_break = false;
 
+        let action = match action {
+            // a read-only client should not be able to end the session for everyone else
+            Action::Quit if self.is_read_only => Action::Detach,
+            action => action,
+        };
+        // the server has the final say, since the session might not be mirrored the way our own
+        // config says it is
+        let session_is_mirrored = self.options.mirror_session.unwrap_or(false);
+        if self.is_read_only && !action.is_allowed_for_read_only_clients(session_is_mirrored) {
+            return should_break;
+        }
+
         match action {
             Action::NoOp => {},
             Action::Quit => {
@@ -364,6 +381,7 @@ pub(crate) fn input_loop(
     send_client_instructions: SenderWithContext<ClientInstruction>,
     default_mode: InputMode,
     receive_input_instructions: Receiver<(InputInstruction, ErrorContext)>,
+    is_read_only: bool,
 ) {
     let _handler = InputHandler::new(
         os_input,
@@ -373,6 +391,7 @@ pub(crate) fn input_loop(
         send_client_instructions,
         default_mode,
         receive_input_instructions,
+        is_read_only,
     )
     .handle_input();
 }
@@ -31,7 +31,10 @@ use zellij_utils::{
     ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
     termwiz::input::InputEvent,
 };
-use zellij_utils::{cli::CliArgs, input::layout::Layout};
+use zellij_utils::{
+    cli::{CliArgs, Sessions},
+    input::layout::Layout,
+};
 
 /// Instructions related to the client-side application
 #[derive(Debug, Clone)]
@@ -216,6 +219,13 @@ pub fn start_client(
         sock_dir
     };
 
+    let is_read_only = matches!(
+        opts.command,
+        Some(zellij_utils::cli::Command::Sessions(Sessions::Attach {
+            read_only: true,
+            ..
+        }))
+    );
     let (first_msg, ipc_pipe) = match info {
         ClientInfo::Attach(name, config_options) => {
             envs::set_session_name(name.clone());
@@ -228,6 +238,7 @@ pub fn start_client(
                     config_options,
                     tab_position_to_focus,
                     pane_id_to_focus,
+                    is_read_only,
                 ),
                 ipc_pipe,
             )
@@ -246,6 +257,8 @@ pub fn start_client(
                     Box::new(config_options.clone()),
                     Box::new(layout.unwrap()),
                     Box::new(config.plugins.clone()),
+                    Box::new(config.scratchpads.clone()),
+                    Box::new(config.hooks.clone()),
                 ),
                 ipc_pipe,
             )
@@ -312,6 +325,7 @@ pub fn start_client(
                     send_client_instructions,
                     default_mode,
                     receive_input_instructions,
+                    is_read_only,
                 )
             }
         });
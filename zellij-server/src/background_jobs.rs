@@ -1,10 +1,12 @@
 use zellij_utils::async_std::task;
 use zellij_utils::consts::{
     session_info_cache_file_name, session_info_folder_for_session, session_layout_cache_file_name,
-    ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR,
+    session_snapshots_folder_for_session, ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR,
 };
 use zellij_utils::data::{Event, HttpVerb, SessionInfo};
 use zellij_utils::errors::{prelude::*, BackgroundJobContext, ContextType};
+use zellij_utils::input::hooks::{HookContext, HookEvent};
+use zellij_utils::session_snapshots::{prune_snapshots, take_snapshot};
 use zellij_utils::surf::{
     http::{Method, Url},
     RequestBuilder,
@@ -15,17 +17,19 @@ use std::fs;
 use std::io::Write;
 use std::os::unix::fs::FileTypeExt;
 use std::path::PathBuf;
+use std::process::Child;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::panes::PaneId;
 use crate::plugins::{PluginId, PluginInstruction};
 use crate::screen::ScreenInstruction;
 use crate::thread_bus::Bus;
 use crate::ClientId;
+use crate::ServerInstruction;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum BackgroundJob {
@@ -53,6 +57,11 @@ pub enum BackgroundJob {
         Vec<u8>,                  // body
         BTreeMap<String, String>, // context
     ),
+    ExpireClosedPanes(Duration), // Duration - how long closed panes are kept around for
+    CheckPaneDependencies(Duration), // Duration - when a dependency timeout runs out
+    CheckIdleness(Duration),     // Duration - when the session might have been idle for long enough
+    EnforceIdlePolicies,
+    RunHookCommand(String, BTreeMap<String, String>), // command, env_variables
     Exit,
 }
 
@@ -71,6 +80,11 @@ impl From<&BackgroundJob> for BackgroundJobContext {
             BackgroundJob::ReportLayoutInfo(..) => BackgroundJobContext::ReportLayoutInfo,
             BackgroundJob::RunCommand(..) => BackgroundJobContext::RunCommand,
             BackgroundJob::WebRequest(..) => BackgroundJobContext::WebRequest,
+            BackgroundJob::ExpireClosedPanes(..) => BackgroundJobContext::ExpireClosedPanes,
+            BackgroundJob::CheckPaneDependencies(..) => BackgroundJobContext::CheckPaneDependencies,
+            BackgroundJob::CheckIdleness(..) => BackgroundJobContext::CheckIdleness,
+            BackgroundJob::EnforceIdlePolicies => BackgroundJobContext::EnforceIdlePolicies,
+            BackgroundJob::RunHookCommand(..) => BackgroundJobContext::RunHookCommand,
             BackgroundJob::Exit => BackgroundJobContext::Exit,
         }
     }
@@ -80,10 +94,14 @@ static FLASH_DURATION_MS: u64 = 1000;
 static PLUGIN_ANIMATION_OFFSET_DURATION_MD: u64 = 500;
 static SESSION_READ_DURATION: u64 = 1000;
 static DEFAULT_SERIALIZATION_INTERVAL: u64 = 60000;
+static DEFAULT_SESSION_SNAPSHOT_COUNT: usize = 20;
+static IDLE_POLICY_CHECK_INTERVAL_MS: u64 = 1000;
 
 pub(crate) fn background_jobs_main(
     bus: Bus<BackgroundJob>,
     serialization_interval: Option<u64>,
+    session_snapshot_count: Option<usize>,
+    session_snapshot_max_age: Option<u64>,
 ) -> Result<()> {
     let err_context = || "failed to write to pty".to_string();
     let mut running_jobs: HashMap<BackgroundJob, Instant> = HashMap::new();
@@ -94,10 +112,14 @@ pub(crate) fn background_jobs_main(
     let last_serialization_time = Arc::new(Mutex::new(Instant::now()));
     let serialization_interval = serialization_interval.map(|s| s * 1000); // convert to
                                                                            // milliseconds
+    let session_snapshot_count = session_snapshot_count.unwrap_or(DEFAULT_SESSION_SNAPSHOT_COUNT);
+    let session_snapshot_max_age = session_snapshot_max_age.map(Duration::from_secs);
+    let mut running_hook_commands: Vec<(String, Child)> = vec![];
 
     loop {
         let (event, mut err_ctx) = bus.recv().with_context(err_context)?;
         err_ctx.add_call(ContextType::BackgroundJob((&event).into()));
+        reap_hook_commands(&mut running_hook_commands);
         let job = event.clone();
         match event {
             BackgroundJob::DisplayPaneError(pane_ids, text) => {
@@ -152,6 +174,14 @@ pub(crate) fn background_jobs_main(
                 *current_session_info.lock().unwrap() = session_info;
             },
             BackgroundJob::ReportLayoutInfo(session_layout) => {
+                if session_snapshot_count > 0 {
+                    snapshot_session_layout(
+                        &current_session_name.lock().unwrap(),
+                        &session_layout,
+                        session_snapshot_count,
+                        session_snapshot_max_age,
+                    );
+                }
                 *current_session_layout.lock().unwrap() = session_layout;
             },
             BackgroundJob::ReadAllSessionInfosOnMachine => {
@@ -169,17 +199,31 @@ pub(crate) fn background_jobs_main(
                     let current_session_layout = current_session_layout.clone();
                     let last_serialization_time = last_serialization_time.clone();
                     async move {
+                        let mut last_written_session_layout = String::new();
                         loop {
                             let current_session_name =
                                 current_session_name.lock().unwrap().to_string();
                             let current_session_info = current_session_info.lock().unwrap().clone();
                             let current_session_layout =
                                 current_session_layout.lock().unwrap().clone();
+                            // the same layout is written every time, but it is only a new
+                            // serialization of the session the first time
+                            let is_new_serialization = !current_session_layout.0.is_empty()
+                                && current_session_layout.0 != last_written_session_layout;
+                            if is_new_serialization {
+                                last_written_session_layout = current_session_layout.0.clone();
+                            }
                             write_session_state_to_disk(
                                 current_session_name.clone(),
                                 current_session_info,
                                 current_session_layout,
                             );
+                            if is_new_serialization {
+                                let _ = senders.send_to_server(ServerInstruction::RunHooks(
+                                    HookEvent::SessionSerialized,
+                                    HookContext::default(),
+                                ));
+                            }
                             let session_infos_on_machine =
                                 read_other_live_session_states(&current_session_name);
                             let resurrectable_sessions =
@@ -188,6 +232,10 @@ pub(crate) fn background_jobs_main(
                                 session_infos_on_machine,
                                 resurrectable_sessions,
                             ));
+                            // swap layouts can depend on the commands running in the panes,
+                            // whether or not the session is serialized
+                            let _ = senders
+                                .send_to_screen(ScreenInstruction::RefreshForegroundCommands);
                             if last_serialization_time
                                 .lock()
                                 .unwrap()
@@ -315,6 +363,74 @@ pub(crate) fn background_jobs_main(
                     }
                 });
             },
+            BackgroundJob::ExpireClosedPanes(grace_period) => {
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        task::sleep(grace_period).await;
+                        let _ = senders.send_to_screen(ScreenInstruction::ExpireClosedPanes);
+                    }
+                });
+            },
+            BackgroundJob::CheckPaneDependencies(delay) => {
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        task::sleep(delay).await;
+                        let _ = senders.send_to_screen(ScreenInstruction::CheckPaneDependencies);
+                    }
+                });
+            },
+            BackgroundJob::CheckIdleness(delay) => {
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        task::sleep(delay).await;
+                        let _ = senders.send_to_server(ServerInstruction::CheckIdleness);
+                    }
+                });
+            },
+            BackgroundJob::EnforceIdlePolicies => {
+                if running_jobs.get(&job).is_some() {
+                    continue;
+                }
+                running_jobs.insert(job, Instant::now());
+                task::spawn({
+                    let senders = bus.senders.clone();
+                    async move {
+                        loop {
+                            task::sleep(std::time::Duration::from_millis(
+                                IDLE_POLICY_CHECK_INTERVAL_MS,
+                            ))
+                            .await;
+                            if senders
+                                .send_to_server(ServerInstruction::EnforceIdlePolicies)
+                                .is_err()
+                            {
+                                // the server is gone
+                                break;
+                            }
+                        }
+                    }
+                });
+            },
+            BackgroundJob::RunHookCommand(command, env_variables) => {
+                // we do not wait for hook commands here, they are reaped as later jobs come in
+                let child = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .envs(env_variables)
+                    .stdin(std::process::Stdio::null())
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .spawn();
+                match child {
+                    Ok(child) => running_hook_commands.push((command, child)),
+                    Err(e) => {
+                        log::error!("Failed to run hook command {:?}: {}", command, e);
+                    },
+                }
+            },
             BackgroundJob::Exit => {
                 for loading_plugin in loading_plugins.values() {
                     loading_plugin.store(false, Ordering::SeqCst);
@@ -349,6 +465,22 @@ fn job_already_running(
     }
 }
 
+fn reap_hook_commands(running_hook_commands: &mut Vec<(String, Child)>) {
+    running_hook_commands.retain_mut(|(command, child)| match child.try_wait() {
+        Ok(Some(status)) => {
+            if !status.success() {
+                log::error!("Hook command {:?} failed: {}", command, status);
+            }
+            false
+        },
+        Ok(None) => true,
+        Err(e) => {
+            log::error!("Failed to wait for hook command {:?}: {}", command, e);
+            false
+        },
+    });
+}
+
 fn write_session_state_to_disk(
     current_session_name: String,
     current_session_info: SessionInfo,
@@ -382,6 +514,25 @@ fn write_session_state_to_disk(
     }
 }
 
+fn snapshot_session_layout(
+    current_session_name: &str,
+    session_layout: &(String, BTreeMap<String, String>),
+    max_count: usize,
+    max_age: Option<Duration>,
+) {
+    let (layout, layout_files) = session_layout;
+    if current_session_name.is_empty() || layout.is_empty() {
+        return;
+    }
+    let snapshots_folder = session_snapshots_folder_for_session(current_session_name);
+    let now = SystemTime::now();
+    let _ = take_snapshot(&snapshots_folder, layout, layout_files, now)
+        .and_then(|_| prune_snapshots(&snapshots_folder, max_count, max_age, now))
+        .map_err(|e| {
+            log::error!("Failed to snapshot session layout: {:?}", e);
+        });
+}
+
 fn read_other_live_session_states(current_session_name: &str) -> BTreeMap<String, SessionInfo> {
     let mut other_session_names = vec![];
     let mut session_infos_on_machine = BTreeMap::new();
@@ -0,0 +1,82 @@
+//! Runs the commands and actions configured in the `hooks` section of the config when their
+//! event happens in the session.
+use std::sync::{Arc, RwLock};
+
+use zellij_utils::{
+    envs,
+    input::hooks::{Hook, HookContext, HookEvent},
+};
+
+use crate::{
+    background_jobs::BackgroundJob, route::route_action, ClientId, SessionMetaData, SessionState,
+};
+
+/// Must not be called while holding a lock on `session_data` or `session_state`
+pub(crate) fn run_hooks(
+    event: HookEvent,
+    context: HookContext,
+    session_data: &Arc<RwLock<Option<SessionMetaData>>>,
+    session_state: &Arc<RwLock<SessionState>>,
+) {
+    let attached_clients = session_state.read().unwrap().attached_client_ids();
+    if let Some(session_data) = session_data.read().unwrap().as_ref() {
+        run_session_hooks(event, context, session_data, &attached_clients);
+    }
+}
+
+pub(crate) fn run_session_hooks(
+    event: HookEvent,
+    context: HookContext,
+    session_data: &SessionMetaData,
+    attached_clients: &[ClientId],
+) {
+    let hooks = session_data.hooks.get(event);
+    if hooks.is_empty() {
+        return;
+    }
+    let session_name = envs::get_session_name().unwrap_or_default();
+    for hook in hooks {
+        match hook {
+            Hook::Command(command) => {
+                let _ =
+                    session_data
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::RunHookCommand(
+                            command.clone(),
+                            context.env_vars(event, &session_name),
+                        ));
+            },
+            Hook::Actions(actions) => {
+                // actions are run as if by the client the event is about, or by the attached
+                // client with the lowest id if it is not about one (or that client is no longer
+                // attached)
+                let client_id = context
+                    .client_id
+                    .filter(|client_id| attached_clients.contains(client_id))
+                    .or_else(|| attached_clients.iter().min().copied());
+                let Some(client_id) = client_id else {
+                    log::warn!(
+                        "Not running the actions of the {} hook, no client is attached",
+                        event
+                    );
+                    continue;
+                };
+                for action in actions {
+                    if let Err(e) = route_action(
+                        action.clone(),
+                        client_id,
+                        None,
+                        session_data.senders.clone(),
+                        session_data.capabilities,
+                        session_data.client_attributes.clone(),
+                        session_data.default_shell.clone(),
+                        session_data.layout.clone(),
+                        None,
+                    ) {
+                        log::error!("Failed to run action of the {} hook: {:?}", event, e);
+                    }
+                }
+            },
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub mod panes;
 pub mod tab;
 
 mod background_jobs;
+mod hooks;
 mod logging_pipe;
 mod plugins;
 mod pty;
@@ -23,7 +24,9 @@ use std::{
     path::PathBuf,
     sync::{Arc, RwLock},
     thread,
+    time::{Duration, Instant},
 };
+use unicode_width::UnicodeWidthChar;
 use zellij_utils::envs;
 use zellij_utils::nix::sys::stat::{umask, Mode};
 use zellij_utils::pane_size::Size;
@@ -36,23 +39,27 @@ use crate::{
     pty::{get_default_shell, pty_thread_main, Pty, PtyInstruction},
     screen::{screen_thread_main, ScreenInstruction},
     thread_bus::{Bus, ThreadSenders},
+    ui::overlay::lock_screen::LockScreen,
 };
 use route::route_thread_main;
 use zellij_utils::{
     channels::{self, ChannelWithContext, SenderWithContext},
     cli::CliArgs,
     consts::{DEFAULT_SCROLL_BUFFER_SIZE, SCROLL_BUFFER_SIZE},
-    data::{ConnectToSession, Event, PluginCapabilities},
+    data::{ConnectToSession, Event, InputMode, PluginCapabilities},
     errors::{prelude::*, ContextType, ErrorInstruction, FatalError, ServerContext},
     home::{default_layout_dir, get_default_data_dir},
     input::{
         command::{RunCommand, TerminalAction},
         get_mode_info,
+        hooks::{HookContext, HookEvent, Hooks},
         layout::Layout,
-        options::Options,
+        options::{ClientSizePolicy, Options},
         plugins::PluginAliases,
+        scratchpads::Scratchpads,
     },
     ipc::{ClientAttributes, ExitReason, ServerToClientMsg},
+    session_lock,
 };
 
 pub type ClientId = u16;
@@ -66,6 +73,8 @@ pub enum ServerInstruction {
         Box<Options>,
         Box<Layout>,
         Box<PluginAliases>,
+        Box<Scratchpads>,
+        Box<Hooks>,
         ClientId,
     ),
     Render(Option<HashMap<ClientId, String>>),
@@ -80,6 +89,7 @@ pub enum ServerInstruction {
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
+        bool,                // is_read_only
         ClientId,
     ),
     ConnStatus(ClientId),
@@ -94,6 +104,14 @@ pub enum ServerInstruction {
         client_id: ClientId,
     },
     DisconnectAllClientsExcept(ClientId),
+    ResizeToClient(ClientId),
+    LockSession,
+    LockScreenInput(Vec<u8>, ClientId), // bytes typed by a client while the session is locked
+    CheckIdleness,
+    EnforceIdlePolicies,
+    AllPanesExited(bool), // whether the commands in all terminal panes exited
+    ToggleKeepSession,
+    RunHooks(HookEvent, HookContext),
 }
 
 impl From<&ServerInstruction> for ServerContext {
@@ -121,6 +139,14 @@ impl From<&ServerInstruction> for ServerContext {
             ServerInstruction::DisconnectAllClientsExcept(..) => {
                 ServerContext::DisconnectAllClientsExcept
             },
+            ServerInstruction::ResizeToClient(..) => ServerContext::ResizeToClient,
+            ServerInstruction::LockSession => ServerContext::LockSession,
+            ServerInstruction::LockScreenInput(..) => ServerContext::LockScreenInput,
+            ServerInstruction::CheckIdleness => ServerContext::CheckIdleness,
+            ServerInstruction::EnforceIdlePolicies => ServerContext::EnforceIdlePolicies,
+            ServerInstruction::AllPanesExited(..) => ServerContext::AllPanesExited,
+            ServerInstruction::ToggleKeepSession => ServerContext::ToggleKeepSession,
+            ServerInstruction::RunHooks(..) => ServerContext::RunHooks,
         }
     }
 }
@@ -138,6 +164,8 @@ pub(crate) struct SessionMetaData {
     pub default_shell: Option<TerminalAction>,
     pub layout: Box<Layout>,
     pub config_options: Box<Options>,
+    pub data_dir: PathBuf,
+    pub hooks: Box<Hooks>,
     screen_thread: Option<thread::JoinHandle<()>>,
     pty_thread: Option<thread::JoinHandle<()>>,
     plugin_thread: Option<thread::JoinHandle<()>>,
@@ -205,13 +233,77 @@ macro_rules! send_to_client {
 pub(crate) struct SessionState {
     clients: HashMap<ClientId, Option<Size>>,
     pipes: HashMap<String, ClientId>, // String => pipe_id
+    read_only_clients: HashSet<ClientId>,
+    client_size_policy: ClientSizePolicy,
+    latest_client: Option<ClientId>, // the client that was most recently active
+    session_size: Option<Size>,      // the size last given to the screen
+    clients_needing_filler: HashSet<ClientId>,
+    lock_screen: Option<LockScreen>, // Some while the session is locked
+    clients_needing_lock_screen: HashSet<ClientId>,
+    last_input: Instant,
+    client_last_input: HashMap<ClientId, Instant>,
+    detached_since: Option<Instant>, // since when no client with a terminal is attached
+    all_panes_exited_since: Option<Instant>,
+    keep_session: bool, // excludes the session from the idle policies
+    idle_warnings: HashMap<ClientId, String>, // the idle policy warnings clients are shown
 }
 
+/// The idle session policies from the config
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct IdlePolicies {
+    pub detach_idle_clients_after: Option<Duration>,
+    pub kill_session_when_detached_after: Option<Duration>,
+    pub kill_session_when_all_panes_exited: bool,
+}
+
+impl IdlePolicies {
+    pub fn from_options(options: &Options) -> Self {
+        IdlePolicies {
+            detach_idle_clients_after: options.detach_idle_clients_after.map(Duration::from_secs),
+            kill_session_when_detached_after: options
+                .kill_session_when_detached_after
+                .map(Duration::from_secs),
+            kill_session_when_all_panes_exited: options
+                .kill_session_when_all_panes_exited
+                .unwrap_or(false),
+        }
+    }
+    pub fn any(&self) -> bool {
+        self.detach_idle_clients_after.is_some()
+            || self.kill_session_when_detached_after.is_some()
+            || self.kill_session_when_all_panes_exited
+    }
+}
+
+/// What the idle policies call for right now
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct IdlePolicyVerdict {
+    pub clients_to_detach: Vec<ClientId>,
+    pub kill_session: bool,
+    pub warnings: HashMap<ClientId, String>,
+}
+
+// how long attached clients are warned before the idle policies act
+const IDLE_POLICY_WARNING_PERIOD: Duration = Duration::from_secs(60);
+
 impl SessionState {
     pub fn new() -> Self {
         SessionState {
             clients: HashMap::new(),
             pipes: HashMap::new(),
+            read_only_clients: HashSet::new(),
+            client_size_policy: ClientSizePolicy::default(),
+            latest_client: None,
+            session_size: None,
+            clients_needing_filler: HashSet::new(),
+            lock_screen: None,
+            clients_needing_lock_screen: HashSet::new(),
+            last_input: Instant::now(),
+            client_last_input: HashMap::new(),
+            detached_since: Some(Instant::now()),
+            all_panes_exited_since: None,
+            keep_session: false,
+            idle_warnings: HashMap::new(),
         }
     }
     pub fn new_client(&mut self) -> ClientId {
@@ -225,6 +317,8 @@ impl SessionState {
             }
         }
         self.clients.insert(next_client_id, None);
+        self.client_last_input
+            .insert(next_client_id, Instant::now());
         next_client_id
     }
     pub fn associate_pipe_with_client(&mut self, pipe_id: String, client_id: ClientId) {
@@ -233,9 +327,132 @@ impl SessionState {
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.clients.remove(&client_id);
         self.pipes.retain(|_p_id, c_id| c_id != &client_id);
+        self.read_only_clients.remove(&client_id);
+        self.clients_needing_filler.remove(&client_id);
+        self.clients_needing_lock_screen.remove(&client_id);
+        if let Some(lock_screen) = self.lock_screen.as_mut() {
+            lock_screen.remove_client(client_id);
+        }
+        self.client_last_input.remove(&client_id);
+        self.idle_warnings.remove(&client_id);
+        if self.latest_client == Some(client_id) {
+            self.latest_client = None;
+        }
+        let has_attached_clients = self.clients.values().any(|size| size.is_some());
+        if !has_attached_clients && self.detached_since.is_none() {
+            self.detached_since = Some(Instant::now());
+        }
+    }
+    pub fn set_client_read_only(&mut self, client_id: ClientId) {
+        self.read_only_clients.insert(client_id);
+    }
+    /// Clients with a terminal, as opposed to eg. ones sending cli actions
+    pub fn client_is_attached(&self, client_id: ClientId) -> bool {
+        matches!(self.clients.get(&client_id), Some(Some(_)))
+    }
+    pub fn attached_client_ids(&self) -> Vec<ClientId> {
+        self.clients
+            .iter()
+            .filter(|(_, size)| size.is_some())
+            .map(|(client_id, _)| *client_id)
+            .collect()
+    }
+    pub fn client_is_read_only(&self, client_id: ClientId) -> bool {
+        self.read_only_clients.contains(&client_id)
     }
     pub fn set_client_size(&mut self, client_id: ClientId, size: Size) {
         self.clients.insert(client_id, Some(size));
+        // clients without a size (eg. the ones sending cli actions) do not count as attached
+        self.detached_since = None;
+        self.clients_needing_filler.insert(client_id);
+        if self.is_locked() {
+            self.clients_needing_lock_screen.insert(client_id);
+        }
+        if self.latest_client.is_none() {
+            self.latest_client = Some(client_id);
+        }
+    }
+    pub fn set_client_size_policy(&mut self, client_size_policy: ClientSizePolicy) {
+        self.client_size_policy = client_size_policy;
+    }
+    /// Returns true if the session should be resized because this client became the latest
+    pub fn mark_client_active(&mut self, client_id: ClientId) -> bool {
+        // observers should not get to resize the session for everyone else
+        let client_has_size = matches!(self.clients.get(&client_id), Some(Some(_)))
+            && !self.read_only_clients.contains(&client_id);
+        if client_has_size && self.latest_client != Some(client_id) {
+            self.latest_client = Some(client_id);
+            self.client_size_policy == ClientSizePolicy::Latest
+        } else {
+            false
+        }
+    }
+    /// Chooses the size of the session from the sizes of its clients according to the
+    /// `client_size_policy` and returns it
+    pub fn resize_session(&mut self) -> Option<Size> {
+        let size = match self.client_size_policy {
+            ClientSizePolicy::Smallest => self.min_client_terminal_size(),
+            ClientSizePolicy::Largest => self.max_client_terminal_size(),
+            ClientSizePolicy::Latest => self
+                .latest_client
+                .and_then(|client_id| self.clients.get(&client_id).copied().flatten())
+                .or_else(|| self.min_client_terminal_size()),
+            ClientSizePolicy::Manual => self
+                .session_size
+                .or_else(|| self.min_client_terminal_size()),
+        };
+        if let Some(size) = size {
+            self.set_session_size(size);
+        }
+        size
+    }
+    /// Resizes the session to the size of this client, or of the latest one if it has no size of
+    /// its own (eg. if it's the cli)
+    pub fn resize_session_to_client(&mut self, client_id: ClientId) -> Option<Size> {
+        let size = self.clients.get(&client_id).copied().flatten().or_else(|| {
+            self.latest_client
+                .and_then(|client_id| self.clients.get(&client_id).copied().flatten())
+        });
+        if let Some(size) = size {
+            self.set_session_size(size);
+        }
+        size
+    }
+    fn set_session_size(&mut self, size: Size) {
+        if self.session_size != Some(size) {
+            self.session_size = Some(size);
+            self.clients_needing_filler.extend(self.clients.keys());
+        }
+    }
+    /// The filler to draw on the parts of this client's terminal that are outside the session,
+    /// if it is bigger than the session and its display might have been cleared since it was last
+    /// drawn
+    pub fn take_out_of_bounds_filler(
+        &mut self,
+        client_id: ClientId,
+        display_was_cleared: bool,
+    ) -> Option<String> {
+        let needs_filler = self.clients_needing_filler.remove(&client_id) || display_was_cleared;
+        if !needs_filler {
+            return None;
+        }
+        let client_size = self.clients.get(&client_id).copied().flatten()?;
+        out_of_bounds_filler(client_size, self.session_size?)
+    }
+    /// Clips the render to this client's terminal if it is smaller than the session, so that the
+    /// parts of the session outside of it are left out instead of piling up on its edges
+    pub fn clip_render_to_client(&self, client_id: ClientId, render: String) -> String {
+        match (
+            self.clients.get(&client_id).copied().flatten(),
+            self.session_size,
+        ) {
+            (Some(client_size), Some(session_size))
+                if client_size.rows < session_size.rows || client_size.cols < session_size.cols =>
+            {
+                clip_render(&render, client_size)
+            },
+            _ => render,
+        }
     }
     pub fn min_client_terminal_size(&self) -> Option<Size> {
         // None if there are no client sizes
@@ -261,6 +478,169 @@ impl SessionState {
             _ => None,
         }
     }
+    pub fn max_client_terminal_size(&self) -> Option<Size> {
+        // None if there are no client sizes
+        let max_rows = self
+            .clients
+            .values()
+            .filter_map(|size| size.map(|size| size.rows))
+            .max();
+        let max_cols = self
+            .clients
+            .values()
+            .filter_map(|size| size.map(|size| size.cols))
+            .max();
+        match (max_rows, max_cols) {
+            (Some(rows), Some(cols)) => Some(Size { rows, cols }),
+            _ => None,
+        }
+    }
+    pub fn lock_session(&mut self, lock_screen: LockScreen) {
+        self.lock_screen = Some(lock_screen);
+        self.clients_needing_lock_screen.clear();
+    }
+    pub fn is_locked(&self) -> bool {
+        self.lock_screen.is_some()
+    }
+    /// Returns true if this input unlocked the session
+    pub fn handle_lock_screen_input(&mut self, client_id: ClientId, bytes: &[u8]) -> bool {
+        let unlocked = self
+            .lock_screen
+            .as_mut()
+            .map(|lock_screen| lock_screen.handle_input(client_id, bytes))
+            .unwrap_or(false);
+        if unlocked {
+            self.lock_screen = None;
+            self.clients_needing_lock_screen.clear();
+            self.clients_needing_filler.extend(self.clients.keys());
+        }
+        unlocked
+    }
+    pub fn render_lock_screen(&self, client_id: ClientId) -> Option<String> {
+        let client_size = self.clients.get(&client_id).copied().flatten()?;
+        self.lock_screen
+            .as_ref()?
+            .render(client_size, client_id)
+            .ok()
+    }
+    /// The lock screen, if it needs to be drawn again for this client (eg. because it was
+    /// resized or its display was cleared)
+    pub fn take_lock_screen_redraw(
+        &mut self,
+        client_id: ClientId,
+        display_was_cleared: bool,
+    ) -> Option<String> {
+        let needs_redraw =
+            self.clients_needing_lock_screen.remove(&client_id) || display_was_cleared;
+        if needs_redraw {
+            self.render_lock_screen(client_id)
+        } else {
+            None
+        }
+    }
+    pub fn mark_input(&mut self, client_id: ClientId) {
+        self.last_input = Instant::now();
+        self.client_last_input.insert(client_id, self.last_input);
+    }
+    pub fn time_since_last_input(&self) -> Duration {
+        self.last_input.elapsed()
+    }
+    pub fn set_all_panes_exited(&mut self, all_panes_exited: bool) {
+        if !all_panes_exited {
+            self.all_panes_exited_since = None;
+        } else if self.all_panes_exited_since.is_none() {
+            self.all_panes_exited_since = Some(Instant::now());
+        }
+    }
+    pub fn set_keep_session(&mut self, keep_session: bool) {
+        self.keep_session = keep_session;
+    }
+    pub fn toggle_keep_session(&mut self) -> bool {
+        self.keep_session = !self.keep_session;
+        self.keep_session
+    }
+    pub fn apply_idle_policies(
+        &self,
+        idle_policies: &IdlePolicies,
+        now: Instant,
+    ) -> IdlePolicyVerdict {
+        let mut verdict = IdlePolicyVerdict::default();
+        if self.keep_session {
+            return verdict;
+        }
+        let attached_clients = self
+            .clients
+            .iter()
+            .filter(|(_, size)| size.is_some())
+            .map(|(client_id, _)| *client_id);
+        if let Some(detach_idle_clients_after) = idle_policies.detach_idle_clients_after {
+            for client_id in attached_clients.clone() {
+                let last_input = self
+                    .client_last_input
+                    .get(&client_id)
+                    .copied()
+                    .unwrap_or(now);
+                let idle_for = now.saturating_duration_since(last_input);
+                match detach_idle_clients_after.checked_sub(idle_for) {
+                    Some(remaining) if !remaining.is_zero() => {
+                        if remaining <= IDLE_POLICY_WARNING_PERIOD {
+                            verdict.warnings.insert(
+                                client_id,
+                                format!(
+                                    "Detaching in {}s due to inactivity, press any key to stay",
+                                    seconds_left(remaining)
+                                ),
+                            );
+                        }
+                    },
+                    _ => verdict.clients_to_detach.push(client_id),
+                }
+            }
+        }
+        if let (true, Some(all_panes_exited_since)) = (
+            idle_policies.kill_session_when_all_panes_exited,
+            self.all_panes_exited_since,
+        ) {
+            let exited_for = now.saturating_duration_since(all_panes_exited_since);
+            match IDLE_POLICY_WARNING_PERIOD.checked_sub(exited_for) {
+                Some(remaining) if !remaining.is_zero() => {
+                    for client_id in attached_clients {
+                        verdict.warnings.insert(
+                            client_id,
+                            format!(
+                                "All panes exited, killing the session in {}s",
+                                seconds_left(remaining)
+                            ),
+                        );
+                    }
+                },
+                _ => verdict.kill_session = true,
+            }
+        }
+        if let (Some(kill_session_when_detached_after), Some(detached_since)) = (
+            idle_policies.kill_session_when_detached_after,
+            self.detached_since,
+        ) {
+            if now.saturating_duration_since(detached_since) >= kill_session_when_detached_after {
+                verdict.kill_session = true;
+            }
+        }
+        verdict
+    }
+    /// Returns true if a warning some client was shown is no longer relevant
+    pub fn set_idle_warnings(&mut self, idle_warnings: HashMap<ClientId, String>) -> bool {
+        let warning_was_removed = self
+            .idle_warnings
+            .keys()
+            .any(|client_id| !idle_warnings.contains_key(client_id));
+        self.idle_warnings = idle_warnings;
+        warning_was_removed
+    }
+    pub fn render_idle_warning(&self, client_id: ClientId) -> Option<String> {
+        let idle_warning = self.idle_warnings.get(&client_id)?;
+        let client_size = self.clients.get(&client_id).copied().flatten()?;
+        Some(idle_warning_banner(idle_warning, client_size))
+    }
     pub fn client_ids(&self) -> Vec<ClientId> {
         self.clients.keys().copied().collect()
     }
@@ -277,6 +657,190 @@ impl SessionState {
     }
 }
 
+const CLEAR_DISPLAY: &str = "\u{1b}[2J";
+
+fn render_lock_screen(
+    os_input: &mut Box<dyn ServerOsApi>,
+    session_state: &Arc<RwLock<SessionState>>,
+) {
+    let client_ids = session_state.read().unwrap().client_ids();
+    for client_id in client_ids {
+        let lock_screen = session_state.read().unwrap().render_lock_screen(client_id);
+        if let Some(lock_screen) = lock_screen {
+            send_to_client!(
+                client_id,
+                os_input,
+                ServerToClientMsg::Render(lock_screen),
+                session_state
+            );
+        }
+    }
+}
+
+/// Switches every client to this input mode, as if each of them did so themselves
+fn switch_all_clients_to_mode(
+    input_mode: InputMode,
+    session_data: &SessionMetaData,
+    os_input: &mut Box<dyn ServerOsApi>,
+    session_state: &Arc<RwLock<SessionState>>,
+) {
+    let mode_info = get_mode_info(
+        input_mode,
+        &session_data.client_attributes,
+        session_data.capabilities,
+    );
+    session_data
+        .senders
+        .send_to_plugin(PluginInstruction::Update(vec![(
+            None,
+            None,
+            Event::ModeUpdate(mode_info.clone()),
+        )]))
+        .unwrap();
+    session_data
+        .senders
+        .send_to_screen(ScreenInstruction::ChangeModeForAllClients(mode_info))
+        .unwrap();
+    let client_ids = session_state.read().unwrap().client_ids();
+    for client_id in client_ids {
+        send_to_client!(
+            client_id,
+            os_input,
+            ServerToClientMsg::SwitchToMode(input_mode),
+            session_state
+        );
+    }
+}
+
+fn seconds_left(remaining: Duration) -> u64 {
+    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+}
+
+fn idle_warning_banner(idle_warning: &str, client_size: Size) -> String {
+    let idle_warning: String = format!(" {} ", idle_warning)
+        .chars()
+        .take(client_size.cols)
+        .collect();
+    let x = (client_size
+        .cols
+        .saturating_sub(idle_warning.chars().count())
+        / 2)
+        + 1;
+    // save the cursor (and its style) so that we leave it where the render placed it
+    format!(
+        "\u{1b}7\u{1b}[m\u{1b}[1;30;43m\u{1b}[1;{}H{}\u{1b}[m\u{1b}8",
+        x, idle_warning
+    )
+}
+
+fn out_of_bounds_filler(client_size: Size, session_size: Size) -> Option<String> {
+    if client_size.rows <= session_size.rows && client_size.cols <= session_size.cols {
+        return None;
+    }
+    // save the cursor (and its style) so that we leave it where the render placed it
+    let mut filler = String::from("\u{1b}7\u{1b}[m\u{1b}[2m");
+    for row in 0..client_size.rows {
+        let first_col = if row < session_size.rows {
+            session_size.cols
+        } else {
+            0
+        };
+        if first_col < client_size.cols {
+            filler.push_str(&format!("\u{1b}[{};{}H", row + 1, first_col + 1));
+            filler.push_str(&"·".repeat(client_size.cols - first_col));
+        }
+    }
+    filler.push_str("\u{1b}[m\u{1b}8");
+    Some(filler)
+}
+
+fn clip_render(render: &str, client_size: Size) -> String {
+    // the (1 based) position the render moves the cursor of the terminal to, escape sequences
+    // are kept as they are and characters are only kept if they are inside the terminal
+    let (mut row, mut col) = (1, 1);
+    let mut saved_position = (row, col);
+    let mut cursor_is_outside = false;
+    let mut clipped = String::with_capacity(render.len());
+    let mut characters = render.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\u{1b}' => {
+                let mut sequence = String::from(character);
+                match characters.next() {
+                    Some('[') => {
+                        sequence.push('[');
+                        for character in characters.by_ref() {
+                            sequence.push(character);
+                            if ('\u{40}'..='\u{7e}').contains(&character) {
+                                break;
+                            }
+                        }
+                        if sequence.ends_with('H') || sequence.ends_with('f') {
+                            let mut coordinates = sequence[2..sequence.len() - 1]
+                                .split(';')
+                                .map(|coordinate| coordinate.parse::<usize>().unwrap_or(1).max(1));
+                            row = coordinates.next().unwrap_or(1);
+                            col = coordinates.next().unwrap_or(1);
+                            cursor_is_outside = row > client_size.rows || col > client_size.cols;
+                        } else if sequence == "\u{1b}[s" {
+                            saved_position = (row, col);
+                        } else if sequence == "\u{1b}[u" {
+                            (row, col) = saved_position;
+                        }
+                    },
+                    Some(introducer @ (']' | 'P')) => {
+                        // OSC (eg. hyperlinks) and DCS (eg. sixel images) sequences
+                        sequence.push(introducer);
+                        for character in characters.by_ref() {
+                            sequence.push(character);
+                            if (introducer == ']' && character == '\u{7}')
+                                || sequence.ends_with("\u{1b}\\")
+                            {
+                                break;
+                            }
+                        }
+                    },
+                    Some('7') => {
+                        sequence.push('7');
+                        saved_position = (row, col);
+                    },
+                    Some('8') => {
+                        sequence.push('8');
+                        (row, col) = saved_position;
+                    },
+                    Some(character) => sequence.push(character),
+                    None => {},
+                }
+                clipped.push_str(&sequence);
+            },
+            '\r' => {
+                col = 1;
+                clipped.push(character);
+            },
+            '\n' => {
+                row += 1;
+                // moving past the last line would scroll the terminal
+                if row <= client_size.rows {
+                    clipped.push(character);
+                }
+            },
+            character if character.is_control() => clipped.push(character),
+            character => {
+                let width = character.width().unwrap_or(0);
+                if row <= client_size.rows && col + width <= client_size.cols + 1 {
+                    clipped.push(character);
+                }
+                col += width;
+            },
+        }
+    }
+    if cursor_is_outside {
+        // otherwise it would be shown on the edge of the terminal
+        clipped.push_str("\u{1b}[?25l");
+    }
+    clipped
+}
+
 pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
     info!("Starting Zellij server!");
 
@@ -366,6 +930,8 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 config_options,
                 layout,
                 plugin_aliases,
+                scratchpads,
+                hooks,
                 client_id,
             ) => {
                 let session = init_session(
@@ -378,12 +944,23 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                         config_options: config_options.clone(),
                     },
                     plugin_aliases,
+                    scratchpads,
+                    hooks,
                 );
+                let client_name = config_options.client_name.clone();
+                let auto_lock_session_after = config_options.auto_lock_session_after;
+                let idle_policies = IdlePolicies::from_options(&config_options);
                 *session_data.write().unwrap() = Some(session);
-                session_state
-                    .write()
-                    .unwrap()
-                    .set_client_size(client_id, client_attributes.size);
+                {
+                    let mut session_state = session_state.write().unwrap();
+                    session_state.set_client_size_policy(
+                        config_options.client_size_policy.unwrap_or_default(),
+                    );
+                    session_state.set_keep_session(config_options.keep_session.unwrap_or(false));
+                    session_state.set_client_size(client_id, client_attributes.size);
+                    // the screen starts out with this client's size
+                    session_state.resize_session();
+                }
 
                 let default_shell = config_options.default_shell.map(|shell| {
                     TerminalAction::RunCommand(RunCommand {
@@ -458,12 +1035,61 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_plugin(PluginInstruction::AddClient(client_id))
                     .unwrap();
+                if let Some(client_name) = client_name {
+                    session_data
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_screen(ScreenInstruction::SetClientName(client_id, client_name))
+                        .unwrap();
+                }
+                if let Some(auto_lock_session_after) = auto_lock_session_after {
+                    session_data
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::CheckIdleness(Duration::from_secs(
+                            auto_lock_session_after,
+                        )))
+                        .unwrap();
+                }
+                if idle_policies.any() {
+                    session_data
+                        .read()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::EnforceIdlePolicies)
+                        .unwrap();
+                }
+                let hook_context = HookContext {
+                    client_id: Some(client_id),
+                    ..Default::default()
+                };
+                hooks::run_hooks(
+                    HookEvent::SessionCreated,
+                    hook_context.clone(),
+                    &session_data,
+                    &session_state,
+                );
+                hooks::run_hooks(
+                    HookEvent::ClientAttached,
+                    hook_context,
+                    &session_data,
+                    &session_state,
+                );
             },
             ServerInstruction::AttachClient(
                 attrs,
                 options,
                 tab_position_to_focus,
                 pane_id_to_focus,
+                is_read_only,
                 client_id,
             ) => {
                 let rlock = session_data.read().unwrap();
@@ -472,14 +1098,10 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .write()
                     .unwrap()
                     .set_client_size(client_id, attrs.size);
-                let min_size = session_state
-                    .read()
-                    .unwrap()
-                    .min_client_terminal_size()
-                    .unwrap();
+                let session_size = session_state.write().unwrap().resize_session().unwrap();
                 session_data
                     .senders
-                    .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                    .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                     .unwrap();
                 session_data
                     .senders
@@ -487,14 +1109,22 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                         client_id,
                         tab_position_to_focus,
                         pane_id_to_focus,
+                        is_read_only,
                     ))
                     .unwrap();
+                if let Some(client_name) = options.client_name.clone() {
+                    session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::SetClientName(client_id, client_name))
+                        .unwrap();
+                }
                 session_data
                     .senders
                     .send_to_plugin(PluginInstruction::AddClient(client_id))
                     .unwrap();
                 let default_mode = options.default_mode.unwrap_or_default();
-                let mode_info = get_mode_info(default_mode, &attrs, session_data.capabilities);
+                let mut mode_info = get_mode_info(default_mode, &attrs, session_data.capabilities);
+                mode_info.is_read_only = is_read_only;
                 let mode = mode_info.mode;
                 session_data
                     .senders
@@ -514,6 +1144,16 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     ServerToClientMsg::SwitchToMode(mode),
                     session_state
                 );
+                let attached_clients = session_state.read().unwrap().attached_client_ids();
+                hooks::run_session_hooks(
+                    HookEvent::ClientAttached,
+                    HookContext {
+                        client_id: Some(client_id),
+                        ..Default::default()
+                    },
+                    session_data,
+                    &attached_clients,
+                );
             },
             ServerInstruction::UnblockInputThread => {
                 for client_id in session_state.read().unwrap().clients.keys() {
@@ -572,17 +1212,19 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 }
             },
             ServerInstruction::ClientExit(client_id) => {
+                let client_was_attached =
+                    session_state.read().unwrap().client_is_attached(client_id);
                 let _ =
                     os_input.send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
                 remove_client!(client_id, os_input, session_state);
-                if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+                if let Some(session_size) = session_state.write().unwrap().resize_session() {
                     session_data
                         .write()
                         .unwrap()
                         .as_ref()
                         .unwrap()
                         .senders
-                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                         .unwrap();
                 }
                 session_data
@@ -601,6 +1243,17 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_plugin(PluginInstruction::RemoveClient(client_id))
                     .unwrap();
+                if client_was_attached {
+                    hooks::run_hooks(
+                        HookEvent::ClientDetached,
+                        HookContext {
+                            client_id: Some(client_id),
+                            ..Default::default()
+                        },
+                        &session_data,
+                        &session_state,
+                    );
+                }
                 if !session_state.read().unwrap().active_clients_are_connected() {
                     *session_data.write().unwrap() = None;
                     let client_ids_to_cleanup: Vec<ClientId> = session_state
@@ -618,15 +1271,17 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 }
             },
             ServerInstruction::RemoveClient(client_id) => {
+                let client_was_attached =
+                    session_state.read().unwrap().client_is_attached(client_id);
                 remove_client!(client_id, os_input, session_state);
-                if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+                if let Some(session_size) = session_state.write().unwrap().resize_session() {
                     session_data
                         .write()
                         .unwrap()
                         .as_ref()
                         .unwrap()
                         .senders
-                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                         .unwrap();
                 }
                 session_data
@@ -645,6 +1300,17 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .senders
                     .send_to_plugin(PluginInstruction::RemoveClient(client_id))
                     .unwrap();
+                if client_was_attached {
+                    hooks::run_hooks(
+                        HookEvent::ClientDetached,
+                        HookContext {
+                            client_id: Some(client_id),
+                            ..Default::default()
+                        },
+                        &session_data,
+                        &session_state,
+                    );
+                }
             },
             ServerInstruction::KillSession => {
                 let client_ids = session_state.read().unwrap().client_ids();
@@ -670,20 +1336,178 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     remove_client!(client_id, os_input, session_state);
                 }
             },
+            ServerInstruction::ResizeToClient(client_id) => {
+                let session_size = session_state
+                    .write()
+                    .unwrap()
+                    .resize_session_to_client(client_id);
+                if let Some(session_size) = session_size {
+                    session_data
+                        .write()
+                        .unwrap()
+                        .as_ref()
+                        .unwrap()
+                        .senders
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
+                        .unwrap();
+                }
+            },
+            ServerInstruction::LockSession => {
+                let rlock = session_data.read().unwrap();
+                let session_data = rlock.as_ref().unwrap();
+                if session_state.read().unwrap().is_locked() {
+                    continue;
+                }
+                let passphrase_hash = session_lock::read_passphrase_hash(
+                    session_data
+                        .config_options
+                        .lock_session_passphrase_hash
+                        .as_ref(),
+                    &session_data.data_dir,
+                );
+                match passphrase_hash {
+                    Some(passphrase_hash) => {
+                        session_state
+                            .write()
+                            .unwrap()
+                            .lock_session(LockScreen::new(passphrase_hash));
+                        // in locked mode all keys are sent through as they are, so that they can
+                        // be used to enter the passphrase
+                        switch_all_clients_to_mode(
+                            InputMode::Locked,
+                            session_data,
+                            &mut os_input,
+                            &session_state,
+                        );
+                        render_lock_screen(&mut os_input, &session_state);
+                    },
+                    None => {
+                        log::error!(
+                            "Cannot lock the session without a lock passphrase, set one with `zellij setup --set-lock-passphrase`"
+                        );
+                    },
+                }
+            },
+            ServerInstruction::LockScreenInput(bytes, client_id) => {
+                let rlock = session_data.read().unwrap();
+                let session_data = rlock.as_ref().unwrap();
+                let unlocked = session_state
+                    .write()
+                    .unwrap()
+                    .handle_lock_screen_input(client_id, &bytes);
+                if unlocked {
+                    switch_all_clients_to_mode(
+                        session_data.config_options.default_mode.unwrap_or_default(),
+                        session_data,
+                        &mut os_input,
+                        &session_state,
+                    );
+                    session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::Redraw)
+                        .unwrap();
+                } else {
+                    render_lock_screen(&mut os_input, &session_state);
+                }
+            },
+            ServerInstruction::CheckIdleness => {
+                let rlock = session_data.read().unwrap();
+                let session_data = rlock.as_ref().unwrap();
+                if let Some(auto_lock_session_after) = session_data
+                    .config_options
+                    .auto_lock_session_after
+                    .map(Duration::from_secs)
+                {
+                    let time_since_last_input =
+                        session_state.read().unwrap().time_since_last_input();
+                    let next_check = if time_since_last_input >= auto_lock_session_after {
+                        session_data
+                            .senders
+                            .send_to_server(ServerInstruction::LockSession)
+                            .unwrap();
+                        auto_lock_session_after
+                    } else {
+                        auto_lock_session_after - time_since_last_input
+                    };
+                    session_data
+                        .senders
+                        .send_to_background_jobs(BackgroundJob::CheckIdleness(next_check))
+                        .unwrap();
+                }
+            },
+            ServerInstruction::EnforceIdlePolicies => {
+                let rlock = session_data.read().unwrap();
+                let session_data = rlock.as_ref().unwrap();
+                let idle_policies = IdlePolicies::from_options(&session_data.config_options);
+                let verdict = session_state
+                    .read()
+                    .unwrap()
+                    .apply_idle_policies(&idle_policies, Instant::now());
+                if verdict.kill_session {
+                    log::info!("Killing idle session");
+                    session_data
+                        .senders
+                        .send_to_server(ServerInstruction::KillSession)
+                        .unwrap();
+                    continue;
+                }
+                if !verdict.clients_to_detach.is_empty() {
+                    log::info!("Detaching idle clients: {:?}", verdict.clients_to_detach);
+                    session_data
+                        .senders
+                        .send_to_server(ServerInstruction::DetachSession(verdict.clients_to_detach))
+                        .unwrap();
+                }
+                let warning_was_removed = session_state
+                    .write()
+                    .unwrap()
+                    .set_idle_warnings(verdict.warnings);
+                if warning_was_removed {
+                    // draw over the warnings that are no longer relevant
+                    session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::Redraw)
+                        .unwrap();
+                }
+                let client_ids = session_state.read().unwrap().client_ids();
+                for client_id in client_ids {
+                    let idle_warning = session_state.read().unwrap().render_idle_warning(client_id);
+                    if let Some(idle_warning) = idle_warning {
+                        send_to_client!(
+                            client_id,
+                            os_input,
+                            ServerToClientMsg::Render(idle_warning),
+                            session_state
+                        );
+                    }
+                }
+            },
+            ServerInstruction::AllPanesExited(all_panes_exited) => {
+                session_state
+                    .write()
+                    .unwrap()
+                    .set_all_panes_exited(all_panes_exited);
+            },
+            ServerInstruction::ToggleKeepSession => {
+                let keep_session = session_state.write().unwrap().toggle_keep_session();
+                log::info!("Keep session: {}", keep_session);
+            },
+            ServerInstruction::RunHooks(event, context) => {
+                hooks::run_hooks(event, context, &session_data, &session_state);
+            },
             ServerInstruction::DetachSession(client_ids) => {
                 for client_id in client_ids {
                     let _ = os_input
                         .send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
                     remove_client!(client_id, os_input, session_state);
-                    if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size()
-                    {
+                    if let Some(session_size) = session_state.write().unwrap().resize_session() {
                         session_data
                             .write()
                             .unwrap()
                             .as_ref()
                             .unwrap()
                             .senders
-                            .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                            .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                             .unwrap();
                     }
                     session_data
@@ -702,6 +1526,15 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                         .senders
                         .send_to_plugin(PluginInstruction::RemoveClient(client_id))
                         .unwrap();
+                    hooks::run_hooks(
+                        HookEvent::ClientDetached,
+                        HookContext {
+                            client_id: Some(client_id),
+                            ..Default::default()
+                        },
+                        &session_data,
+                        &session_state,
+                    );
                 }
             },
             ServerInstruction::Render(serialized_output) => {
@@ -709,14 +1542,52 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 // If `Some(_)`- unwrap it and forward it to the clients to render.
                 // If `None`- Send an exit instruction. This is the case when a user closes the last Tab/Pane.
                 if let Some(output) = &serialized_output {
+                    let session_is_locked = session_state.read().unwrap().is_locked();
                     for (client_id, client_render_instruction) in output.iter() {
+                        if session_is_locked {
+                            // nothing of the session is shown while it is locked, the lock screen
+                            // is only drawn again if it might have been drawn over
+                            let lock_screen =
+                                session_state.write().unwrap().take_lock_screen_redraw(
+                                    *client_id,
+                                    client_render_instruction.contains(CLEAR_DISPLAY),
+                                );
+                            if let Some(lock_screen) = lock_screen {
+                                send_to_client!(
+                                    *client_id,
+                                    os_input,
+                                    ServerToClientMsg::Render(lock_screen),
+                                    session_state
+                                );
+                            }
+                            continue;
+                        }
+                        let mut client_render_instruction = session_state
+                            .read()
+                            .unwrap()
+                            .clip_render_to_client(*client_id, client_render_instruction.clone());
+                        let out_of_bounds_filler =
+                            session_state.write().unwrap().take_out_of_bounds_filler(
+                                *client_id,
+                                client_render_instruction.contains(CLEAR_DISPLAY),
+                            );
+                        if let Some(out_of_bounds_filler) = out_of_bounds_filler {
+                            client_render_instruction.push_str(&out_of_bounds_filler);
+                        }
+                        let idle_warning = session_state
+                            .read()
+                            .unwrap()
+                            .render_idle_warning(*client_id);
+                        if let Some(idle_warning) = idle_warning {
+                            client_render_instruction.push_str(&idle_warning);
+                        }
                         // TODO: When a client is too slow or unresponsive, the channel fills up
                         // and this call will disconnect the client in turn. Should this be
                         // changed?
                         send_to_client!(
                             *client_id,
                             os_input,
-                            ServerToClientMsg::Render(client_render_instruction.clone()),
+                            ServerToClientMsg::Render(client_render_instruction),
                             session_state
                         );
                     }
@@ -779,14 +1650,14 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 if let Some(layout_dir) = layout_dir {
                     connect_to_session.apply_layout_dir(&layout_dir);
                 }
-                if let Some(min_size) = session_state.read().unwrap().min_client_terminal_size() {
+                if let Some(session_size) = session_state.write().unwrap().resize_session() {
                     session_data
                         .write()
                         .unwrap()
                         .as_ref()
                         .unwrap()
                         .senders
-                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                        .send_to_screen(ScreenInstruction::TerminalResize(session_size))
                         .unwrap();
                 }
                 session_data
@@ -812,6 +1683,15 @@ pub fn start_server(mut os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     session_state
                 );
                 remove_client!(client_id, os_input, session_state);
+                hooks::run_hooks(
+                    HookEvent::ClientDetached,
+                    HookContext {
+                        client_id: Some(client_id),
+                        ..Default::default()
+                    },
+                    &session_data,
+                    &session_state,
+                );
             },
             ServerInstruction::AssociatePipeWithClient { pipe_id, client_id } => {
                 session_state
@@ -840,6 +1720,8 @@ fn init_session(
     client_attributes: ClientAttributes,
     options: SessionOptions,
     plugin_aliases: Box<PluginAliases>,
+    scratchpads: Box<Scratchpads>,
+    hooks: Box<Hooks>,
 ) -> SessionMetaData {
     let SessionOptions {
         opts,
@@ -875,12 +1757,15 @@ fn init_session(
 
     // Determine and initialize the data directory
     let data_dir = opts.data_dir.unwrap_or_else(get_default_data_dir);
+    let session_data_dir = data_dir.clone();
 
     let capabilities = PluginCapabilities {
         arrow_fonts: config_options.simplified_ui.unwrap_or_default(),
     };
 
     let serialization_interval = config_options.serialization_interval;
+    let session_snapshot_count = config_options.session_snapshot_count;
+    let session_snapshot_max_age = config_options.session_snapshot_max_age;
 
     let default_shell = config_options.default_shell.clone().map(|command| {
         TerminalAction::RunCommand(RunCommand {
@@ -935,6 +1820,7 @@ fn init_session(
             let debug = opts.debug;
             let layout = layout.clone();
             let config_options = config_options.clone();
+            let hooks = hooks.clone();
             move || {
                 screen_thread_main(
                     screen_bus,
@@ -943,6 +1829,8 @@ fn init_session(
                     config_options,
                     debug,
                     layout,
+                    scratchpads,
+                    hooks,
                 )
                 .fatal();
             }
@@ -1017,7 +1905,15 @@ fn init_session(
                 None,
                 Some(os_input.clone()),
             );
-            move || background_jobs_main(background_jobs_bus, serialization_interval).fatal()
+            move || {
+                background_jobs_main(
+                    background_jobs_bus,
+                    serialization_interval,
+                    session_snapshot_count,
+                    session_snapshot_max_age,
+                )
+                .fatal()
+            }
         })
         .unwrap();
 
@@ -1036,6 +1932,8 @@ fn init_session(
         client_attributes,
         layout,
         config_options: config_options.clone(),
+        data_dir: session_data_dir,
+        hooks,
         screen_thread: Some(screen_thread),
         pty_thread: Some(pty_thread),
         plugin_thread: Some(plugin_thread),
@@ -1064,3 +1962,7 @@ fn get_store() -> Store {
     log::info!("Compiling plugins using Singlepass");
     Store::new(wasmer::Singlepass::default())
 }
+
+#[cfg(test)]
+#[path = "./unit/session_state_tests.rs"]
+mod session_state_tests;
@@ -270,7 +270,8 @@ impl Output {
         self.link_handler = Some(link_handler);
         self.floating_panes_stack = floating_panes_stack;
         for client_id in client_ids {
-            self.client_character_chunks.insert(*client_id, vec![]);
+            // sticky panes are rendered for the clients before their tab is
+            self.client_character_chunks.entry(*client_id).or_default();
         }
     }
     pub fn add_character_chunks_to_client(
@@ -358,6 +358,9 @@ pub struct Grid {
     pub search_results: SearchResult,
     pub pending_clipboard_update: Option<String>,
     ui_component_bytes: Option<Vec<u8>>,
+    // the lines printed since they were last taken (the last one possibly incomplete), only
+    // recorded while something is waiting for this output
+    received_lines: Option<Vec<String>>,
     style: Style,
     debug: bool,
     arrow_fonts: bool,
@@ -500,6 +503,7 @@ impl Grid {
             sixel_grid,
             pending_clipboard_update: None,
             ui_component_bytes: None,
+            received_lines: None,
             style,
             debug,
             arrow_fonts,
@@ -1686,6 +1690,27 @@ impl Grid {
     pub fn mark_for_rerender(&mut self) {
         self.should_render = true;
     }
+    pub fn record_received_lines(&mut self, should_record: bool) {
+        self.received_lines = if should_record {
+            self.received_lines
+                .take()
+                .or_else(|| Some(vec![String::new()]))
+        } else {
+            None
+        };
+    }
+    pub fn take_received_lines(&mut self) -> Vec<String> {
+        match self.received_lines.as_mut() {
+            Some(received_lines) => {
+                // the last line is kept so that it is checked again once it is complete
+                let last_line = received_lines.pop().unwrap_or_default();
+                let mut lines = std::mem::replace(received_lines, vec![last_line.clone()]);
+                lines.push(last_line);
+                lines
+            },
+            None => vec![],
+        }
+    }
     pub fn reset_terminal_state(&mut self) {
         self.lines_above = VecDeque::new();
         self.lines_below = vec![];
@@ -2152,6 +2177,9 @@ impl Perform for Grid {
     fn print(&mut self, c: char) {
         let c = self.cursor.charsets[self.active_charset].map(c);
 
+        if let Some(current_line) = self.received_lines.as_mut().and_then(|l| l.last_mut()) {
+            current_line.push(c);
+        }
         let terminal_character =
             TerminalCharacter::new_styled(c, self.cursor.pending_styles.clone());
         self.set_preceding_character(terminal_character.clone());
@@ -2175,6 +2203,9 @@ impl Perform for Grid {
                 // 0a, newline
                 // 0b, vertical tabulation
                 // 0c, form feed
+                if let Some(received_lines) = self.received_lines.as_mut() {
+                    received_lines.push(String::new());
+                }
                 self.add_newline();
             },
             13 => {
@@ -9,6 +9,7 @@ mod active_panes;
 mod floating_panes;
 mod plugin_pane;
 mod search;
+mod sticky_panes;
 mod terminal_pane;
 mod tiled_panes;
 
@@ -19,6 +20,7 @@ pub use grid::*;
 pub use link_handler::*;
 pub(crate) use plugin_pane::*;
 pub use sixel::*;
+pub use sticky_panes::*;
 pub(crate) use terminal_character::*;
 pub use terminal_pane::*;
 pub use tiled_panes::*;
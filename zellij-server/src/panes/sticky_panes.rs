@@ -0,0 +1,257 @@
+use crate::resize_pty;
+use crate::tab::{pane_info_for_pane, Pane};
+
+use crate::{
+    output::{FloatingPanesStack, Output},
+    panes::{LinkHandler, PaneId},
+    plugins::PluginInstruction,
+    thread_bus::ThreadSenders,
+    ui::pane_contents_and_ui::PaneContentsAndUi,
+    ClientId,
+};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use zellij_utils::{
+    data::{ModeInfo, PaneInfo, Style},
+    errors::prelude::*,
+    input::command::RunCommand,
+    pane_size::{PaneGeom, Size, SizeInPixels},
+    position::Position,
+};
+
+/// Floating panes that are shown on top of every tab, whichever tab each client is looking at.
+///
+/// They belong to the session rather than to a [`Tab`](crate::tab::Tab): the [`Screen`](crate::screen::Screen)
+/// renders them over the tabs of all clients and each tab crops its own panes underneath them.
+pub struct StickyPanes {
+    panes: BTreeMap<PaneId, Box<dyn Pane>>,
+    z_indices: Vec<PaneId>,
+    focused_panes: HashMap<ClientId, PaneId>,
+    style: Style,
+    session_is_mirrored: bool,
+    layout_changed: bool,
+    link_handler: Rc<RefCell<LinkHandler>>,
+}
+
+#[allow(clippy::borrowed_box)]
+impl StickyPanes {
+    pub fn new(style: Style, session_is_mirrored: bool) -> Self {
+        StickyPanes {
+            panes: BTreeMap::new(),
+            z_indices: vec![],
+            focused_panes: HashMap::new(),
+            style,
+            session_is_mirrored,
+            layout_changed: false,
+            link_handler: Rc::new(RefCell::new(LinkHandler::new())),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+    pub fn contains(&self, pane_id: &PaneId) -> bool {
+        self.panes.contains_key(pane_id)
+    }
+    pub fn pane_ids(&self) -> impl Iterator<Item = &PaneId> {
+        self.panes.keys()
+    }
+    pub fn panes(&self) -> impl Iterator<Item = (&PaneId, &Box<dyn Pane>)> {
+        self.panes.iter()
+    }
+    pub fn get_mut(&mut self, pane_id: &PaneId) -> Option<&mut Box<dyn Pane>> {
+        self.panes.get_mut(pane_id)
+    }
+    pub fn add_pane(
+        &mut self,
+        pane_id: PaneId,
+        mut pane: Box<dyn Pane>,
+        focus_for_client: Option<ClientId>,
+    ) {
+        pane.set_should_render(true);
+        self.panes.insert(pane_id, pane);
+        self.z_indices.retain(|id| id != &pane_id);
+        self.z_indices.push(pane_id);
+        if let Some(client_id) = focus_for_client {
+            self.focused_panes.insert(client_id, pane_id);
+        }
+        self.layout_changed = true;
+    }
+    pub fn remove_pane(&mut self, pane_id: PaneId) -> Option<Box<dyn Pane>> {
+        let pane = self.panes.remove(&pane_id)?;
+        self.z_indices.retain(|id| id != &pane_id);
+        self.focused_panes.retain(|_, id| id != &pane_id);
+        self.layout_changed = true;
+        Some(pane)
+    }
+    pub fn focused_pane_id(&self, client_id: ClientId) -> Option<PaneId> {
+        self.focused_panes.get(&client_id).copied()
+    }
+    pub fn focus_pane(&mut self, pane_id: PaneId, client_id: ClientId) {
+        if self.panes.contains_key(&pane_id) {
+            self.focused_panes.insert(client_id, pane_id);
+            self.z_indices.retain(|id| id != &pane_id);
+            self.z_indices.push(pane_id);
+            self.layout_changed = true;
+        }
+    }
+    /// Returns true if the client had one of the sticky panes focused
+    pub fn unfocus(&mut self, client_id: ClientId) -> bool {
+        let was_focused = self.focused_panes.remove(&client_id).is_some();
+        if was_focused {
+            // the frame colors change
+            self.set_force_render();
+        }
+        was_focused
+    }
+    pub fn top_pane_id(&self) -> Option<PaneId> {
+        self.z_indices.last().copied()
+    }
+    pub fn pane_id_at(&self, position: &Position) -> Option<PaneId> {
+        self.z_indices
+            .iter()
+            .rev()
+            .find(|pane_id| {
+                self.panes
+                    .get(pane_id)
+                    .map(|pane| pane.contains(position))
+                    .unwrap_or(false)
+            })
+            .copied()
+    }
+    pub fn stack_layers(&self) -> Vec<PaneGeom> {
+        self.z_indices
+            .iter()
+            .filter_map(|pane_id| self.panes.get(pane_id))
+            .map(|pane| pane.position_and_size())
+            .collect()
+    }
+    pub fn cursor_position(&self, client_id: ClientId) -> Option<(usize, usize)> {
+        // (x, y)
+        let pane = self.focused_pane(client_id)?;
+        pane.cursor_coordinates()
+            .map(|(x_in_terminal, y_in_terminal)| {
+                (pane.x() + x_in_terminal, pane.y() + y_in_terminal)
+            })
+    }
+    pub fn cursor_shape_csi(&self, client_id: ClientId) -> Option<String> {
+        self.focused_pane(client_id)
+            .map(|pane| pane.cursor_shape_csi())
+    }
+    pub fn hold_pane(
+        &mut self,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        is_first_run: bool,
+        run_command: RunCommand,
+    ) {
+        if let Some(pane) = self.panes.get_mut(&pane_id) {
+            pane.hold(exit_status, is_first_run, run_command);
+        }
+    }
+    pub fn pane_info(&self) -> Vec<PaneInfo> {
+        self.panes
+            .iter()
+            .map(|(pane_id, pane)| {
+                let mut pane_info = pane_info_for_pane(pane_id, pane);
+                pane_info.is_floating = true;
+                pane_info.is_focused = self.focused_panes.values().any(|id| id == pane_id);
+                pane_info
+            })
+            .collect()
+    }
+    /// Keeps the panes inside the screen, shrinking and moving them as needed
+    pub fn resize(
+        &mut self,
+        new_screen_size: Size,
+        senders: &ThreadSenders,
+        character_cell_size: &Rc<RefCell<Option<SizeInPixels>>>,
+    ) -> Result<()> {
+        for pane in self.panes.values_mut() {
+            let mut geom = pane.position_and_size();
+            let cols = geom.cols.as_usize().min(new_screen_size.cols).max(1);
+            let rows = geom.rows.as_usize().min(new_screen_size.rows).max(1);
+            geom.cols.set_inner(cols);
+            geom.rows.set_inner(rows);
+            geom.x = geom.x.min(new_screen_size.cols.saturating_sub(cols));
+            geom.y = geom.y.min(new_screen_size.rows.saturating_sub(rows));
+            if geom != pane.position_and_size() {
+                pane.set_geom(geom);
+                resize_pty!(pane, os_api, senders, character_cell_size)
+                    .with_context(|| format!("failed to resize sticky pane {:?}", pane.pid()))?;
+            }
+        }
+        self.layout_changed = true;
+        Ok(())
+    }
+    pub fn set_force_render(&mut self) {
+        for pane in self.panes.values_mut() {
+            pane.set_should_render(true);
+        }
+    }
+    /// Returns true (once) if panes were added, removed, moved or raised since the last call,
+    /// meaning whatever is underneath them needs to be rendered again
+    pub fn take_layout_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.layout_changed, false)
+    }
+    pub fn render(
+        &mut self,
+        output: &mut Output,
+        connected_clients: &HashSet<ClientId>,
+        mode_info: &BTreeMap<ClientId, ModeInfo>,
+        default_mode_info: &ModeInfo,
+    ) -> Result<()> {
+        let err_context = || "failed to render sticky panes";
+        output.add_clients(
+            connected_clients,
+            self.link_handler.clone(),
+            Some(FloatingPanesStack {
+                layers: self.stack_layers(),
+            }),
+        );
+        let multiple_users_exist_in_session = connected_clients.len() > 1;
+        let mut active_panes = self.focused_panes.clone();
+        active_panes.retain(|c_id, _| connected_clients.contains(c_id));
+        for (z_index, pane_id) in self.z_indices.iter().enumerate() {
+            let Some(pane) = self.panes.get_mut(pane_id) else {
+                continue;
+            };
+            let mut pane_contents_and_ui = PaneContentsAndUi::new(
+                pane,
+                output,
+                self.style,
+                &active_panes,
+                multiple_users_exist_in_session,
+                Some(z_index + 1), // +1 because 0 is reserved for non-floating panes
+                false,
+                false,
+                true,
+            );
+            for client_id in connected_clients {
+                let client_mode = mode_info.get(client_id).unwrap_or(default_mode_info).mode;
+                pane_contents_and_ui
+                    .render_pane_frame(*client_id, client_mode, self.session_is_mirrored)
+                    .with_context(err_context)?;
+                if let PaneId::Plugin(..) = pane_id {
+                    pane_contents_and_ui
+                        .render_pane_contents_for_client(*client_id)
+                        .with_context(err_context)?;
+                }
+                pane_contents_and_ui
+                    .render_fake_cursor_if_needed(*client_id)
+                    .with_context(err_context)?;
+            }
+            if let PaneId::Terminal(..) = pane_id {
+                pane_contents_and_ui
+                    .render_pane_contents_to_multiple_clients(connected_clients.iter().copied())
+                    .with_context(err_context)?;
+            }
+        }
+        Ok(())
+    }
+    fn focused_pane(&self, client_id: ClientId) -> Option<&Box<dyn Pane>> {
+        self.focused_panes
+            .get(&client_id)
+            .and_then(|pane_id| self.panes.get(pane_id))
+    }
+}
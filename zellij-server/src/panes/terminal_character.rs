@@ -979,7 +979,16 @@ pub fn render_first_run_banner(
             let command_color_text = RESET_STYLES
                 .foreground(Some(AnsiCode::from(style.colors.green)))
                 .bold(Some(AnsiCode::On));
-            let waiting_to_run_text = "Waiting to run: ";
+            let waiting_to_run_text = if run_command.depends_on.is_empty() {
+                "Waiting to run: ".to_owned()
+            } else {
+                let dependency_names: Vec<&str> = run_command
+                    .depends_on
+                    .iter()
+                    .map(|dependency| dependency.pane_name.as_str())
+                    .collect();
+                format!("Waiting for {} to run: ", dependency_names.join(", "))
+            };
             let command_text = run_command.to_string();
             let waiting_to_run_text_width = waiting_to_run_text.width() + command_text.width();
             let column_start_postion = middle_column.saturating_sub(waiting_to_run_text_width / 2);
@@ -15,6 +15,7 @@ use std::rc::Rc;
 use std::time::{self, Instant};
 use zellij_utils::input::command::RunCommand;
 use zellij_utils::pane_size::Offset;
+use zellij_utils::regex::Regex;
 use zellij_utils::{
     data::{InputMode, Palette, PaletteColor, PaneId as ZellijUtilsPaneId, Style},
     errors::prelude::*,
@@ -114,6 +115,8 @@ pub struct TerminalPane {
     pane_title: String,
     pane_name: String,
     prev_pane_name: String,
+    initial_pane_name: String, // eg. from the layout, what the depends_on of other panes refer to
+    foreground_command: Option<Vec<String>>, // as of the last time it was queried
     frame: HashMap<ClientId, PaneFrame>,
     borderless: bool,
     exclude_from_sync: bool,
@@ -121,6 +124,8 @@ pub struct TerminalPane {
     search_term: String,
     is_held: Option<(Option<i32>, IsFirstRun, RunCommand)>, // a "held" pane means that its command has either exited and the pane is waiting for a
     // possible user instruction to be re-run, or that the command has not yet been run
+    restarts: Option<(usize, Option<i32>)>, // how many times the command was restarted by its restart
+    // policy and the exit status that last triggered a restart
     banner: Option<String>, // a banner to be rendered inside this TerminalPane, used for panes
     // held on startup and can possibly be used to display some errors
     pane_frame_color_override: Option<(PaletteColor, Option<String>)>,
@@ -197,18 +202,14 @@ impl Pane for TerminalPane {
         // we send back the original input
         if let Some((_exit_status, _is_first_run, run_command)) = &self.is_held {
             match input_bytes.as_slice() {
-                ENTER_CARRIAGE_RETURN | ENTER_NEWLINE | SPACE => {
-                    let run_command = run_command.clone();
-                    self.is_held = None;
-                    self.grid.reset_terminal_state();
-                    self.set_should_render(true);
-                    self.remove_banner();
-                    Some(AdjustedInput::ReRunCommandInThisPane(run_command))
-                },
+                ENTER_CARRIAGE_RETURN | ENTER_NEWLINE | SPACE => self
+                    .run_held_command()
+                    .map(AdjustedInput::ReRunCommandInThisPane),
                 ESC => {
                     // Drop to shell in the same working directory as the command was run
                     let working_dir = run_command.cwd.clone();
                     self.is_held = None;
+                    self.restarts = None;
                     self.grid.reset_terminal_state();
                     self.set_should_render(true);
                     self.remove_banner();
@@ -395,6 +396,9 @@ impl Pane for TerminalPane {
                 frame.add_exit_status(exit_status.as_ref().copied());
             }
         }
+        if let Some((restart_count, last_exit_status)) = self.restarts {
+            frame.add_restarts(restart_count, last_exit_status);
+        }
         if let Some((frame_color_override, _text)) = self.pane_frame_color_override.as_ref() {
             frame.override_color(*frame_color_override);
         }
@@ -720,6 +724,10 @@ impl Pane for TerminalPane {
     fn is_alternate_mode_active(&self) -> bool {
         self.grid.is_alternate_mode_active()
     }
+    fn indicate_restart(&mut self, restart_count: usize, exit_status: Option<i32>) {
+        self.restarts = Some((restart_count, exit_status));
+        self.set_should_render(true);
+    }
     fn hold(&mut self, exit_status: Option<i32>, is_first_run: bool, run_command: RunCommand) {
         self.invoked_with = Some(Run::Command(run_command.clone()));
         self.is_held = Some((exit_status, is_first_run, run_command));
@@ -771,6 +779,38 @@ impl Pane for TerminalPane {
     fn is_held(&self) -> bool {
         self.is_held.is_some()
     }
+    fn command_waiting_for_first_run(&self) -> Option<&RunCommand> {
+        match &self.is_held {
+            Some((_, true, run_command)) => Some(run_command),
+            _ => None,
+        }
+    }
+    fn run_held_command(&mut self) -> Option<RunCommand> {
+        let (_exit_status, _is_first_run, run_command) = self.is_held.take()?;
+        self.restarts = None;
+        self.grid.reset_terminal_state();
+        self.set_should_render(true);
+        self.remove_banner();
+        Some(run_command)
+    }
+    fn output_matches(&mut self, pattern: &Regex) -> bool {
+        pattern.is_match(&self.grid.dump_screen(true))
+    }
+    fn watch_output(&mut self, should_watch: bool) {
+        self.grid.record_received_lines(should_watch);
+    }
+    fn take_received_lines(&mut self) -> Vec<String> {
+        self.grid.take_received_lines()
+    }
+    fn initial_pane_name(&self) -> Option<&str> {
+        Some(self.initial_pane_name.as_str()).filter(|name| !name.is_empty())
+    }
+    fn set_foreground_command(&mut self, command_line: Option<Vec<String>>) {
+        self.foreground_command = command_line;
+    }
+    fn foreground_command(&self) -> Option<&[String]> {
+        self.foreground_command.as_deref()
+    }
     fn exited(&self) -> bool {
         match self.is_held {
             Some((_, is_first_run, _)) => !is_first_run,
@@ -834,12 +874,15 @@ impl TerminalPane {
             selection_scrolled_at: time::Instant::now(),
             pane_title: initial_pane_title,
             pane_name: pane_name.clone(),
-            prev_pane_name: pane_name,
+            prev_pane_name: pane_name.clone(),
+            initial_pane_name: pane_name,
+            foreground_command: None,
             borderless: false,
             exclude_from_sync: false,
             fake_cursor_locations: HashSet::new(),
             search_term: String::new(),
             is_held: None,
+            restarts: None,
             banner: None,
             pane_frame_color_override: None,
             invoked_with,
@@ -1481,6 +1481,60 @@ impl TiledPanes {
             }
         }
     }
+    pub fn stack_active_pane(&mut self, client_id: ClientId, direction: Direction) -> Result<bool> {
+        // returns whether the panes were stacked
+        let err_context = || format!("failed to stack active pane for client {client_id}");
+        let active_pane_id = self
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let pane_grid = TiledPaneGrid::new(
+            &mut self.panes,
+            &self.panes_to_hide,
+            *self.display_area.borrow(),
+            *self.viewport.borrow(),
+        );
+        let Some(neighbour_pane_id) = pane_grid.pane_id_to_stack_with(&active_pane_id, direction)
+        else {
+            // nothing to stack with in this direction
+            return Ok(false);
+        };
+        let stacked = StackedPanes::new_from_btreemap(&mut self.panes, &self.panes_to_hide)
+            .combine_into_stack(&active_pane_id, &neighbour_pane_id, direction)
+            .with_context(err_context)?;
+        self.after_stack_change(stacked);
+        Ok(stacked)
+    }
+    pub fn unstack_active_pane(&mut self, client_id: ClientId) -> Result<bool> {
+        // returns whether the panes were unstacked
+        let err_context = || format!("failed to unstack active pane for client {client_id}");
+        let active_pane_id = self
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let unstacked = StackedPanes::new_from_btreemap(&mut self.panes, &self.panes_to_hide)
+            .break_up_stack(&active_pane_id)
+            .with_context(err_context)?;
+        self.after_stack_change(unstacked);
+        Ok(unstacked)
+    }
+    pub fn remove_active_pane_from_stack(&mut self, client_id: ClientId) -> Result<bool> {
+        // returns whether the pane was removed from its stack
+        let err_context =
+            || format!("failed to remove active pane from its stack for client {client_id}");
+        let active_pane_id = self
+            .get_active_pane_id(client_id)
+            .with_context(err_context)?;
+        let removed = StackedPanes::new_from_btreemap(&mut self.panes, &self.panes_to_hide)
+            .remove_from_stack(&active_pane_id)
+            .with_context(err_context)?;
+        self.after_stack_change(removed);
+        Ok(removed)
+    }
+    fn after_stack_change(&mut self, changed: bool) {
+        if changed {
+            self.reapply_pane_frames();
+            self.set_force_render();
+        }
+    }
     pub fn move_clients_out_of_pane(&mut self, pane_id: PaneId) {
         let active_panes: Vec<(ClientId, PaneId)> = self
             .active_panes
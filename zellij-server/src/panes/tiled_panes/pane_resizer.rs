@@ -3,7 +3,7 @@ use crate::{panes::PaneId, tab::Pane};
 use cassowary::{
     strength::{REQUIRED, STRONG},
     Expression, Solver, Variable,
-    WeightedRelation::EQ,
+    WeightedRelation::{EQ, GE, LE},
 };
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -33,6 +33,10 @@ struct Span {
 
 type Grid = Vec<Vec<Span>>;
 
+// min_size/max_size should win over keeping pane ratios, but not over using all of the available
+// space, so that a terminal too small for all of them still gets filled
+const SIZE_BOUND_STRENGTH: f64 = STRONG * 10.0;
+
 impl<'a> PaneResizer<'a> {
     pub fn new(panes: Rc<RefCell<HashMap<PaneId, &'a mut Box<dyn Pane>>>>) -> Self {
         let mut vars = HashMap::new();
@@ -102,6 +106,11 @@ impl<'a> PaneResizer<'a> {
             if error < 0 {
                 flex_spans.reverse();
             }
+            // prefer not to round spans out of their min_size/max_size
+            flex_spans.sort_by_key(|s| {
+                let rounded_size = rounded_sizes[&s.size_var] + error.signum();
+                rounded_size < 0 || !s.size.is_within_size_bounds(rounded_size as usize)
+            });
             for span in flex_spans {
                 rounded_sizes
                     .entry(span.size_var)
@@ -305,6 +314,14 @@ fn constrain_spans(space: usize, spans: &[Span]) -> HashSet<cassowary::Constrain
             Constraint::Percent(p) => constraints
                 .insert((span.size_var / new_flex_space as f64) | EQ(STRONG) | (p / 100.0)),
         };
+        if span.size.is_percent() {
+            if let Some(min_size) = span.size.min_size() {
+                constraints.insert(span.size_var | GE(SIZE_BOUND_STRENGTH) | min_size as f64);
+            }
+            if let Some(max_size) = span.size.max_size() {
+                constraints.insert(span.size_var | LE(SIZE_BOUND_STRENGTH) | max_size as f64);
+            }
+        }
     }
 
     constraints
@@ -1,10 +1,12 @@
+use crate::tab::MIN_TERMINAL_HEIGHT;
 use crate::{panes::PaneId, tab::Pane};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use zellij_utils::{
+    data::Direction,
     errors::prelude::*,
-    pane_size::{Dimension, PaneGeom},
+    pane_size::{Constraint, Dimension, PaneGeom},
 };
 
 pub struct StackedPanes<'a> {
@@ -327,6 +329,229 @@ impl<'a> StackedPanes<'a> {
         }
         Ok(())
     }
+    pub fn combine_into_stack(
+        &mut self,
+        pane_id: &PaneId,
+        neighbour_pane_id: &PaneId,
+        direction: Direction,
+    ) -> Result<bool> {
+        // stacks the pane (or the stack it is in) together with its neighbour in this direction
+        // (or the stack the neighbour is in), the pane will be the expanded one in the new stack
+        // returns false if the panes can't be stacked (eg. they are not aligned)
+        let err_context =
+            || format!("Failed to stack pane {pane_id:?} with pane {neighbour_pane_id:?}");
+        let (pane_ids, pane_rect) = self.pane_ids_and_rect(pane_id).with_context(err_context)?;
+        let (neighbour_pane_ids, neighbour_rect) = self
+            .pane_ids_and_rect(neighbour_pane_id)
+            .with_context(err_context)?;
+        let (first_pane_ids, first_rect, second_pane_ids, second_rect) = match direction {
+            Direction::Up | Direction::Left => {
+                (neighbour_pane_ids, neighbour_rect, pane_ids, pane_rect)
+            },
+            Direction::Down | Direction::Right => {
+                (pane_ids, pane_rect, neighbour_pane_ids, neighbour_rect)
+            },
+        };
+        let stack_geom = match direction {
+            Direction::Up | Direction::Down => {
+                let is_aligned = first_rect.x == second_rect.x
+                    && first_rect.cols.as_usize() == second_rect.cols.as_usize()
+                    && first_rect.y + first_rect.rows.as_usize() == second_rect.y;
+                if !is_aligned {
+                    return Ok(false);
+                }
+                let Some(rows) = combine_dimensions(first_rect.rows, second_rect.rows) else {
+                    return Ok(false);
+                };
+                PaneGeom {
+                    x: first_rect.x,
+                    y: first_rect.y,
+                    cols: pane_rect.cols,
+                    rows,
+                    is_stacked: true,
+                }
+            },
+            Direction::Left | Direction::Right => {
+                let is_aligned = first_rect.y == second_rect.y
+                    && first_rect.rows.as_usize() == second_rect.rows.as_usize()
+                    && first_rect.x + first_rect.cols.as_usize() == second_rect.x;
+                if !is_aligned {
+                    return Ok(false);
+                }
+                let Some(cols) = combine_dimensions(first_rect.cols, second_rect.cols) else {
+                    return Ok(false);
+                };
+                PaneGeom {
+                    x: first_rect.x,
+                    y: first_rect.y,
+                    cols,
+                    rows: pane_rect.rows,
+                    is_stacked: true,
+                }
+            },
+        };
+        let pane_ids_in_stack: Vec<PaneId> = first_pane_ids
+            .into_iter()
+            .chain(second_pane_ids.into_iter())
+            .collect();
+        self.lay_out_stack(&pane_ids_in_stack, pane_id, stack_geom)
+            .with_context(err_context)
+    }
+    pub fn break_up_stack(&mut self, pane_id_in_stack: &PaneId) -> Result<bool> {
+        // lays out all the panes in the stack as horizontal splits taking up the space of the stack
+        // returns false if the pane is not stacked or there is not enough room to unstack it
+        let err_context = || format!("Failed to unstack panes");
+        let (pane_ids, stack_geom) = self
+            .pane_ids_and_rect(pane_id_in_stack)
+            .with_context(err_context)?;
+        let stack_rows = stack_geom.rows.as_usize();
+        if !stack_geom.is_stacked || stack_rows < pane_ids.len() * MIN_TERMINAL_HEIGHT {
+            return Ok(false);
+        }
+        let mut panes = self.panes.borrow_mut();
+        let mut y = stack_geom.y;
+        for (i, pane_id) in pane_ids.iter().enumerate() {
+            let pane_rows = stack_rows / pane_ids.len()
+                + if i < stack_rows % pane_ids.len() {
+                    1
+                } else {
+                    0
+                };
+            let geom = PaneGeom {
+                y,
+                rows: portion_of_dimension(stack_geom.rows, pane_rows),
+                is_stacked: false,
+                ..stack_geom
+            };
+            y += pane_rows;
+            panes
+                .get_mut(pane_id)
+                .with_context(err_context)?
+                .set_geom(geom);
+        }
+        Ok(true)
+    }
+    pub fn remove_from_stack(&mut self, pane_id: &PaneId) -> Result<bool> {
+        // takes the pane out of its stack, placing it below the remaining panes of the stack
+        // returns false if the pane is not stacked or there is not enough room to take it out
+        let err_context = || format!("Failed to remove pane {pane_id:?} from its stack");
+        let (pane_ids_in_stack, stack_geom) =
+            self.pane_ids_and_rect(pane_id).with_context(err_context)?;
+        if !stack_geom.is_stacked {
+            return Ok(false);
+        }
+        let flexible_pane_id = self.flexible_pane_id_in_stack(pane_id);
+        let remaining_pane_ids: Vec<PaneId> = pane_ids_in_stack
+            .into_iter()
+            .filter(|p_id| p_id != pane_id)
+            .collect();
+        let stack_rows = stack_geom.rows.as_usize();
+        let removed_pane_rows = stack_rows / 2;
+        let remaining_stack_rows = stack_rows - removed_pane_rows;
+        let remaining_stack_has_room = if remaining_pane_ids.len() > 1 {
+            remaining_stack_rows > remaining_pane_ids.len()
+        } else {
+            remaining_stack_rows >= MIN_TERMINAL_HEIGHT
+        };
+        if remaining_pane_ids.is_empty()
+            || removed_pane_rows < MIN_TERMINAL_HEIGHT
+            || !remaining_stack_has_room
+        {
+            return Ok(false);
+        }
+        let remaining_stack_geom = PaneGeom {
+            rows: portion_of_dimension(stack_geom.rows, remaining_stack_rows),
+            is_stacked: remaining_pane_ids.len() > 1,
+            ..stack_geom
+        };
+        let removed_pane_geom = PaneGeom {
+            y: stack_geom.y + remaining_stack_rows,
+            rows: portion_of_dimension(stack_geom.rows, removed_pane_rows),
+            is_stacked: false,
+            ..stack_geom
+        };
+        if remaining_pane_ids.len() > 1 {
+            let expanded_pane_id = flexible_pane_id
+                .filter(|p_id| p_id != pane_id)
+                .unwrap_or(remaining_pane_ids[0]);
+            let laid_out = self
+                .lay_out_stack(&remaining_pane_ids, &expanded_pane_id, remaining_stack_geom)
+                .with_context(err_context)?;
+            if !laid_out {
+                return Ok(false);
+            }
+        } else {
+            self.panes
+                .borrow_mut()
+                .get_mut(&remaining_pane_ids[0])
+                .with_context(err_context)?
+                .set_geom(remaining_stack_geom);
+        }
+        self.panes
+            .borrow_mut()
+            .get_mut(pane_id)
+            .with_context(err_context)?
+            .set_geom(removed_pane_geom);
+        Ok(true)
+    }
+    fn pane_ids_and_rect(&self, pane_id: &PaneId) -> Result<(Vec<PaneId>, PaneGeom)> {
+        // the ids of the stack this pane is in (or just the pane if it is not stacked) and the
+        // space they take up
+        let err_context = || format!("Failed to find pane {pane_id:?}");
+        let pane_geom = self
+            .panes
+            .borrow()
+            .get(pane_id)
+            .with_context(err_context)?
+            .position_and_size();
+        if pane_geom.is_stacked {
+            let pane_ids = self
+                .positions_in_stack(pane_id)?
+                .iter()
+                .map(|(p_id, _)| *p_id)
+                .collect();
+            let stack_geom = self
+                .position_and_size_of_stack(pane_id)
+                .with_context(err_context)?;
+            Ok((pane_ids, stack_geom))
+        } else {
+            Ok((vec![*pane_id], pane_geom))
+        }
+    }
+    fn lay_out_stack(
+        &mut self,
+        pane_ids: &[PaneId],
+        expanded_pane_id: &PaneId,
+        stack_geom: PaneGeom,
+    ) -> Result<bool> {
+        // returns false if the panes can't be stacked in this space
+        let err_context = || format!("Failed to lay out stacked panes");
+        let stack_rows = stack_geom.rows.as_usize();
+        if stack_geom.rows.is_fixed() || stack_rows <= pane_ids.len() {
+            return Ok(false);
+        }
+        let mut panes = self.panes.borrow_mut();
+        let mut y = stack_geom.y;
+        for pane_id in pane_ids {
+            let mut rows = Dimension::fixed(1);
+            if pane_id == expanded_pane_id {
+                rows = stack_geom.rows;
+                rows.set_inner(stack_rows - (pane_ids.len() - 1));
+            }
+            let geom = PaneGeom {
+                y,
+                rows,
+                is_stacked: true,
+                ..stack_geom
+            };
+            y += rows.as_usize();
+            panes
+                .get_mut(pane_id)
+                .with_context(err_context)?
+                .set_geom(geom);
+        }
+        Ok(true)
+    }
     fn pane_is_one_liner(&self, id: &PaneId) -> Result<bool> {
         let err_context = || format!("Cannot determin if pane is one liner or not");
         let panes = self.panes.borrow();
@@ -682,3 +907,28 @@ impl<'a> StackedPanes<'a> {
         Ok(())
     }
 }
+
+fn combine_dimensions(first: Dimension, second: Dimension) -> Option<Dimension> {
+    // fixed and flexible panes can't be stacked together
+    let inner = first.as_usize() + second.as_usize();
+    let mut combined = match (first.constraint, second.constraint) {
+        (Constraint::Percent(first), Constraint::Percent(second)) => {
+            Dimension::percent(first + second)
+        },
+        (Constraint::Fixed(_), Constraint::Fixed(_)) => Dimension::fixed(inner),
+        _ => return None,
+    };
+    combined.set_inner(inner);
+    Some(combined)
+}
+
+fn portion_of_dimension(dimension: Dimension, portion: usize) -> Dimension {
+    let mut portion_of_dimension = match dimension.constraint {
+        Constraint::Percent(percent) => {
+            Dimension::percent(percent * portion as f64 / dimension.as_usize() as f64)
+        },
+        Constraint::Fixed(_) => Dimension::fixed(portion),
+    };
+    portion_of_dimension.set_inner(portion);
+    portion_of_dimension
+}
@@ -180,6 +180,12 @@ impl<'a> TiledPaneGrid<'a> {
             if direction.is_horizontal() {
                 match strategy.resize {
                     Resize::Increase => {
+                        if !self
+                            .can_increase_pane_width(pane_id, change_by.0 as f64)
+                            .with_context(err_context)?
+                        {
+                            return Ok(false);
+                        }
                         for id in pane_ids {
                             if !self
                                 .can_reduce_pane_width(&id, change_by.0 as f64)
@@ -197,6 +203,12 @@ impl<'a> TiledPaneGrid<'a> {
             } else {
                 match strategy.resize {
                     Resize::Increase => {
+                        if !self
+                            .can_increase_pane_height(pane_id, change_by.1 as f64)
+                            .with_context(err_context)?
+                        {
+                            return Ok(false);
+                        }
                         for id in pane_ids {
                             if !self
                                 .can_reduce_pane_height(&id, change_by.1 as f64)
@@ -523,7 +535,8 @@ impl<'a> TiledPaneGrid<'a> {
             .with_context(err_context)?;
         let current_fixed_cols = pane.cols.as_usize();
         let will_reduce_by = ((self.display_area.cols as f64 / 100.0) * reduce_by) as usize;
-        if current_fixed_cols.saturating_sub(will_reduce_by) < MIN_TERMINAL_WIDTH {
+        let min_pane_width = pane.cols.min_size().unwrap_or(0).max(MIN_TERMINAL_WIDTH);
+        if current_fixed_cols.saturating_sub(will_reduce_by) < min_pane_width {
             Ok(false)
         } else if let Some(cols) = pane.cols.as_percent() {
             Ok(cols - reduce_by >= RESIZE_PERCENT)
@@ -547,7 +560,8 @@ impl<'a> TiledPaneGrid<'a> {
         };
         let current_fixed_rows = pane.rows.as_usize();
         let will_reduce_by = ((self.display_area.rows as f64 / 100.0) * reduce_by) as usize;
-        if current_fixed_rows.saturating_sub(will_reduce_by) < min_terminal_height {
+        let min_pane_height = pane.rows.min_size().unwrap_or(0).max(min_terminal_height);
+        if current_fixed_rows.saturating_sub(will_reduce_by) < min_pane_height {
             Ok(false)
         } else if let Some(rows) = pane.rows.as_percent() {
             Ok(rows - reduce_by >= RESIZE_PERCENT)
@@ -556,6 +570,41 @@ impl<'a> TiledPaneGrid<'a> {
         }
     }
 
+    fn can_increase_pane_width(&self, pane_id: &PaneId, increase_by: f64) -> Result<bool> {
+        let err_context = || {
+            format!("failed to determine if pane {pane_id:?} can increase width by {increase_by} %")
+        };
+
+        let pane = self
+            .get_pane_geom(pane_id)
+            .with_context(|| no_pane_id(pane_id))
+            .with_context(err_context)?;
+        let will_increase_by = ((self.display_area.cols as f64 / 100.0) * increase_by) as usize;
+        Ok(pane
+            .cols
+            .max_size()
+            .map(|max_size| pane.cols.as_usize() + will_increase_by <= max_size)
+            .unwrap_or(true))
+    }
+    fn can_increase_pane_height(&self, pane_id: &PaneId, increase_by: f64) -> Result<bool> {
+        let err_context = || {
+            format!(
+                "failed to determine if pane {pane_id:?} can increase height by {increase_by} %"
+            )
+        };
+
+        let pane = self
+            .get_pane_geom(pane_id)
+            .with_context(|| no_pane_id(pane_id))
+            .with_context(err_context)?;
+        let will_increase_by = ((self.display_area.rows as f64 / 100.0) * increase_by) as usize;
+        Ok(pane
+            .rows
+            .max_size()
+            .map(|max_size| pane.rows.as_usize() + will_increase_by <= max_size)
+            .unwrap_or(true))
+    }
+
     fn reduce_pane_height(&mut self, id: &PaneId, percent: f64) {
         if self.can_reduce_pane_height(id, percent).unwrap() {
             let current_pane_is_stacked = self
@@ -938,6 +987,51 @@ impl<'a> TiledPaneGrid<'a> {
         }
         next_pane.map(|p| p.pid())
     }
+    pub fn pane_id_to_stack_with(
+        &self,
+        current_pane_id: &PaneId,
+        direction: Direction,
+    ) -> Option<PaneId> {
+        // the pane directly next to this pane (or to the stack it is in) in this direction
+        let current_geom = self
+            .panes
+            .borrow()
+            .get(current_pane_id)?
+            .position_and_size();
+        let current_rect = if current_geom.is_stacked {
+            StackedPanes::new(self.panes.clone()).position_and_size_of_stack(current_pane_id)?
+        } else {
+            current_geom
+        };
+        let panes = self.panes.borrow();
+        let next_pane_id = panes
+            .iter()
+            .filter(|(_, p)| p.selectable())
+            .filter(|(_, p)| {
+                let geom = p.position_and_size();
+                let rows_overlap = geom.y < current_rect.y + current_rect.rows.as_usize()
+                    && current_rect.y < geom.y + geom.rows.as_usize();
+                let cols_overlap = geom.x < current_rect.x + current_rect.cols.as_usize()
+                    && current_rect.x < geom.x + geom.cols.as_usize();
+                match direction {
+                    Direction::Up => {
+                        geom.y + geom.rows.as_usize() == current_rect.y && cols_overlap
+                    },
+                    Direction::Down => {
+                        current_rect.y + current_rect.rows.as_usize() == geom.y && cols_overlap
+                    },
+                    Direction::Left => {
+                        geom.x + geom.cols.as_usize() == current_rect.x && rows_overlap
+                    },
+                    Direction::Right => {
+                        current_rect.x + current_rect.cols.as_usize() == geom.x && rows_overlap
+                    },
+                }
+            })
+            .max_by_key(|(_, p)| p.active_at())
+            .map(|(p_id, _)| *p_id);
+        next_pane_id
+    }
     pub fn progress_stack_up_if_in_stack(&mut self, source_pane_id: &PaneId) -> Option<PaneId> {
         let destination_pane_id_in_stack = {
             let panes = self.panes.borrow();
@@ -17,7 +17,7 @@ use wasmer::Store;
 
 use crate::panes::PaneId;
 use crate::screen::ScreenInstruction;
-use crate::session_layout_metadata::SessionLayoutMetadata;
+use crate::session_layout_metadata::{SaveLayoutRequest, SessionLayoutMetadata};
 use crate::{pty::PtyInstruction, thread_bus::Bus, ClientId, ServerInstruction};
 
 pub use wasm_bridge::PluginRenderAsset;
@@ -104,6 +104,7 @@ pub enum PluginInstruction {
         Option<PathBuf>,
     ),
     DumpLayout(SessionLayoutMetadata, ClientId),
+    SaveLayout(SessionLayoutMetadata, SaveLayoutRequest, ClientId),
     LogLayoutToHd(SessionLayoutMetadata),
     CliPipe {
         pipe_id: String,
@@ -171,6 +172,7 @@ impl From<&PluginInstruction> for PluginContext {
                 PluginContext::PermissionRequestResult
             },
             PluginInstruction::DumpLayout(..) => PluginContext::DumpLayout,
+            PluginInstruction::SaveLayout(..) => PluginContext::SaveLayout,
             PluginInstruction::LogLayoutToHd(..) => PluginContext::LogLayoutToHd,
             PluginInstruction::CliPipe { .. } => PluginContext::CliPipe,
             PluginInstruction::CachePluginEvents { .. } => PluginContext::CachePluginEvents,
@@ -484,6 +486,18 @@ pub(crate) fn plugin_thread_main(
                     client_id,
                 )));
             },
+            PluginInstruction::SaveLayout(
+                mut session_layout_metadata,
+                save_layout_request,
+                client_id,
+            ) => {
+                populate_session_layout_metadata(&mut session_layout_metadata, &wasm_bridge);
+                drop(bus.senders.send_to_pty(PtyInstruction::SaveLayout(
+                    session_layout_metadata,
+                    save_layout_request,
+                    client_id,
+                )));
+            },
             PluginInstruction::LogLayoutToHd(mut session_layout_metadata) => {
                 populate_session_layout_metadata(&mut session_layout_metadata, &wasm_bridge);
                 drop(
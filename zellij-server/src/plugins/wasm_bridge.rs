@@ -1242,6 +1242,7 @@ fn check_event_permission(
         | Event::TabUpdate(..)
         | Event::PaneUpdate(..)
         | Event::SessionUpdate(..)
+        | Event::SessionSnapshots(..)
         | Event::CopyToClipboard(..)
         | Event::SystemClipboardFailure
         | Event::InputReceived => PermissionType::ReadApplicationState,
@@ -13,7 +13,7 @@ use std::{
     str::FromStr,
     sync::{Arc, Mutex},
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 use wasmer::{imports, AsStoreMut, Function, FunctionEnv, FunctionEnvMut, Imports};
 use wasmer_wasi::WasiEnv;
@@ -30,7 +30,10 @@ use zellij_utils::{
 use crate::{panes::PaneId, screen::ScreenInstruction};
 
 use zellij_utils::{
-    consts::{VERSION, ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR},
+    consts::{
+        session_info_folder_for_session, session_snapshots_folder_for_session, VERSION,
+        ZELLIJ_SESSION_INFO_CACHE_DIR, ZELLIJ_SOCK_DIR,
+    },
     data::{
         CommandToRun, Direction, Event, EventType, FileToOpen, InputMode, PluginCommand, PluginIds,
         PluginMessage, Resize, ResizeStrategy,
@@ -48,6 +51,7 @@ use zellij_utils::{
     },
     prost::Message,
     serde,
+    session_snapshots::{list_snapshots, restore_snapshot},
 };
 
 macro_rules! apply_action {
@@ -263,6 +267,19 @@ fn host_run_plugin_command(env: FunctionEnvMut<ForeignFunctionEnv>) {
                         scan_host_folder(env, folder_to_scan)
                     },
                     PluginCommand::WatchFilesystem => watch_filesystem(env),
+                    PluginCommand::StackPane(direction) => stack_pane(env, direction),
+                    PluginCommand::UnstackPanes => unstack_panes(env),
+                    PluginCommand::RemovePaneFromStack => remove_pane_from_stack(env),
+                    PluginCommand::SaveLayout(name, tab, strip_contents, relative_to, force) => {
+                        save_layout(env, name, tab, strip_contents, relative_to, force)
+                    },
+                    PluginCommand::SetTabCwd(cwd) => set_tab_cwd(env, cwd),
+                    PluginCommand::ListSessionSnapshots(session_name) => {
+                        list_session_snapshots(env, session_name)?
+                    },
+                    PluginCommand::RestoreSessionSnapshot(session_name, timestamp) => {
+                        restore_session_snapshot(session_name, timestamp)?
+                    },
                 },
                 (PermissionStatus::Denied, permission) => {
                     log::error!(
@@ -577,6 +594,7 @@ fn open_command_pane(env: &ForeignFunctionEnv, command_to_run: CommandToRun) {
         direction,
         hold_on_close,
         hold_on_start,
+        ..Default::default()
     };
     let action = Action::NewTiledPane(direction, Some(run_command_action), name);
     apply_action!(action, error_msg, env);
@@ -604,6 +622,7 @@ fn open_command_pane_floating(
         direction,
         hold_on_close,
         hold_on_start,
+        ..Default::default()
     };
     let action = Action::NewFloatingPane(Some(run_command_action), name, floating_pane_coordinates);
     apply_action!(action, error_msg, env);
@@ -627,6 +646,7 @@ fn open_command_pane_in_place(env: &ForeignFunctionEnv, command_to_run: CommandT
         direction,
         hold_on_close,
         hold_on_start,
+        ..Default::default()
     };
     let action = Action::NewInPlacePane(Some(run_command_action), name);
     apply_action!(action, error_msg, env);
@@ -920,6 +940,59 @@ fn move_focus_or_tab(env: &ForeignFunctionEnv, direction: Direction) {
     apply_action!(action, error_msg, env);
 }
 
+fn stack_pane(env: &ForeignFunctionEnv, direction: Direction) {
+    let error_msg = || format!("failed to stack pane in plugin {}", env.plugin_env.name());
+    let action = Action::StackPane(direction);
+    apply_action!(action, error_msg, env);
+}
+
+fn unstack_panes(env: &ForeignFunctionEnv) {
+    let error_msg = || {
+        format!(
+            "failed to unstack panes in plugin {}",
+            env.plugin_env.name()
+        )
+    };
+    let action = Action::UnstackPanes;
+    apply_action!(action, error_msg, env);
+}
+
+fn remove_pane_from_stack(env: &ForeignFunctionEnv) {
+    let error_msg = || {
+        format!(
+            "failed to remove pane from stack in plugin {}",
+            env.plugin_env.name()
+        )
+    };
+    let action = Action::RemovePaneFromStack;
+    apply_action!(action, error_msg, env);
+}
+
+fn save_layout(
+    env: &ForeignFunctionEnv,
+    name: String,
+    tab: Option<String>,
+    strip_contents: bool,
+    relative_to: Option<PathBuf>,
+    force: bool,
+) {
+    let error_msg = || format!("failed to save layout in plugin {}", env.plugin_env.name());
+    let action = Action::SaveLayout {
+        name,
+        tab,
+        strip_contents,
+        relative_to,
+        force,
+    };
+    apply_action!(action, error_msg, env);
+}
+
+fn set_tab_cwd(env: &ForeignFunctionEnv, cwd: Option<PathBuf>) {
+    let error_msg = || format!("failed to set tab cwd in plugin {}", env.plugin_env.name());
+    let action = Action::SetTabCwd(cwd);
+    apply_action!(action, error_msg, env);
+}
+
 fn detach(env: &ForeignFunctionEnv) {
     let action = Action::Detach;
     let error_msg = || format!("Failed to detach");
@@ -960,6 +1033,35 @@ fn delete_dead_session(session_name: String) -> Result<()> {
         .with_context(|| format!("Failed to delete dead session: {:?}", &session_name))
 }
 
+fn list_session_snapshots(env: &ForeignFunctionEnv, session_name: String) -> Result<()> {
+    let now = SystemTime::now();
+    let snapshots = list_snapshots(&session_snapshots_folder_for_session(&session_name))
+        .iter()
+        .map(|snapshot| {
+            let age = now.duration_since(snapshot.time()).unwrap_or_default();
+            (snapshot.timestamp, age)
+        })
+        .collect();
+    env.plugin_env
+        .senders
+        .send_to_plugin(PluginInstruction::Update(vec![(
+            Some(env.plugin_env.plugin_id),
+            Some(env.plugin_env.client_id),
+            Event::SessionSnapshots(session_name, snapshots),
+        )]))
+        .with_context(|| "Failed to report session snapshots")
+}
+
+fn restore_session_snapshot(session_name: String, timestamp: u64) -> Result<()> {
+    let snapshots = list_snapshots(&session_snapshots_folder_for_session(&session_name));
+    let snapshot = snapshots
+        .iter()
+        .find(|s| s.timestamp == timestamp)
+        .with_context(|| format!("No snapshot {} for session {:?}", timestamp, session_name))?;
+    restore_snapshot(snapshot, &session_info_folder_for_session(&session_name))
+        .with_context(|| format!("Failed to restore snapshot of session {:?}", session_name))
+}
+
 fn delete_all_dead_sessions() -> Result<()> {
     use std::os::unix::fs::FileTypeExt;
     let mut live_sessions = vec![];
@@ -1499,6 +1601,11 @@ fn check_command_permission(
         | PluginCommand::FocusNextPane
         | PluginCommand::MoveFocus(..)
         | PluginCommand::MoveFocusOrTab(..)
+        | PluginCommand::StackPane(..)
+        | PluginCommand::UnstackPanes
+        | PluginCommand::RemovePaneFromStack
+        | PluginCommand::SaveLayout(..)
+        | PluginCommand::SetTabCwd(..)
         | PluginCommand::Detach
         | PluginCommand::EditScrollback
         | PluginCommand::ToggleTab
@@ -1534,6 +1641,7 @@ fn check_command_permission(
         | PluginCommand::SwitchSession(..)
         | PluginCommand::DeleteDeadSession(..)
         | PluginCommand::DeleteAllDeadSessions
+        | PluginCommand::RestoreSessionSnapshot(..)
         | PluginCommand::RenameSession(..)
         | PluginCommand::RenameTab(..)
         | PluginCommand::DisconnectOtherClients
@@ -1542,6 +1650,7 @@ fn check_command_permission(
         | PluginCommand::BlockCliPipeInput(..)
         | PluginCommand::CliPipeOutput(..) => PermissionType::ReadCliPipes,
         PluginCommand::MessageToPlugin(..) => PermissionType::MessageAndLaunchOtherPlugins,
+        PluginCommand::ListSessionSnapshots(..) => PermissionType::ReadApplicationState,
         _ => return (PermissionStatus::Granted, None),
     };
 
@@ -4,16 +4,21 @@ use crate::{
     panes::PaneId,
     plugins::PluginInstruction,
     screen::ScreenInstruction,
-    session_layout_metadata::SessionLayoutMetadata,
+    session_layout_metadata::{SaveLayoutRequest, SessionLayoutMetadata},
     thread_bus::{Bus, ThreadSenders},
     ClientId, ServerInstruction,
 };
 use async_std::task::{self, JoinHandle};
-use std::{collections::HashMap, os::unix::io::RawFd, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    os::unix::io::RawFd,
+    path::{Component, Path, PathBuf},
+    time::{Duration, Instant},
+};
 use zellij_utils::nix::unistd::Pid;
 use zellij_utils::{
     async_std,
-    data::FloatingPaneCoordinates,
+    data::{Event, FloatingPaneCoordinates},
     errors::prelude::*,
     errors::{ContextType, PtyContext},
     input::{
@@ -21,12 +26,17 @@ use zellij_utils::{
         layout::{FloatingPaneLayout, Layout, Run, RunPluginOrAlias, TiledPaneLayout},
     },
     pane_size::Size,
-    session_serialization,
+    session_serialization::{self, GlobalLayoutManifest},
 };
 
 pub type VteBytes = Vec<u8>;
 pub type TabIndex = u32;
 
+const RESTART_BACKOFF_BASE_MS: u64 = 1000;
+const RESTART_BACKOFF_MAX_MS: u64 = 30000;
+// a command that ran for this long before exiting starts over with the shortest backoff
+const RESTART_COUNT_RESET_MS: u64 = 60000;
+
 #[derive(Clone, Copy, Debug)]
 pub enum ClientTabIndexOrPaneId {
     ClientId(ClientId),
@@ -53,6 +63,8 @@ pub enum PtyInstruction {
     // optional pane
     // name
     UpdateActivePane(Option<PaneId>, ClientId),
+    UpdateTabDefaults(Option<PathBuf>, BTreeMap<String, String>, ClientId), // the default cwd and
+    // env of the client's active tab
     GoToTab(TabIndex, ClientId),
     NewTab(
         Option<PathBuf>,
@@ -66,6 +78,8 @@ pub enum PtyInstruction {
     ClosePane(PaneId),
     CloseTab(Vec<PaneId>),
     ReRunCommandInPane(PaneId, RunCommand),
+    ScheduleCommandRestart(PaneId, Option<i32>, RunCommand), // Option<i32> is the exit status
+    RestartCommandInPane(PaneId, RunCommand),
     DropToShellInPane {
         pane_id: PaneId,
         shell: Option<PathBuf>,
@@ -76,8 +90,16 @@ pub enum PtyInstruction {
         Option<String>,
         ClientTabIndexOrPaneId,
     ), // String is an optional pane name
+    SpawnScratchpad(
+        String, // scratchpad name
+        RunCommand,
+        Option<FloatingPaneCoordinates>,
+        ClientId,
+    ),
     DumpLayout(SessionLayoutMetadata, ClientId),
+    SaveLayout(SessionLayoutMetadata, SaveLayoutRequest, ClientId),
     LogLayoutToHd(SessionLayoutMetadata),
+    QueryForegroundCommands(Vec<u32>), // terminal pane ids
     FillPluginCwd(
         Option<bool>,   // should float
         bool,           // should be opened in place
@@ -102,15 +124,21 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::SpawnTerminalVertically(..) => PtyContext::SpawnTerminalVertically,
             PtyInstruction::SpawnTerminalHorizontally(..) => PtyContext::SpawnTerminalHorizontally,
             PtyInstruction::UpdateActivePane(..) => PtyContext::UpdateActivePane,
+            PtyInstruction::UpdateTabDefaults(..) => PtyContext::UpdateTabDefaults,
             PtyInstruction::GoToTab(..) => PtyContext::GoToTab,
             PtyInstruction::ClosePane(_) => PtyContext::ClosePane,
             PtyInstruction::CloseTab(_) => PtyContext::CloseTab,
             PtyInstruction::NewTab(..) => PtyContext::NewTab,
             PtyInstruction::ReRunCommandInPane(..) => PtyContext::ReRunCommandInPane,
+            PtyInstruction::ScheduleCommandRestart(..) => PtyContext::ScheduleCommandRestart,
+            PtyInstruction::RestartCommandInPane(..) => PtyContext::RestartCommandInPane,
             PtyInstruction::DropToShellInPane { .. } => PtyContext::DropToShellInPane,
             PtyInstruction::SpawnInPlaceTerminal(..) => PtyContext::SpawnInPlaceTerminal,
+            PtyInstruction::SpawnScratchpad(..) => PtyContext::SpawnScratchpad,
             PtyInstruction::DumpLayout(..) => PtyContext::DumpLayout,
+            PtyInstruction::SaveLayout(..) => PtyContext::SaveLayout,
             PtyInstruction::LogLayoutToHd(..) => PtyContext::LogLayoutToHd,
+            PtyInstruction::QueryForegroundCommands(..) => PtyContext::QueryForegroundCommands,
             PtyInstruction::FillPluginCwd(..) => PtyContext::FillPluginCwd,
             PtyInstruction::Exit => PtyContext::Exit,
         }
@@ -119,11 +147,14 @@ impl From<&PtyInstruction> for PtyContext {
 
 pub(crate) struct Pty {
     pub active_panes: HashMap<ClientId, PaneId>,
+    pub active_tab_defaults: HashMap<ClientId, (Option<PathBuf>, BTreeMap<String, String>)>, // (cwd, env)
     pub bus: Bus<PtyInstruction>,
     pub id_to_child_pid: HashMap<u32, RawFd>, // terminal_id => child raw fd
     debug_to_file: bool,
     task_handles: HashMap<u32, JoinHandle<()>>, // terminal_id to join-handle
     default_editor: Option<PathBuf>,
+    command_restarts: HashMap<u32, (usize, bool, Instant)>, // terminal_id => (restart count, restart is pending, last (re)started at)
+    closed_terminal_ids: HashSet<u32>, // so that commands killed by closing their pane are not restarted
 }
 
 pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
@@ -217,6 +248,78 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     },
                 }
             },
+            PtyInstruction::SpawnScratchpad(
+                name,
+                run_command,
+                floating_pane_coordinates,
+                client_id,
+            ) => {
+                let err_context = || format!("failed to spawn scratchpad {name}");
+                let client_or_tab_index = ClientTabIndexOrPaneId::ClientId(client_id);
+                let should_float = Some(true);
+                let invoked_with = Some(Run::Command(run_command.clone()));
+                let (pid, hold_for_command, command_not_found) = match pty
+                    .spawn_terminal(
+                        Some(TerminalAction::RunCommand(run_command.clone())),
+                        client_or_tab_index,
+                    )
+                    .with_context(err_context)
+                {
+                    Ok((pid, starts_held)) => {
+                        let hold_for_command = if starts_held {
+                            Some(run_command.clone())
+                        } else {
+                            None
+                        };
+                        (pid, hold_for_command, false)
+                    },
+                    Err(err) => match err.downcast_ref::<ZellijError>() {
+                        // we still open the pane so that the error is shown to the user
+                        Some(ZellijError::CommandNotFound { terminal_id, .. }) => {
+                            (*terminal_id, None, true)
+                        },
+                        _ => {
+                            Err::<(), _>(err).non_fatal();
+                            // so that the scratchpad can be toggled again
+                            pty.bus
+                                .senders
+                                .send_to_screen(ScreenInstruction::CancelScratchpadSpawn(
+                                    name.clone(),
+                                ))
+                                .with_context(err_context)?;
+                            continue;
+                        },
+                    },
+                };
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::NewPane(
+                        PaneId::Terminal(pid),
+                        Some(name.clone()),
+                        should_float,
+                        hold_for_command,
+                        invoked_with,
+                        floating_pane_coordinates,
+                        client_or_tab_index,
+                    ))
+                    .with_context(err_context)?;
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::AddScratchpadPane(
+                        name.clone(),
+                        PaneId::Terminal(pid),
+                    ))
+                    .with_context(err_context)?;
+                if command_not_found {
+                    send_command_not_found_to_screen(
+                        pty.bus.senders.clone(),
+                        pid,
+                        run_command,
+                        None,
+                    )
+                    .with_context(err_context)?;
+                }
+            },
             PtyInstruction::SpawnInPlaceTerminal(
                 terminal_action,
                 name,
@@ -463,6 +566,9 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
             PtyInstruction::UpdateActivePane(pane_id, client_id) => {
                 pty.set_active_pane(pane_id, client_id);
             },
+            PtyInstruction::UpdateTabDefaults(cwd, env, client_id) => {
+                pty.active_tab_defaults.insert(client_id, (cwd, env));
+            },
             PtyInstruction::GoToTab(tab_index, client_id) => {
                 pty.bus
                     .senders
@@ -518,6 +624,10 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
             },
             PtyInstruction::ReRunCommandInPane(pane_id, run_command) => {
                 let err_context = || format!("failed to rerun command in pane {:?}", pane_id);
+                if let PaneId::Terminal(id) = pane_id {
+                    // the user re-ran the command, so it gets a fresh set of restarts
+                    pty.command_restarts.remove(&id);
+                }
 
                 match pty
                     .rerun_command_in_pane(pane_id, run_command.clone())
@@ -555,6 +665,15 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     },
                 }
             },
+            PtyInstruction::ScheduleCommandRestart(pane_id, exit_status, run_command) => {
+                pty.schedule_command_restart(pane_id, exit_status, run_command)
+                    .with_context(|| format!("failed to schedule restart of pane {:?}", pane_id))?;
+            },
+            PtyInstruction::RestartCommandInPane(pane_id, run_command) => {
+                pty.restart_command_in_pane(pane_id, run_command)
+                    .with_context(|| format!("failed to restart command in pane {:?}", pane_id))
+                    .non_fatal();
+            },
             PtyInstruction::DropToShellInPane {
                 pane_id,
                 shell,
@@ -630,9 +749,52 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     },
                 }
             },
+            PtyInstruction::SaveLayout(
+                mut session_layout_metadata,
+                save_layout_request,
+                client_id,
+            ) => {
+                let err_context = || format!("Failed to save layout");
+                pty.populate_session_layout_metadata(&mut session_layout_metadata);
+                if let Some(relative_to) = &save_layout_request.relative_to {
+                    session_layout_metadata.make_cwds_relative_to(relative_to);
+                }
+                let mut global_layout_manifest: GlobalLayoutManifest =
+                    session_layout_metadata.into();
+                global_layout_manifest.portable = true;
+                let saved = session_serialization::serialize_session_layout(global_layout_manifest)
+                    .map_err(|e| e.to_owned())
+                    .and_then(|(kdl_layout, pane_contents)| {
+                        save_layout_to_layout_dir(kdl_layout, pane_contents, &save_layout_request)
+                    });
+                let instruction = match saved {
+                    Ok(layout_path) => ServerInstruction::Log(
+                        vec![format!("Saved layout to: {}", layout_path.display())],
+                        client_id,
+                    ),
+                    Err(e) => {
+                        log::error!("Failed to save layout: {}", e);
+                        ServerInstruction::LogError(vec![e], client_id)
+                    },
+                };
+                pty.bus
+                    .senders
+                    .send_to_server(instruction)
+                    .with_context(err_context)
+                    .non_fatal();
+            },
             PtyInstruction::LogLayoutToHd(mut session_layout_metadata) => {
                 let err_context = || format!("Failed to dump layout");
-                pty.populate_session_layout_metadata(&mut session_layout_metadata);
+                let (terminal_cwds, terminal_commands) =
+                    pty.populate_session_layout_metadata(&mut session_layout_metadata);
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::UpdateTerminalCwds(terminal_cwds))
+                    .with_context(err_context)?;
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::UpdateTerminalCommands(terminal_commands))
+                    .with_context(err_context)?;
                 match session_serialization::serialize_session_layout(
                     session_layout_metadata.into(),
                 ) {
@@ -649,6 +811,13 @@ pub(crate) fn pty_thread_main(mut pty: Pty, layout: Box<Layout>) -> Result<()> {
                     },
                 }
             },
+            PtyInstruction::QueryForegroundCommands(terminal_ids) => {
+                let terminal_commands = pty.get_foreground_commands(terminal_ids);
+                pty.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::UpdateTerminalCommands(terminal_commands))
+                    .with_context(|| format!("Failed to query foreground commands"))?;
+            },
             PtyInstruction::FillPluginCwd(
                 should_float,
                 should_be_open_in_place,
@@ -690,11 +859,14 @@ impl Pty {
     ) -> Self {
         Pty {
             active_panes: HashMap::new(),
+            active_tab_defaults: HashMap::new(),
             bus,
             id_to_child_pid: HashMap::new(),
             debug_to_file,
             task_handles: HashMap::new(),
             default_editor,
+            command_restarts: HashMap::new(),
+            closed_terminal_ids: HashSet::new(),
         }
     }
     pub fn get_default_terminal(
@@ -731,12 +903,19 @@ impl Pty {
                     cwd, // note: this might also be filled by the calling function, eg. spawn_terminal
                     hold_on_close: false,
                     hold_on_start: false,
+                    ..Default::default()
                 })
             },
         }
     }
     fn fill_cwd(&self, terminal_action: &mut TerminalAction, client_id: ClientId) {
         if let TerminalAction::RunCommand(run_command) = terminal_action {
+            if run_command.cwd.is_none() {
+                run_command.cwd = self
+                    .active_tab_defaults
+                    .get(&client_id)
+                    .and_then(|(cwd, _env)| cwd.clone());
+            }
             if run_command.cwd.is_none() {
                 run_command.cwd = self
                     .active_panes
@@ -754,6 +933,13 @@ impl Pty {
             };
         };
     }
+    fn fill_env(&self, terminal_action: &mut TerminalAction, client_id: ClientId) {
+        if let TerminalAction::RunCommand(run_command) = terminal_action {
+            if let Some((_cwd, env)) = self.active_tab_defaults.get(&client_id) {
+                run_command.inherit_env(env);
+            }
+        }
+    }
     fn fill_cwd_from_pane_id(&self, terminal_action: &mut TerminalAction, pane_id: &u32) {
         if let TerminalAction::RunCommand(run_command) = terminal_action {
             if run_command.cwd.is_none() {
@@ -780,6 +966,7 @@ impl Pty {
                 let mut terminal_action =
                     terminal_action.unwrap_or_else(|| self.get_default_terminal(None, None));
                 self.fill_cwd(&mut terminal_action, client_id);
+                self.fill_env(&mut terminal_action, client_id);
                 terminal_action
             },
             ClientTabIndexOrPaneId::TabIndex(_) => {
@@ -795,9 +982,11 @@ impl Pty {
             },
         };
         let (hold_on_start, hold_on_close) = match &terminal_action {
-            TerminalAction::RunCommand(run_command) => {
-                (run_command.hold_on_start, run_command.hold_on_close)
-            },
+            TerminalAction::RunCommand(run_command) => (
+                // commands with dependencies are started by the screen once those are ready
+                run_command.hold_on_start || !run_command.depends_on.is_empty(),
+                run_command.hold_on_close,
+            ),
             _ => (false, false),
         };
 
@@ -816,8 +1005,14 @@ impl Pty {
 
         let quit_cb = Box::new({
             let senders = self.bus.senders.clone();
-            move |pane_id, exit_status, command| {
-                if hold_on_close {
+            move |pane_id, exit_status: Option<i32>, command: RunCommand| {
+                if command.restart.should_restart(exit_status) {
+                    let _ = senders.send_to_pty(PtyInstruction::ScheduleCommandRestart(
+                        pane_id,
+                        exit_status,
+                        command,
+                    ));
+                } else if hold_on_close {
                     let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
                         pane_id,
                         exit_status,
@@ -861,7 +1056,7 @@ impl Pty {
         });
 
         self.task_handles.insert(terminal_id, terminal_bytes);
-        self.id_to_child_pid.insert(terminal_id, child_fd);
+        self.add_terminal(terminal_id, child_fd);
         let starts_held = false;
         Ok((terminal_id, starts_held))
     }
@@ -1018,12 +1213,19 @@ impl Pty {
         });
         match run_instruction {
             Some(Run::Command(mut command)) => {
-                let starts_held = command.hold_on_start;
+                // commands with dependencies are started by the screen once those are ready
+                let starts_held = command.hold_on_start || !command.depends_on.is_empty();
                 let hold_on_close = command.hold_on_close;
                 let quit_cb = Box::new({
                     let senders = self.bus.senders.clone();
-                    move |pane_id, exit_status, command| {
-                        if hold_on_close {
+                    move |pane_id, exit_status: Option<i32>, command: RunCommand| {
+                        if command.restart.should_restart(exit_status) {
+                            let _ = senders.send_to_pty(PtyInstruction::ScheduleCommandRestart(
+                                pane_id,
+                                exit_status,
+                                command,
+                            ));
+                        } else if hold_on_close {
                             let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
                                 pane_id,
                                 exit_status,
@@ -1075,7 +1277,7 @@ impl Pty {
                         .with_context(err_context)
                     {
                         Ok((terminal_id, pid_primary, child_fd)) => {
-                            self.id_to_child_pid.insert(terminal_id, child_fd);
+                            self.add_terminal(terminal_id, child_fd);
                             Ok(Some((
                                 terminal_id,
                                 starts_held,
@@ -1094,9 +1296,16 @@ impl Pty {
                     }
                 }
             },
-            Some(Run::Cwd(cwd)) => {
+            Some(run @ Run::Cwd(_)) | Some(run @ Run::Shell(..)) => {
                 let starts_held = false; // we do not hold Cwd panes
-                let shell = self.get_default_terminal(Some(cwd), Some(default_shell.clone()));
+                let (cwd, env) = match run {
+                    Run::Shell(cwd, env) => (cwd, env),
+                    run => (run.get_cwd(), Default::default()),
+                };
+                let mut shell = self.get_default_terminal(cwd, Some(default_shell.clone()));
+                if let TerminalAction::RunCommand(command) = &mut shell {
+                    command.env.extend(env);
+                }
                 match self
                     .bus
                     .os_input
@@ -1107,7 +1316,7 @@ impl Pty {
                     .with_context(err_context)
                 {
                     Ok((terminal_id, pid_primary, child_fd)) => {
-                        self.id_to_child_pid.insert(terminal_id, child_fd);
+                        self.add_terminal(terminal_id, child_fd);
                         Ok(Some((terminal_id, starts_held, None, Ok(pid_primary))))
                     },
                     Err(err) => match err.downcast_ref::<ZellijError>() {
@@ -1134,7 +1343,7 @@ impl Pty {
                     .with_context(err_context)
                 {
                     Ok((terminal_id, pid_primary, child_fd)) => {
-                        self.id_to_child_pid.insert(terminal_id, child_fd);
+                        self.add_terminal(terminal_id, child_fd);
                         Ok(Some((terminal_id, starts_held, None, Ok(pid_primary))))
                     },
                     Err(err) => match err.downcast_ref::<ZellijError>() {
@@ -1157,7 +1366,7 @@ impl Pty {
                     .with_context(err_context)
                 {
                     Ok((terminal_id, pid_primary, child_fd)) => {
-                        self.id_to_child_pid.insert(terminal_id, child_fd);
+                        self.add_terminal(terminal_id, child_fd);
                         Ok(Some((terminal_id, starts_held, None, Ok(pid_primary))))
                     },
                     Err(err) => match err.downcast_ref::<ZellijError>() {
@@ -1177,6 +1386,8 @@ impl Pty {
         match id {
             PaneId::Terminal(id) => {
                 self.task_handles.remove(&id);
+                self.command_restarts.remove(&id);
+                self.closed_terminal_ids.insert(id);
                 if let Some(child_fd) = self.id_to_child_pid.remove(&id) {
                     task::block_on(async {
                         let err_context = || format!("failed to run async task for pane {id}");
@@ -1217,6 +1428,114 @@ impl Pty {
             self.active_panes.insert(client_id, pane_id);
         }
     }
+    pub fn schedule_command_restart(
+        &mut self,
+        pane_id: PaneId,
+        exit_status: Option<i32>,
+        run_command: RunCommand,
+    ) -> Result<()> {
+        let err_context = || format!("failed to schedule restart of pane {:?}", pane_id);
+        let PaneId::Terminal(id) = pane_id else {
+            return Ok(());
+        };
+        if !self.terminal_is_open(id) {
+            // the command was killed because its pane was closed
+            return Ok(());
+        }
+        let (restart_count, is_pending, started_at) =
+            self.command_restarts
+                .entry(id)
+                .or_insert((0, false, Instant::now()));
+        if started_at.elapsed() >= Duration::from_millis(RESTART_COUNT_RESET_MS) {
+            *restart_count = 0;
+        }
+        let max_restarts_reached = run_command
+            .max_restarts
+            .map(|max_restarts| *restart_count >= max_restarts)
+            .unwrap_or(false);
+        if max_restarts_reached {
+            // give up and treat the command as if it had no restart policy
+            if run_command.hold_on_close {
+                self.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::HoldPane(
+                        pane_id,
+                        exit_status,
+                        run_command,
+                        None,
+                        None,
+                    ))
+                    .with_context(err_context)?;
+            } else {
+                self.bus
+                    .senders
+                    .send_to_screen(ScreenInstruction::ClosePane(pane_id, None))
+                    .with_context(err_context)?;
+            }
+            return Ok(());
+        }
+        *restart_count += 1;
+        *is_pending = true;
+        let restart_count = *restart_count;
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::IndicateCommandRestart(
+                pane_id,
+                restart_count,
+                exit_status,
+            ))
+            .with_context(err_context)?;
+        self.bus
+            .senders
+            .send_to_plugin(PluginInstruction::Update(vec![(
+                None,
+                None,
+                Event::CommandPaneRestarted(id, exit_status, restart_count),
+            )]))
+            .with_context(err_context)?;
+        task::spawn({
+            let senders = self.bus.senders.clone();
+            async move {
+                task::sleep(restart_backoff(restart_count)).await;
+                let _ =
+                    senders.send_to_pty(PtyInstruction::RestartCommandInPane(pane_id, run_command));
+            }
+        });
+        Ok(())
+    }
+    pub fn restart_command_in_pane(
+        &mut self,
+        pane_id: PaneId,
+        run_command: RunCommand,
+    ) -> Result<()> {
+        let PaneId::Terminal(id) = pane_id else {
+            return Ok(());
+        };
+        if !self.terminal_is_open(id) {
+            return Ok(());
+        }
+        let is_pending = match self.command_restarts.get_mut(&id) {
+            Some((_restart_count, is_pending, started_at)) if *is_pending => {
+                *is_pending = false;
+                *started_at = Instant::now();
+                true
+            },
+            _ => false,
+        };
+        if is_pending {
+            self.rerun_command_in_pane(pane_id, run_command)?;
+        }
+        Ok(())
+    }
+    fn add_terminal(&mut self, terminal_id: u32, child_fd: RawFd) {
+        // terminal ids are reused once the pane that had them is closed
+        self.closed_terminal_ids.remove(&terminal_id);
+        self.id_to_child_pid.insert(terminal_id, child_fd);
+    }
+    fn terminal_is_open(&self, terminal_id: u32) -> bool {
+        !self.closed_terminal_ids.contains(&terminal_id)
+            && self.id_to_child_pid.contains_key(&terminal_id)
+    }
     pub fn rerun_command_in_pane(
         &mut self,
         pane_id: PaneId,
@@ -1232,8 +1551,14 @@ impl Pty {
                 let hold_on_close = run_command.hold_on_close;
                 let quit_cb = Box::new({
                     let senders = self.bus.senders.clone();
-                    move |pane_id, exit_status, command| {
-                        if hold_on_close {
+                    move |pane_id, exit_status: Option<i32>, command: RunCommand| {
+                        if command.restart.should_restart(exit_status) {
+                            let _ = senders.send_to_pty(PtyInstruction::ScheduleCommandRestart(
+                                pane_id,
+                                exit_status,
+                                command,
+                            ));
+                        } else if hold_on_close {
                             let _ = senders.send_to_screen(ScreenInstruction::HoldPane(
                                 pane_id,
                                 exit_status,
@@ -1284,12 +1609,14 @@ impl Pty {
             _ => Err(anyhow!("cannot respawn plugin panes")).with_context(err_context),
         }
     }
+    /// Returns the cwds of the terminal panes (as they are before being made relative to each
+    /// other in the layout) and the commands running in their foreground
     pub fn populate_session_layout_metadata(
         &self,
         session_layout_metadata: &mut SessionLayoutMetadata,
-    ) {
+    ) -> (HashMap<u32, PathBuf>, HashMap<u32, Vec<String>>) {
         let terminal_ids = session_layout_metadata.all_terminal_ids();
-        let mut terminal_ids_to_commands: HashMap<u32, Vec<String>> = HashMap::new();
+        let terminal_ids_to_commands = self.get_foreground_commands(terminal_ids.clone());
         let mut terminal_ids_to_cwds: HashMap<u32, PathBuf> = HashMap::new();
 
         let pids: Vec<_> = terminal_ids
@@ -1303,31 +1630,38 @@ impl Pty {
             .as_ref()
             .map(|os_input| os_input.get_cwds(pids))
             .unwrap_or_default();
-        let ppids_to_cmds = self
-            .bus
-            .os_input
-            .as_ref()
-            .map(|os_input| os_input.get_all_cmds_by_ppid())
-            .unwrap_or_default();
 
         for terminal_id in terminal_ids {
             let process_id = self.id_to_child_pid.get(&terminal_id);
             let cwd = process_id
                 .as_ref()
                 .and_then(|pid| pids_to_cwds.get(&Pid::from_raw(**pid)));
-            let cmd = process_id
-                .as_ref()
-                .and_then(|pid| ppids_to_cmds.get(&format!("{}", pid)));
-            if let Some(cmd) = cmd {
-                terminal_ids_to_commands.insert(terminal_id, cmd.clone());
-            }
             if let Some(cwd) = cwd {
                 terminal_ids_to_cwds.insert(terminal_id, cwd.clone());
             }
         }
         session_layout_metadata.update_default_shell(get_default_shell());
-        session_layout_metadata.update_terminal_commands(terminal_ids_to_commands);
-        session_layout_metadata.update_terminal_cwds(terminal_ids_to_cwds);
+        session_layout_metadata.update_terminal_commands(terminal_ids_to_commands.clone());
+        session_layout_metadata.update_terminal_cwds(terminal_ids_to_cwds.clone());
+        (terminal_ids_to_cwds, terminal_ids_to_commands)
+    }
+    /// The commands running in the foreground of these terminal panes (those that run anything
+    /// other than their shell)
+    pub fn get_foreground_commands(&self, terminal_ids: Vec<u32>) -> HashMap<u32, Vec<String>> {
+        let ppids_to_cmds = self
+            .bus
+            .os_input
+            .as_ref()
+            .map(|os_input| os_input.get_all_cmds_by_ppid())
+            .unwrap_or_default();
+        terminal_ids
+            .into_iter()
+            .filter_map(|terminal_id| {
+                let process_id = self.id_to_child_pid.get(&terminal_id)?;
+                let cmd = ppids_to_cmds.get(&format!("{}", process_id))?;
+                Some((terminal_id, cmd.clone()))
+            })
+            .collect()
     }
     pub fn fill_plugin_cwd(
         &self,
@@ -1420,3 +1754,65 @@ pub fn get_default_shell() -> PathBuf {
         "/bin/sh".to_string()
     }))
 }
+
+/// How long to wait before restarting a command for the restart_count-th time, doubling from a
+/// second up to half a minute
+fn restart_backoff(restart_count: usize) -> Duration {
+    let exponent = restart_count.saturating_sub(1).min(16) as u32;
+    Duration::from_millis(
+        RESTART_BACKOFF_BASE_MS
+            .saturating_mul(2u64.pow(exponent))
+            .min(RESTART_BACKOFF_MAX_MS),
+    )
+}
+
+/// Writes a layout (and the pane contents it refers to) to `<layout_dir>/<name>.kdl`, returning
+/// the path of the layout file
+fn save_layout_to_layout_dir(
+    mut kdl_layout: String,
+    pane_contents: BTreeMap<String, String>,
+    save_layout_request: &SaveLayoutRequest,
+) -> Result<PathBuf, String> {
+    let layout_dir = save_layout_request
+        .layout_dir
+        .as_ref()
+        .ok_or_else(|| "No layout folder to save the layout in".to_owned())?;
+    // the name should be a plain file name, so that the layout (and its contents files) are
+    // saved in the layout folder rather than anywhere else
+    let name = &save_layout_request.name;
+    let mut name_components = Path::new(name).components();
+    let is_file_name = matches!(
+        (name_components.next(), name_components.next()),
+        (Some(Component::Normal(_)), None)
+    ) && !name.contains(std::path::is_separator);
+    if !is_file_name {
+        return Err(format!(
+            "Invalid layout name \"{}\", it should be a file name without a folder",
+            name
+        ));
+    }
+    let layout_path = layout_dir.join(format!("{}.kdl", name));
+    if layout_path.exists() && !save_layout_request.force {
+        return Err(format!(
+            "Layout {} already exists, use --force to overwrite it",
+            layout_path.display()
+        ));
+    }
+    std::fs::create_dir_all(layout_dir).map_err(|e| e.to_string())?;
+    for (file_name, contents) in pane_contents {
+        // contents files live next to the layout file, so we prefix them to avoid clashes
+        // with those of other layouts
+        let prefixed_file_name = format!("{}_{}", save_layout_request.name, file_name);
+        kdl_layout = kdl_layout.replace(
+            &format!("contents_file=\"{}\"", file_name),
+            &format!("contents_file=\"{}\"", prefixed_file_name),
+        );
+        std::fs::write(layout_dir.join(prefixed_file_name), contents).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&layout_path, kdl_layout).map_err(|e| e.to_string())?;
+    Ok(layout_path)
+}
+
+#[path = "./unit/pty_tests.rs"]
+#[cfg(test)]
+mod pty_tests;
@@ -8,12 +8,13 @@ use crate::{
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction},
     screen::ScreenInstruction,
+    session_layout_metadata::SaveLayoutRequest,
     ServerInstruction, SessionMetaData, SessionState,
 };
 use uuid::Uuid;
 use zellij_utils::{
     channels::SenderWithContext,
-    data::{Direction, Event, PluginCapabilities, ResizeStrategy},
+    data::{Direction, Event, InputMode, PluginCapabilities, ResizeStrategy},
     errors::prelude::*,
     input::{
         actions::{Action, SearchDirection, SearchOption},
@@ -184,6 +185,33 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::DumpLayout(default_shell, client_id))
                 .with_context(err_context)?;
         },
+        Action::SaveLayout {
+            name,
+            tab,
+            strip_contents,
+            relative_to,
+            force,
+        } => {
+            let default_shell = match default_shell {
+                Some(TerminalAction::RunCommand(run_command)) => Some(run_command.command),
+                _ => None,
+            };
+            let save_layout_request = SaveLayoutRequest {
+                name,
+                tab,
+                strip_contents,
+                relative_to,
+                force,
+                layout_dir: None,
+            };
+            senders
+                .send_to_screen(ScreenInstruction::SaveLayout(
+                    save_layout_request,
+                    default_shell,
+                    client_id,
+                ))
+                .with_context(err_context)?;
+        },
         Action::EditScrollback => {
             senders
                 .send_to_screen(ScreenInstruction::EditScrollback(client_id))
@@ -541,6 +569,36 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::UndoRenameTab(client_id))
                 .with_context(err_context)?;
         },
+        Action::SetTabCwd(cwd) => {
+            senders
+                .send_to_screen(ScreenInstruction::SetTabCwd(cwd, client_id))
+                .with_context(err_context)?;
+        },
+        Action::StartSuspendedPanes => {
+            senders
+                .send_to_screen(ScreenInstruction::StartSuspendedPanes(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ResizeToClient => {
+            senders
+                .send_to_server(ServerInstruction::ResizeToClient(client_id))
+                .with_context(err_context)?;
+        },
+        Action::FollowClient(leader) => {
+            senders
+                .send_to_screen(ScreenInstruction::FollowClient(leader, client_id))
+                .with_context(err_context)?;
+        },
+        Action::LockSession => {
+            senders
+                .send_to_server(ServerInstruction::LockSession)
+                .with_context(err_context)?;
+        },
+        Action::ToggleKeepSession => {
+            senders
+                .send_to_server(ServerInstruction::ToggleKeepSession)
+                .with_context(err_context)?;
+        },
         Action::MoveTab(direction) => {
             let screen_instr = match direction {
                 Direction::Left => ScreenInstruction::MoveTabLeft(client_id),
@@ -671,6 +729,41 @@ pub(crate) fn route_action(
                 .send_to_screen(ScreenInstruction::NextSwapLayout(client_id))
                 .with_context(err_context)?;
         },
+        Action::Undo => {
+            senders
+                .send_to_screen(ScreenInstruction::UndoLayoutChange(client_id))
+                .with_context(err_context)?;
+        },
+        Action::Redo => {
+            senders
+                .send_to_screen(ScreenInstruction::RedoLayoutChange(client_id))
+                .with_context(err_context)?;
+        },
+        Action::StackPane(direction) => {
+            senders
+                .send_to_screen(ScreenInstruction::StackPane(direction, client_id))
+                .with_context(err_context)?;
+        },
+        Action::UnstackPanes => {
+            senders
+                .send_to_screen(ScreenInstruction::UnstackPanes(client_id))
+                .with_context(err_context)?;
+        },
+        Action::RemovePaneFromStack => {
+            senders
+                .send_to_screen(ScreenInstruction::RemovePaneFromStack(client_id))
+                .with_context(err_context)?;
+        },
+        Action::TogglePaneSticky => {
+            senders
+                .send_to_screen(ScreenInstruction::TogglePaneSticky(client_id))
+                .with_context(err_context)?;
+        },
+        Action::ToggleScratchpad(name) => {
+            senders
+                .send_to_screen(ScreenInstruction::ToggleScratchpad(name, client_id))
+                .with_context(err_context)?;
+        },
         Action::QueryTabNames => {
             senders
                 .send_to_screen(ScreenInstruction::QueryTabNames(client_id))
@@ -970,6 +1063,94 @@ pub(crate) fn route_thread_main(
                     let mut should_break = false;
                     match instruction {
                         ClientToServerMsg::Action(action, maybe_pane_id, maybe_client_id) => {
+                            let session_is_locked = {
+                                let mut state = session_state
+                                    .write()
+                                    .to_anyhow()
+                                    .with_context(err_context)?;
+                                state.mark_input(client_id);
+                                state.is_locked()
+                            };
+                            if session_is_locked {
+                                // while the session is locked, everything typed goes to the lock
+                                // screen (unless the client is read-only) and the only thing a
+                                // client can do is detach
+                                let client_is_read_only = session_state
+                                    .read()
+                                    .to_anyhow()
+                                    .with_context(err_context)?
+                                    .client_is_read_only(client_id);
+                                match action {
+                                    Action::Write(_) | Action::WriteChars(_)
+                                        if client_is_read_only =>
+                                    {
+                                        return Ok(should_break);
+                                    },
+                                    Action::Write(bytes) => {
+                                        to_server
+                                            .send(ServerInstruction::LockScreenInput(
+                                                bytes, client_id,
+                                            ))
+                                            .with_context(err_context)?;
+                                        return Ok(should_break);
+                                    },
+                                    Action::WriteChars(chars) => {
+                                        to_server
+                                            .send(ServerInstruction::LockScreenInput(
+                                                chars.into_bytes(),
+                                                client_id,
+                                            ))
+                                            .with_context(err_context)?;
+                                        return Ok(should_break);
+                                    },
+                                    Action::SwitchToMode(_) => {
+                                        let _ = os_input.send_to_client(
+                                            client_id,
+                                            ServerToClientMsg::SwitchToMode(InputMode::Locked),
+                                        );
+                                        return Ok(should_break);
+                                    },
+                                    Action::Detach => {},
+                                    _ => return Ok(should_break),
+                                }
+                            }
+                            let session_is_mirrored = rlocked_sessions
+                                .as_ref()
+                                .and_then(|session| session.config_options.mirror_session)
+                                .unwrap_or(false);
+                            if !action.is_allowed_for_read_only_clients(session_is_mirrored)
+                                && session_state
+                                    .read()
+                                    .to_anyhow()
+                                    .with_context(err_context)?
+                                    .client_is_read_only(client_id)
+                            {
+                                log::warn!(
+                                    "Ignoring action {:?} from read-only client {}",
+                                    action,
+                                    client_id
+                                );
+                                return Ok(should_break);
+                            }
+                            let session_size_for_latest_client = {
+                                let mut state = session_state
+                                    .write()
+                                    .to_anyhow()
+                                    .with_context(err_context)?;
+                                if state.mark_client_active(client_id) {
+                                    state.resize_session()
+                                } else {
+                                    None
+                                }
+                            };
+                            if let (Some(session_size), Some(rlocked_sessions)) =
+                                (session_size_for_latest_client, rlocked_sessions.as_ref())
+                            {
+                                rlocked_sessions
+                                    .senders
+                                    .send_to_screen(ScreenInstruction::TerminalResize(session_size))
+                                    .with_context(err_context)?;
+                            }
                             let client_id = maybe_client_id.unwrap_or(client_id);
                             if let Some(rlocked_sessions) = rlocked_sessions.as_ref() {
                                 if let Action::SwitchToMode(input_mode) = action {
@@ -1002,22 +1183,21 @@ pub(crate) fn route_thread_main(
                             session_state
                                 .write()
                                 .to_anyhow()
-                                .with_context(err_context)?
-                                .set_client_size(client_id, new_size);
-                            session_state
-                                .read()
-                                .to_anyhow()
-                                .and_then(|state| {
-                                    state.min_client_terminal_size().ok_or(anyhow!(
-                                        "failed to determine minimal client terminal size"
-                                    ))
+                                .and_then(|mut state| {
+                                    state.set_client_size(client_id, new_size);
+                                    state.mark_client_active(client_id);
+                                    state
+                                        .resize_session()
+                                        .ok_or(anyhow!("failed to determine session terminal size"))
                                 })
-                                .and_then(|min_size| {
+                                .and_then(|session_size| {
                                     rlocked_sessions
                                         .as_ref()
                                         .context("couldn't get reference to read-locked session")?
                                         .senders
-                                        .send_to_screen(ScreenInstruction::TerminalResize(min_size))
+                                        .send_to_screen(ScreenInstruction::TerminalResize(
+                                            session_size,
+                                        ))
                                 })
                                 .with_context(err_context)?;
                         },
@@ -1067,6 +1247,8 @@ pub(crate) fn route_thread_main(
                             opts,
                             layout,
                             plugin_aliases,
+                            scratchpads,
+                            hooks,
                         ) => {
                             let new_client_instruction = ServerInstruction::NewClient(
                                 client_attributes,
@@ -1074,6 +1256,8 @@ pub(crate) fn route_thread_main(
                                 opts,
                                 layout,
                                 plugin_aliases,
+                                scratchpads,
+                                hooks,
                                 client_id,
                             );
                             to_server
@@ -1085,12 +1269,23 @@ pub(crate) fn route_thread_main(
                             opts,
                             tab_position_to_focus,
                             pane_id_to_focus,
+                            is_read_only,
                         ) => {
+                            if is_read_only {
+                                // we mark the client here rather than in the server thread so
+                                // that no action it sends right after attaching can slip through
+                                session_state
+                                    .write()
+                                    .to_anyhow()
+                                    .with_context(err_context)?
+                                    .set_client_read_only(client_id);
+                            }
                             let attach_client_instruction = ServerInstruction::AttachClient(
                                 client_attributes,
                                 opts,
                                 tab_position_to_focus,
                                 pane_id_to_focus,
+                                is_read_only,
                                 client_id,
                             );
                             to_server
@@ -1104,6 +1299,18 @@ pub(crate) fn route_thread_main(
                             return Ok(true);
                         },
                         ClientToServerMsg::KillSession => {
+                            if session_state
+                                .read()
+                                .to_anyhow()
+                                .with_context(err_context)?
+                                .client_is_read_only(client_id)
+                            {
+                                log::warn!(
+                                    "Ignoring request to kill the session from read-only client {}",
+                                    client_id
+                                );
+                                return Ok(should_break);
+                            }
                             to_server
                                 .send(ServerInstruction::KillSession)
                                 .with_context(err_context)?;
@@ -1112,8 +1319,23 @@ pub(crate) fn route_thread_main(
                             let _ = to_server.send(ServerInstruction::ConnStatus(client_id));
                             should_break = true;
                         },
-                        ClientToServerMsg::DetachSession(client_id) => {
-                            let _ = to_server.send(ServerInstruction::DetachSession(client_id));
+                        ClientToServerMsg::DetachSession(client_ids) => {
+                            // read-only clients can only detach themselves
+                            if client_ids.iter().any(|id| *id != client_id)
+                                && session_state
+                                    .read()
+                                    .to_anyhow()
+                                    .with_context(err_context)?
+                                    .client_is_read_only(client_id)
+                            {
+                                log::warn!(
+                                    "Ignoring request to detach clients {:?} from read-only client {}",
+                                    client_ids,
+                                    client_id
+                                );
+                                return Ok(should_break);
+                            }
+                            let _ = to_server.send(ServerInstruction::DetachSession(client_ids));
                             should_break = true;
                         },
                         ClientToServerMsg::ListClients => {
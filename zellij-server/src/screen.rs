@@ -5,19 +5,21 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::str;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use log::{debug, warn};
 use zellij_utils::data::{
     Direction, PaneManifest, PluginPermission, Resize, ResizeStrategy, SessionInfo,
 };
 use zellij_utils::errors::prelude::*;
-use zellij_utils::input::command::RunCommand;
+use zellij_utils::input::command::{PaneDependency, RunCommand};
 use zellij_utils::input::options::Clipboard;
 use zellij_utils::pane_size::{Size, SizeInPixels};
+use zellij_utils::regex::Regex;
 use zellij_utils::{
     consts::{session_info_folder_for_session, ZELLIJ_SOCK_DIR},
     envs::set_session_name,
+    home::default_layout_dir,
     input::command::TerminalAction,
     input::layout::{
         FloatingPaneLayout, Layout, Run, RunPluginOrAlias, SwapFloatingLayout, SwapTiledLayout,
@@ -30,12 +32,14 @@ use crate::background_jobs::BackgroundJob;
 use crate::os_input_output::ResizeCache;
 use crate::panes::alacritty_functions::xparse_color;
 use crate::panes::terminal_character::AnsiCode;
-use crate::session_layout_metadata::{PaneLayoutMetadata, SessionLayoutMetadata};
+use crate::session_layout_metadata::{
+    PaneLayoutMetadata, SaveLayoutRequest, SessionLayoutMetadata,
+};
 
 use crate::{
     output::Output,
     panes::sixel::SixelImageStore,
-    panes::PaneId,
+    panes::{PaneId, StickyPanes},
     plugins::{PluginInstruction, PluginRenderAsset},
     pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
     tab::Tab,
@@ -48,11 +52,16 @@ use crate::{
 };
 use zellij_utils::{
     data::{
-        Event, FloatingPaneCoordinates, InputMode, ModeInfo, Palette, PaletteColor,
-        PluginCapabilities, Style, TabInfo,
+        ConnectedClientInfo, Event, FloatingPaneCoordinates, InputMode, ModeInfo, Palette,
+        PaletteColor, PluginCapabilities, Style, TabInfo,
     },
     errors::{ContextType, ScreenContext},
-    input::{get_mode_info, options::Options},
+    input::{
+        get_mode_info,
+        hooks::{HookContext, HookEvent, Hooks},
+        options::Options,
+        scratchpads::Scratchpads,
+    },
     ipc::{ClientAttributes, PixelDimensions, ServerToClientMsg},
 };
 
@@ -178,6 +187,8 @@ pub enum ScreenInstruction {
     Exit,
     ClearScreen(ClientId),
     DumpScreen(String, ClientId, bool),
+    SaveLayout(SaveLayoutRequest, Option<PathBuf>, ClientId), // PathBuf is the default configured
+    // shell
     DumpLayout(Option<PathBuf>, ClientId), // PathBuf is the default configured
     // shell
     EditScrollback(ClientId),
@@ -204,6 +215,8 @@ pub enum ScreenInstruction {
         Option<usize>,
         Option<ClientId>,
     ), // Option<i32> is the exit status, Option<usize> is the tab_index
+    IndicateCommandRestart(PaneId, usize, Option<i32>), // usize is the restart count, Option<i32> is the
+    // exit status that triggered the restart
     UpdatePaneName(Vec<u8>, ClientId),
     UndoRenamePane(ClientId),
     NewTab(
@@ -239,6 +252,11 @@ pub enum ScreenInstruction {
     ToggleTab(ClientId),
     UpdateTabName(Vec<u8>, ClientId),
     UndoRenameTab(ClientId),
+    SetTabCwd(Option<PathBuf>, ClientId),
+    StartSuspendedPanes(ClientId),
+    FollowClient(Option<ClientId>, ClientId), // leader (None to stop following), follower
+    SetClientName(ClientId, String),
+    Redraw,
     MoveTabLeft(ClientId),
     MoveTabRight(ClientId),
     TerminalResize(Size),
@@ -262,6 +280,7 @@ pub enum ScreenInstruction {
         ClientId,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane_id to focus
+        bool,                // is_read_only
     ),
     RemoveClient(ClientId),
     AddOverlay(Overlay, ClientId),
@@ -356,7 +375,21 @@ pub enum ScreenInstruction {
         ClientTabIndexOrPaneId,
     ),
     DumpLayoutToHd,
-    RenameSession(String, ClientId), // String -> new name
+    RefreshForegroundCommands,
+    UpdateTerminalCwds(HashMap<u32, PathBuf>), // terminal pane id => cwd
+    UpdateTerminalCommands(HashMap<u32, Vec<String>>), // terminal pane id => foreground command
+    RenameSession(String, ClientId),           // String -> new name
+    UndoLayoutChange(ClientId),
+    RedoLayoutChange(ClientId),
+    ExpireClosedPanes,
+    CheckPaneDependencies,
+    StackPane(Direction, ClientId),
+    UnstackPanes(ClientId),
+    RemovePaneFromStack(ClientId),
+    TogglePaneSticky(ClientId),
+    ToggleScratchpad(String, ClientId),
+    AddScratchpadPane(String, PaneId),
+    CancelScratchpadSpawn(String),
 }
 
 impl From<&ScreenInstruction> for ScreenContext {
@@ -421,6 +454,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::ClearScreen(..) => ScreenContext::ClearScreen,
             ScreenInstruction::DumpScreen(..) => ScreenContext::DumpScreen,
             ScreenInstruction::DumpLayout(..) => ScreenContext::DumpLayout,
+            ScreenInstruction::SaveLayout(..) => ScreenContext::SaveLayout,
             ScreenInstruction::EditScrollback(..) => ScreenContext::EditScrollback,
             ScreenInstruction::ScrollUp(..) => ScreenContext::ScrollUp,
             ScreenInstruction::ScrollDown(..) => ScreenContext::ScrollDown,
@@ -439,6 +473,7 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::SetSelectable(..) => ScreenContext::SetSelectable,
             ScreenInstruction::ClosePane(..) => ScreenContext::ClosePane,
             ScreenInstruction::HoldPane(..) => ScreenContext::HoldPane,
+            ScreenInstruction::IndicateCommandRestart(..) => ScreenContext::IndicateCommandRestart,
             ScreenInstruction::UpdatePaneName(..) => ScreenContext::UpdatePaneName,
             ScreenInstruction::UndoRenamePane(..) => ScreenContext::UndoRenamePane,
             ScreenInstruction::NewTab(..) => ScreenContext::NewTab,
@@ -450,6 +485,11 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::GoToTabName(..) => ScreenContext::GoToTabName,
             ScreenInstruction::UpdateTabName(..) => ScreenContext::UpdateTabName,
             ScreenInstruction::UndoRenameTab(..) => ScreenContext::UndoRenameTab,
+            ScreenInstruction::SetTabCwd(..) => ScreenContext::SetTabCwd,
+            ScreenInstruction::StartSuspendedPanes(..) => ScreenContext::StartSuspendedPanes,
+            ScreenInstruction::FollowClient(..) => ScreenContext::FollowClient,
+            ScreenInstruction::SetClientName(..) => ScreenContext::SetClientName,
+            ScreenInstruction::Redraw => ScreenContext::Redraw,
             ScreenInstruction::MoveTabLeft(..) => ScreenContext::MoveTabLeft,
             ScreenInstruction::MoveTabRight(..) => ScreenContext::MoveTabRight,
             ScreenInstruction::TerminalResize(..) => ScreenContext::TerminalResize,
@@ -538,7 +578,23 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::ReplacePane(..) => ScreenContext::ReplacePane,
             ScreenInstruction::NewInPlacePluginPane(..) => ScreenContext::NewInPlacePluginPane,
             ScreenInstruction::DumpLayoutToHd => ScreenContext::DumpLayoutToHd,
+            ScreenInstruction::RefreshForegroundCommands => {
+                ScreenContext::RefreshForegroundCommands
+            },
+            ScreenInstruction::UpdateTerminalCwds(..) => ScreenContext::UpdateTerminalCwds,
+            ScreenInstruction::UpdateTerminalCommands(..) => ScreenContext::UpdateTerminalCommands,
             ScreenInstruction::RenameSession(..) => ScreenContext::RenameSession,
+            ScreenInstruction::UndoLayoutChange(..) => ScreenContext::UndoLayoutChange,
+            ScreenInstruction::RedoLayoutChange(..) => ScreenContext::RedoLayoutChange,
+            ScreenInstruction::StackPane(..) => ScreenContext::StackPane,
+            ScreenInstruction::UnstackPanes(..) => ScreenContext::UnstackPanes,
+            ScreenInstruction::RemovePaneFromStack(..) => ScreenContext::RemovePaneFromStack,
+            ScreenInstruction::TogglePaneSticky(..) => ScreenContext::TogglePaneSticky,
+            ScreenInstruction::ToggleScratchpad(..) => ScreenContext::ToggleScratchpad,
+            ScreenInstruction::AddScratchpadPane(..) => ScreenContext::AddScratchpadPane,
+            ScreenInstruction::CancelScratchpadSpawn(..) => ScreenContext::CancelScratchpadSpawn,
+            ScreenInstruction::ExpireClosedPanes => ScreenContext::ExpireClosedPanes,
+            ScreenInstruction::CheckPaneDependencies => ScreenContext::CheckPaneDependencies,
         }
     }
 }
@@ -573,6 +629,14 @@ impl CopyOptions {
     }
 }
 
+/// The output patterns panes wait for in the output of another pane (their `depends_on`), and
+/// those it already printed
+#[derive(Debug, Default)]
+struct WatchedPaneOutput {
+    pending_patterns: HashSet<String>,
+    matched_patterns: HashSet<String>,
+}
+
 /// A [`Screen`] holds multiple [`Tab`]s, each one holding multiple [`panes`](crate::client::panes).
 /// It only directly controls which tab is active, delegating the rest to the individual `Tab`.
 pub(crate) struct Screen {
@@ -594,8 +658,35 @@ pub(crate) struct Screen {
     connected_clients: Rc<RefCell<HashSet<ClientId>>>,
     /// The indices of this [`Screen`]'s active [`Tab`]s.
     active_tab_indices: BTreeMap<ClientId, usize>,
+    /// Floating panes shown on top of all tabs, shared with every [`Tab`]
+    sticky_panes: Rc<RefCell<StickyPanes>>,
+    /// Scratchpad definitions from the config, the panes of the ones that were already opened and
+    /// the ones whose pane is still being spawned
+    scratchpads: Scratchpads,
+    scratchpad_panes: BTreeMap<String, PaneId>,
+    pending_scratchpads: HashSet<String>,
+    hooks: Hooks, // the lifecycle hooks from the config
+    /// The cwds of the terminal panes as of the last time the session was serialized, reported
+    /// along with the rest of the pane state
+    terminal_cwds: HashMap<u32, PathBuf>,
+    /// Whether there are command panes waiting for other panes (their `depends_on`) to be ready,
+    /// the output patterns they wait for (compiled when their layout is applied), the panes whose
+    /// output is watched for those, when those were first seen running and when we next need to
+    /// check for a timeout
+    panes_wait_for_dependencies: bool,
+    pane_dependency_patterns: HashMap<String, Regex>,
+    watched_pane_outputs: HashMap<PaneId, WatchedPaneOutput>,
+    pane_dependency_start_times: HashMap<PaneId, Instant>,
+    next_pane_dependency_check: Option<Instant>,
     tab_history: BTreeMap<ClientId, Vec<usize>>,
     mode_info: BTreeMap<ClientId, ModeInfo>,
+    read_only_clients: HashSet<ClientId>,
+    /// Clients whose active tab and focused pane follow those of another client (follower =>
+    /// leader), and the names clients attached with
+    followed_clients: HashMap<ClientId, ClientId>,
+    client_names: HashMap<ClientId, String>,
+    /// Whether the commands in all terminal panes exited, as last reported to the server
+    all_terminal_panes_exited: bool,
     default_mode_info: ModeInfo, // TODO: restructure ModeInfo to prevent this duplication
     style: Style,
     draw_pane_frames: bool,
@@ -640,6 +731,8 @@ impl Screen {
         styled_underlines: bool,
         arrow_fonts: bool,
         layout_dir: Option<PathBuf>,
+        scratchpads: Scratchpads,
+        hooks: Hooks,
     ) -> Self {
         let session_name = mode_info.session_name.clone().unwrap_or_default();
         let session_info = SessionInfo::new(session_name.clone());
@@ -656,12 +749,30 @@ impl Screen {
             style: client_attributes.style,
             connected_clients: Rc::new(RefCell::new(HashSet::new())),
             active_tab_indices: BTreeMap::new(),
+            sticky_panes: Rc::new(RefCell::new(StickyPanes::new(
+                client_attributes.style,
+                session_is_mirrored,
+            ))),
+            scratchpads,
+            scratchpad_panes: BTreeMap::new(),
+            pending_scratchpads: HashSet::new(),
+            hooks,
+            terminal_cwds: HashMap::new(),
+            panes_wait_for_dependencies: false,
+            pane_dependency_patterns: HashMap::new(),
+            watched_pane_outputs: HashMap::new(),
+            pane_dependency_start_times: HashMap::new(),
+            next_pane_dependency_check: None,
             tabs: BTreeMap::new(),
             overlay: OverlayWindow::default(),
             terminal_emulator_colors: Rc::new(RefCell::new(Palette::default())),
             terminal_emulator_color_codes: Rc::new(RefCell::new(HashMap::new())),
             tab_history: BTreeMap::new(),
             mode_info: BTreeMap::new(),
+            read_only_clients: HashSet::new(),
+            followed_clients: HashMap::new(),
+            client_names: HashMap::new(),
+            all_terminal_panes_exited: false,
             default_mode_info: mode_info,
             draw_pane_frames,
             auto_layout,
@@ -969,7 +1080,25 @@ impl Screen {
         let err_context = || format!("failed to close tab at index {tab_index:?}");
 
         let mut tab_to_close = self.tabs.remove(&tab_index).with_context(err_context)?;
-        let pane_ids = tab_to_close.get_all_pane_ids();
+        let mut pane_ids = tab_to_close.get_all_pane_ids();
+        pane_ids.append(&mut tab_to_close.closed_pane_ids());
+        self.report_exited_panes();
+        self.run_hook(
+            HookEvent::TabClosed,
+            HookContext {
+                tab_name: Some(tab_to_close.name.clone()),
+                ..Default::default()
+            },
+        );
+        if self.tabs.is_empty() {
+            // sticky panes outlive the tabs they are shown on, but not the last one
+            let mut sticky_panes = self.sticky_panes.borrow_mut();
+            let sticky_pane_ids: Vec<PaneId> = sticky_panes.pane_ids().copied().collect();
+            for pane_id in sticky_pane_ids {
+                sticky_panes.remove_pane(pane_id);
+                pane_ids.push(pane_id);
+            }
+        }
         // below we don't check the result of sending the CloseTab instruction to the pty thread
         // because this might be happening when the app is closing, at which point the pty thread
         // has already closed and this would result in an error
@@ -1036,11 +1165,29 @@ impl Screen {
                 .with_context(err_context)?;
             tab.set_force_render();
         }
+        self.sticky_panes
+            .borrow_mut()
+            .resize(
+                new_screen_size,
+                &self.bus.senders,
+                &self.character_cell_size,
+            )
+            .with_context(err_context)?;
         self.log_and_report_session_state()
             .with_context(err_context)?;
         self.render(None).with_context(err_context)
     }
 
+    /// Clears the display of all clients and draws everything anew (eg. once the lock screen no
+    /// longer covers it)
+    pub fn redraw(&mut self) -> Result<()> {
+        for tab in self.tabs.values_mut() {
+            tab.clear_display_before_rendering();
+            tab.set_force_render();
+        }
+        self.render(None).context("failed to redraw screen")
+    }
+
     pub fn update_pixel_dimensions(&mut self, pixel_dimensions: PixelDimensions) {
         self.pixel_dimensions.merge(pixel_dimensions);
         if let Some(character_cell_size) = self.pixel_dimensions.character_cell_size {
@@ -1090,6 +1237,30 @@ impl Screen {
             self.character_cell_size.clone(),
             self.styled_underlines,
         );
+        {
+            // sticky panes are rendered first so that each tab crops its own panes underneath them
+            let mut sticky_panes = self.sticky_panes.borrow_mut();
+            if sticky_panes.take_layout_changed() {
+                for tab in self.tabs.values_mut() {
+                    tab.set_force_render();
+                }
+            }
+            if self
+                .tabs
+                .values()
+                .any(|tab| tab.will_clear_display_before_rendering())
+            {
+                sticky_panes.set_force_render();
+            }
+            sticky_panes
+                .render(
+                    &mut output,
+                    &self.connected_clients.borrow(),
+                    &self.mode_info,
+                    &self.default_mode_info,
+                )
+                .context(err_context)?;
+        }
         let mut tabs_to_close = vec![];
         for (tab_index, tab) in &mut self.tabs {
             if tab.has_selectable_tiled_panes() {
@@ -1206,6 +1377,7 @@ impl Screen {
             self.size,
             self.character_cell_size.clone(),
             self.sixel_image_store.clone(),
+            self.sticky_panes.clone(),
             self.bus
                 .os_input
                 .as_ref()
@@ -1232,6 +1404,200 @@ impl Screen {
         self.tabs.insert(tab_index, tab);
         Ok(())
     }
+    /// Starts the held commands of panes whose dependencies (their `depends_on`) are all ready
+    pub fn start_panes_with_ready_dependencies(&mut self) -> Result<()> {
+        if !self.panes_wait_for_dependencies {
+            return Ok(());
+        }
+        let mut waiting_panes = vec![];
+        for (tab_index, tab) in &self.tabs {
+            for (pane_id, depends_on) in tab.panes_waiting_for_dependencies() {
+                waiting_panes.push((*tab_index, pane_id, depends_on));
+            }
+        }
+        self.panes_wait_for_dependencies = !waiting_panes.is_empty();
+        for (tab_index, pane_id, depends_on) in waiting_panes {
+            let mut all_ready = true;
+            for dependency in &depends_on {
+                // we check all of them (rather than stopping at the first one that is not ready)
+                // so that their start times are recorded and their timeouts scheduled
+                all_ready &= self.pane_dependency_is_ready(tab_index, dependency);
+            }
+            if all_ready {
+                if let Some(tab) = self.tabs.get_mut(&tab_index) {
+                    tab.run_held_command_in_pane(pane_id)?;
+                }
+            }
+        }
+        if !self.panes_wait_for_dependencies {
+            self.pane_dependency_start_times.clear();
+            self.pane_dependency_patterns.clear();
+            for pane_id in std::mem::take(&mut self.watched_pane_outputs).into_keys() {
+                self.watch_pane_output(pane_id, false);
+            }
+        }
+        Ok(())
+    }
+    /// Compiles the output patterns the panes of the applied layouts wait for, once
+    fn compile_pane_dependency_patterns(&mut self) {
+        for tab in self.tabs.values() {
+            for (_pane_id, depends_on) in tab.panes_waiting_for_dependencies() {
+                for output in depends_on.into_iter().filter_map(|d| d.output) {
+                    if self.pane_dependency_patterns.contains_key(&output) {
+                        continue;
+                    }
+                    match Regex::new(&output) {
+                        Ok(pattern) => {
+                            self.pane_dependency_patterns.insert(output, pattern);
+                        },
+                        Err(e) => {
+                            log::error!("Invalid output pattern {:?} in depends_on: {}", output, e)
+                        },
+                    }
+                }
+            }
+        }
+    }
+    fn pane_dependency_is_ready(&mut self, tab_index: usize, dependency: &PaneDependency) -> bool {
+        // a pane in the same tab takes precedence over panes with the same name in other tabs
+        let dependency_pane = self
+            .tabs
+            .get(&tab_index)
+            .and_then(|tab| tab.get_pane_id_by_name(&dependency.pane_name))
+            .map(|pane_id| (tab_index, pane_id))
+            .or_else(|| {
+                self.tabs.iter().find_map(|(tab_index, tab)| {
+                    tab.get_pane_id_by_name(&dependency.pane_name)
+                        .map(|pane_id| (*tab_index, pane_id))
+                })
+            });
+        let Some((dependency_tab_index, dependency_pane_id)) = dependency_pane else {
+            return false;
+        };
+        let Some(pane) = self
+            .tabs
+            .get_mut(&dependency_tab_index)
+            .and_then(|tab| tab.get_pane_with_id_mut(dependency_pane_id))
+        else {
+            return false;
+        };
+        if pane.command_waiting_for_first_run().is_some() {
+            // the dependency itself did not start yet
+            return false;
+        }
+        let is_ready = match &dependency.output {
+            Some(output) => self.pane_output_matched(dependency_pane_id, output),
+            None => pane.exit_status() == Some(0),
+        };
+        if is_ready {
+            return true;
+        }
+        let started_at = *self
+            .pane_dependency_start_times
+            .entry(dependency_pane_id)
+            .or_insert_with(Instant::now);
+        match dependency.timeout {
+            Some(timeout) => {
+                let deadline = started_at + Duration::from_secs(timeout);
+                if deadline <= Instant::now() {
+                    return true;
+                }
+                self.schedule_pane_dependency_check(deadline);
+                false
+            },
+            None => false,
+        }
+    }
+    fn pane_output_matched(&mut self, pane_id: PaneId, output: &str) -> bool {
+        let Some(pattern) = self.pane_dependency_patterns.get(output) else {
+            // an invalid pattern never matches
+            return false;
+        };
+        let watched_output = self.watched_pane_outputs.entry(pane_id).or_default();
+        if watched_output.matched_patterns.contains(output) {
+            return true;
+        }
+        if watched_output.pending_patterns.contains(output) {
+            return false;
+        }
+        // the first time, we check what the pane already printed - from then on only the lines it
+        // receives are checked (in match_received_pane_output)
+        let Some(pane) = self
+            .tabs
+            .values_mut()
+            .find_map(|tab| tab.get_pane_with_id_mut(pane_id))
+        else {
+            return false;
+        };
+        if pane.output_matches(pattern) {
+            watched_output.matched_patterns.insert(output.to_owned());
+            true
+        } else {
+            pane.watch_output(true);
+            watched_output.pending_patterns.insert(output.to_owned());
+            false
+        }
+    }
+    /// Matches the lines a pane received since it was last checked against the patterns that are
+    /// still waited for in its output
+    fn match_received_pane_output(&mut self, pane_id: PaneId) {
+        let Some(watched_output) = self.watched_pane_outputs.get_mut(&pane_id) else {
+            return;
+        };
+        if watched_output.pending_patterns.is_empty() {
+            return;
+        }
+        let Some(pane) = self
+            .tabs
+            .values_mut()
+            .find_map(|tab| tab.get_pane_with_id_mut(pane_id))
+        else {
+            return;
+        };
+        let received_lines = pane.take_received_lines();
+        let matched_patterns: Vec<String> = watched_output
+            .pending_patterns
+            .iter()
+            .filter(|output| {
+                self.pane_dependency_patterns
+                    .get(*output)
+                    .map(|pattern| received_lines.iter().any(|line| pattern.is_match(line)))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect();
+        for output in matched_patterns {
+            watched_output.pending_patterns.remove(&output);
+            watched_output.matched_patterns.insert(output);
+        }
+        if watched_output.pending_patterns.is_empty() {
+            pane.watch_output(false);
+        }
+    }
+    fn watch_pane_output(&mut self, pane_id: PaneId, should_watch: bool) {
+        if let Some(pane) = self
+            .tabs
+            .values_mut()
+            .find_map(|tab| tab.get_pane_with_id_mut(pane_id))
+        {
+            pane.watch_output(should_watch);
+        }
+    }
+    fn schedule_pane_dependency_check(&mut self, deadline: Instant) {
+        let is_earlier_than_scheduled = self
+            .next_pane_dependency_check
+            .map(|next_check| deadline < next_check)
+            .unwrap_or(true);
+        if is_earlier_than_scheduled {
+            self.next_pane_dependency_check = Some(deadline);
+            let _ = self
+                .bus
+                .senders
+                .send_to_background_jobs(BackgroundJob::CheckPaneDependencies(
+                    deadline.saturating_duration_since(Instant::now()),
+                ));
+        }
+    }
     pub fn apply_layout(
         &mut self,
         layout: TiledPaneLayout,
@@ -1256,6 +1622,11 @@ impl Screen {
             client_id
         };
         let err_context = || format!("failed to apply layout for tab {tab_index:?}",);
+        let new_terminal_pane_ids: Vec<PaneId> = new_terminal_ids
+            .iter()
+            .chain(new_floating_terminal_ids.iter())
+            .map(|(terminal_id, _)| PaneId::Terminal(*terminal_id))
+            .collect();
 
         // move the relevant clients out of the current tab and place them in the new one
         let drained_clients = if self.session_is_mirrored {
@@ -1292,7 +1663,7 @@ impl Screen {
             .get_mut(&tab_index)
             .context("couldn't find tab with index {tab_index}")
             .and_then(|tab| {
-                tab.apply_layout(
+                let sticky_pane_ids = tab.apply_layout(
                     layout,
                     floating_panes_layout,
                     new_terminal_ids,
@@ -1305,6 +1676,16 @@ impl Screen {
                 if let Some(drained_clients) = drained_clients {
                     tab.add_multiple_clients(drained_clients)?;
                 }
+                Ok(sticky_pane_ids)
+            })
+            .and_then(|sticky_pane_ids| {
+                // panes the layout marks as sticky leave the tab for the session-wide container
+                let tab = self.tabs.get_mut(&tab_index).with_context(err_context)?;
+                for pane_id in sticky_pane_ids {
+                    if let Some(pane) = tab.extract_pane(pane_id, None) {
+                        self.sticky_panes.borrow_mut().add_pane(pane_id, pane, None);
+                    }
+                }
                 Ok(())
             })
             .with_context(err_context)?;
@@ -1314,6 +1695,18 @@ impl Screen {
             self.add_client(client_id).with_context(err_context)?;
         }
 
+        let tab_name = self.tabs.get(&tab_index).map(|tab| tab.name.clone());
+        self.run_hook(
+            HookEvent::TabCreated,
+            HookContext {
+                tab_name,
+                ..Default::default()
+            },
+        );
+        for pane_id in new_terminal_pane_ids {
+            self.report_pane_opened(pane_id);
+        }
+
         self.log_and_report_session_state()
             .and_then(|_| self.render(None))
             .with_context(err_context)
@@ -1366,6 +1759,10 @@ impl Screen {
         if self.tab_history.contains_key(&client_id) {
             self.tab_history.remove(&client_id);
         }
+        self.read_only_clients.remove(&client_id);
+        self.client_names.remove(&client_id);
+        self.followed_clients
+            .retain(|follower, leader| *follower != client_id && *leader != client_id);
         self.connected_clients.borrow_mut().remove(&client_id);
         self.log_and_report_session_state()
             .with_context(err_context)
@@ -1394,6 +1791,7 @@ impl Screen {
                 other_focused_clients: all_focused_clients,
                 active_swap_layout_name,
                 is_swap_layout_dirty,
+                cwd: tab.default_cwd().cloned(),
             };
             tab_infos_for_screen_state.insert(tab.position, tab_info_for_screen);
         }
@@ -1424,6 +1822,7 @@ impl Screen {
                     other_focused_clients,
                     active_swap_layout_name,
                     is_swap_layout_dirty,
+                    cwd: tab.default_cwd().cloned(),
                 };
                 plugin_tab_updates.push(tab_info_for_plugins);
             }
@@ -1437,8 +1836,21 @@ impl Screen {
     }
     fn generate_and_report_pane_state(&mut self) -> Result<PaneManifest> {
         let mut pane_manifest = PaneManifest::default();
+        // sticky panes are shown on every tab, but are only reported once: with the tab the first
+        // client is looking at
+        let sticky_panes_tab_index = self
+            .get_first_client_id()
+            .and_then(|client_id| self.active_tab_indices.get(&client_id).copied())
+            .or_else(|| self.tabs.keys().next().copied());
         for tab in self.tabs.values() {
-            pane_manifest.panes.insert(tab.position, tab.pane_infos());
+            let mut pane_infos = tab.pane_infos();
+            if Some(tab.index) == sticky_panes_tab_index {
+                pane_infos.append(&mut self.sticky_panes.borrow().pane_info());
+            }
+            for pane_info in pane_infos.iter_mut().filter(|p| !p.is_plugin) {
+                pane_info.cwd = self.terminal_cwds.get(&pane_info.id).cloned();
+            }
+            pane_manifest.panes.insert(tab.position, pane_infos);
         }
         self.bus
             .senders
@@ -1471,6 +1883,16 @@ impl Screen {
             connected_clients: self.active_tab_indices.keys().len(),
             is_current_session: true,
             available_layouts,
+            clients: self
+                .active_tab_indices
+                .keys()
+                .map(|client_id| ConnectedClientInfo {
+                    client_id: *client_id,
+                    name: self.client_names.get(client_id).cloned(),
+                    following: self.followed_clients.get(client_id).copied(),
+                    is_read_only: self.read_only_clients.contains(client_id),
+                })
+                .collect(),
         };
         self.bus
             .senders
@@ -1496,6 +1918,22 @@ impl Screen {
 
         Ok(())
     }
+    pub fn refresh_foreground_commands(&mut self) -> Result<()> {
+        // finding the foreground commands means going over all the processes on the machine, so
+        // we only do this if a swap layout depends on them
+        if !self.tabs.values().any(|tab| tab.has_command_constraints()) {
+            return Ok(());
+        }
+        let terminal_ids = self
+            .tabs
+            .values()
+            .flat_map(|tab| tab.get_terminal_pane_ids())
+            .collect();
+        self.bus
+            .senders
+            .send_to_pty(PtyInstruction::QueryForegroundCommands(terminal_ids))
+            .with_context(|| format!("failed to refresh foreground commands"))
+    }
     pub fn update_session_infos(
         &mut self,
         new_session_infos: BTreeMap<String, SessionInfo>,
@@ -1679,6 +2117,7 @@ impl Screen {
         if mode_info.session_name.as_ref() != Some(&self.session_name) {
             mode_info.session_name = Some(self.session_name.clone());
         }
+        mode_info.is_read_only = self.read_only_clients.contains(&client_id);
         let previous_mode = self
             .mode_info
             .get(&client_id)
@@ -1931,6 +2370,284 @@ impl Screen {
         };
         Ok(())
     }
+    pub fn follow_client(&mut self, leader: Option<ClientId>, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to have client {client_id} follow {leader:?}");
+        // actions from the cli come from a client that isn't connected to the screen
+        let follower = if self.active_tab_indices.contains_key(&client_id) {
+            client_id
+        } else {
+            match self.get_first_client_id() {
+                Some(client_id) => client_id,
+                None => return Ok(()),
+            }
+        };
+        match leader {
+            Some(leader) if leader == follower => {
+                log::error!("Client {} cannot follow itself", follower);
+                return Ok(());
+            },
+            Some(leader) if !self.active_tab_indices.contains_key(&leader) => {
+                log::error!("Cannot follow client {}, it is not connected", leader);
+                return Ok(());
+            },
+            Some(leader) => {
+                self.followed_clients.insert(follower, leader);
+            },
+            None => {
+                self.followed_clients.remove(&follower);
+            },
+        }
+        self.update_followers().with_context(err_context)?;
+        self.log_and_report_session_state()
+            .with_context(err_context)
+    }
+    /// Let the server know once the commands in all terminal panes exited, or once one of them
+    /// runs again (for the `kill_session_when_all_panes_exited` option). This is called whenever
+    /// a pane exits or closes.
+    pub fn report_exited_panes(&mut self) {
+        let mut terminal_panes = self
+            .tabs
+            .values()
+            .flat_map(|tab| tab.get_tiled_panes().chain(tab.get_floating_panes()))
+            .filter(|(pane_id, _)| matches!(pane_id, PaneId::Terminal(_)))
+            .peekable();
+        let all_terminal_panes_exited =
+            terminal_panes.peek().is_some() && terminal_panes.all(|(_, pane)| pane.exited());
+        if all_terminal_panes_exited != self.all_terminal_panes_exited {
+            self.all_terminal_panes_exited = all_terminal_panes_exited;
+            let _ = self
+                .bus
+                .senders
+                .send_to_server(ServerInstruction::AllPanesExited(all_terminal_panes_exited))
+                .context("failed to report exited panes to server");
+        }
+    }
+    /// Runs the hooks of this event if any are configured, called where the event happens
+    fn run_hook(&self, event: HookEvent, context: HookContext) {
+        if !self.hooks.contains(event) {
+            return;
+        }
+        let _ = self
+            .bus
+            .senders
+            .send_to_server(ServerInstruction::RunHooks(event, context))
+            .context("failed to run hooks");
+    }
+    fn tab_name_of_pane(&self, pane_id: PaneId) -> Option<String> {
+        self.tabs
+            .values()
+            .find(|tab| tab.get_all_pane_ids().contains(&pane_id))
+            .map(|tab| tab.name.clone())
+    }
+    pub fn report_pane_opened(&self, pane_id: PaneId) {
+        let PaneId::Terminal(terminal_id) = pane_id else {
+            return;
+        };
+        if !self.hooks.contains(HookEvent::PaneOpened) {
+            return;
+        }
+        let context = HookContext {
+            tab_name: self.tab_name_of_pane(pane_id),
+            pane_id: Some(terminal_id),
+            ..Default::default()
+        };
+        self.run_hook(HookEvent::PaneOpened, context);
+    }
+    pub fn report_command_pane_exited(&self, pane_id: PaneId, exit_status: Option<i32>) {
+        let PaneId::Terminal(terminal_id) = pane_id else {
+            return;
+        };
+        if !self.hooks.contains(HookEvent::CommandPaneExited) {
+            return;
+        }
+        let context = HookContext {
+            tab_name: self.tab_name_of_pane(pane_id),
+            pane_id: Some(terminal_id),
+            exit_code: exit_status,
+            ..Default::default()
+        };
+        self.run_hook(HookEvent::CommandPaneExited, context);
+    }
+    /// Move the clients following other clients to the tab and pane their leader is focused on
+    pub fn update_followers(&mut self) -> Result<()> {
+        if self.followed_clients.is_empty() {
+            return Ok(());
+        }
+        let err_context = || format!("failed to update clients following other clients");
+        let mut should_render = false;
+        let followed_clients: Vec<(ClientId, ClientId)> = self
+            .followed_clients
+            .iter()
+            .map(|(follower, leader)| (*follower, *leader))
+            .collect();
+        for (follower, leader) in followed_clients {
+            let (Some(leader_tab_index), Some(follower_tab_index)) = (
+                self.active_tab_indices.get(&leader).copied(),
+                self.active_tab_indices.get(&follower).copied(),
+            ) else {
+                continue;
+            };
+            if leader_tab_index != follower_tab_index {
+                if let Some(leader_tab_position) =
+                    self.tabs.get(&leader_tab_index).map(|tab| tab.position)
+                {
+                    self.switch_active_tab(leader_tab_position, None, true, follower)
+                        .with_context(err_context)?;
+                }
+            }
+            if let Some(tab) = self.tabs.get_mut(&leader_tab_index) {
+                match (
+                    tab.get_active_pane_id(leader),
+                    tab.get_active_pane_id(follower),
+                ) {
+                    (Some(leader_pane_id), follower_pane_id)
+                        if follower_pane_id != Some(leader_pane_id) =>
+                    {
+                        tab.focus_pane_with_id(leader_pane_id, false, follower)
+                            .with_context(err_context)?;
+                        should_render = true;
+                    },
+                    _ => {},
+                }
+            }
+        }
+        if should_render {
+            self.render(None).with_context(err_context)?;
+        }
+        Ok(())
+    }
+    pub fn toggle_active_pane_sticky(&mut self, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to toggle sticky pane for client {client_id}");
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else {
+            match self.get_first_client_id() {
+                Some(client_id) => client_id,
+                None => {
+                    log::error!("No client ids in screen found");
+                    return Ok(());
+                },
+            }
+        };
+        let focused_sticky_pane_id = self.sticky_panes.borrow().focused_pane_id(client_id);
+        if let Some(pane_id) = focused_sticky_pane_id {
+            // no longer sticky, the pane stays in the tab the client is looking at
+            let pane = self.sticky_panes.borrow_mut().remove_pane(pane_id);
+            if let Some(pane) = pane {
+                self.get_active_tab_mut(client_id)
+                    .with_context(err_context)?
+                    .add_floating_pane_in_place(pane, pane_id, client_id)
+                    .with_context(err_context)?;
+            }
+            return Ok(());
+        }
+        let top_sticky_pane_id = self.sticky_panes.borrow().top_pane_id();
+        let sticky_panes = self.sticky_panes.clone();
+        let active_tab = self
+            .get_active_tab_mut(client_id)
+            .with_context(err_context)?;
+        let Some(active_pane_id) = active_tab.get_active_pane_id(client_id) else {
+            return Ok(());
+        };
+        if active_tab.has_floating_pane(&active_pane_id) {
+            if let Some(pane) = active_tab.extract_pane(active_pane_id, Some(client_id)) {
+                sticky_panes
+                    .borrow_mut()
+                    .add_pane(active_pane_id, pane, Some(client_id));
+            }
+        } else if let Some(top_sticky_pane_id) = top_sticky_pane_id {
+            // a tiled pane can't be made sticky, but this is how the sticky panes are reached
+            // from the keyboard
+            sticky_panes
+                .borrow_mut()
+                .focus_pane(top_sticky_pane_id, client_id);
+        } else {
+            self.bus
+                .senders
+                .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                    vec![active_pane_id],
+                    "Only floating panes can be sticky".into(),
+                ))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+    pub fn toggle_scratchpad(&mut self, name: String, client_id: ClientId) -> Result<()> {
+        let err_context = || format!("failed to toggle scratchpad {name} for client {client_id}");
+        let client_id = if self.get_active_tab(client_id).is_ok() {
+            client_id
+        } else {
+            match self.get_first_client_id() {
+                Some(client_id) => client_id,
+                None => {
+                    log::error!("No client ids in screen found");
+                    return Ok(());
+                },
+            }
+        };
+        let tabs = &self.tabs;
+        self.scratchpad_panes.retain(|_name, pane_id| {
+            tabs.values().any(|tab| {
+                tab.has_non_suppressed_pane_with_pid(pane_id) || tab.has_suppressed_pane(pane_id)
+            })
+        });
+        if self.pending_scratchpads.contains(&name) {
+            // toggled again before its pane was spawned, this would open a second one
+            return Ok(());
+        }
+        let Some(pane_id) = self.scratchpad_panes.get(&name).copied() else {
+            // first use, the pane will be registered with AddScratchpadPane once it is spawned
+            match self.scratchpads.get(&name) {
+                Some(scratchpad) => {
+                    self.bus
+                        .senders
+                        .send_to_pty(PtyInstruction::SpawnScratchpad(
+                            name.clone(),
+                            scratchpad.command.clone(),
+                            scratchpad.coordinates.clone(),
+                            client_id,
+                        ))
+                        .with_context(err_context)?;
+                    self.pending_scratchpads.insert(name);
+                },
+                None => log::error!("Could not find a scratchpad named {name} in the config"),
+            }
+            return Ok(());
+        };
+        let active_tab = self.get_active_tab(client_id).with_context(err_context)?;
+        let is_visible_in_active_tab = active_tab.has_non_suppressed_pane_with_pid(&pane_id)
+            && (!active_tab.has_floating_pane(&pane_id) || active_tab.are_floating_panes_visible());
+        if is_visible_in_active_tab {
+            // suppressed panes keep receiving the output of their process while hidden
+            self.get_active_tab_mut(client_id)
+                .with_context(err_context)?
+                .suppress_pane(pane_id, client_id);
+        } else {
+            let Some(pane) = self
+                .tabs
+                .values_mut()
+                .find(|tab| {
+                    tab.has_non_suppressed_pane_with_pid(&pane_id)
+                        || tab.has_suppressed_pane(&pane_id)
+                })
+                .and_then(|tab| tab.extract_pane(pane_id, Some(client_id)))
+            else {
+                return Ok(());
+            };
+            let floating_pane_coordinates = self
+                .scratchpads
+                .get(&name)
+                .and_then(|scratchpad| scratchpad.coordinates.clone());
+            let active_tab = self
+                .get_active_tab_mut(client_id)
+                .with_context(err_context)?;
+            active_tab.show_floating_panes();
+            active_tab
+                .add_floating_pane(pane, pane_id, floating_pane_coordinates, Some(client_id))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
     pub fn break_pane(
         &mut self,
         default_shell: Option<TerminalAction>,
@@ -2165,10 +2882,11 @@ impl Screen {
                         } else {
                             None
                         },
+                        false, // tiled panes are never sticky
                     )
                 })
                 .collect();
-            let floating_panes: Vec<PaneLayoutMetadata> = tab
+            let mut floating_panes: Vec<PaneLayoutMetadata> = tab
                 .get_floating_panes()
                 .map(|(pane_id, p)| {
                     // here we look to see if this pane triggers any suppressed pane,
@@ -2196,13 +2914,35 @@ impl Screen {
                         } else {
                             None
                         },
+                        false,
                     )
                 })
                 .collect();
+            if tab_index == 0 {
+                // sticky panes don't belong to any tab, they are restored from the first one
+                for (pane_id, p) in self.sticky_panes.borrow().panes() {
+                    floating_panes.push(PaneLayoutMetadata::new(
+                        *pane_id,
+                        p.position_and_size(),
+                        false,
+                        p.invoked_with().clone(),
+                        p.custom_title(),
+                        false,
+                        if self.serialize_pane_viewport {
+                            p.serialize(self.scrollback_lines_to_serialize)
+                        } else {
+                            None
+                        },
+                        true,
+                    ));
+                }
+            }
             session_layout_metadata.add_tab(
                 tab.name.clone(),
                 tab_is_focused,
                 hide_floating_panes,
+                tab.default_cwd().cloned(),
+                tab.default_env().clone(),
                 tiled_panes,
                 floating_panes,
             );
@@ -2237,6 +2977,8 @@ pub(crate) fn screen_thread_main(
     config_options: Box<Options>,
     debug: bool,
     default_layout: Box<Layout>,
+    scratchpads: Box<Scratchpads>,
+    hooks: Box<Hooks>,
 ) -> Result<()> {
     let arrow_fonts = !config_options.simplified_ui.unwrap_or_default();
     let draw_pane_frames = config_options.pane_frames.unwrap_or(true);
@@ -2284,6 +3026,8 @@ pub(crate) fn screen_thread_main(
         styled_underlines,
         arrow_fonts,
         layout_dir,
+        *scratchpads,
+        *hooks,
     );
 
     let mut pending_tab_ids: HashSet<usize> = HashSet::new();
@@ -2303,14 +3047,23 @@ pub(crate) fn screen_thread_main(
 
         match event {
             ScreenInstruction::PtyBytes(pid, vte_bytes) => {
+                let is_sticky_pane = screen
+                    .sticky_panes
+                    .borrow()
+                    .contains(&PaneId::Terminal(pid));
                 let all_tabs = screen.get_tabs_mut();
                 for tab in all_tabs.values_mut() {
-                    if tab.has_terminal_pid(pid) {
+                    // any tab can handle the bytes of a sticky pane
+                    if is_sticky_pane || tab.has_terminal_pid(pid) {
                         tab.handle_pty_bytes(pid, vte_bytes)
                             .context("failed to process pty bytes")?;
                         break;
                     }
                 }
+                if screen.panes_wait_for_dependencies {
+                    screen.match_received_pane_output(PaneId::Terminal(pid));
+                    screen.start_panes_with_ready_dependencies()?;
+                }
             },
             ScreenInstruction::PluginBytes(mut plugin_render_assets) => {
                 for plugin_render_asset in plugin_render_assets.iter_mut() {
@@ -2318,9 +3071,13 @@ pub(crate) fn screen_thread_main(
                     let client_id = plugin_render_asset.client_id;
                     let vte_bytes = plugin_render_asset.bytes.drain(..).collect();
 
+                    let is_sticky_pane = screen
+                        .sticky_panes
+                        .borrow()
+                        .contains(&PaneId::Plugin(plugin_id));
                     let all_tabs = screen.get_tabs_mut();
                     for tab in all_tabs.values_mut() {
-                        if tab.has_plugin(plugin_id) {
+                        if is_sticky_pane || tab.has_plugin(plugin_id) {
                             tab.handle_plugin_bytes(plugin_id, client_id, vte_bytes)
                                 .context("failed to process plugin bytes")?;
                             break;
@@ -2388,6 +3145,7 @@ pub(crate) fn screen_thread_main(
                         log::error!("cannot open a pane with a pane id??");
                     },
                 };
+                screen.report_pane_opened(pid);
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
 
@@ -2396,6 +3154,7 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::OpenInPlaceEditor(pid, client_id) => {
                 active_tab!(screen, client_id, |tab: &mut Tab| tab
                     .replace_active_pane_with_editor_pane(pid, client_id), ?);
+                screen.report_pane_opened(pid);
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
 
@@ -2429,6 +3188,7 @@ pub(crate) fn screen_thread_main(
                     |tab: &mut Tab, client_id: ClientId| tab.horizontal_split(pid, initial_pane_title, client_id),
                     ?
                 );
+                screen.report_pane_opened(pid);
                 if let Some(hold_for_command) = hold_for_command {
                     let is_first_run = true;
                     active_tab_and_connected_client_id!(
@@ -2458,6 +3218,7 @@ pub(crate) fn screen_thread_main(
                     |tab: &mut Tab, client_id: ClientId| tab.vertical_split(pid, initial_pane_title, client_id),
                     ?
                 );
+                screen.report_pane_opened(pid);
                 if let Some(hold_for_command) = hold_for_command {
                     let is_first_run = true;
                     active_tab_and_connected_client_id!(
@@ -2630,6 +3391,46 @@ pub(crate) fn screen_thread_main(
                     ))
                     .with_context(err_context)?;
             },
+            ScreenInstruction::SaveLayout(mut save_layout_request, default_shell, client_id) => {
+                let err_context = || format!("Failed to save layout");
+                let mut session_layout_metadata = screen.get_layout_metadata(default_shell);
+                let mut error = None;
+                if let Some(tab) = &save_layout_request.tab {
+                    if !session_layout_metadata.retain_tab(tab) {
+                        error = Some(format!("No tab at position or with name: {}", tab));
+                    }
+                }
+                save_layout_request.layout_dir =
+                    screen.layout_dir.clone().or_else(|| default_layout_dir());
+                if save_layout_request.layout_dir.is_none() {
+                    error = Some("Could not find a layout folder to save the layout in".to_owned());
+                }
+                match error {
+                    Some(error) => {
+                        log::error!("{}", error);
+                        if let Some(os_input) = &mut screen.bus.os_input {
+                            let _ = os_input.send_to_client(
+                                client_id,
+                                ServerToClientMsg::LogError(vec![error]),
+                            );
+                        }
+                    },
+                    None => {
+                        if save_layout_request.strip_contents {
+                            session_layout_metadata.strip_pane_contents();
+                        }
+                        screen
+                            .bus
+                            .senders
+                            .send_to_plugin(PluginInstruction::SaveLayout(
+                                session_layout_metadata,
+                                save_layout_request,
+                                client_id,
+                            ))
+                            .with_context(err_context)?;
+                    },
+                }
+            },
             ScreenInstruction::EditScrollback(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2814,6 +3615,7 @@ pub(crate) fn screen_thread_main(
                     client_id,
                     |tab: &mut Tab, client_id: ClientId| tab.close_focused_pane(client_id), ?
                 );
+                screen.report_exited_panes();
                 screen.render(None)?;
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
@@ -2843,19 +3645,26 @@ pub(crate) fn screen_thread_main(
                         ));
                     },
                     None => {
+                        let is_sticky_pane = screen.sticky_panes.borrow().contains(&id);
                         for tab in screen.tabs.values_mut() {
-                            if tab.get_all_pane_ids().contains(&id) {
+                            if is_sticky_pane
+                                || tab.get_all_pane_ids().contains(&id)
+                                || tab.has_closed_pane(&id)
+                            {
                                 tab.close_pane(id, false, None);
                                 break;
                             }
                         }
                     },
                 }
+                screen.pane_dependency_start_times.remove(&id);
+                screen.report_exited_panes();
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
             },
             ScreenInstruction::HoldPane(id, exit_status, run_command, tab_index, client_id) => {
                 let is_first_run = false;
+                screen.report_command_pane_exited(id, exit_status);
                 match (client_id, tab_index) {
                     (Some(client_id), _) => {
                         active_tab!(screen, client_id, |tab: &mut Tab| tab.hold_pane(
@@ -2873,17 +3682,30 @@ pub(crate) fn screen_thread_main(
                         ),
                     },
                     _ => {
+                        let is_sticky_pane = screen.sticky_panes.borrow().contains(&id);
                         for tab in screen.tabs.values_mut() {
-                            if tab.get_all_pane_ids().contains(&id) {
+                            if is_sticky_pane || tab.get_all_pane_ids().contains(&id) {
                                 tab.hold_pane(id, exit_status, is_first_run, run_command);
                                 break;
                             }
                         }
                     },
                 }
+                screen.report_exited_panes();
+                screen.start_panes_with_ready_dependencies()?;
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
             },
+            ScreenInstruction::IndicateCommandRestart(id, restart_count, exit_status) => {
+                let is_sticky_pane = screen.sticky_panes.borrow().contains(&id);
+                for tab in screen.tabs.values_mut() {
+                    if is_sticky_pane || tab.get_all_pane_ids().contains(&id) {
+                        tab.indicate_command_restart(id, restart_count, exit_status);
+                        break;
+                    }
+                }
+                screen.render(None)?;
+            },
             ScreenInstruction::UpdatePaneName(c, client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2903,6 +3725,91 @@ pub(crate) fn screen_thread_main(
                 screen.render(None)?;
                 screen.unblock_input()?;
             },
+            ScreenInstruction::UndoLayoutChange(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.undo_layout_change(client_id), ?
+                );
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::RedoLayoutChange(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.redo_layout_change(client_id), ?
+                );
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::StackPane(direction, client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.stack_active_pane(client_id, direction), ?
+                );
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::UnstackPanes(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.unstack_active_pane(client_id), ?
+                );
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::RemovePaneFromStack(client_id) => {
+                active_tab_and_connected_client_id!(
+                    screen,
+                    client_id,
+                    |tab: &mut Tab, client_id: ClientId| tab.remove_active_pane_from_stack(client_id), ?
+                );
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::TogglePaneSticky(client_id) => {
+                screen.toggle_active_pane_sticky(client_id).non_fatal();
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::ToggleScratchpad(name, client_id) => {
+                screen.toggle_scratchpad(name, client_id).non_fatal();
+                screen.render(None)?;
+                screen.log_and_report_session_state()?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::AddScratchpadPane(name, pane_id) => {
+                screen.pending_scratchpads.remove(&name);
+                screen.scratchpad_panes.insert(name, pane_id);
+            },
+            ScreenInstruction::CancelScratchpadSpawn(name) => {
+                screen.pending_scratchpads.remove(&name);
+            },
+            ScreenInstruction::ExpireClosedPanes => {
+                for tab in screen.tabs.values_mut() {
+                    tab.expire_closed_panes()?;
+                }
+            },
+            ScreenInstruction::CheckPaneDependencies => {
+                if screen
+                    .next_pane_dependency_check
+                    .map(|next_check| next_check <= Instant::now())
+                    .unwrap_or(false)
+                {
+                    screen.next_pane_dependency_check = None;
+                }
+                screen.start_panes_with_ready_dependencies()?;
+                screen.render(None)?;
+            },
             ScreenInstruction::ToggleActiveTerminalFullscreen(client_id) => {
                 active_tab_and_connected_client_id!(
                     screen,
@@ -2980,6 +3887,9 @@ pub(crate) fn screen_thread_main(
                     tab_index,
                     client_id,
                 )?;
+                screen.panes_wait_for_dependencies = true;
+                screen.compile_pane_dependency_patterns();
+                screen.start_panes_with_ready_dependencies()?;
                 pending_tab_ids.remove(&tab_index);
                 if pending_tab_ids.is_empty() {
                     for (tab_index, client_id) in pending_tab_switches.drain() {
@@ -3091,6 +4001,29 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.render(None)?;
             },
+            ScreenInstruction::SetTabCwd(cwd, client_id) => {
+                active_tab_and_connected_client_id!(screen, client_id, |tab: &mut Tab, _client_id: ClientId| tab
+                    .set_default_cwd(cwd), ?);
+                screen.unblock_input()?;
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::FollowClient(leader, client_id) => {
+                screen.follow_client(leader, client_id)?;
+                screen.unblock_input()?;
+            },
+            ScreenInstruction::Redraw => {
+                screen.redraw()?;
+            },
+            ScreenInstruction::SetClientName(client_id, name) => {
+                screen.client_names.insert(client_id, name);
+                screen.log_and_report_session_state()?;
+            },
+            ScreenInstruction::StartSuspendedPanes(client_id) => {
+                active_tab_and_connected_client_id!(screen, client_id, |tab: &mut Tab, _client_id: ClientId| tab
+                    .start_suspended_panes(), ?);
+                screen.unblock_input()?;
+                screen.render(None)?;
+            },
             ScreenInstruction::MoveTabLeft(client_id) => {
                 screen.move_active_tab_to_left(client_id)?;
                 screen.unblock_input()?;
@@ -3203,8 +4136,16 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
                 screen.render(None)?;
             },
-            ScreenInstruction::AddClient(client_id, tab_position_to_focus, pane_id_to_focus) => {
+            ScreenInstruction::AddClient(
+                client_id,
+                tab_position_to_focus,
+                pane_id_to_focus,
+                is_read_only,
+            ) => {
                 screen.add_client(client_id)?;
+                if is_read_only {
+                    screen.read_only_clients.insert(client_id);
+                }
                 let pane_id = pane_id_to_focus.map(|(pane_id, is_plugin)| {
                     if is_plugin {
                         PaneId::Plugin(pane_id)
@@ -3333,7 +4274,10 @@ pub(crate) fn screen_thread_main(
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.previous_swap_layout(Some(client_id)),
+                    |tab: &mut Tab, client_id: ClientId| {
+                        tab.record_layout_history();
+                        tab.previous_swap_layout(Some(client_id))
+                    },
                     ?
                 );
                 screen.render(None)?;
@@ -3344,7 +4288,10 @@ pub(crate) fn screen_thread_main(
                 active_tab_and_connected_client_id!(
                     screen,
                     client_id,
-                    |tab: &mut Tab, client_id: ClientId| tab.next_swap_layout(Some(client_id), true),
+                    |tab: &mut Tab, client_id: ClientId| {
+                        tab.record_layout_history();
+                        tab.next_swap_layout(Some(client_id), true)
+                    },
                     ?
                 );
                 screen.render(None)?;
@@ -3827,6 +4774,7 @@ pub(crate) fn screen_thread_main(
                     pane_title,
                     client_id_tab_index_or_pane_id,
                 )?;
+                screen.report_pane_opened(new_pane_id);
 
                 screen.unblock_input()?;
                 screen.log_and_report_session_state()?;
@@ -3838,6 +4786,24 @@ pub(crate) fn screen_thread_main(
                     screen.dump_layout_to_hd()?;
                 }
             },
+            ScreenInstruction::UpdateTerminalCwds(terminal_cwds) => {
+                if screen.terminal_cwds != terminal_cwds {
+                    screen.terminal_cwds = terminal_cwds;
+                    screen.log_and_report_session_state()?;
+                }
+            },
+            ScreenInstruction::RefreshForegroundCommands => {
+                screen.refresh_foreground_commands()?;
+            },
+            ScreenInstruction::UpdateTerminalCommands(terminal_commands) => {
+                let mut should_render = false;
+                for tab in screen.tabs.values_mut() {
+                    should_render |= tab.update_foreground_commands(&terminal_commands)?;
+                }
+                if should_render {
+                    screen.render(None)?;
+                }
+            },
             ScreenInstruction::RenameSession(name, client_id) => {
                 if screen.session_infos_on_machine.contains_key(&name) {
                     let error_text = "A session by this name already exists.";
@@ -3902,6 +4868,12 @@ pub(crate) fn screen_thread_main(
                 screen.unblock_input()?;
             },
         }
+        screen.update_followers().non_fatal();
+        if screen.all_terminal_panes_exited {
+            // panes exit (or close) in the instructions above, but once they all did any
+            // instruction can run one of them again (eg. pressing enter in a held pane)
+            screen.report_exited_panes();
+        }
     }
     Ok(())
 }
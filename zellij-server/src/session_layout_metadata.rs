@@ -1,6 +1,6 @@
 use crate::panes::PaneId;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use zellij_utils::common_path::common_path_all;
 use zellij_utils::pane_size::PaneGeom;
 use zellij_utils::{
@@ -9,6 +9,17 @@ use zellij_utils::{
     session_serialization::{GlobalLayoutManifest, PaneLayoutManifest, TabLayoutManifest},
 };
 
+/// The options of `Action::SaveLayout`, along with the layout folder the layout will be saved in
+#[derive(Default, Debug, Clone)]
+pub struct SaveLayoutRequest {
+    pub name: String,
+    pub tab: Option<String>, // tab position (starting at 1) or name
+    pub strip_contents: bool,
+    pub relative_to: Option<PathBuf>,
+    pub force: bool,
+    pub layout_dir: Option<PathBuf>,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct SessionLayoutMetadata {
     default_layout: Box<Layout>,
@@ -73,6 +84,8 @@ impl SessionLayoutMetadata {
         name: String,
         is_focused: bool,
         hide_floating_panes: bool,
+        cwd: Option<PathBuf>,
+        env: BTreeMap<String, String>,
         tiled_panes: Vec<PaneLayoutMetadata>,
         floating_panes: Vec<PaneLayoutMetadata>,
     ) {
@@ -80,10 +93,87 @@ impl SessionLayoutMetadata {
             name: Some(name),
             is_focused,
             hide_floating_panes,
+            cwd,
+            env,
             tiled_panes,
             floating_panes,
         })
     }
+    /// Keeps only the tab at this position (starting at 1) or with this name, returns false if
+    /// there is no such tab
+    pub fn retain_tab(&mut self, tab: &str) -> bool {
+        let tab_index = match tab.parse::<usize>() {
+            Ok(position) => position.checked_sub(1),
+            Err(_) => self
+                .tabs
+                .iter()
+                .position(|t| t.name.as_deref() == Some(tab)),
+        };
+        match tab_index.filter(|i| *i < self.tabs.len()) {
+            Some(tab_index) => {
+                let mut tab = self.tabs.remove(tab_index);
+                tab.is_focused = true;
+                self.tabs = vec![tab];
+                true
+            },
+            None => false,
+        }
+    }
+    pub fn strip_pane_contents(&mut self) {
+        for tab in self.tabs.iter_mut() {
+            for pane_layout_metadata in tab
+                .tiled_panes
+                .iter_mut()
+                .chain(tab.floating_panes.iter_mut())
+            {
+                pane_layout_metadata.pane_contents = None;
+            }
+        }
+    }
+    /// Makes the cwds under `base` relative to it, so that the layout can be used from another
+    /// copy of the same folder
+    pub fn make_cwds_relative_to(&mut self, base: &Path) {
+        let relative_to_base = |path: &mut PathBuf| {
+            if let Ok(stripped) = path.strip_prefix(base) {
+                *path = PathBuf::from(stripped);
+            }
+        };
+        // a cwd that is the base itself becomes empty, and is dropped instead
+        let relative_cwd_to_base = |cwd: &mut Option<PathBuf>| {
+            if let Some(path) = cwd.as_mut() {
+                relative_to_base(path);
+            }
+            if cwd.as_ref().map(|c| c.as_os_str().is_empty()) == Some(true) {
+                *cwd = None;
+            }
+        };
+        relative_cwd_to_base(&mut self.global_cwd);
+        for tab in self.tabs.iter_mut() {
+            relative_cwd_to_base(&mut tab.cwd);
+            for pane_layout_metadata in tab
+                .tiled_panes
+                .iter_mut()
+                .chain(tab.floating_panes.iter_mut())
+            {
+                relative_cwd_to_base(&mut pane_layout_metadata.cwd);
+                match pane_layout_metadata.run.as_mut() {
+                    Some(Run::Command(run_command)) => relative_cwd_to_base(&mut run_command.cwd),
+                    Some(Run::EditFile(path_to_file, _line_number, cwd)) => {
+                        relative_to_base(path_to_file);
+                        relative_cwd_to_base(cwd);
+                    },
+                    Some(Run::Cwd(cwd)) => {
+                        relative_to_base(cwd);
+                        if cwd.as_os_str().is_empty() {
+                            pane_layout_metadata.run = None;
+                        }
+                    },
+                    Some(Run::Shell(cwd, _env)) => relative_cwd_to_base(cwd),
+                    _ => {},
+                }
+            }
+        }
+    }
     pub fn all_terminal_ids(&self) -> Vec<u32> {
         let mut terminal_ids = vec![];
         for tab in &self.tabs {
@@ -128,7 +218,10 @@ impl SessionLayoutMetadata {
                         let args: Vec<String> = command_line.map(|c| c.to_owned()).collect();
                         if Self::is_default_shell(self.default_shell.as_ref(), &command_name, &args)
                         {
-                            pane_layout_metadata.run = None;
+                            // default shells started with environment variables keep them
+                            if !matches!(pane_layout_metadata.run, Some(Run::Shell(..))) {
+                                pane_layout_metadata.run = None;
+                            }
                         } else {
                             let mut run_command = RunCommand::new(PathBuf::from(command_name));
                             run_command.args = args;
@@ -205,6 +298,7 @@ impl Into<GlobalLayoutManifest> for SessionLayoutMetadata {
                 .into_iter()
                 .map(|t| (t.name.clone().unwrap_or_default(), t.into()))
                 .collect(),
+            portable: false,
         }
     }
 }
@@ -216,6 +310,8 @@ impl Into<TabLayoutManifest> for TabLayoutMetadata {
             floating_panes: self.floating_panes.into_iter().map(|t| t.into()).collect(),
             is_focused: self.is_focused,
             hide_floating_panes: self.hide_floating_panes,
+            cwd: self.cwd,
+            env: self.env,
         }
     }
 }
@@ -230,6 +326,7 @@ impl Into<PaneLayoutManifest> for PaneLayoutMetadata {
             title: self.title,
             is_focused: self.is_focused,
             pane_contents: self.pane_contents,
+            is_sticky: self.is_sticky,
         }
     }
 }
@@ -241,6 +338,8 @@ pub struct TabLayoutMetadata {
     floating_panes: Vec<PaneLayoutMetadata>,
     is_focused: bool,
     hide_floating_panes: bool,
+    cwd: Option<PathBuf>,
+    env: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone)]
@@ -253,6 +352,7 @@ pub struct PaneLayoutMetadata {
     title: Option<String>,
     is_focused: bool,
     pane_contents: Option<String>,
+    is_sticky: bool,
 }
 
 impl PaneLayoutMetadata {
@@ -264,6 +364,7 @@ impl PaneLayoutMetadata {
         title: Option<String>,
         is_focused: bool,
         pane_contents: Option<String>,
+        is_sticky: bool,
     ) -> Self {
         PaneLayoutMetadata {
             id,
@@ -274,6 +375,11 @@ impl PaneLayoutMetadata {
             title,
             is_focused,
             pane_contents,
+            is_sticky,
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/session_layout_metadata_tests.rs"]
+mod session_layout_metadata_tests;
@@ -104,19 +104,21 @@ impl<'a> LayoutApplier<'a> {
         new_floating_terminal_ids: Vec<(u32, HoldForCommand)>,
         mut new_plugin_ids: HashMap<RunPluginOrAlias, Vec<u32>>,
         client_id: ClientId,
-    ) -> Result<bool> {
-        // true => should_show_floating_panes
+    ) -> Result<(bool, Vec<PaneId>)> {
+        // (should_show_floating_panes, ids of the panes the layout marks as sticky)
         let layout_name = layout.name.clone();
         let hide_floating_panes = layout.hide_floating_panes;
         self.apply_tiled_panes_layout(layout, new_terminal_ids, &mut new_plugin_ids, client_id)?;
+        let mut sticky_pane_ids = vec![];
         let layout_has_floating_panes = self.apply_floating_panes_layout(
             floating_panes_layout,
             new_floating_terminal_ids,
             &mut new_plugin_ids,
+            &mut sticky_pane_ids,
             layout_name,
         )?;
         let should_show_floating_panes = layout_has_floating_panes && !hide_floating_panes;
-        return Ok(should_show_floating_panes);
+        return Ok((should_show_floating_panes, sticky_pane_ids));
     }
     pub fn apply_tiled_panes_layout_to_existing_panes(
         &mut self,
@@ -371,6 +373,7 @@ impl<'a> LayoutApplier<'a> {
         floating_panes_layout: Vec<FloatingPaneLayout>,
         new_floating_terminal_ids: Vec<(u32, HoldForCommand)>,
         new_plugin_ids: &mut HashMap<RunPluginOrAlias, Vec<u32>>,
+        sticky_pane_ids: &mut Vec<PaneId>,
         layout_name: Option<String>,
     ) -> Result<bool> {
         // true => has floating panes
@@ -434,6 +437,9 @@ impl<'a> LayoutApplier<'a> {
                 if floating_pane_layout.focus.unwrap_or(false) {
                     focused_floating_pane = Some(PaneId::Plugin(pid));
                 }
+                if floating_pane_layout.is_sticky {
+                    sticky_pane_ids.push(PaneId::Plugin(pid));
+                }
             } else if let Some((pid, hold_for_command)) = new_floating_terminal_ids.next() {
                 let next_terminal_position =
                     get_next_terminal_position(&self.tiled_panes, &self.floating_panes);
@@ -478,6 +484,9 @@ impl<'a> LayoutApplier<'a> {
                 if floating_pane_layout.focus.unwrap_or(false) {
                     focused_floating_pane = Some(PaneId::Terminal(*pid));
                 }
+                if floating_pane_layout.is_sticky {
+                    sticky_pane_ids.push(PaneId::Terminal(*pid));
+                }
             }
         }
         if let Some(focused_floating_pane) = focused_floating_pane {
@@ -0,0 +1,98 @@
+use crate::panes::PaneId;
+use crate::tab::Pane;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use zellij_utils::pane_size::{PaneGeom, Size, Viewport};
+
+const MAX_LAYOUT_HISTORY: usize = 50;
+
+// closed panes are kept alive (suppressed) for this long so that closing them can be undone
+pub const CLOSED_PANE_GRACE_PERIOD: Duration = Duration::from_secs(120);
+
+/// The geometry of all the panes in a tab at a certain point in time
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LayoutSnapshot {
+    pub tiled_panes: HashMap<PaneId, PaneGeom>,
+    pub floating_panes: HashMap<PaneId, PaneGeom>,
+    pub display_area: Size,
+    pub viewport: Viewport,
+}
+
+#[derive(Default)]
+pub struct LayoutHistory {
+    undo_stack: VecDeque<LayoutSnapshot>,
+    redo_stack: Vec<LayoutSnapshot>,
+    closed_panes: HashMap<PaneId, (Instant, Box<dyn Pane>)>, // Instant => time of closing
+}
+
+impl LayoutHistory {
+    pub fn record(&mut self, snapshot: LayoutSnapshot) {
+        if self.undo_stack.back() == Some(&snapshot) {
+            return;
+        }
+        self.undo_stack.push_back(snapshot);
+        if self.undo_stack.len() > MAX_LAYOUT_HISTORY {
+            self.undo_stack.pop_front();
+        }
+        self.redo_stack.clear();
+    }
+    /// Returns the most recent snapshot that differs from `current` and can be restored, moving
+    /// `current` to the redo stack
+    pub fn undo(
+        &mut self,
+        current: LayoutSnapshot,
+        can_restore: impl Fn(&LayoutSnapshot, &LayoutHistory) -> bool,
+    ) -> Option<LayoutSnapshot> {
+        while let Some(snapshot) = self.undo_stack.pop_back() {
+            if snapshot != current && can_restore(&snapshot, self) {
+                self.redo_stack.push(current);
+                return Some(snapshot);
+            }
+        }
+        None
+    }
+    /// Returns the most recently undone snapshot that can be restored, moving `current` back to
+    /// the undo stack
+    pub fn redo(
+        &mut self,
+        current: LayoutSnapshot,
+        can_restore: impl Fn(&LayoutSnapshot, &LayoutHistory) -> bool,
+    ) -> Option<LayoutSnapshot> {
+        while let Some(snapshot) = self.redo_stack.pop() {
+            if snapshot != current && can_restore(&snapshot, self) {
+                self.undo_stack.push_back(current);
+                return Some(snapshot);
+            }
+        }
+        None
+    }
+    pub fn retain_closed_pane(&mut self, pane: Box<dyn Pane>) {
+        self.closed_panes.insert(pane.pid(), (Instant::now(), pane));
+    }
+    pub fn has_closed_pane(&self, pane_id: &PaneId) -> bool {
+        self.closed_panes.contains_key(pane_id)
+    }
+    pub fn take_closed_pane(&mut self, pane_id: &PaneId) -> Option<Box<dyn Pane>> {
+        self.closed_panes.remove(pane_id).map(|(_, pane)| pane)
+    }
+    pub fn get_closed_pane_mut(&mut self, pane_id: &PaneId) -> Option<&mut Box<dyn Pane>> {
+        self.closed_panes.get_mut(pane_id).map(|(_, pane)| pane)
+    }
+    pub fn closed_pane_ids(&self) -> Vec<PaneId> {
+        self.closed_panes.keys().copied().collect()
+    }
+    /// Removes and returns the ids of the closed panes whose grace period is over, these should
+    /// now be closed for real
+    pub fn drain_expired_closed_panes(&mut self) -> Vec<PaneId> {
+        let expired_pane_ids: Vec<PaneId> = self
+            .closed_panes
+            .iter()
+            .filter(|(_, (closed_at, _))| closed_at.elapsed() >= CLOSED_PANE_GRACE_PERIOD)
+            .map(|(pane_id, _)| *pane_id)
+            .collect();
+        for pane_id in &expired_pane_ids {
+            self.closed_panes.remove(pane_id);
+        }
+        expired_pane_ids
+    }
+}
@@ -4,6 +4,7 @@
 mod clipboard;
 mod copy_command;
 mod layout_applier;
+mod layout_history;
 mod swap_layouts;
 
 use copy_command::CopyCommand;
@@ -14,8 +15,9 @@ use zellij_utils::data::{
     Direction, PaneInfo, PermissionStatus, PermissionType, PluginPermission, ResizeStrategy,
 };
 use zellij_utils::errors::prelude::*;
-use zellij_utils::input::command::RunCommand;
+use zellij_utils::input::command::{PaneDependency, RunCommand};
 use zellij_utils::position::{Column, Line};
+use zellij_utils::regex::Regex;
 use zellij_utils::{position::Position, serde};
 
 use crate::background_jobs::BackgroundJob;
@@ -23,14 +25,15 @@ use crate::pty_writer::PtyWriteInstruction;
 use crate::screen::CopyOptions;
 use crate::ui::{loading_indication::LoadingIndication, pane_boundaries_frame::FrameParams};
 use layout_applier::LayoutApplier;
+use layout_history::{LayoutHistory, LayoutSnapshot, CLOSED_PANE_GRACE_PERIOD};
 use swap_layouts::SwapLayouts;
 
 use self::clipboard::ClipboardProvider;
 use crate::{
     os_input_output::ServerOsApi,
-    output::{CharacterChunk, Output, SixelImageChunk},
+    output::{CharacterChunk, FloatingPanesStack, Output, SixelImageChunk},
     panes::sixel::SixelImageStore,
-    panes::{FloatingPanes, TiledPanes},
+    panes::{FloatingPanes, StickyPanes, TiledPanes},
     panes::{LinkHandler, PaneId, PluginPane, TerminalPane},
     plugins::PluginInstruction,
     pty::{ClientTabIndexOrPaneId, PtyInstruction, VteBytes},
@@ -42,7 +45,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 use std::time::Instant;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     str,
 };
 use zellij_utils::{
@@ -154,6 +157,7 @@ pub(crate) struct Tab {
     display_area: Rc<RefCell<Size>>, // includes all panes (including eg. the status bar and tab bar in the default layout)
     character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
     sixel_image_store: Rc<RefCell<SixelImageStore>>,
+    sticky_panes: Rc<RefCell<StickyPanes>>, // shared by all tabs, rendered on top of them
     os_api: Box<dyn ServerOsApi>,
     pub senders: ThreadSenders,
     synchronize_is_active: bool,
@@ -183,7 +187,13 @@ pub(crate) struct Tab {
     pending_instructions: Vec<BufferedTabInstruction>, // instructions that came while the tab was
     // pending and need to be re-applied
     swap_layouts: SwapLayouts,
+    layout_history: LayoutHistory,
     default_shell: Option<PathBuf>,
+    default_cwd: Option<PathBuf>, // new panes in this tab start here rather than in the focused
+    // pane's cwd
+    default_env: BTreeMap<String, String>, // given to new panes in this tab
+    clients_with_tab_defaults: HashSet<ClientId>, // the clients the pty thread already has the
+    // above defaults for
     debug: bool,
     arrow_fonts: bool,
     styled_underlines: bool,
@@ -455,6 +465,9 @@ pub trait Pane {
     fn hold(&mut self, _exit_status: Option<i32>, _is_first_run: bool, _run_command: RunCommand) {
         // No-op by default, only terminal panes support holding
     }
+    fn indicate_restart(&mut self, _restart_count: usize, _exit_status: Option<i32>) {
+        // No-op by default, only terminal panes run commands that can be restarted
+    }
     fn add_red_pane_frame_color_override(&mut self, _error_text: Option<String>);
     fn clear_pane_frame_color_override(&mut self);
     fn frame_color_override(&self) -> Option<PaletteColor>;
@@ -474,6 +487,27 @@ pub trait Pane {
     fn exit_status(&self) -> Option<i32> {
         None
     }
+    fn command_waiting_for_first_run(&self) -> Option<&RunCommand> {
+        // only terminal panes can hold a command before it first runs
+        None
+    }
+    fn run_held_command(&mut self) -> Option<RunCommand> {
+        None
+    }
+    fn output_matches(&mut self, _pattern: &Regex) -> bool {
+        false
+    }
+    fn watch_output(&mut self, _should_watch: bool) {}
+    fn take_received_lines(&mut self) -> Vec<String> {
+        vec![]
+    }
+    fn initial_pane_name(&self) -> Option<&str> {
+        None
+    }
+    fn set_foreground_command(&mut self, _command_line: Option<Vec<String>>) {}
+    fn foreground_command(&self) -> Option<&[String]> {
+        None
+    }
     fn rename(&mut self, _buf: Vec<u8>) {}
     fn serialize(&self, _scrollback_lines_to_serialize: Option<usize>) -> Option<String> {
         None
@@ -519,6 +553,7 @@ impl Tab {
         display_area: Size,
         character_cell_size: Rc<RefCell<Option<SizeInPixels>>>,
         sixel_image_store: Rc<RefCell<SixelImageStore>>,
+        sticky_panes: Rc<RefCell<StickyPanes>>,
         os_api: Box<dyn ServerOsApi>,
         senders: ThreadSenders,
         max_panes: Option<usize>,
@@ -599,6 +634,7 @@ impl Tab {
             display_area,
             character_cell_size,
             sixel_image_store,
+            sticky_panes,
             synchronize_is_active: false,
             os_api,
             senders,
@@ -623,7 +659,11 @@ impl Tab {
             is_pending: true, // will be switched to false once the layout is applied
             pending_instructions: vec![],
             swap_layouts,
+            layout_history: LayoutHistory::default(),
             default_shell,
+            default_cwd: None,
+            default_env: BTreeMap::new(),
+            clients_with_tab_defaults: HashSet::new(),
             debug,
             arrow_fonts,
             styled_underlines,
@@ -638,10 +678,16 @@ impl Tab {
         new_floating_terminal_ids: Vec<(u32, HoldForCommand)>,
         new_plugin_ids: HashMap<RunPluginOrAlias, Vec<u32>>,
         client_id: ClientId,
-    ) -> Result<()> {
+    ) -> Result<Vec<PaneId>> {
+        // returns the ids of the panes the layout marks as sticky
         self.swap_layouts
             .set_base_layout((layout.clone(), floating_panes_layout.clone()));
-        let should_show_floating_panes = LayoutApplier::new(
+        if layout.default_cwd.is_some() {
+            self.default_cwd = layout.default_cwd.clone();
+        }
+        self.default_env.extend(layout.default_env.clone());
+        self.clients_with_tab_defaults.clear();
+        let (should_show_floating_panes, sticky_pane_ids) = LayoutApplier::new(
             &self.viewport,
             &self.senders,
             &self.sixel_image_store,
@@ -678,7 +724,7 @@ impl Tab {
         self.tiled_panes.reapply_pane_frames();
         self.is_pending = false;
         self.apply_buffered_instructions()?;
-        Ok(())
+        Ok(sticky_pane_ids)
     }
     pub fn swap_layout_info(&self) -> (Option<String>, bool) {
         if self.floating_panes.panes_are_visible() {
@@ -932,6 +978,7 @@ impl Tab {
     pub fn remove_client(&mut self, client_id: ClientId) {
         self.focus_pane_id = None;
         self.connected_clients.borrow_mut().remove(&client_id);
+        self.clients_with_tab_defaults.remove(&client_id);
         self.set_force_render();
     }
     pub fn drain_connected_clients(
@@ -954,6 +1001,7 @@ impl Tab {
             .remove(&client_id)
             .unwrap_or_else(|| self.default_mode_info.clone());
         self.connected_clients.borrow_mut().remove(&client_id);
+        self.clients_with_tab_defaults.remove(&client_id);
         (client_id, client_mode_info)
     }
     pub fn has_no_connected_clients(&self) -> bool {
@@ -1006,6 +1054,9 @@ impl Tab {
         client_id: Option<ClientId>,
         default_shell: Option<TerminalAction>,
     ) -> Result<()> {
+        if let Some(client_id) = client_id {
+            self.sticky_panes.borrow_mut().unfocus(client_id);
+        }
         if self.floating_panes.panes_are_visible() {
             self.hide_floating_panes();
             self.set_force_render();
@@ -1058,6 +1109,7 @@ impl Tab {
         client_id: Option<ClientId>,
     ) -> Result<()> {
         let err_context = || format!("failed to create new pane with id {pid:?}");
+        self.record_layout_history();
         match should_float {
             Some(true) => self.show_floating_panes(),
             Some(false) => self.hide_floating_panes(),
@@ -1320,6 +1372,7 @@ impl Tab {
         if self.floating_panes.panes_are_visible() {
             return Ok(());
         }
+        self.record_layout_history();
         self.close_down_to_max_terminals()
             .with_context(err_context)?;
         if self.tiled_panes.fullscreen_is_active() {
@@ -1379,6 +1432,7 @@ impl Tab {
         if self.floating_panes.panes_are_visible() {
             return Ok(());
         }
+        self.record_layout_history();
         self.close_down_to_max_terminals()
             .with_context(err_context)?;
         if self.tiled_panes.fullscreen_is_active() {
@@ -1477,6 +1531,7 @@ impl Tab {
                 .suppressed_panes
                 .values()
                 .any(|s_p| s_p.1.pid() == PaneId::Terminal(pid))
+            || self.layout_history.has_closed_pane(&PaneId::Terminal(pid))
     }
     pub fn has_plugin(&self, plugin_id: u32) -> bool {
         self.tiled_panes.panes_contain(&PaneId::Plugin(plugin_id))
@@ -1495,10 +1550,14 @@ impl Tab {
                 .suppressed_panes
                 .values()
                 .any(|s_p| s_p.1.pid() == *pid)
+            || self.layout_history.has_closed_pane(pid)
     }
     pub fn has_non_suppressed_pane_with_pid(&self, pid: &PaneId) -> bool {
         self.tiled_panes.panes_contain(pid) || self.floating_panes.panes_contain(pid)
     }
+    pub fn has_suppressed_pane(&self, pid: &PaneId) -> bool {
+        self.suppressed_panes.contains_key(pid)
+    }
     pub fn handle_pty_bytes(&mut self, pid: u32, bytes: VteBytes) -> Result<()> {
         if self.is_pending {
             self.pending_instructions
@@ -1516,6 +1575,10 @@ impl Tab {
                     .find(|s_p| s_p.1.pid() == PaneId::Terminal(pid))
                     .map(|s_p| &mut s_p.1)
             })
+            .or_else(|| {
+                self.layout_history
+                    .get_closed_pane_mut(&PaneId::Terminal(pid))
+            })
         {
             // If the pane is scrolled buffer the vte events
             if terminal_output.is_scrolled() {
@@ -1539,6 +1602,8 @@ impl Tab {
         client_id: ClientId,
         bytes: VteBytes,
     ) -> Result<()> {
+        let sticky_panes = self.sticky_panes.clone();
+        let mut sticky_panes = sticky_panes.borrow_mut();
         if let Some(plugin_pane) = self
             .tiled_panes
             .get_pane_mut(PaneId::Plugin(pid))
@@ -1549,6 +1614,7 @@ impl Tab {
                     .find(|s_p| s_p.1.pid() == PaneId::Plugin(pid))
                     .map(|s_p| &mut s_p.1)
             })
+            .or_else(|| sticky_panes.get_mut(&PaneId::Plugin(pid)))
         {
             plugin_pane.handle_plugin_bytes(client_id, bytes);
         }
@@ -1567,6 +1633,8 @@ impl Tab {
     fn process_pty_bytes(&mut self, pid: u32, bytes: VteBytes) -> Result<()> {
         let err_context = || format!("failed to process pty bytes from pid {pid}");
 
+        let sticky_panes = self.sticky_panes.clone();
+        let mut sticky_panes = sticky_panes.borrow_mut();
         if let Some(terminal_output) = self
             .tiled_panes
             .get_pane_mut(PaneId::Terminal(pid))
@@ -1577,6 +1645,11 @@ impl Tab {
                     .find(|s_p| s_p.1.pid() == PaneId::Terminal(pid))
                     .map(|s_p| &mut s_p.1)
             })
+            .or_else(|| {
+                self.layout_history
+                    .get_closed_pane_mut(&PaneId::Terminal(pid))
+            })
+            .or_else(|| sticky_panes.get_mut(&PaneId::Terminal(pid)))
         {
             if self.pids_waiting_resize.remove(&pid) {
                 resize_pty!(
@@ -1590,6 +1663,7 @@ impl Tab {
             terminal_output.handle_pty_bytes(bytes);
             let messages_to_pty = terminal_output.drain_messages_to_pty();
             let clipboard_update = terminal_output.drain_clipboard_update();
+            drop(sticky_panes); // writing to the pane borrows them again
             for message in messages_to_pty {
                 self.write_to_pane_id(message, PaneId::Terminal(pid), None)
                     .with_context(err_context)?;
@@ -1636,7 +1710,10 @@ impl Tab {
         };
 
         self.clear_search(client_id); // this is an inexpensive operation if empty, if we need more such cleanups we should consider moving this and the rest to some sort of cleanup method
-        let pane_id = if self.floating_panes.panes_are_visible() {
+        let focused_sticky_pane_id = self.sticky_panes.borrow().focused_pane_id(client_id);
+        let pane_id = if let Some(focused_sticky_pane_id) = focused_sticky_pane_id {
+            focused_sticky_pane_id
+        } else if self.floating_panes.panes_are_visible() {
             self.floating_panes
                 .get_active_pane_id(client_id)
                 .or_else(|| self.tiled_panes.get_active_pane_id(client_id))
@@ -1699,66 +1776,70 @@ impl Tab {
         let mut should_update_ui = false;
         let is_sync_panes_active = self.is_sync_panes_active();
 
-        let active_terminal = self
-            .floating_panes
-            .get_mut(&pane_id)
-            .or_else(|| self.tiled_panes.get_pane_mut(pane_id))
-            .or_else(|| self.suppressed_panes.get_mut(&pane_id).map(|p| &mut p.1))
-            .ok_or_else(|| anyhow!(format!("failed to find pane with id {pane_id:?}")))
-            .with_context(err_context)?;
+        let adjusted_input = {
+            let sticky_panes = self.sticky_panes.clone();
+            let mut sticky_panes = sticky_panes.borrow_mut();
+            let active_terminal = self
+                .floating_panes
+                .get_mut(&pane_id)
+                .or_else(|| self.tiled_panes.get_pane_mut(pane_id))
+                .or_else(|| self.suppressed_panes.get_mut(&pane_id).map(|p| &mut p.1))
+                .or_else(|| sticky_panes.get_mut(&pane_id))
+                .ok_or_else(|| anyhow!(format!("failed to find pane with id {pane_id:?}")))
+                .with_context(err_context)?;
 
-        // We always write for non-synced terminals.
-        // However if the terminal is part of a tab-sync, we need to
-        // check if the terminal should receive input or not (depending on its
-        // 'exclude_from_sync' configuration).
-        let should_not_write_to_terminal =
-            is_sync_panes_active && active_terminal.exclude_from_sync();
+            // We always write for non-synced terminals.
+            // However if the terminal is part of a tab-sync, we need to
+            // check if the terminal should receive input or not (depending on its
+            // 'exclude_from_sync' configuration).
+            let should_not_write_to_terminal =
+                is_sync_panes_active && active_terminal.exclude_from_sync();
 
-        if should_not_write_to_terminal {
-            return Ok(should_update_ui);
-        }
+            if should_not_write_to_terminal {
+                return Ok(should_update_ui);
+            }
+            active_terminal.adjust_input_to_terminal(input_bytes)
+        };
 
         match pane_id {
-            PaneId::Terminal(active_terminal_id) => {
-                match active_terminal.adjust_input_to_terminal(input_bytes) {
-                    Some(AdjustedInput::WriteBytesToTerminal(adjusted_input)) => {
-                        self.senders
-                            .send_to_pty_writer(PtyWriteInstruction::Write(
-                                adjusted_input,
-                                active_terminal_id,
-                            ))
-                            .with_context(err_context)?;
-                    },
-                    Some(AdjustedInput::ReRunCommandInThisPane(command)) => {
-                        self.pids_waiting_resize.insert(active_terminal_id);
-                        self.senders
-                            .send_to_pty(PtyInstruction::ReRunCommandInPane(
-                                PaneId::Terminal(active_terminal_id),
-                                command,
-                            ))
-                            .with_context(err_context)?;
-                        should_update_ui = true;
-                    },
-                    Some(AdjustedInput::CloseThisPane) => {
-                        self.close_pane(PaneId::Terminal(active_terminal_id), false, None);
-                        should_update_ui = true;
-                    },
-                    Some(AdjustedInput::DropToShellInThisPane { working_dir }) => {
-                        self.pids_waiting_resize.insert(active_terminal_id);
-                        self.senders
-                            .send_to_pty(PtyInstruction::DropToShellInPane {
-                                pane_id: PaneId::Terminal(active_terminal_id),
-                                shell: self.default_shell.clone(),
-                                working_dir,
-                            })
-                            .with_context(err_context)?;
-                        should_update_ui = true;
-                    },
-                    Some(_) => {},
-                    None => {},
-                }
+            PaneId::Terminal(active_terminal_id) => match adjusted_input {
+                Some(AdjustedInput::WriteBytesToTerminal(adjusted_input)) => {
+                    self.senders
+                        .send_to_pty_writer(PtyWriteInstruction::Write(
+                            adjusted_input,
+                            active_terminal_id,
+                        ))
+                        .with_context(err_context)?;
+                },
+                Some(AdjustedInput::ReRunCommandInThisPane(command)) => {
+                    self.pids_waiting_resize.insert(active_terminal_id);
+                    self.senders
+                        .send_to_pty(PtyInstruction::ReRunCommandInPane(
+                            PaneId::Terminal(active_terminal_id),
+                            command,
+                        ))
+                        .with_context(err_context)?;
+                    should_update_ui = true;
+                },
+                Some(AdjustedInput::CloseThisPane) => {
+                    self.close_pane(PaneId::Terminal(active_terminal_id), false, None);
+                    should_update_ui = true;
+                },
+                Some(AdjustedInput::DropToShellInThisPane { working_dir }) => {
+                    self.pids_waiting_resize.insert(active_terminal_id);
+                    self.senders
+                        .send_to_pty(PtyInstruction::DropToShellInPane {
+                            pane_id: PaneId::Terminal(active_terminal_id),
+                            shell: self.default_shell.clone(),
+                            working_dir,
+                        })
+                        .with_context(err_context)?;
+                    should_update_ui = true;
+                },
+                Some(_) => {},
+                None => {},
             },
-            PaneId::Plugin(pid) => match active_terminal.adjust_input_to_terminal(input_bytes) {
+            PaneId::Plugin(pid) => match adjusted_input {
                 Some(AdjustedInput::WriteBytesToTerminal(adjusted_input)) => {
                     let mut plugin_updates = vec![];
                     for key in parse_keys(&adjusted_input) {
@@ -1792,6 +1873,9 @@ impl Tab {
         client_id: ClientId,
     ) -> Option<(usize, usize)> {
         // (x, y)
+        if self.has_focused_sticky_pane(client_id) {
+            return self.sticky_panes.borrow().cursor_position(client_id);
+        }
         let active_pane_id = if self.floating_panes.panes_are_visible() {
             self.floating_panes
                 .get_active_pane_id(client_id)
@@ -1867,6 +1951,12 @@ impl Tab {
         self.tiled_panes.set_force_render();
         self.floating_panes.set_force_render();
     }
+    pub fn clear_display_before_rendering(&mut self) {
+        self.should_clear_display_before_rendering = true;
+    }
+    pub fn will_clear_display_before_rendering(&self) -> bool {
+        self.should_clear_display_before_rendering
+    }
     pub fn is_sync_panes_active(&self) -> bool {
         self.synchronize_is_active
     }
@@ -1878,7 +1968,7 @@ impl Tab {
             active_pane.set_should_render(true);
         }
     }
-    fn update_active_panes_in_pty_thread(&self) -> Result<()> {
+    fn update_active_panes_in_pty_thread(&mut self) -> Result<()> {
         // this is a bit hacky and we should ideally not keep this state in two different places at
         // some point
         let connected_clients: Vec<ClientId> =
@@ -1890,9 +1980,34 @@ impl Tab {
                     client_id,
                 ))
                 .with_context(|| format!("failed to update active pane for client {client_id}"))?;
+            // the tab defaults only need to be sent when they change or when the client moves to
+            // this tab, since the pty thread keeps the ones of the last tab it was told about
+            if self.clients_with_tab_defaults.insert(client_id) {
+                self.senders
+                    .send_to_pty(PtyInstruction::UpdateTabDefaults(
+                        self.default_cwd.clone(),
+                        self.default_env.clone(),
+                        client_id,
+                    ))
+                    .with_context(|| {
+                        format!("failed to update tab defaults for client {client_id}")
+                    })?;
+            }
         }
         Ok(())
     }
+    pub fn default_cwd(&self) -> Option<&PathBuf> {
+        self.default_cwd.as_ref()
+    }
+    pub fn default_env(&self) -> &BTreeMap<String, String> {
+        &self.default_env
+    }
+    pub fn set_default_cwd(&mut self, cwd: Option<PathBuf>) -> Result<()> {
+        self.default_cwd = cwd;
+        self.clients_with_tab_defaults.clear();
+        self.update_active_panes_in_pty_thread()
+            .with_context(|| format!("failed to set the default cwd of tab {}", self.index))
+    }
 
     pub fn render(&mut self, output: &mut Output) -> Result<()> {
         let err_context = || "failed to render tab".to_string();
@@ -1905,7 +2020,18 @@ impl Tab {
         self.update_active_panes_in_pty_thread()
             .with_context(err_context)?;
 
-        let floating_panes_stack = self.floating_panes.stack();
+        // panes underneath the sticky panes are cropped as if they were floating panes above them
+        let sticky_pane_layers = self.sticky_panes.borrow().stack_layers();
+        let floating_panes_stack = match self.floating_panes.stack() {
+            Some(mut floating_panes_stack) => {
+                floating_panes_stack.layers.extend(sticky_pane_layers);
+                Some(floating_panes_stack)
+            },
+            None if !sticky_pane_layers.is_empty() => Some(FloatingPanesStack {
+                layers: sticky_pane_layers,
+            }),
+            None => None,
+        };
         output.add_clients(
             &connected_clients,
             self.link_handler.clone(),
@@ -1953,8 +2079,13 @@ impl Tab {
             match self.get_active_terminal_cursor_position(client_id) {
                 Some((cursor_position_x, cursor_position_y)) => {
                     let desired_cursor_shape = self
-                        .get_active_pane(client_id)
-                        .map(|ap| ap.cursor_shape_csi())
+                        .sticky_panes
+                        .borrow()
+                        .cursor_shape_csi(client_id)
+                        .or_else(|| {
+                            self.get_active_pane(client_id)
+                                .map(|ap| ap.cursor_shape_csi())
+                        })
                         .unwrap_or_default();
                     let cursor_changed_position_or_shape = self
                         .cursor_positions_and_shape
@@ -2075,6 +2206,7 @@ impl Tab {
     }
     pub fn resize(&mut self, client_id: ClientId, strategy: ResizeStrategy) -> Result<()> {
         let err_context = || format!("unable to resize pane");
+        self.record_layout_history();
         self.swap_layouts.set_is_floating_damaged();
         self.swap_layouts.set_is_tiled_damaged();
         if self.floating_panes.panes_are_visible() {
@@ -2119,6 +2251,7 @@ impl Tab {
         }
     }
     pub fn focus_next_pane(&mut self, client_id: ClientId) {
+        self.sticky_panes.borrow_mut().unfocus(client_id);
         if !self.has_selectable_panes() {
             return;
         }
@@ -2129,6 +2262,7 @@ impl Tab {
         self.tiled_panes.focus_next_pane(client_id);
     }
     pub fn focus_previous_pane(&mut self, client_id: ClientId) {
+        self.sticky_panes.borrow_mut().unfocus(client_id);
         if !self.has_selectable_panes() {
             return;
         }
@@ -2148,6 +2282,7 @@ impl Tab {
     // returns a boolean that indicates whether the focus moved
     pub fn move_focus_left(&mut self, client_id: ClientId) -> Result<bool> {
         let err_context = || format!("failed to move focus left for client {}", client_id);
+        self.sticky_panes.borrow_mut().unfocus(client_id);
 
         if self.floating_panes.panes_are_visible() {
             self.floating_panes
@@ -2170,6 +2305,7 @@ impl Tab {
     }
     pub fn move_focus_down(&mut self, client_id: ClientId) -> Result<bool> {
         let err_context = || format!("failed to move focus down for client {}", client_id);
+        self.sticky_panes.borrow_mut().unfocus(client_id);
 
         if self.floating_panes.panes_are_visible() {
             self.floating_panes
@@ -2192,6 +2328,7 @@ impl Tab {
     }
     pub fn move_focus_up(&mut self, client_id: ClientId) -> Result<bool> {
         let err_context = || format!("failed to move focus up for client {}", client_id);
+        self.sticky_panes.borrow_mut().unfocus(client_id);
 
         if self.floating_panes.panes_are_visible() {
             self.floating_panes
@@ -2215,6 +2352,7 @@ impl Tab {
     // returns a boolean that indicates whether the focus moved
     pub fn move_focus_right(&mut self, client_id: ClientId) -> Result<bool> {
         let err_context = || format!("failed to move focus right for client {}", client_id);
+        self.sticky_panes.borrow_mut().unfocus(client_id);
 
         if self.floating_panes.panes_are_visible() {
             self.floating_panes
@@ -2239,6 +2377,7 @@ impl Tab {
         if !self.has_selectable_panes() {
             return;
         }
+        self.record_layout_history();
         if self.tiled_panes.fullscreen_is_active() {
             return;
         }
@@ -2255,6 +2394,7 @@ impl Tab {
         if !self.has_selectable_panes() {
             return;
         }
+        self.record_layout_history();
         if self.tiled_panes.fullscreen_is_active() {
             return;
         }
@@ -2268,6 +2408,7 @@ impl Tab {
         }
     }
     pub fn move_active_pane_down(&mut self, client_id: ClientId) {
+        self.record_layout_history();
         if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_active_pane_down(client_id);
             self.swap_layouts.set_is_floating_damaged();
@@ -2283,6 +2424,7 @@ impl Tab {
         }
     }
     pub fn move_active_pane_up(&mut self, client_id: ClientId) {
+        self.record_layout_history();
         if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_active_pane_up(client_id);
             self.swap_layouts.set_is_floating_damaged();
@@ -2298,6 +2440,7 @@ impl Tab {
         }
     }
     pub fn move_active_pane_right(&mut self, client_id: ClientId) {
+        self.record_layout_history();
         if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_active_pane_right(client_id);
             self.swap_layouts.set_is_floating_damaged();
@@ -2313,6 +2456,7 @@ impl Tab {
         }
     }
     pub fn move_active_pane_left(&mut self, client_id: ClientId) {
+        self.record_layout_history();
         if self.floating_panes.panes_are_visible() {
             self.floating_panes.move_active_pane_left(client_id);
             self.swap_layouts.set_is_floating_damaged();
@@ -2327,6 +2471,65 @@ impl Tab {
             self.tiled_panes.move_active_pane_left(client_id);
         }
     }
+    pub fn stack_active_pane(&mut self, client_id: ClientId, direction: Direction) -> Result<()> {
+        if self.floating_panes.panes_are_visible() || self.tiled_panes.fullscreen_is_active() {
+            return Ok(());
+        }
+        let snapshot = self.layout_snapshot();
+        if self.tiled_panes.stack_active_pane(client_id, direction)? {
+            self.layout_history.record(snapshot);
+            self.swap_layouts.set_is_tiled_damaged();
+        } else {
+            self.display_pane_error_for_client(
+                client_id,
+                "Only aligned panes with enough room between them can be stacked",
+            )?;
+        }
+        Ok(())
+    }
+    pub fn unstack_active_pane(&mut self, client_id: ClientId) -> Result<()> {
+        if self.floating_panes.panes_are_visible() || self.tiled_panes.fullscreen_is_active() {
+            return Ok(());
+        }
+        let snapshot = self.layout_snapshot();
+        if self.tiled_panes.unstack_active_pane(client_id)? {
+            self.layout_history.record(snapshot);
+            self.swap_layouts.set_is_tiled_damaged();
+        } else {
+            self.display_pane_error_for_client(
+                client_id,
+                "Only stacked panes with enough room between them can be unstacked",
+            )?;
+        }
+        Ok(())
+    }
+    pub fn remove_active_pane_from_stack(&mut self, client_id: ClientId) -> Result<()> {
+        if self.floating_panes.panes_are_visible() || self.tiled_panes.fullscreen_is_active() {
+            return Ok(());
+        }
+        let snapshot = self.layout_snapshot();
+        if self.tiled_panes.remove_active_pane_from_stack(client_id)? {
+            self.layout_history.record(snapshot);
+            self.swap_layouts.set_is_tiled_damaged();
+        } else {
+            self.display_pane_error_for_client(
+                client_id,
+                "Only stacked panes with enough room can be removed from their stack",
+            )?;
+        }
+        Ok(())
+    }
+    fn display_pane_error_for_client(&self, client_id: ClientId, error_text: &str) -> Result<()> {
+        if let Some(active_pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
+            self.senders
+                .send_to_background_jobs(BackgroundJob::DisplayPaneError(
+                    vec![active_pane_id],
+                    error_text.into(),
+                ))
+                .with_context(|| format!("failed to display pane error for client {client_id}"))?;
+        }
+        Ok(())
+    }
     fn close_down_to_max_terminals(&mut self) -> Result<()> {
         if let Some(max_panes) = self.max_panes {
             let terminals = self.get_tiled_pane_ids();
@@ -2396,6 +2599,14 @@ impl Tab {
         //
         // TODO: separate the "close_pane" logic and the "move_pane_somewhere_else" logic, they're
         // overloaded here and that's not great
+        if let Some(closed_pane) = self.layout_history.take_closed_pane(&id) {
+            // this pane was already closed by the user and was only kept around so that closing it
+            // could be undone
+            return Some(closed_pane);
+        }
+        if let Some(closed_pane) = self.sticky_panes.borrow_mut().remove_pane(id) {
+            return Some(closed_pane);
+        }
         if !ignore_suppressed_panes && self.suppressed_panes.contains_key(&id) {
             return match self.replace_pane_with_suppressed_pane(id) {
                 Ok(pane) => pane,
@@ -2501,7 +2712,11 @@ impl Tab {
                 ));
             return;
         }
-        if self.floating_panes.panes_contain(&id) {
+        if self.sticky_panes.borrow().contains(&id) {
+            self.sticky_panes
+                .borrow_mut()
+                .hold_pane(id, exit_status, is_first_run, run_command);
+        } else if self.floating_panes.panes_contain(&id) {
             self.floating_panes
                 .hold_pane(id, exit_status, is_first_run, run_command);
         } else {
@@ -2509,6 +2724,140 @@ impl Tab {
                 .hold_pane(id, exit_status, is_first_run, run_command);
         }
     }
+    pub fn indicate_command_restart(
+        &mut self,
+        id: PaneId,
+        restart_count: usize,
+        exit_status: Option<i32>,
+    ) {
+        let sticky_panes = self.sticky_panes.clone();
+        let mut sticky_panes = sticky_panes.borrow_mut();
+        if let Some(pane) = self
+            .floating_panes
+            .get_pane_mut(id)
+            .or_else(|| self.tiled_panes.get_pane_mut(id))
+            .or_else(|| sticky_panes.get_mut(&id))
+        {
+            pane.indicate_restart(restart_count, exit_status);
+        }
+    }
+    pub fn has_command_constraints(&self) -> bool {
+        self.swap_layouts.has_command_constraints()
+    }
+    pub fn get_terminal_pane_ids(&self) -> Vec<u32> {
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .filter_map(|(pane_id, _pane)| match pane_id {
+                PaneId::Terminal(id) => Some(*id),
+                PaneId::Plugin(_) => None,
+            })
+            .collect()
+    }
+    /// Updates the commands running in the foreground of the terminal panes, as found when they
+    /// were last queried, and reapplies the swap layouts if this changes which of their
+    /// has_command constraints are met
+    ///
+    /// Returns whether the panes were relayouted
+    pub fn update_foreground_commands(
+        &mut self,
+        terminal_commands: &HashMap<u32, Vec<String>>,
+    ) -> Result<bool> {
+        let constraints_met_before = self
+            .swap_layouts
+            .command_constraints_met(&self.tiled_panes, &self.floating_panes);
+        for id in self.get_terminal_pane_ids() {
+            if let Some(pane) = self.get_pane_with_id_mut(PaneId::Terminal(id)) {
+                pane.set_foreground_command(terminal_commands.get(&id).cloned());
+            }
+        }
+        let constraints_met_after = self
+            .swap_layouts
+            .command_constraints_met(&self.tiled_panes, &self.floating_panes);
+        if constraints_met_before == constraints_met_after {
+            return Ok(false);
+        }
+        // the current layout might still fit, but one that was skipped because of a command that
+        // was not running might fit better now - so we look for the first one that fits
+        let mut relayouted = false;
+        if self.auto_layout && !self.swap_layouts.is_floating_damaged() {
+            // we do this so that we won't skip to the next layout
+            self.swap_layouts.set_is_floating_damaged();
+            self.swap_layouts.reset_floating_layout_position();
+            self.relayout_floating_panes(None, false, false)
+                .with_context(|| format!("failed to relayout floating panes"))?;
+            relayouted = true;
+        }
+        if self.auto_layout && !self.swap_layouts.is_tiled_damaged() && !self.is_fullscreen_active()
+        {
+            self.swap_layouts.set_is_tiled_damaged();
+            self.swap_layouts.reset_tiled_layout_position();
+            self.relayout_tiled_panes(None, false, false, false)
+                .with_context(|| format!("failed to relayout tiled panes"))?;
+            relayouted = true;
+        }
+        if relayouted {
+            self.senders
+                .send_to_pty_writer(PtyWriteInstruction::ApplyCachedResizes)
+                .with_context(|| format!("failed to apply cached resizes"))?;
+        }
+        Ok(relayouted)
+    }
+    pub fn panes_waiting_for_dependencies(&self) -> Vec<(PaneId, Vec<PaneDependency>)> {
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .filter_map(|(pane_id, pane)| {
+                pane.command_waiting_for_first_run()
+                    .filter(|run_command| !run_command.depends_on.is_empty())
+                    .map(|run_command| (*pane_id, run_command.depends_on.clone()))
+            })
+            .collect()
+    }
+    /// The pane that was given this name when it was opened (eg. in its layout), even if it was
+    /// renamed since
+    pub fn get_pane_id_by_name(&self, pane_name: &str) -> Option<PaneId> {
+        self.tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .find(|(_pane_id, pane)| pane.initial_pane_name() == Some(pane_name))
+            .map(|(pane_id, _pane)| *pane_id)
+    }
+    pub fn get_pane_with_id_mut(&mut self, pane_id: PaneId) -> Option<&mut Box<dyn Pane>> {
+        self.floating_panes
+            .get_pane_mut(pane_id)
+            .or_else(|| self.tiled_panes.get_pane_mut(pane_id))
+    }
+    pub fn run_held_command_in_pane(&mut self, pane_id: PaneId) -> Result<()> {
+        let err_context = || format!("failed to run held command in pane {:?}", pane_id);
+        let run_command = self
+            .get_pane_with_id_mut(pane_id)
+            .and_then(|pane| pane.run_held_command());
+        if let Some(run_command) = run_command {
+            self.senders
+                .send_to_pty(PtyInstruction::ReRunCommandInPane(pane_id, run_command))
+                .with_context(err_context)?;
+        }
+        Ok(())
+    }
+    /// Starts the commands of the panes waiting for Enter to be pressed in them, those waiting for
+    /// other panes (their `depends_on`) are left to start once these are ready
+    pub fn start_suspended_panes(&mut self) -> Result<()> {
+        let suspended_panes: Vec<PaneId> = self
+            .tiled_panes
+            .get_panes()
+            .chain(self.floating_panes.get_panes())
+            .filter_map(|(pane_id, pane)| {
+                pane.command_waiting_for_first_run()
+                    .filter(|run_command| run_command.depends_on.is_empty())
+                    .map(|_| *pane_id)
+            })
+            .collect();
+        for pane_id in suspended_panes {
+            self.run_held_command_in_pane(pane_id)?;
+        }
+        Ok(())
+    }
     pub fn replace_pane_with_suppressed_pane(
         &mut self,
         pane_id: PaneId,
@@ -2562,21 +2911,206 @@ impl Tab {
             format!("failed to close focused pane (ID {pane_id:?}) for client {client_id}")
         };
 
-        if self.floating_panes.panes_are_visible() {
-            if let Some(active_floating_pane_id) = self.floating_panes.active_pane_id(client_id) {
-                self.close_pane(active_floating_pane_id, false, Some(client_id));
-                self.senders
-                    .send_to_pty(PtyInstruction::ClosePane(active_floating_pane_id))
-                    .with_context(|| err_context(active_floating_pane_id))?;
-                return Ok(());
+        let focused_sticky_pane_id = self.sticky_panes.borrow().focused_pane_id(client_id);
+        let active_pane_id = if focused_sticky_pane_id.is_some() {
+            focused_sticky_pane_id
+        } else if self.floating_panes.panes_are_visible() {
+            self.floating_panes
+                .active_pane_id(client_id)
+                .or_else(|| self.tiled_panes.get_active_pane_id(client_id))
+        } else {
+            self.tiled_panes.get_active_pane_id(client_id)
+        };
+        if let Some(active_pane_id) = active_pane_id {
+            // panes replacing a suppressed pane (eg. the scrollback editor) are not kept around,
+            // their closing is what brings back the suppressed pane - sticky panes do not belong
+            // to this tab's layout history
+            let can_be_undone = matches!(active_pane_id, PaneId::Terminal(_))
+                && !self.suppressed_panes.contains_key(&active_pane_id)
+                && focused_sticky_pane_id.is_none();
+            if can_be_undone {
+                self.record_layout_history();
+            }
+            let closed_pane = self.close_pane(active_pane_id, false, Some(client_id));
+            match closed_pane {
+                Some(closed_pane) if can_be_undone => {
+                    self.retain_closed_pane(closed_pane)
+                        .with_context(|| err_context(active_pane_id))?;
+                },
+                _ => {
+                    self.senders
+                        .send_to_pty(PtyInstruction::ClosePane(active_pane_id))
+                        .with_context(|| err_context(active_pane_id))?;
+                },
             }
         }
-        if let Some(active_pane_id) = self.tiled_panes.get_active_pane_id(client_id) {
-            self.close_pane(active_pane_id, false, Some(client_id));
+        Ok(())
+    }
+    fn retain_closed_pane(&mut self, closed_pane: Box<dyn Pane>) -> Result<()> {
+        // the pane is kept around (and its process running) for a little while, so that closing it
+        // can be undone
+        self.layout_history.retain_closed_pane(closed_pane);
+        self.senders
+            .send_to_background_jobs(BackgroundJob::ExpireClosedPanes(CLOSED_PANE_GRACE_PERIOD))
+            .context("failed to schedule closing of retained panes")
+    }
+    pub fn has_closed_pane(&self, pane_id: &PaneId) -> bool {
+        self.layout_history.has_closed_pane(pane_id)
+    }
+    pub fn closed_pane_ids(&self) -> Vec<PaneId> {
+        self.layout_history.closed_pane_ids()
+    }
+    pub fn expire_closed_panes(&mut self) -> Result<()> {
+        for pane_id in self.layout_history.drain_expired_closed_panes() {
             self.senders
-                .send_to_pty(PtyInstruction::ClosePane(active_pane_id))
-                .with_context(|| err_context(active_pane_id))?;
+                .send_to_pty(PtyInstruction::ClosePane(pane_id))
+                .with_context(|| format!("failed to close expired pane {:?}", pane_id))?;
+        }
+        Ok(())
+    }
+    fn layout_snapshot(&self) -> LayoutSnapshot {
+        LayoutSnapshot {
+            tiled_panes: self
+                .tiled_panes
+                .get_panes()
+                .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+                .collect(),
+            floating_panes: self
+                .floating_panes
+                .get_panes()
+                .map(|(pane_id, pane)| (*pane_id, pane.current_geom()))
+                .collect(),
+            display_area: *self.display_area.borrow(),
+            viewport: *self.viewport.borrow(),
+        }
+    }
+    /// Records the current layout of the tab, so that the change about to be made to it can be
+    /// undone
+    pub fn record_layout_history(&mut self) {
+        let snapshot = self.layout_snapshot();
+        self.layout_history.record(snapshot);
+    }
+    pub fn undo_layout_change(&mut self, client_id: ClientId) -> Result<()> {
+        let current = self.layout_snapshot();
+        let (tiled_panes, floating_panes) = (&self.tiled_panes, &self.floating_panes);
+        let snapshot = self
+            .layout_history
+            .undo(current, |snapshot, layout_history| {
+                can_restore_layout_snapshot(snapshot, tiled_panes, floating_panes, layout_history)
+            });
+        match snapshot {
+            Some(snapshot) => self
+                .restore_layout_snapshot(snapshot, client_id)
+                .with_context(|| format!("failed to undo layout change for client {client_id}")),
+            None => Ok(()),
+        }
+    }
+    pub fn redo_layout_change(&mut self, client_id: ClientId) -> Result<()> {
+        let current = self.layout_snapshot();
+        let (tiled_panes, floating_panes) = (&self.tiled_panes, &self.floating_panes);
+        let snapshot = self
+            .layout_history
+            .redo(current, |snapshot, layout_history| {
+                can_restore_layout_snapshot(snapshot, tiled_panes, floating_panes, layout_history)
+            });
+        match snapshot {
+            Some(snapshot) => self
+                .restore_layout_snapshot(snapshot, client_id)
+                .with_context(|| format!("failed to redo layout change for client {client_id}")),
+            None => Ok(()),
+        }
+    }
+    fn restore_layout_snapshot(
+        &mut self,
+        snapshot: LayoutSnapshot,
+        client_id: ClientId,
+    ) -> Result<()> {
+        if self.tiled_panes.fullscreen_is_active() {
+            self.tiled_panes.unset_fullscreen();
+        }
+        self.swap_layouts.set_is_floating_damaged();
+        self.swap_layouts.set_is_tiled_damaged();
+
+        // panes that are not part of the snapshot are closed, but kept around so that this can
+        // be redone
+        let panes_to_close: Vec<PaneId> = self
+            .tiled_panes
+            .pane_ids()
+            .filter(|pane_id| !snapshot.tiled_panes.contains_key(pane_id))
+            .chain(
+                self.floating_panes
+                    .pane_ids()
+                    .filter(|pane_id| !snapshot.floating_panes.contains_key(pane_id)),
+            )
+            .copied()
+            .collect();
+        for pane_id in panes_to_close {
+            let closed_pane = if self.floating_panes.panes_contain(&pane_id) {
+                let closed_pane = self.floating_panes.remove_pane(pane_id);
+                self.floating_panes.move_clients_out_of_pane(pane_id);
+                closed_pane
+            } else {
+                self.tiled_panes.remove_pane(pane_id)
+            };
+            if let Some(closed_pane) = closed_pane {
+                self.retain_closed_pane(closed_pane)?;
+            }
         }
+
+        let mut restored_pane_ids = vec![];
+        for (pane_id, geom) in &snapshot.tiled_panes {
+            if let Some(mut pane) = self.layout_history.take_closed_pane(pane_id) {
+                pane.set_geom(*geom);
+                self.tiled_panes.add_pane_with_existing_geom(*pane_id, pane);
+                restored_pane_ids.push(*pane_id);
+            }
+        }
+        for (pane_id, geom) in &snapshot.floating_panes {
+            if let Some(mut pane) = self.layout_history.take_closed_pane(pane_id) {
+                pane.set_geom(*geom);
+                self.floating_panes.add_pane(*pane_id, pane);
+                restored_pane_ids.push(*pane_id);
+            }
+        }
+        for (pane_id, geom) in &snapshot.tiled_panes {
+            if let Some(pane) = self.tiled_panes.get_pane_mut(*pane_id) {
+                pane.set_geom(*geom);
+            }
+        }
+        for (pane_id, geom) in &snapshot.floating_panes {
+            if let Some(pane) = self.floating_panes.get_pane_mut(*pane_id) {
+                pane.set_geom(*geom);
+            }
+        }
+        if !self.floating_panes.has_panes() {
+            self.hide_floating_panes();
+        }
+        // the panes were laid out for the display area at the time of the snapshot, so we bring
+        // them up to date with the current one
+        let current_display_area = *self.display_area.borrow();
+        *self.display_area.borrow_mut() = snapshot.display_area;
+        *self.viewport.borrow_mut() = snapshot.viewport;
+        if current_display_area != snapshot.display_area {
+            self.resize_whole_tab(current_display_area)?;
+        }
+
+        self.tiled_panes.reapply_pane_frames();
+        self.floating_panes.set_pane_frames()?;
+        self.tiled_panes.reapply_pane_focus();
+        for pane_id in restored_pane_ids {
+            if self.floating_panes.panes_contain(&pane_id) {
+                self.show_floating_panes();
+                self.floating_panes.focus_pane(pane_id, client_id);
+            } else if self
+                .tiled_panes
+                .get_pane(pane_id)
+                .map(|p| p.selectable())
+                .unwrap_or(false)
+            {
+                self.tiled_panes.focus_pane(pane_id, client_id);
+            }
+        }
+        self.set_force_render();
         Ok(())
     }
     pub fn clear_active_terminal_screen(&mut self, client_id: ClientId) -> Result<()> {
@@ -2625,6 +3159,9 @@ impl Tab {
             .with_context(err_context)
     }
     pub fn scroll_active_terminal_up(&mut self, client_id: ClientId) {
+        if self.scroll_focused_sticky_pane(client_id, |pane| pane.scroll_up(1, client_id)) {
+            return;
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.scroll_up(1, client_id);
         }
@@ -2633,6 +3170,9 @@ impl Tab {
     pub fn scroll_active_terminal_down(&mut self, client_id: ClientId) -> Result<()> {
         let err_context = || format!("failed to scroll down active pane for client {client_id}");
 
+        if self.scroll_focused_sticky_pane(client_id, |pane| pane.scroll_down(1, client_id)) {
+            return Ok(());
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.scroll_down(1, client_id);
             if !active_pane.is_scrolled() {
@@ -2646,6 +3186,11 @@ impl Tab {
     }
 
     pub fn scroll_active_terminal_up_page(&mut self, client_id: ClientId) {
+        if self.scroll_focused_sticky_pane(client_id, |pane| {
+            pane.scroll_up(pane.rows().max(1) - 1, client_id)
+        }) {
+            return;
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             // prevent overflow when row == 0
             let scroll_rows = active_pane.rows().max(1) - 1;
@@ -2657,6 +3202,11 @@ impl Tab {
         let err_context =
             || format!("failed to scroll down one page in active pane for client {client_id}");
 
+        if self.scroll_focused_sticky_pane(client_id, |pane| {
+            pane.scroll_down(pane.get_content_rows(), client_id)
+        }) {
+            return Ok(());
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             let scroll_rows = active_pane.get_content_rows();
             active_pane.scroll_down(scroll_rows, client_id);
@@ -2671,6 +3221,11 @@ impl Tab {
     }
 
     pub fn scroll_active_terminal_up_half_page(&mut self, client_id: ClientId) {
+        if self.scroll_focused_sticky_pane(client_id, |pane| {
+            pane.scroll_up((pane.rows().max(1) - 1) / 2, client_id)
+        }) {
+            return;
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             // prevent overflow when row == 0
             let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
@@ -2682,6 +3237,11 @@ impl Tab {
         let err_context =
             || format!("failed to scroll down half a page in active pane for client {client_id}");
 
+        if self.scroll_focused_sticky_pane(client_id, |pane| {
+            pane.scroll_down((pane.rows().max(1) - 1) / 2, client_id)
+        }) {
+            return Ok(());
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             let scroll_rows = (active_pane.rows().max(1) - 1) / 2;
             active_pane.scroll_down(scroll_rows, client_id);
@@ -2699,6 +3259,9 @@ impl Tab {
         let err_context =
             || format!("failed to scroll to bottom in active pane for client {client_id}");
 
+        if self.scroll_focused_sticky_pane(client_id, |pane| pane.clear_scroll()) {
+            return Ok(());
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.clear_scroll();
             if !active_pane.is_scrolled() {
@@ -2712,6 +3275,14 @@ impl Tab {
     }
 
     pub fn scroll_active_terminal_to_top(&mut self, client_id: ClientId) -> Result<()> {
+        if self.scroll_focused_sticky_pane(client_id, |pane| {
+            pane.clear_scroll();
+            if let Some(size) = pane.get_line_number() {
+                pane.scroll_up(size, client_id);
+            }
+        }) {
+            return Ok(());
+        }
         if let Some(active_pane) = self.get_active_pane_or_floating_pane_mut(client_id) {
             active_pane.clear_scroll();
             if let Some(size) = active_pane.get_line_number() {
@@ -2721,6 +3292,29 @@ impl Tab {
         Ok(())
     }
 
+    fn has_focused_sticky_pane(&self, client_id: ClientId) -> bool {
+        self.sticky_panes
+            .borrow()
+            .focused_pane_id(client_id)
+            .is_some()
+    }
+    fn scroll_focused_sticky_pane(
+        &mut self,
+        client_id: ClientId,
+        scroll: impl FnOnce(&mut Box<dyn Pane>),
+    ) -> bool {
+        // returns true if the client has a sticky pane focused, in which case it is the one
+        // being scrolled
+        let mut sticky_panes = self.sticky_panes.borrow_mut();
+        let Some(pane_id) = sticky_panes.focused_pane_id(client_id) else {
+            return false;
+        };
+        if let Some(pane) = sticky_panes.get_mut(&pane_id) {
+            scroll(pane);
+        }
+        true
+    }
+
     pub fn clear_active_terminal_scroll(&mut self, client_id: ClientId) -> Result<()> {
         // TODO: is this a thing?
         let err_context =
@@ -2748,6 +3342,13 @@ impl Tab {
             format!("failed to handle scrollwheel up at position {point:?} for client {client_id}")
         };
 
+        let sticky_pane_id = self.sticky_panes.borrow().pane_id_at(point);
+        if let Some(sticky_pane_id) = sticky_pane_id {
+            if let Some(pane) = self.sticky_panes.borrow_mut().get_mut(&sticky_pane_id) {
+                pane.scroll_up(lines, client_id);
+            }
+            return Ok(());
+        }
         if let Some(pane) = self.get_pane_at(point, false).with_context(err_context)? {
             let relative_position = pane.relative_position(point);
             if let Some(mouse_event) = pane.mouse_scroll_up(&relative_position) {
@@ -2779,6 +3380,13 @@ impl Tab {
             )
         };
 
+        let sticky_pane_id = self.sticky_panes.borrow().pane_id_at(point);
+        if let Some(sticky_pane_id) = sticky_pane_id {
+            if let Some(pane) = self.sticky_panes.borrow_mut().get_mut(&sticky_pane_id) {
+                pane.scroll_down(lines, client_id);
+            }
+            return Ok(());
+        }
         if let Some(pane) = self.get_pane_at(point, false).with_context(err_context)? {
             let relative_position = pane.relative_position(point);
             if let Some(mouse_event) = pane.mouse_scroll_down(&relative_position) {
@@ -2868,6 +3476,14 @@ impl Tab {
             )
         };
 
+        let clicked_sticky_pane_id = self.sticky_panes.borrow().pane_id_at(position);
+        if let Some(clicked_sticky_pane_id) = clicked_sticky_pane_id {
+            self.sticky_panes
+                .borrow_mut()
+                .focus_pane(clicked_sticky_pane_id, client_id);
+            return Ok(());
+        }
+        self.sticky_panes.borrow_mut().unfocus(client_id);
         self.focus_pane_at(position, client_id)
             .with_context(err_context)?;
 
@@ -3690,6 +4306,28 @@ impl Tab {
         }
         Ok(())
     }
+    pub fn add_floating_pane_in_place(
+        &mut self,
+        pane: Box<dyn Pane>,
+        pane_id: PaneId,
+        client_id: ClientId,
+    ) -> Result<()> {
+        // unlike add_floating_pane, this keeps the pane where it was (eg. when it stops being
+        // sticky)
+        let err_context = || format!("failed to add floating pane {pane_id:?} in place");
+        resize_pty!(pane, self.os_api, self.senders, self.character_cell_size)
+            .with_context(err_context)?;
+        self.floating_panes.add_pane(pane_id, pane);
+        if !self.floating_panes.panes_are_visible() {
+            self.show_floating_panes();
+        }
+        self.floating_panes.focus_pane(pane_id, client_id);
+        self.set_force_render();
+        Ok(())
+    }
+    pub fn has_floating_pane(&self, pane_id: &PaneId) -> bool {
+        self.floating_panes.panes_contain(pane_id)
+    }
     pub fn add_tiled_pane(
         &mut self,
         mut pane: Box<dyn Pane>,
@@ -3778,6 +4416,29 @@ pub fn pane_info_for_pane(pane_id: &PaneId, pane: &Box<dyn Pane>) -> PaneInfo {
     pane_info
 }
 
+// a snapshot can only be restored if all of its panes are still around and it would not leave the
+// tab without any selectable tiled panes
+fn can_restore_layout_snapshot(
+    snapshot: &LayoutSnapshot,
+    tiled_panes: &TiledPanes,
+    floating_panes: &FloatingPanes,
+    layout_history: &LayoutHistory,
+) -> bool {
+    let tiled_panes_can_be_restored = snapshot.tiled_panes.keys().all(|pane_id| {
+        tiled_panes.panes_contain(pane_id) || layout_history.has_closed_pane(pane_id)
+    });
+    let floating_panes_can_be_restored = snapshot.floating_panes.keys().all(|pane_id| {
+        floating_panes.panes_contain(pane_id) || layout_history.has_closed_pane(pane_id)
+    });
+    let has_selectable_tiled_panes = snapshot.tiled_panes.keys().any(|pane_id| {
+        tiled_panes
+            .get_pane(*pane_id)
+            .map(|p| p.selectable())
+            .unwrap_or_else(|| layout_history.has_closed_pane(pane_id))
+    });
+    tiled_panes_can_be_restored && floating_panes_can_be_restored && has_selectable_tiled_panes
+}
+
 #[cfg(test)]
 #[path = "./unit/tab_tests.rs"]
 mod tab_tests;
@@ -1,10 +1,14 @@
 use crate::panes::{FloatingPanes, TiledPanes};
+use crate::tab::Pane;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::Path;
 use std::rc::Rc;
 use zellij_utils::{
     input::layout::{
-        FloatingPaneLayout, LayoutConstraint, SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
+        FloatingPaneLayout, LayoutConstraint, Run, RunPluginOrAlias, SwapFloatingLayout,
+        SwapTiledLayout, TiledPaneLayout,
     },
     pane_size::{PaneGeom, Size},
 };
@@ -61,6 +65,12 @@ impl SwapLayouts {
     pub fn set_is_tiled_damaged(&mut self) {
         self.is_tiled_damaged = true;
     }
+    pub fn reset_floating_layout_position(&mut self) {
+        self.current_floating_layout_position = 0;
+    }
+    pub fn reset_tiled_layout_position(&mut self) {
+        self.current_tiled_layout_position = 0;
+    }
     pub fn is_floating_damaged(&self) -> bool {
         self.is_floating_damaged
     }
@@ -105,6 +115,52 @@ impl SwapLayouts {
             None => (None, self.is_floating_damaged),
         }
     }
+    pub fn has_command_constraints(&self) -> bool {
+        !self.tiled_command_constraints().is_empty()
+            || !self.floating_command_constraints().is_empty()
+    }
+    /// Whether each of the has_command constraints of the swap layouts is met, so that a change
+    /// in the commands running in the panes can be told apart from one that does not matter
+    pub fn command_constraints_met(
+        &self,
+        tiled_panes: &TiledPanes,
+        floating_panes: &FloatingPanes,
+    ) -> Vec<bool> {
+        let tiled_constraints_met = self.tiled_command_constraints().into_iter().map(|command| {
+            tiled_panes
+                .get_panes()
+                .any(|(_, p)| runs_command(p.as_ref(), command))
+        });
+        let floating_constraints_met =
+            self.floating_command_constraints()
+                .into_iter()
+                .map(|command| {
+                    floating_panes
+                        .get_panes()
+                        .any(|(_, p)| runs_command(p.as_ref(), command))
+                });
+        tiled_constraints_met
+            .chain(floating_constraints_met)
+            .collect()
+    }
+    fn tiled_command_constraints(&self) -> Vec<&str> {
+        let mut commands = vec![];
+        for swap_layout in &self.swap_tiled_layouts {
+            for constraint in swap_layout.0.keys() {
+                command_constraints(constraint, &mut commands);
+            }
+        }
+        commands
+    }
+    fn floating_command_constraints(&self) -> Vec<&str> {
+        let mut commands = vec![];
+        for swap_layout in &self.swap_floating_layouts {
+            for constraint in swap_layout.0.keys() {
+                command_constraints(constraint, &mut commands);
+            }
+        }
+        commands
+    }
     pub fn swap_floating_panes(
         &mut self,
         floating_panes: &FloatingPanes,
@@ -179,7 +235,14 @@ impl SwapLayouts {
             LayoutConstraint::ExactPanes(pane_count) => {
                 tiled_panes.visible_panes_count() == *pane_count
             },
+            LayoutConstraint::All(constraints) => constraints
+                .iter()
+                .all(|constraint| self.state_fits_tiled_panes_constraint(constraint, tiled_panes)),
             LayoutConstraint::NoConstraint => true,
+            constraint => self.state_fits_content_or_size_constraint(
+                constraint,
+                tiled_panes.get_panes().map(|(_, p)| p),
+            ),
         }
     }
     fn state_fits_floating_panes_constraint(
@@ -197,7 +260,34 @@ impl SwapLayouts {
             LayoutConstraint::ExactPanes(pane_count) => {
                 floating_panes.visible_panes_count() == *pane_count
             },
+            LayoutConstraint::All(constraints) => constraints.iter().all(|constraint| {
+                self.state_fits_floating_panes_constraint(constraint, floating_panes)
+            }),
             LayoutConstraint::NoConstraint => true,
+            constraint => self.state_fits_content_or_size_constraint(
+                constraint,
+                floating_panes.get_panes().map(|(_, p)| p),
+            ),
+        }
+    }
+    fn state_fits_content_or_size_constraint<'a>(
+        &self,
+        constraint: &LayoutConstraint,
+        mut panes: impl Iterator<Item = &'a Box<dyn Pane>>,
+    ) -> bool {
+        let display_area = self.display_area.borrow();
+        match constraint {
+            LayoutConstraint::HasCommand(command) => {
+                panes.any(|p| runs_command(p.as_ref(), command))
+            },
+            LayoutConstraint::HasPlugin(plugin) => {
+                panes.any(|p| runs_plugin(p.invoked_with(), plugin))
+            },
+            LayoutConstraint::MinColumns(min_columns) => display_area.cols >= *min_columns,
+            LayoutConstraint::MaxColumns(max_columns) => display_area.cols <= *max_columns,
+            LayoutConstraint::MinRows(min_rows) => display_area.rows >= *min_rows,
+            LayoutConstraint::MaxRows(max_rows) => display_area.rows <= *max_rows,
+            _ => true,
         }
     }
     pub fn swap_tiled_panes(
@@ -290,3 +380,51 @@ impl SwapLayouts {
         None
     }
 }
+
+fn runs_command(pane: &dyn Pane, command: &str) -> bool {
+    // the command can be given either by its name or by its full path
+    let is_command = |command_name: &Path| {
+        command_name == Path::new(command) || command_name.file_name() == Some(OsStr::new(command))
+    };
+    // what runs in the foreground of the pane as of the last time it was queried, or what the
+    // pane was opened to run while it still runs it
+    let runs_in_foreground = pane
+        .foreground_command()
+        .and_then(|command_line| command_line.first())
+        .map(|command_name| is_command(Path::new(command_name)))
+        .unwrap_or(false);
+    let was_opened_to_run = match pane.invoked_with() {
+        Some(Run::Command(run_command)) => !pane.is_held() && is_command(&run_command.command),
+        _ => false,
+    };
+    runs_in_foreground || was_opened_to_run
+}
+
+fn command_constraints<'a>(constraint: &'a LayoutConstraint, commands: &mut Vec<&'a str>) {
+    match constraint {
+        LayoutConstraint::HasCommand(command) => commands.push(command),
+        LayoutConstraint::All(constraints) => {
+            for constraint in constraints {
+                command_constraints(constraint, commands);
+            }
+        },
+        _ => {},
+    }
+}
+
+fn runs_plugin(run: &Option<Run>, plugin: &str) -> bool {
+    match run {
+        Some(Run::Plugin(RunPluginOrAlias::RunPlugin(run_plugin))) => {
+            run_plugin.location.display() == plugin
+        },
+        Some(Run::Plugin(RunPluginOrAlias::Alias(plugin_alias))) => {
+            plugin_alias.name == plugin
+                || plugin_alias
+                    .run_plugin
+                    .as_ref()
+                    .map(|r| r.location.display() == plugin)
+                    .unwrap_or(false)
+        },
+        _ => false,
+    }
+}
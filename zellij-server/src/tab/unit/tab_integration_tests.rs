@@ -1,5 +1,6 @@
 use super::{Output, Tab};
 use crate::panes::sixel::SixelImageStore;
+use crate::panes::StickyPanes;
 use crate::screen::CopyOptions;
 use crate::Arc;
 
@@ -20,7 +21,7 @@ use zellij_utils::data::ResizeStrategy;
 use zellij_utils::envs::set_session_name;
 use zellij_utils::errors::{prelude::*, ErrorContext};
 use zellij_utils::input::layout::{
-    FloatingPaneLayout, Layout, PluginUserConfiguration, RunPluginLocation, RunPluginOrAlias,
+    FloatingPaneLayout, Layout, PluginUserConfiguration, Run, RunPluginLocation, RunPluginOrAlias,
     SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
 };
 use zellij_utils::input::plugins::PluginTag;
@@ -38,7 +39,7 @@ use std::rc::Rc;
 
 use zellij_utils::{
     data::{InputMode, ModeInfo, Palette, Style},
-    input::command::{RunCommand, TerminalAction},
+    input::command::{PaneDependency, RunCommand, TerminalAction},
     interprocess::local_socket::LocalSocketStream,
     ipc::{ClientToServerMsg, ServerToClientMsg},
 };
@@ -233,6 +234,7 @@ fn create_new_tab(size: Size, default_mode: ModeInfo) -> Tab {
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -310,6 +312,7 @@ fn create_new_tab_with_swap_layouts(
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -389,6 +392,7 @@ fn create_new_tab_with_os_api(
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -454,6 +458,7 @@ fn create_new_tab_with_layout(size: Size, default_mode: ModeInfo, layout: &str)
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -533,6 +538,7 @@ fn create_new_tab_with_mock_pty_writer(
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -603,6 +609,7 @@ fn create_new_tab_with_sixel_support(
         size,
         character_cell_size,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -3269,6 +3276,208 @@ fn swapping_layouts_after_resize_snaps_to_current_layout() {
     assert_snapshot!(snapshot);
 }
 
+#[test]
+fn swap_tiled_layout_with_terminal_size_constraints_follows_resizes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout name="wide" {
+                tab min_columns=120 split_direction="vertical" {
+                    pane
+                    pane
+                }
+            }
+            swap_tiled_layout name="narrow" {
+                tab max_columns=119 {
+                    pane stacked=true {
+                        children
+                    }
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(swap_layouts, "file_name.kdl".into(), None, None).unwrap();
+    let swap_tiled_layouts = layout.swap_tiled_layouts.clone();
+    let swap_floating_layouts = layout.swap_floating_layouts.clone();
+    let mut tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (swap_tiled_layouts, swap_floating_layouts),
+        None,
+        true,
+    );
+    tab.new_pane(PaneId::Terminal(2), None, None, None, None, Some(client_id))
+        .unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("wide".to_owned()), false),
+        "wide layout applied to a wide terminal"
+    );
+    tab.resize_whole_tab(Size {
+        cols: 100,
+        rows: 20,
+    })
+    .unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("narrow".to_owned()), false),
+        "narrow layout applied once the terminal is too narrow"
+    );
+}
+
+#[test]
+fn swap_tiled_layout_with_command_constraint_is_applied_when_command_pane_opens() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout name="editor" {
+                tab has_command="nvim" split_direction="vertical" {
+                    pane
+                    pane
+                }
+            }
+            swap_tiled_layout name="default" {
+                tab {
+                    pane
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(swap_layouts, "file_name.kdl".into(), None, None).unwrap();
+    let swap_tiled_layouts = layout.swap_tiled_layouts.clone();
+    let swap_floating_layouts = layout.swap_floating_layouts.clone();
+    let mut tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (swap_tiled_layouts.clone(), swap_floating_layouts.clone()),
+        None,
+        true,
+    );
+    tab.new_pane(PaneId::Terminal(2), None, None, None, None, Some(client_id))
+        .unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("default".to_owned()), false),
+        "constraint not met without an nvim pane"
+    );
+
+    let mut tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (swap_tiled_layouts, swap_floating_layouts),
+        None,
+        true,
+    );
+    let nvim = Run::Command(RunCommand {
+        command: PathBuf::from("/usr/bin/nvim"),
+        ..Default::default()
+    });
+    tab.new_pane(
+        PaneId::Terminal(2),
+        None,
+        None,
+        Some(nvim),
+        None,
+        Some(client_id),
+    )
+    .unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("editor".to_owned()), false),
+        "editor layout applied when an nvim pane opens"
+    );
+}
+
+#[test]
+fn swap_tiled_layout_with_command_constraint_follows_foreground_commands() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout name="editor" {
+                tab has_command="nvim" split_direction="vertical" {
+                    pane
+                    pane
+                }
+            }
+            swap_tiled_layout name="wide editor" {
+                tab has_command="nvim" min_columns=200 split_direction="vertical" {
+                    pane
+                    pane
+                }
+            }
+            swap_tiled_layout name="default" {
+                tab {
+                    pane
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(swap_layouts, "file_name.kdl".into(), None, None).unwrap();
+    let swap_tiled_layouts = layout.swap_tiled_layouts.clone();
+    let swap_floating_layouts = layout.swap_floating_layouts.clone();
+    let mut tab = create_new_tab_with_swap_layouts(
+        size,
+        ModeInfo::default(),
+        (swap_tiled_layouts, swap_floating_layouts),
+        None,
+        true,
+    );
+    tab.new_pane(PaneId::Terminal(2), None, None, None, None, Some(client_id))
+        .unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("default".to_owned()), false),
+        "constraint not met without nvim running"
+    );
+
+    // nvim was started from the shell of the pane
+    let terminal_commands = HashMap::from([(2, vec!["nvim".to_owned(), "src/main.rs".to_owned()])]);
+    assert!(tab.update_foreground_commands(&terminal_commands).unwrap());
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("editor".to_owned()), false),
+        "editor layout applied once nvim runs in the foreground"
+    );
+    assert!(
+        !tab.update_foreground_commands(&terminal_commands).unwrap(),
+        "no relayout while the same commands run"
+    );
+    tab.next_swap_layout(Some(client_id), true).unwrap();
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("default".to_owned()), false),
+        "layout with more than one constraint skipped unless they are all met"
+    );
+
+    // nvim exited and the pane is back to its shell
+    assert!(tab.update_foreground_commands(&HashMap::new()).unwrap());
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("default".to_owned()), false),
+        "editor layout no longer applies once nvim exits"
+    );
+    assert!(tab.update_foreground_commands(&terminal_commands).unwrap());
+    assert_eq!(
+        tab.swap_layout_info(),
+        (Some("editor".to_owned()), false),
+        "editor layout applied again when nvim runs again, even though the current layout fits"
+    );
+}
+
 #[test]
 fn swap_tiled_layout_with_stacked_children() {
     let size = Size {
@@ -6946,3 +7155,61 @@ fn can_define_expanded_pane_in_stack() {
     );
     assert_snapshot!(snapshot);
 }
+
+#[test]
+fn start_suspended_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut tab = create_new_tab(size, ModeInfo::default());
+    for pane_id in [2, 3] {
+        tab.new_pane(
+            PaneId::Terminal(pane_id),
+            None,
+            None,
+            None,
+            None,
+            Some(client_id),
+        )
+        .unwrap();
+    }
+    let suspended_command = RunCommand::new(PathBuf::from("cargo"));
+    let command_with_dependency = RunCommand {
+        depends_on: vec![PaneDependency {
+            pane_name: "db".to_owned(),
+            ..Default::default()
+        }],
+        ..RunCommand::new(PathBuf::from("npm"))
+    };
+    let is_first_run = true;
+    tab.hold_pane(
+        PaneId::Terminal(1),
+        None,
+        is_first_run,
+        suspended_command.clone(),
+    );
+    tab.hold_pane(
+        PaneId::Terminal(2),
+        None,
+        is_first_run,
+        command_with_dependency,
+    );
+    tab.start_suspended_panes().unwrap();
+    let is_suspended = |tab: &mut Tab, pane_id| {
+        tab.get_pane_with_id_mut(PaneId::Terminal(pane_id))
+            .unwrap()
+            .command_waiting_for_first_run()
+            .is_some()
+    };
+    assert!(!is_suspended(&mut tab, 1), "suspended command was started");
+    assert!(
+        is_suspended(&mut tab, 2),
+        "command waiting for its dependencies was left to start once they are ready"
+    );
+    assert!(
+        !is_suspended(&mut tab, 3),
+        "pane without a command is unaffected"
+    );
+}
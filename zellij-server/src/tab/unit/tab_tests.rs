@@ -1,5 +1,6 @@
 use super::Tab;
 use crate::panes::sixel::SixelImageStore;
+use crate::panes::StickyPanes;
 use crate::screen::CopyOptions;
 use crate::{
     os_input_output::{AsyncReader, Pid, ServerOsApi},
@@ -174,6 +175,7 @@ fn create_new_tab(size: Size) -> Tab {
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -236,6 +238,7 @@ fn create_new_tab_with_layout(size: Size, layout: TiledPaneLayout) -> Tab {
         size,
         character_cell_info,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -304,6 +307,7 @@ fn create_new_tab_with_cell_size(
         size,
         character_cell_size,
         sixel_image_store,
+        Rc::new(RefCell::new(StickyPanes::new(style, session_is_mirrored))),
         os_api,
         senders,
         max_panes,
@@ -6020,6 +6024,98 @@ pub fn cannot_resize_down_when_pane_has_fixed_rows() {
     );
 }
 
+#[test]
+pub fn cannot_resize_down_when_pane_is_at_its_max_size() {
+    // ┌───────────┐                  ┌───────────┐
+    // │███████████│                  │███████████│
+    // ├───────────┤ ==resize=down==> ├───────────┤
+    // │           │                  │           │
+    // └───────────┘                  └───────────┘
+    // █ == focused pane
+
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Horizontal;
+    let mut bounded_child = TiledPaneLayout::default();
+    bounded_child.max_size = Some(10);
+    initial_layout.children = vec![bounded_child, TiledPaneLayout::default()];
+    let mut tab = create_new_tab_with_layout(size, initial_layout);
+    tab_resize_down(&mut tab, 1);
+
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(0))
+            .unwrap()
+            .position_and_size()
+            .rows
+            .as_usize(),
+        10,
+        "pane 1 height stayed the same"
+    );
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(1))
+            .unwrap()
+            .position_and_size()
+            .rows
+            .as_usize(),
+        10,
+        "pane 2 height stayed the same"
+    );
+}
+
+#[test]
+pub fn pane_keeps_its_min_size_when_tab_shrinks() {
+    // ┌──────┬────────────┐                  ┌──────┬─────┐
+    // │      │            │                  │      │     │
+    // │      │            │ ==shrink=tab==>  │      │     │
+    // │      │            │                  │      │     │
+    // └──────┴────────────┘                  └──────┴─────┘
+
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+
+    let mut initial_layout = TiledPaneLayout::default();
+    initial_layout.children_split_direction = SplitDirection::Vertical;
+    let mut bounded_child = TiledPaneLayout::default();
+    bounded_child.split_size = Some(SplitSize::Percent(20));
+    bounded_child.min_size = Some(30);
+    initial_layout.children = vec![bounded_child, TiledPaneLayout::default()];
+    let mut tab = create_new_tab_with_layout(size, initial_layout);
+    tab.resize_whole_tab(Size { cols: 60, rows: 20 }).unwrap();
+
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(0))
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize(),
+        30,
+        "pane 1 kept its min_size"
+    );
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(1))
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize(),
+        30,
+        "pane 2 took the remaining space"
+    );
+}
+
 #[test]
 pub fn cannot_resize_down_when_pane_below_has_fixed_rows() {
     // ┌───────────┐                  ┌───────────┐
@@ -14424,3 +14520,340 @@ fn correctly_resize_frameless_panes_on_pane_close() {
     let content_size = (pane.get_content_columns(), pane.get_content_rows());
     assert_eq!(content_size, (cols, rows));
 }
+
+#[test]
+fn undo_and_redo_pane_split() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.undo_layout_change(1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 1, "split was undone");
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(1))
+            .unwrap()
+            .position_and_size()
+            .cols
+            .as_usize(),
+        121,
+        "remaining pane column count"
+    );
+    assert!(
+        tab.has_closed_pane(&PaneId::Terminal(2)),
+        "split pane was kept around"
+    );
+
+    tab.redo_layout_change(1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "split was redone");
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .position_and_size()
+            .x,
+        61,
+        "split pane x position"
+    );
+    assert_eq!(
+        tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(2)),
+        "split pane is focused again"
+    );
+}
+
+#[test]
+fn undo_pane_close_restores_pane_and_its_contents() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.handle_pty_bytes(2, "I am still here".as_bytes().to_vec())
+        .unwrap();
+    tab.close_focused_pane(1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 1, "pane was closed");
+    assert!(
+        tab.has_terminal_pid(2),
+        "closed pane is kept around for the grace period"
+    );
+
+    tab.undo_layout_change(1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "pane close was undone");
+    let restored_pane = tab.tiled_panes.panes.get_mut(&PaneId::Terminal(2)).unwrap();
+    assert_eq!(restored_pane.position_and_size().y, 10, "restored pane y");
+    assert_eq!(
+        restored_pane.position_and_size().rows.as_usize(),
+        10,
+        "restored pane rows"
+    );
+    assert!(
+        restored_pane
+            .dump_screen(1, false)
+            .contains("I am still here"),
+        "restored pane kept its contents"
+    );
+}
+
+#[test]
+fn undo_resize() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab_resize_left(&mut tab, 1);
+    tab.undo_layout_change(1).unwrap();
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .position_and_size()
+            .x,
+        61,
+        "resize was undone"
+    );
+    assert_eq!(tab.tiled_panes.panes.len(), 2, "split was not undone");
+}
+
+#[test]
+fn cannot_undo_close_of_pane_whose_process_exited() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.close_focused_pane(1).unwrap();
+    // the process in the pane exited while it was closed
+    tab.close_pane(PaneId::Terminal(2), false, None);
+    tab.undo_layout_change(1).unwrap();
+    assert_eq!(tab.tiled_panes.panes.len(), 1, "pane was not restored");
+}
+
+#[test]
+fn stack_pane_with_neighbour_above() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.stack_active_pane(1, Direction::Up).unwrap();
+    let first_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(1))
+        .unwrap()
+        .position_and_size();
+    let second_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(2))
+        .unwrap()
+        .position_and_size();
+    assert!(first_pane_geom.is_stacked, "first pane is stacked");
+    assert!(second_pane_geom.is_stacked, "second pane is stacked");
+    assert_eq!(first_pane_geom.y, 0, "first pane y position");
+    assert_eq!(
+        first_pane_geom.rows.as_usize(),
+        1,
+        "first pane is a one-liner"
+    );
+    assert_eq!(second_pane_geom.y, 1, "second pane y position");
+    assert_eq!(
+        second_pane_geom.rows.as_usize(),
+        19,
+        "focused pane is the expanded one"
+    );
+    assert_eq!(
+        tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(2)),
+        "focus stays on the stacked pane"
+    );
+}
+
+#[test]
+fn stack_pane_with_neighbour_to_the_right() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.move_focus_left(1).unwrap();
+    tab.stack_active_pane(1, Direction::Right).unwrap();
+    let first_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(1))
+        .unwrap()
+        .position_and_size();
+    let second_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(2))
+        .unwrap()
+        .position_and_size();
+    assert!(first_pane_geom.is_stacked, "first pane is stacked");
+    assert!(second_pane_geom.is_stacked, "second pane is stacked");
+    assert_eq!(
+        first_pane_geom.cols.as_usize(),
+        121,
+        "stack takes up both columns"
+    );
+    assert_eq!(
+        first_pane_geom.rows.as_usize(),
+        19,
+        "focused pane is expanded"
+    );
+    assert_eq!(second_pane_geom.y, 19, "neighbour is stacked below");
+    assert_eq!(
+        second_pane_geom.rows.as_usize(),
+        1,
+        "neighbour is a one-liner"
+    );
+}
+
+#[test]
+fn stack_pane_without_neighbour_is_a_no_op() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.stack_active_pane(1, Direction::Down).unwrap();
+    assert!(
+        !tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .position_and_size()
+            .is_stacked,
+        "pane was not stacked"
+    );
+}
+
+#[test]
+fn unstack_panes_into_splits() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.stack_active_pane(1, Direction::Up).unwrap();
+    tab.unstack_active_pane(1).unwrap();
+    let first_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(1))
+        .unwrap()
+        .position_and_size();
+    let second_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(2))
+        .unwrap()
+        .position_and_size();
+    assert!(
+        !first_pane_geom.is_stacked,
+        "first pane is no longer stacked"
+    );
+    assert!(
+        !second_pane_geom.is_stacked,
+        "second pane is no longer stacked"
+    );
+    assert_eq!(first_pane_geom.rows.as_usize(), 10, "first pane rows");
+    assert_eq!(second_pane_geom.y, 10, "second pane y position");
+    assert_eq!(second_pane_geom.rows.as_usize(), 10, "second pane rows");
+}
+
+#[test]
+fn remove_pane_from_stack() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.horizontal_split(PaneId::Terminal(3), None, 1).unwrap();
+    tab.stack_active_pane(1, Direction::Up).unwrap();
+    tab.stack_active_pane(1, Direction::Up).unwrap();
+    assert!(
+        tab.tiled_panes
+            .panes
+            .values()
+            .all(|p| p.position_and_size().is_stacked),
+        "all three panes are stacked"
+    );
+    tab.remove_active_pane_from_stack(1).unwrap();
+    let removed_pane_geom = tab
+        .tiled_panes
+        .panes
+        .get(&PaneId::Terminal(3))
+        .unwrap()
+        .position_and_size();
+    assert!(!removed_pane_geom.is_stacked, "pane was removed from stack");
+    assert_eq!(removed_pane_geom.y, 10, "removed pane is below the stack");
+    assert_eq!(removed_pane_geom.rows.as_usize(), 10, "removed pane rows");
+    let stacked_pane_count = tab
+        .tiled_panes
+        .panes
+        .values()
+        .filter(|p| p.position_and_size().is_stacked)
+        .count();
+    assert_eq!(stacked_pane_count, 2, "remaining panes are still stacked");
+}
+
+#[test]
+fn stack_misaligned_panes_is_a_no_op() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.horizontal_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab.vertical_split(PaneId::Terminal(3), None, 1).unwrap();
+    // the pane above is wider than this one
+    tab.stack_active_pane(1, Direction::Up).unwrap();
+    assert!(
+        tab.tiled_panes
+            .panes
+            .values()
+            .all(|p| !p.position_and_size().is_stacked),
+        "no pane was stacked"
+    );
+}
+
+#[test]
+fn unstack_pane_that_is_not_stacked_is_not_recorded_in_undo_history() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut tab = create_new_tab(size);
+    tab.vertical_split(PaneId::Terminal(2), None, 1).unwrap();
+    tab_resize_left(&mut tab, 1);
+    tab.unstack_active_pane(1).unwrap();
+    tab.remove_active_pane_from_stack(1).unwrap();
+    tab.undo_layout_change(1).unwrap();
+    assert_eq!(
+        tab.tiled_panes
+            .panes
+            .get(&PaneId::Terminal(2))
+            .unwrap()
+            .position_and_size()
+            .x,
+        61,
+        "resize was undone"
+    );
+}
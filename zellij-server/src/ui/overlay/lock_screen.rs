@@ -0,0 +1,146 @@
+use zellij_utils::pane_size::Size;
+use zellij_utils::session_lock::verify_passphrase;
+
+use crate::ClientId;
+use zellij_utils::errors::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+// wrong passphrases are retried right away a few times, after which each one doubles the time
+// until the next attempt is accepted
+const FAILED_ATTEMPTS_BEFORE_DELAY: u32 = 3;
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+/// Drawn over the whole display of every client while the session is locked, it takes the
+/// passphrase that unlocks the session. Each client types its own passphrase, while failed
+/// attempts are counted for the whole session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockScreen {
+    passphrase_hash: String,
+    entered_passphrases: HashMap<ClientId, String>,
+    clients_with_wrong_passphrase: HashSet<ClientId>,
+    failed_attempts: u32,
+    next_attempt_at: Option<Instant>,
+}
+
+impl LockScreen {
+    pub fn new(passphrase_hash: String) -> Self {
+        Self {
+            passphrase_hash,
+            entered_passphrases: HashMap::new(),
+            clients_with_wrong_passphrase: HashSet::new(),
+            failed_attempts: 0,
+            next_attempt_at: None,
+        }
+    }
+    /// Handles the bytes typed by a client while the session is locked, returns true once the
+    /// right passphrase was entered
+    pub fn handle_input(&mut self, client_id: ClientId, bytes: &[u8]) -> bool {
+        if bytes.first() == Some(&27) {
+            // escape or some other key with an escape sequence (eg. an arrow key)
+            self.entered_passphrases.remove(&client_id);
+            return false;
+        }
+        let mut entered_passphrase = self
+            .entered_passphrases
+            .remove(&client_id)
+            .unwrap_or_default();
+        for character in String::from_utf8_lossy(bytes).chars() {
+            match character {
+                '\r' | '\n' => {
+                    let passphrase = std::mem::take(&mut entered_passphrase);
+                    self.clients_with_wrong_passphrase.insert(client_id);
+                    if self.retry_delay().is_some() {
+                        // attempts made before the delay is over are not even checked
+                        continue;
+                    }
+                    if verify_passphrase(&passphrase, &self.passphrase_hash) {
+                        return true;
+                    }
+                    self.failed_attempts += 1;
+                    self.next_attempt_at = self
+                        .failed_attempts
+                        .checked_sub(FAILED_ATTEMPTS_BEFORE_DELAY)
+                        .map(|delays_so_far| {
+                            let delay = FIRST_RETRY_DELAY
+                                .checked_mul(2u32.saturating_pow(delays_so_far))
+                                .unwrap_or(MAX_RETRY_DELAY)
+                                .min(MAX_RETRY_DELAY);
+                            Instant::now() + delay
+                        });
+                },
+                '\u{7f}' | '\u{8}' => {
+                    entered_passphrase.pop();
+                },
+                '\u{15}' => {
+                    // ctrl-u
+                    entered_passphrase.clear();
+                },
+                character if !character.is_control() => {
+                    entered_passphrase.push(character);
+                    self.clients_with_wrong_passphrase.remove(&client_id);
+                },
+                _ => {},
+            }
+        }
+        self.entered_passphrases
+            .insert(client_id, entered_passphrase);
+        false
+    }
+    pub fn remove_client(&mut self, client_id: ClientId) {
+        self.entered_passphrases.remove(&client_id);
+        self.clients_with_wrong_passphrase.remove(&client_id);
+    }
+    fn retry_delay(&self) -> Option<Duration> {
+        self.next_attempt_at
+            .and_then(|next_attempt_at| next_attempt_at.checked_duration_since(Instant::now()))
+            .filter(|retry_delay| !retry_delay.is_zero())
+    }
+    /// The lock screen as this client sees it
+    pub fn render(&self, size: Size, client_id: ClientId) -> Result<String> {
+        let mut output = String::new();
+        // hide the cursor and clear the display so that nothing of the session is visible
+        output.push_str("\u{1b}[?25l\u{1b}[0m\u{1b}[2J");
+        let passphrase_length = self
+            .entered_passphrases
+            .get(&client_id)
+            .map(|entered_passphrase| entered_passphrase.chars().count())
+            .unwrap_or(0);
+        let passphrase_mask = "*".repeat(passphrase_length);
+        let mut lines = vec![
+            (
+                "\u{1b}[1mSession locked\u{1b}[22m".to_owned(),
+                "Session locked".len(),
+            ),
+            (String::new(), 0),
+            (
+                format!("Passphrase: {}", passphrase_mask),
+                12 + passphrase_mask.len(),
+            ),
+        ];
+        if self.clients_with_wrong_passphrase.contains(&client_id) {
+            let message = match self.retry_delay() {
+                Some(retry_delay) => format!(
+                    "Wrong passphrase, try again in {}s",
+                    retry_delay.as_secs() + 1
+                ),
+                None => "Wrong passphrase, try again".to_owned(),
+            };
+            lines.push((String::new(), 0));
+            lines.push((format!("\u{1b}[31m{}\u{1b}[39m", message), message.len()));
+        }
+        let first_line = (size.rows.saturating_sub(lines.len()) / 2) + 1;
+        for (index, (line, width)) in lines.iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let x = (size.cols.saturating_sub(*width) / 2) + 1;
+            write!(&mut output, "\u{1b}[{};{}H{}", first_line + index, x, line)
+                .context("failed to generate VTE output from lock screen")?;
+        }
+        Ok(output)
+    }
+}
@@ -5,7 +5,10 @@
 //! prompt's:
 //!
 //! notification's:
+//!
+//! the lock screen:
 
+pub mod lock_screen;
 pub mod prompt;
 
 use crate::ServerInstruction;
@@ -75,6 +75,7 @@ pub struct PaneFrame {
     pub other_focused_clients: Vec<ClientId>,
     exit_status: Option<ExitStatus>,
     is_first_run: bool,
+    restarts: Option<(usize, Option<i32>)>, // (restart count, last exit status)
     pane_is_stacked_over: bool,
     pane_is_stacked_under: bool,
     should_draw_pane_frames: bool,
@@ -99,6 +100,7 @@ impl PaneFrame {
             other_cursors_exist_in_session: frame_params.other_cursors_exist_in_session,
             exit_status: None,
             is_first_run: false,
+            restarts: None,
             pane_is_stacked_over: frame_params.pane_is_stacked_over,
             pane_is_stacked_under: frame_params.pane_is_stacked_under,
             should_draw_pane_frames: frame_params.should_draw_pane_frames,
@@ -110,6 +112,9 @@ impl PaneFrame {
             None => Some(ExitStatus::Exited),
         };
     }
+    pub fn add_restarts(&mut self, restart_count: usize, last_exit_status: Option<i32>) {
+        self.restarts = Some((restart_count, last_exit_status));
+    }
     pub fn indicate_first_run(&mut self) {
         self.is_first_run = true;
     }
@@ -638,7 +643,12 @@ impl PaneFrame {
     }
     fn render_held_undertitle(&self) -> Result<Vec<TerminalCharacter>> {
         let max_undertitle_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
-        let (mut first_part, first_part_len) = self.first_exited_held_title_part_full();
+        let (mut first_part, mut first_part_len) = self.first_exited_held_title_part_full();
+        if let Some((mut restarts_part, restarts_part_len)) = self.restarts_title_part(false) {
+            // the exit status of the held command is already displayed
+            first_part.append(&mut restarts_part);
+            first_part_len += restarts_part_len;
+        }
         let mut left_boundary =
             foreground_color(self.get_corner(boundary_type::BOTTOM_LEFT), self.color);
         let mut right_boundary =
@@ -694,6 +704,32 @@ impl PaneFrame {
         };
         Ok(res)
     }
+    fn render_restarts_undertitle(&self) -> Vec<TerminalCharacter> {
+        let max_undertitle_length = self.geom.cols.saturating_sub(2); // 2 for the left and right corners
+        match self.restarts_title_part(true) {
+            Some((mut restarts_part, restarts_part_len))
+                if restarts_part_len <= max_undertitle_length =>
+            {
+                let mut padding = String::new();
+                for _ in restarts_part_len..max_undertitle_length {
+                    padding.push_str(boundary_type::HORIZONTAL);
+                }
+                let mut ret = vec![];
+                ret.append(&mut foreground_color(
+                    self.get_corner(boundary_type::BOTTOM_LEFT),
+                    self.color,
+                ));
+                ret.append(&mut restarts_part);
+                ret.append(&mut foreground_color(&padding, self.color));
+                ret.append(&mut foreground_color(
+                    self.get_corner(boundary_type::BOTTOM_RIGHT),
+                    self.color,
+                ));
+                ret
+            },
+            _ => self.empty_undertitle(max_undertitle_length),
+        }
+    }
     pub fn render(&self) -> Result<(Vec<CharacterChunk>, Option<String>)> {
         let err_context = || "failed to render pane frame";
         let mut character_chunks = vec![];
@@ -726,6 +762,14 @@ impl PaneFrame {
                             x,
                             y,
                         ));
+                    } else if self.restarts.is_some() {
+                        let x = self.geom.x;
+                        let y = self.geom.y + row;
+                        character_chunks.push(CharacterChunk::new(
+                            self.render_restarts_undertitle(),
+                            x,
+                            y,
+                        ));
                     } else {
                         let mut bottom_row = vec![];
                         for col in 0..self.geom.cols {
@@ -812,6 +856,36 @@ impl PaneFrame {
             None => (foreground_color(boundary_type::HORIZONTAL, self.color), 1),
         }
     }
+    fn restarts_title_part(
+        &self,
+        with_last_exit_status: bool,
+    ) -> Option<(Vec<TerminalCharacter>, usize)> {
+        // (title part, length)
+        let (restart_count, last_exit_status) = self.restarts?;
+        let mut restarts_part = vec![];
+        let left_bracket = " [ ";
+        let restarts_text = format!("RESTARTS: {}", restart_count);
+        let right_bracket = " ] ";
+        restarts_part.append(&mut foreground_color(left_bracket, self.color));
+        restarts_part.append(&mut foreground_color(&restarts_text, self.color));
+        let mut len = left_bracket.len() + restarts_text.len() + right_bracket.len();
+        if with_last_exit_status {
+            let last_exit_text = ", LAST EXIT CODE: ";
+            let (exit_code_text, exit_code_color) = match last_exit_status {
+                Some(0) => (String::from("0"), self.style.colors.green),
+                Some(exit_code) => (format!("{}", exit_code), self.style.colors.red),
+                None => (String::from("-"), self.style.colors.red),
+            };
+            restarts_part.append(&mut foreground_color(last_exit_text, self.color));
+            restarts_part.append(&mut foreground_color(
+                &exit_code_text,
+                Some(exit_code_color),
+            ));
+            len += last_exit_text.len() + exit_code_text.len();
+        }
+        restarts_part.append(&mut foreground_color(right_bracket, self.color));
+        Some((restarts_part, len))
+    }
     fn second_held_title_part_full(&self) -> (Vec<TerminalCharacter>, usize) {
         // (title part, length)
         let mut second_part = vec![];
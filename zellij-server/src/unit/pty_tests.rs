@@ -0,0 +1,187 @@
+use super::{save_layout_to_layout_dir, Pty, PtyInstruction};
+use crate::os_input_output::{AsyncReader, ServerOsApi};
+use crate::panes::PaneId;
+use crate::session_layout_metadata::SaveLayoutRequest;
+use crate::thread_bus::Bus;
+use crate::ClientId;
+use std::collections::BTreeMap;
+use std::os::unix::io::RawFd;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tempfile::tempdir;
+use zellij_utils::data::Palette;
+use zellij_utils::errors::prelude::*;
+use zellij_utils::input::command::{RestartPolicy, RunCommand, TerminalAction};
+use zellij_utils::interprocess::local_socket::LocalSocketStream;
+use zellij_utils::ipc::{ClientToServerMsg, IpcReceiverWithContext, ServerToClientMsg};
+use zellij_utils::nix::unistd::Pid;
+
+#[derive(Clone, Default)]
+struct FakeInputOutput {
+    killed_pids: Arc<Mutex<Vec<Pid>>>,
+    rerun_terminal_ids: Arc<Mutex<Vec<u32>>>,
+}
+
+impl ServerOsApi for FakeInputOutput {
+    fn set_terminal_size_using_terminal_id(
+        &self,
+        _terminal_id: u32,
+        _cols: u16,
+        _rows: u16,
+        _width_in_pixels: Option<u16>,
+        _height_in_pixels: Option<u16>,
+    ) -> Result<()> {
+        Ok(())
+    }
+    fn spawn_terminal(
+        &self,
+        _file_to_open: TerminalAction,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
+        _default_editor: Option<PathBuf>,
+    ) -> Result<(u32, RawFd, RawFd)> {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _id: u32, _buf: &[u8]) -> Result<usize> {
+        unimplemented!()
+    }
+    fn tcdrain(&self, _id: u32) -> Result<()> {
+        unimplemented!()
+    }
+    fn kill(&self, pid: Pid) -> Result<()> {
+        self.killed_pids.lock().unwrap().push(pid);
+        Ok(())
+    }
+    fn force_kill(&self, _pid: Pid) -> Result<()> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new((*self).clone())
+    }
+    fn send_to_client(&self, _client_id: ClientId, _msg: ServerToClientMsg) -> Result<()> {
+        unimplemented!()
+    }
+    fn new_client(
+        &mut self,
+        _client_id: ClientId,
+        _stream: LocalSocketStream,
+    ) -> Result<IpcReceiverWithContext<ClientToServerMsg>> {
+        unimplemented!()
+    }
+    fn remove_client(&mut self, _client_id: ClientId) -> Result<()> {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        unimplemented!()
+    }
+    fn get_cwd(&self, _pid: Pid) -> Option<PathBuf> {
+        unimplemented!()
+    }
+    fn write_to_file(&mut self, _contents: String, _filename: Option<String>) -> Result<()> {
+        unimplemented!()
+    }
+    fn re_run_command_in_terminal(
+        &self,
+        terminal_id: u32,
+        _run_command: RunCommand,
+        _quit_cb: Box<dyn Fn(PaneId, Option<i32>, RunCommand) + Send>,
+    ) -> Result<(RawFd, RawFd)> {
+        self.rerun_terminal_ids.lock().unwrap().push(terminal_id);
+        Err(anyhow!("not spawning commands in tests"))
+    }
+    fn clear_terminal_id(&self, _terminal_id: u32) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn create_pty(os_input: FakeInputOutput) -> Pty {
+    let mut bus: Bus<PtyInstruction> = Bus::empty();
+    bus.os_input = Some(Box::new(os_input));
+    let debug_to_file = false;
+    let default_editor = None;
+    Pty::new(bus, debug_to_file, default_editor)
+}
+
+#[test]
+fn closing_a_restarting_pane_does_not_respawn_its_command() {
+    let os_input = FakeInputOutput::default();
+    let mut pty = create_pty(os_input.clone());
+    let pane_id = PaneId::Terminal(1);
+    let run_command = RunCommand {
+        command: PathBuf::from("false"),
+        restart: RestartPolicy::Always,
+        ..Default::default()
+    };
+    pty.id_to_child_pid.insert(1, 42);
+
+    pty.schedule_command_restart(pane_id, Some(1), run_command.clone())
+        .unwrap();
+    pty.close_pane(pane_id).unwrap();
+    // killing the command of the closed pane asks for it to be restarted again
+    pty.schedule_command_restart(pane_id, None, run_command.clone())
+        .unwrap();
+    pty.restart_command_in_pane(pane_id, run_command).unwrap();
+
+    assert_eq!(
+        *os_input.killed_pids.lock().unwrap(),
+        vec![Pid::from_raw(42)],
+        "Command of the closed pane was killed"
+    );
+    assert!(
+        os_input.rerun_terminal_ids.lock().unwrap().is_empty(),
+        "Command of the closed pane was not respawned"
+    );
+    assert!(
+        !pty.command_restarts.contains_key(&1),
+        "No restart is pending for the closed pane"
+    );
+}
+
+#[test]
+fn layouts_are_only_saved_in_the_layout_folder() {
+    let temp_dir = tempdir().unwrap();
+    let layout_dir = temp_dir.path().join("layouts");
+    let pane_contents = BTreeMap::from([("pane_1.txt".to_owned(), "ls\n".to_owned())]);
+    for name in [
+        "../escaped",
+        "/tmp/absolute",
+        "nested/layout",
+        "..",
+        ".",
+        "",
+    ] {
+        let save_layout_request = SaveLayoutRequest {
+            name: name.to_owned(),
+            layout_dir: Some(layout_dir.clone()),
+            ..Default::default()
+        };
+        let saved = save_layout_to_layout_dir(
+            "layout {}".to_owned(),
+            pane_contents.clone(),
+            &save_layout_request,
+        );
+        assert!(saved.is_err(), "Layout named {:?} was not saved", name);
+    }
+    assert!(
+        !temp_dir.path().join("escaped.kdl").exists() && !layout_dir.exists(),
+        "Nothing was written outside or inside the layout folder"
+    );
+
+    let save_layout_request = SaveLayoutRequest {
+        name: "dev".to_owned(),
+        layout_dir: Some(layout_dir.clone()),
+        ..Default::default()
+    };
+    let saved =
+        save_layout_to_layout_dir("layout {}".to_owned(), pane_contents, &save_layout_request);
+    assert_eq!(saved, Ok(layout_dir.join("dev.kdl")), "Layout was saved");
+    assert!(
+        layout_dir.join("dev_pane_1.txt").exists(),
+        "Its contents were saved next to it"
+    );
+}
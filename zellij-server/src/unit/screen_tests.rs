@@ -13,12 +13,14 @@ use zellij_utils::cli::CliAction;
 use zellij_utils::data::{Event, Resize, Style};
 use zellij_utils::errors::{prelude::*, ErrorContext};
 use zellij_utils::input::actions::Action;
-use zellij_utils::input::command::{RunCommand, TerminalAction};
+use zellij_utils::input::command::{PaneDependency, RunCommand, TerminalAction};
+use zellij_utils::input::hooks::{Hook, HookEvent, Hooks};
 use zellij_utils::input::layout::{
     FloatingPaneLayout, Layout, PluginAlias, PluginUserConfiguration, Run, RunPlugin,
     RunPluginLocation, RunPluginOrAlias, SplitDirection, SplitSize, TiledPaneLayout,
 };
 use zellij_utils::input::options::Options;
+use zellij_utils::input::scratchpads::{Scratchpad, Scratchpads};
 use zellij_utils::ipc::IpcReceiverWithContext;
 use zellij_utils::pane_size::{Size, SizeInPixels};
 
@@ -42,7 +44,7 @@ use crate::panes::grid::Grid;
 use crate::panes::link_handler::LinkHandler;
 use crate::panes::sixel::SixelImageStore;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 use zellij_utils::vte;
 
@@ -271,6 +273,8 @@ fn create_new_screen(size: Size) -> Screen {
         styled_underlines,
         arrow_fonts,
         layout_dir,
+        Scratchpads::default(),
+        Hooks::default(),
     );
     screen
 }
@@ -327,6 +331,8 @@ impl MockScreen {
                     Box::new(config_options),
                     debug,
                     Box::new(Layout::default()),
+                    Box::new(Scratchpads::default()),
+                    Box::new(Hooks::default()),
                 )
                 .expect("TEST")
             })
@@ -402,6 +408,8 @@ impl MockScreen {
                     Box::new(config_options),
                     debug,
                     Box::new(Layout::default()),
+                    Box::new(Scratchpads::default()),
+                    Box::new(Hooks::default()),
                 )
                 .expect("TEST")
             })
@@ -509,6 +517,8 @@ impl MockScreen {
             pty_writer_thread: None,
             background_jobs_thread: None,
             config_options: Default::default(),
+            data_dir: PathBuf::new(),
+            hooks: Default::default(),
             layout,
         }
     }
@@ -566,6 +576,8 @@ impl MockScreen {
             pty_writer_thread: None,
             background_jobs_thread: None,
             config_options: Default::default(),
+            data_dir: PathBuf::new(),
+            hooks: Default::default(),
             layout,
         };
 
@@ -1272,6 +1284,135 @@ fn attach_after_first_tab_closed() {
     screen.add_client(1).expect("TEST");
 }
 
+#[test]
+fn client_follows_active_tab_and_focused_pane_of_other_client() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.session_is_mirrored = false;
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, None, None, None, Some(1))
+        .unwrap();
+    new_tab(&mut screen, 3, 1);
+    screen.add_client(2).expect("TEST");
+
+    screen.follow_client(Some(1), 2).expect("TEST");
+    screen.go_to_tab(1, 1).expect("TEST");
+    screen.update_followers().expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(2).unwrap().position,
+        0,
+        "follower moved to the tab of the client it follows"
+    );
+
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .focus_pane_with_id(PaneId::Terminal(1), false, 1)
+        .unwrap();
+    screen.update_followers().expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(2).unwrap().get_active_pane_id(2),
+        Some(PaneId::Terminal(1)),
+        "follower focused the pane of the client it follows"
+    );
+
+    screen.follow_client(None, 2).expect("TEST");
+    screen.go_to_tab(2, 1).expect("TEST");
+    screen.update_followers().expect("TEST");
+    assert_eq!(
+        screen.get_active_tab(2).unwrap().position,
+        0,
+        "client no longer follows after it stopped following"
+    );
+}
+
+#[test]
+fn client_cannot_follow_itself() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    screen.follow_client(Some(1), 1).expect("TEST");
+    assert!(screen.followed_clients.is_empty());
+}
+
+fn received_hook_events(
+    server_receiver: &Receiver<(ServerInstruction, ErrorContext)>,
+) -> Vec<(HookEvent, Option<String>, Option<u32>)> {
+    server_receiver
+        .try_iter()
+        .filter_map(|(instruction, _)| match instruction {
+            ServerInstruction::RunHooks(event, context) => {
+                Some((event, context.tab_name, context.pane_id))
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn hook_events_are_reported_for_new_and_closed_tabs_and_new_panes() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let (to_server, server_receiver): ChannelWithContext<ServerInstruction> = channels::unbounded();
+    screen.bus.senders.to_server = Some(SenderWithContext::new(to_server));
+    let mut hooks = BTreeMap::new();
+    for event in [
+        HookEvent::TabCreated,
+        HookEvent::TabClosed,
+        HookEvent::PaneOpened,
+    ] {
+        hooks.insert(event, vec![Hook::Command("true".to_owned())]);
+    }
+    screen.hooks = Hooks::from_data(hooks);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    assert_eq!(
+        received_hook_events(&server_receiver),
+        vec![
+            (HookEvent::TabCreated, Some("Tab #1".to_owned()), None),
+            (HookEvent::PaneOpened, Some("Tab #1".to_owned()), Some(1)),
+            (HookEvent::TabCreated, Some("Tab #2".to_owned()), None),
+            (HookEvent::PaneOpened, Some("Tab #2".to_owned()), Some(2)),
+        ]
+    );
+
+    screen.close_tab_at_index(1).expect("TEST");
+    assert_eq!(
+        received_hook_events(&server_receiver),
+        vec![(HookEvent::TabClosed, Some("Tab #2".to_owned()), None)]
+    );
+}
+
+#[test]
+fn no_hook_events_are_reported_without_hooks() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let (to_server, server_receiver): ChannelWithContext<ServerInstruction> = channels::unbounded();
+    screen.bus.senders.to_server = Some(SenderWithContext::new(to_server));
+
+    new_tab(&mut screen, 1, 0);
+    screen.close_tab_at_index(0).expect("TEST");
+    assert!(received_hook_events(&server_receiver).is_empty());
+}
+
 #[test]
 fn open_new_floating_pane_with_custom_coordinates() {
     let size = Size {
@@ -2187,6 +2328,9 @@ pub fn send_cli_new_pane_action_with_default_parameters() {
         name: None,
         close_on_exit: false,
         start_suspended: false,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
         configuration: None,
         skip_plugin_cache: false,
         x: None,
@@ -2194,6 +2338,8 @@ pub fn send_cli_new_pane_action_with_default_parameters() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2230,6 +2376,9 @@ pub fn send_cli_new_pane_action_with_split_direction() {
         name: None,
         close_on_exit: false,
         start_suspended: false,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
         configuration: None,
         skip_plugin_cache: false,
         x: None,
@@ -2237,6 +2386,8 @@ pub fn send_cli_new_pane_action_with_split_direction() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2273,6 +2424,9 @@ pub fn send_cli_new_pane_action_with_command_and_cwd() {
         name: None,
         close_on_exit: false,
         start_suspended: false,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
         configuration: None,
         skip_plugin_cache: false,
         x: None,
@@ -2280,6 +2434,8 @@ pub fn send_cli_new_pane_action_with_command_and_cwd() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2316,6 +2472,9 @@ pub fn send_cli_new_pane_action_with_floating_pane_and_coordinates() {
         name: None,
         close_on_exit: false,
         start_suspended: false,
+        env: vec![],
+        restart: None,
+        max_restarts: None,
         configuration: None,
         skip_plugin_cache: false,
         x: Some("10".to_owned()),
@@ -2323,6 +2482,8 @@ pub fn send_cli_new_pane_action_with_floating_pane_and_coordinates() {
         width: Some("20%".to_owned()),
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_new_pane_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2361,6 +2522,8 @@ pub fn send_cli_edit_action_with_default_parameters() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2399,6 +2562,8 @@ pub fn send_cli_edit_action_with_line_number() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2437,6 +2602,8 @@ pub fn send_cli_edit_action_with_split_direction() {
         width: None,
         height: None,
     };
+    // the layout's own pty instructions would otherwise race with the ones of the action
+    std::thread::sleep(std::time::Duration::from_millis(100));
     send_cli_action_to_server(&session_metadata, cli_edit_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100)); // give time for actions to be
     mock_screen.teardown(vec![pty_thread, screen_thread]);
@@ -2610,6 +2777,7 @@ pub fn send_cli_new_tab_action_default_params() {
         layout: None,
         layout_dir: None,
         cwd: None,
+        layout_args: vec![],
     };
     send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -2650,6 +2818,7 @@ pub fn send_cli_new_tab_action_with_name_and_layout() {
         ))),
         layout_dir: None,
         cwd: None,
+        layout_args: vec![],
     };
     send_cli_action_to_server(&session_metadata, new_tab_action, client_id);
     std::thread::sleep(std::time::Duration::from_millis(100));
@@ -2910,6 +3079,45 @@ pub fn send_cli_undo_rename_tab() {
     assert_snapshot!(format!("{:#?}", plugin_undo_rename_tab_instruction))
 }
 
+#[test]
+pub fn send_cli_set_tab_cwd_action() {
+    let size = Size { cols: 80, rows: 10 };
+    let client_id = 10; // fake client id should not appear in the screen's state
+    let mut mock_screen = MockScreen::new(size);
+    let session_metadata = mock_screen.clone_session_metadata();
+    let screen_thread = mock_screen.run(Some(TiledPaneLayout::default()), vec![]);
+    let received_plugin_instructions = Arc::new(Mutex::new(vec![]));
+    let plugin_receiver = mock_screen.plugin_receiver.take().unwrap();
+    let plugin_thread = log_actions_in_thread!(
+        received_plugin_instructions,
+        PluginInstruction::Exit,
+        plugin_receiver
+    );
+    let set_tab_cwd = CliAction::SetTabCwd {
+        cwd: Some(PathBuf::from("/path/to/backend")),
+    };
+    send_cli_action_to_server(&session_metadata, set_tab_cwd, client_id);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mock_screen.teardown(vec![plugin_thread, screen_thread]);
+    let tab_cwds: Vec<Option<PathBuf>> = received_plugin_instructions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|instruction| match instruction {
+            PluginInstruction::Update(updates) => updates.iter().find_map(|u| match u {
+                (_, _, Event::TabUpdate(tab_infos)) => tab_infos.first().map(|t| t.cwd.clone()),
+                _ => None,
+            }),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        tab_cwds.last(),
+        Some(&Some(PathBuf::from("/path/to/backend"))),
+        "tab cwd reported to plugins"
+    );
+}
+
 #[test]
 pub fn send_cli_query_tab_names_action() {
     let size = Size { cols: 80, rows: 10 };
@@ -3577,3 +3785,584 @@ pub fn screen_can_move_pane_to_a_new_tab_left() {
     }
     assert_snapshot!(format!("{}", snapshot_count));
 }
+
+fn is_sticky(screen: &Screen, pane_id: PaneId) -> bool {
+    screen.sticky_panes.borrow().contains(&pane_id)
+}
+
+fn tabs_reporting_pane(screen: &mut Screen, pane_id: u32) -> Vec<usize> {
+    let pane_manifest = screen.generate_and_report_pane_state().unwrap();
+    pane_manifest
+        .panes
+        .iter()
+        .filter(|(_tab_position, pane_infos)| {
+            pane_infos
+                .iter()
+                .any(|pane_info| !pane_info.is_plugin && pane_info.id == pane_id)
+        })
+        .map(|(tab_position, _pane_infos)| *tab_position)
+        .collect()
+}
+
+#[test]
+pub fn sticky_pane_is_shown_on_every_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+
+    assert_eq!(
+        screen.get_active_tab(1).unwrap().position,
+        0,
+        "Active tab switched to previous tab"
+    );
+    assert!(
+        is_sticky(&screen, PaneId::Terminal(3)),
+        "Pane is in the sticky panes"
+    );
+    assert!(
+        screen
+            .tabs
+            .values()
+            .all(|tab| !tab.has_floating_pane(&PaneId::Terminal(3))),
+        "Sticky pane does not belong to any tab"
+    );
+    assert_eq!(
+        tabs_reporting_pane(&mut screen, 3),
+        vec![0],
+        "Sticky pane is reported once, with the tab the client is looking at"
+    );
+    assert_eq!(
+        screen.sticky_panes.borrow().focused_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Sticky pane kept its focus"
+    );
+}
+
+#[test]
+pub fn sticky_pane_is_not_moved_by_other_clients_switching_tabs() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    screen.session_is_mirrored = false;
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    screen.add_client(2).expect("TEST");
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+    screen.switch_tab_prev(None, true, 2).expect("TEST");
+
+    assert_ne!(
+        screen.get_active_tab(1).unwrap().position,
+        screen.get_active_tab(2).unwrap().position,
+        "Clients are on different tabs"
+    );
+    assert!(
+        is_sticky(&screen, PaneId::Terminal(3)),
+        "Pane is still sticky"
+    );
+    assert!(
+        screen
+            .tabs
+            .values()
+            .all(|tab| !tab.has_floating_pane(&PaneId::Terminal(3))),
+        "Sticky pane was not moved into the tab of the switching client"
+    );
+    assert_eq!(
+        screen.sticky_panes.borrow().focused_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Sticky pane kept the focus of the client that made it sticky"
+    );
+    assert_eq!(
+        screen.sticky_panes.borrow().focused_pane_id(2),
+        None,
+        "Sticky pane was not focused for the other client"
+    );
+}
+
+#[test]
+pub fn unsticky_pane_stays_in_the_focused_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+    screen.switch_tab_prev(None, true, 1).expect("TEST");
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+
+    assert!(
+        !is_sticky(&screen, PaneId::Terminal(3)),
+        "Pane is no longer sticky"
+    );
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert!(
+        active_tab.has_floating_pane(&PaneId::Terminal(3)),
+        "Pane stayed in the focused tab"
+    );
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(3)),
+        "Pane kept its focus"
+    );
+}
+
+#[test]
+pub fn cli_client_toggles_sticky_pane_of_a_connected_client() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let cli_client_id = 10;
+
+    new_tab(&mut screen, 1, 0);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen
+        .toggle_active_pane_sticky(cli_client_id)
+        .expect("TEST");
+    assert!(
+        is_sticky(&screen, PaneId::Terminal(2)),
+        "Focused pane of the connected client was made sticky"
+    );
+    screen
+        .toggle_active_pane_sticky(cli_client_id)
+        .expect("TEST");
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .has_floating_pane(&PaneId::Terminal(2)),
+        "Pane is back in the tab of the connected client"
+    );
+}
+
+#[test]
+pub fn tiled_pane_cannot_be_sticky() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+
+    assert!(
+        screen.sticky_panes.borrow().is_empty(),
+        "Tiled pane was not made sticky"
+    );
+}
+
+#[test]
+pub fn sticky_pane_survives_closing_its_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    new_tab(&mut screen, 2, 1);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(3), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen.toggle_active_pane_sticky(1).expect("TEST");
+    screen.close_tab(1).expect("TEST");
+
+    assert_eq!(screen.tabs.len(), 1, "Tab was closed");
+    assert!(
+        is_sticky(&screen, PaneId::Terminal(3)),
+        "Sticky pane outlived the tab it was made sticky in"
+    );
+}
+
+#[test]
+pub fn floating_pane_marked_sticky_in_layout_is_sticky() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    screen.new_tab(1, (vec![], vec![]), None, 1).expect("TEST");
+    screen
+        .apply_layout(
+            TiledPaneLayout::default(),
+            vec![FloatingPaneLayout {
+                is_sticky: true,
+                ..Default::default()
+            }],
+            vec![(2, None)],
+            vec![(3, None)],
+            HashMap::new(),
+            1,
+            1,
+        )
+        .expect("TEST");
+
+    assert!(
+        is_sticky(&screen, PaneId::Terminal(3)),
+        "Pane from layout is sticky"
+    );
+    assert!(
+        screen
+            .tabs
+            .values()
+            .all(|tab| !tab.has_floating_pane(&PaneId::Terminal(3))),
+        "Sticky pane from layout does not belong to its tab"
+    );
+}
+
+#[test]
+pub fn toggle_scratchpad_hides_and_shows_its_pane() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen
+        .scratchpad_panes
+        .insert("notes".to_owned(), PaneId::Terminal(2));
+
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert!(
+        active_tab.has_suppressed_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane was hidden without being closed"
+    );
+    assert!(
+        !active_tab.has_floating_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane is no longer displayed"
+    );
+
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+    let active_tab = screen.get_active_tab(1).unwrap();
+    assert!(
+        active_tab.has_floating_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane is displayed again"
+    );
+    assert!(
+        active_tab.are_floating_panes_visible(),
+        "Floating panes are visible"
+    );
+    assert_eq!(
+        active_tab.get_active_pane_id(1),
+        Some(PaneId::Terminal(2)),
+        "Scratchpad pane is focused"
+    );
+}
+
+#[test]
+pub fn cli_client_toggles_scratchpad_of_a_connected_client() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let cli_client_id = 10;
+
+    new_tab(&mut screen, 1, 0);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen
+        .scratchpad_panes
+        .insert("notes".to_owned(), PaneId::Terminal(2));
+
+    screen
+        .toggle_scratchpad("notes".to_owned(), cli_client_id)
+        .expect("TEST");
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .has_suppressed_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane was hidden in the tab of the connected client"
+    );
+    screen
+        .toggle_scratchpad("notes".to_owned(), cli_client_id)
+        .expect("TEST");
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .has_floating_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane is displayed again in the tab of the connected client"
+    );
+}
+
+#[test]
+pub fn toggle_scratchpad_spawns_its_pane_once() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let (to_pty, pty_receiver): ChannelWithContext<PtyInstruction> = channels::unbounded();
+    screen.bus.senders.to_pty = Some(SenderWithContext::new(to_pty));
+    screen.scratchpads = Scratchpads::from_data(BTreeMap::from([(
+        "notes".to_owned(),
+        Scratchpad::default(),
+    )]));
+
+    new_tab(&mut screen, 1, 0);
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+    // toggled again before the pty thread spawned its pane
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+
+    let spawned_scratchpads = pty_receiver
+        .try_iter()
+        .filter(|(instruction, _)| matches!(instruction, PtyInstruction::SpawnScratchpad(..)))
+        .count();
+    assert_eq!(spawned_scratchpads, 1, "Scratchpad pane was spawned once");
+}
+
+#[test]
+pub fn foreground_commands_are_queried_only_if_swap_layouts_depend_on_them() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+    let (to_pty, pty_receiver): ChannelWithContext<PtyInstruction> = channels::unbounded();
+    screen.bus.senders.to_pty = Some(SenderWithContext::new(to_pty));
+    let queried_terminal_ids = || -> Vec<Vec<u32>> {
+        pty_receiver
+            .try_iter()
+            .filter_map(|(instruction, _)| match instruction {
+                PtyInstruction::QueryForegroundCommands(terminal_ids) => Some(terminal_ids),
+                _ => None,
+            })
+            .collect()
+    };
+
+    new_tab(&mut screen, 1, 0);
+    screen.refresh_foreground_commands().expect("TEST");
+    assert!(
+        queried_terminal_ids().is_empty(),
+        "Foreground commands not queried without has_command constraints"
+    );
+
+    let swap_layouts = r#"
+        layout {
+            swap_tiled_layout name="editor" {
+                tab has_command="nvim" {
+                    pane
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(swap_layouts, "file_name.kdl".into(), None, None).unwrap();
+    screen
+        .new_tab(
+            1,
+            (layout.swap_tiled_layouts, layout.swap_floating_layouts),
+            None,
+            1,
+        )
+        .expect("TEST");
+    screen
+        .apply_layout(
+            TiledPaneLayout::default(),
+            vec![],
+            vec![(2, None)],
+            vec![],
+            HashMap::new(),
+            1,
+            1,
+        )
+        .expect("TEST");
+    screen.refresh_foreground_commands().expect("TEST");
+    assert_eq!(
+        queried_terminal_ids(),
+        vec![vec![1, 2]],
+        "Foreground commands of the panes in all tabs queried"
+    );
+}
+
+#[test]
+pub fn toggle_scratchpad_brings_its_pane_to_the_focused_tab() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let mut screen = create_new_screen(size);
+
+    new_tab(&mut screen, 1, 0);
+    let should_float = Some(true);
+    screen
+        .get_active_tab_mut(1)
+        .unwrap()
+        .new_pane(PaneId::Terminal(2), None, should_float, None, None, Some(1))
+        .unwrap();
+    screen
+        .scratchpad_panes
+        .insert("notes".to_owned(), PaneId::Terminal(2));
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+    new_tab(&mut screen, 3, 1);
+    screen
+        .toggle_scratchpad("notes".to_owned(), 1)
+        .expect("TEST");
+
+    assert!(
+        screen
+            .get_active_tab(1)
+            .unwrap()
+            .has_floating_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane is displayed in the focused tab"
+    );
+    assert!(
+        !screen
+            .tabs
+            .get(&0)
+            .unwrap()
+            .has_suppressed_pane(&PaneId::Terminal(2)),
+        "Scratchpad pane was removed from its previous tab"
+    );
+}
+
+#[test]
+pub fn pane_waiting_for_output_starts_once_its_dependency_prints_it() {
+    let size = Size {
+        cols: 121,
+        rows: 20,
+    };
+    let client_id = 1;
+    let mut screen = create_new_screen(size);
+    let (to_pty, pty_receiver): ChannelWithContext<PtyInstruction> = channels::unbounded();
+    screen.bus.senders.to_pty = Some(SenderWithContext::new(to_pty));
+    let command_with_dependency = RunCommand {
+        depends_on: vec![PaneDependency {
+            pane_name: "server".to_owned(),
+            output: Some("listening on \\d+".to_owned()),
+            ..Default::default()
+        }],
+        ..RunCommand::new(PathBuf::from("npm"))
+    };
+    let layout = TiledPaneLayout {
+        children: vec![
+            TiledPaneLayout {
+                name: Some("server".to_owned()),
+                ..Default::default()
+            },
+            TiledPaneLayout {
+                run: Some(Run::Command(command_with_dependency.clone())),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    screen
+        .new_tab(0, (vec![], vec![]), None, client_id)
+        .expect("TEST");
+    screen
+        .apply_layout(
+            layout,
+            vec![], // floating panes layout
+            vec![(1, None), (2, Some(command_with_dependency))],
+            vec![], // new floating terminal ids
+            HashMap::new(),
+            0,
+            client_id,
+        )
+        .expect("TEST");
+    screen.panes_wait_for_dependencies = true;
+    screen.compile_pane_dependency_patterns();
+    screen.start_panes_with_ready_dependencies().expect("TEST");
+    // the dependency is found by its name in the layout, even once it is renamed
+    screen
+        .get_active_tab_mut(client_id)
+        .unwrap()
+        .get_pane_with_id_mut(PaneId::Terminal(1))
+        .unwrap()
+        .rename("renamed".as_bytes().to_vec());
+
+    let mut print_in_dependency = |bytes: &str| {
+        screen
+            .get_active_tab_mut(client_id)
+            .unwrap()
+            .handle_pty_bytes(1, bytes.as_bytes().to_vec())
+            .expect("TEST");
+        screen.match_received_pane_output(PaneId::Terminal(1));
+        screen.start_panes_with_ready_dependencies().expect("TEST");
+        pty_receiver.try_iter().any(|(instruction, _)| {
+            matches!(
+                instruction,
+                PtyInstruction::ReRunCommandInPane(PaneId::Terminal(2), _)
+            )
+        })
+    };
+    assert!(
+        !print_in_dependency("starting...\n\r"),
+        "Pane does not start before its dependency prints the expected output"
+    );
+    assert!(
+        !print_in_dependency("\u{1b}[32mlistening on "),
+        "Pane does not start on a partial line"
+    );
+    assert!(
+        print_in_dependency("8080\u{1b}[m\n\r"),
+        "Pane starts once its dependency printed the expected output"
+    );
+}
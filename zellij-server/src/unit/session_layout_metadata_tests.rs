@@ -0,0 +1,90 @@
+use super::*;
+
+fn pane_in(cwd: &str, run: Option<Run>) -> PaneLayoutMetadata {
+    let mut pane = PaneLayoutMetadata::new(
+        PaneId::Terminal(1),
+        PaneGeom::default(),
+        false,
+        run,
+        None,
+        false,
+        None,
+        false,
+    );
+    pane.cwd = Some(PathBuf::from(cwd));
+    pane
+}
+
+#[test]
+fn cwds_under_base_are_made_relative_to_it() {
+    let mut run_command = RunCommand::new(PathBuf::from("cargo"));
+    run_command.cwd = Some(PathBuf::from("/project/server"));
+    let mut session_layout_metadata = SessionLayoutMetadata {
+        global_cwd: Some(PathBuf::from("/project")),
+        tabs: vec![TabLayoutMetadata {
+            cwd: Some(PathBuf::from("/project/client")),
+            tiled_panes: vec![
+                pane_in("/project/client/src", Some(Run::Command(run_command))),
+                pane_in("/elsewhere", None),
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    session_layout_metadata.make_cwds_relative_to(Path::new("/project"));
+    assert_eq!(session_layout_metadata.global_cwd, None);
+    let tab = &session_layout_metadata.tabs[0];
+    assert_eq!(tab.cwd, Some(PathBuf::from("client")));
+    assert_eq!(tab.tiled_panes[0].cwd, Some(PathBuf::from("client/src")));
+    assert_eq!(
+        tab.tiled_panes[0].run.as_ref().and_then(|r| r.get_cwd()),
+        Some(PathBuf::from("server"))
+    );
+    assert_eq!(
+        tab.tiled_panes[1].cwd,
+        Some(PathBuf::from("/elsewhere")),
+        "cwds outside of the base are left as they are"
+    );
+}
+
+#[test]
+fn cwds_equal_to_base_are_dropped() {
+    let mut run_command = RunCommand::new(PathBuf::from("cargo"));
+    run_command.cwd = Some(PathBuf::from("/project"));
+    let mut session_layout_metadata = SessionLayoutMetadata {
+        tabs: vec![TabLayoutMetadata {
+            cwd: Some(PathBuf::from("/project")),
+            tiled_panes: vec![
+                pane_in("/project", Some(Run::Command(run_command))),
+                pane_in("/project", Some(Run::Cwd(PathBuf::from("/project")))),
+                pane_in(
+                    "/project",
+                    Some(Run::Shell(Some(PathBuf::from("/project")), BTreeMap::new())),
+                ),
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    session_layout_metadata.make_cwds_relative_to(Path::new("/project"));
+    let tab = &session_layout_metadata.tabs[0];
+    assert_eq!(tab.cwd, None);
+    for pane in &tab.tiled_panes {
+        assert_eq!(pane.cwd, None, "pane cwd dropped");
+        assert_eq!(
+            pane.run.as_ref().and_then(|r| r.get_cwd()),
+            None,
+            "run cwd dropped"
+        );
+    }
+    assert!(
+        matches!(tab.tiled_panes[0].run, Some(Run::Command(..))),
+        "command kept"
+    );
+    assert_eq!(tab.tiled_panes[1].run, None, "bare cwd run dropped");
+    assert_eq!(
+        tab.tiled_panes[2].run,
+        Some(Run::Shell(None, BTreeMap::new())),
+        "shell kept"
+    );
+}
@@ -0,0 +1,320 @@
+use super::*;
+
+fn size(rows: usize, cols: usize) -> Size {
+    Size { rows, cols }
+}
+
+fn session_state_with_clients(
+    client_size_policy: ClientSizePolicy,
+    client_sizes: &[Size],
+) -> SessionState {
+    let mut session_state = SessionState::new();
+    session_state.set_client_size_policy(client_size_policy);
+    for client_size in client_sizes {
+        let client_id = session_state.new_client();
+        session_state.set_client_size(client_id, *client_size);
+    }
+    session_state
+}
+
+#[test]
+fn session_is_sized_to_the_smallest_client_by_default() {
+    let mut session_state = SessionState::new();
+    let first_client = session_state.new_client();
+    session_state.set_client_size(first_client, size(50, 100));
+    let second_client = session_state.new_client();
+    session_state.set_client_size(second_client, size(60, 80));
+    assert_eq!(session_state.resize_session(), Some(size(50, 80)));
+}
+
+#[test]
+fn session_is_sized_to_the_largest_client() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Largest, &[size(50, 100), size(60, 80)]);
+    assert_eq!(session_state.resize_session(), Some(size(60, 100)));
+}
+
+#[test]
+fn session_is_sized_to_the_latest_client() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Latest, &[size(50, 100), size(30, 80)]);
+    assert_eq!(
+        session_state.resize_session(),
+        Some(size(50, 100)),
+        "first client is the latest until another one is active"
+    );
+    assert!(session_state.mark_client_active(2));
+    assert_eq!(session_state.resize_session(), Some(size(30, 80)));
+    assert!(
+        !session_state.mark_client_active(2),
+        "no need to resize if the latest client did not change"
+    );
+}
+
+#[test]
+fn read_only_clients_do_not_become_the_latest_client() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Latest, &[size(50, 100), size(30, 80)]);
+    session_state.set_client_read_only(2);
+    assert!(!session_state.mark_client_active(2));
+    assert_eq!(session_state.resize_session(), Some(size(50, 100)));
+}
+
+#[test]
+fn manual_session_size_only_changes_when_resized_to_a_client() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Manual, &[size(50, 100)]);
+    assert_eq!(session_state.resize_session(), Some(size(50, 100)));
+    let second_client = session_state.new_client();
+    session_state.set_client_size(second_client, size(30, 80));
+    assert_eq!(session_state.resize_session(), Some(size(50, 100)));
+    assert_eq!(
+        session_state.resize_session_to_client(second_client),
+        Some(size(30, 80))
+    );
+    assert_eq!(session_state.resize_session(), Some(size(30, 80)));
+}
+
+#[test]
+fn resize_to_a_client_without_a_size_uses_the_latest_client() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Smallest, &[size(50, 100), size(30, 80)]);
+    session_state.mark_client_active(2);
+    let cli_client = session_state.new_client();
+    assert_eq!(
+        session_state.resize_session_to_client(cli_client),
+        Some(size(30, 80))
+    );
+}
+
+#[test]
+fn out_of_bounds_filler_covers_the_parts_of_bigger_clients_outside_the_session() {
+    let filler = out_of_bounds_filler(size(3, 4), size(2, 2)).unwrap();
+    assert_eq!(
+        filler,
+        "\u{1b}7\u{1b}[m\u{1b}[2m\u{1b}[1;3H··\u{1b}[2;3H··\u{1b}[3;1H····\u{1b}[m\u{1b}8"
+    );
+    assert_eq!(out_of_bounds_filler(size(2, 2), size(2, 2)), None);
+    assert_eq!(out_of_bounds_filler(size(1, 2), size(2, 2)), None);
+}
+
+#[test]
+fn out_of_bounds_filler_is_only_drawn_again_when_needed() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Smallest, &[size(2, 2), size(3, 4)]);
+    session_state.resize_session();
+    assert_eq!(session_state.take_out_of_bounds_filler(1, false), None);
+    assert!(session_state.take_out_of_bounds_filler(2, false).is_some());
+    assert_eq!(session_state.take_out_of_bounds_filler(2, false), None);
+    assert!(
+        session_state.take_out_of_bounds_filler(2, true).is_some(),
+        "filler is drawn again when the display was cleared"
+    );
+}
+
+#[test]
+fn render_is_clipped_to_clients_smaller_than_the_session() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Largest, &[size(2, 3), size(3, 5)]);
+    assert_eq!(session_state.resize_session(), Some(size(3, 5)));
+    let render = "\u{1b}[1;1H\u{1b}[31mabcde\u{1b}[m\u{1b}[3;1Hvwxyz\u{1b}[2;2H\u{1b}[?25h";
+    assert_eq!(
+        session_state.clip_render_to_client(1, render.to_owned()),
+        "\u{1b}[1;1H\u{1b}[31mabc\u{1b}[m\u{1b}[3;1H\u{1b}[2;2H\u{1b}[?25h",
+        "smaller client only gets the part of the session inside its terminal"
+    );
+    assert_eq!(
+        session_state.clip_render_to_client(2, render.to_owned()),
+        render,
+        "client as big as the session gets all of it"
+    );
+}
+
+#[test]
+fn cursor_outside_of_smaller_clients_is_hidden() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Largest, &[size(2, 3), size(3, 5)]);
+    session_state.resize_session();
+    assert_eq!(
+        session_state.clip_render_to_client(1, "\u{1b}[?25h\u{1b}[3;4H".to_owned()),
+        "\u{1b}[?25h\u{1b}[3;4H\u{1b}[?25l"
+    );
+    assert_eq!(
+        session_state.clip_render_to_client(1, "\u{1b}[?25h\u{1b}[2;3H".to_owned()),
+        "\u{1b}[?25h\u{1b}[2;3H"
+    );
+}
+
+#[test]
+fn locked_session_is_unlocked_only_with_the_right_passphrase() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let passphrase_hash = session_lock::hash_passphrase("secret").unwrap();
+    session_state.lock_session(LockScreen::new(passphrase_hash));
+    assert!(session_state.is_locked());
+    assert!(!session_state.handle_lock_screen_input(1, b"wrong\r"));
+    assert!(session_state.is_locked());
+    assert!(!session_state.handle_lock_screen_input(1, b"sec"));
+    assert!(session_state.handle_lock_screen_input(1, b"ret\r"));
+    assert!(!session_state.is_locked());
+}
+
+#[test]
+fn each_client_types_its_own_lock_screen_passphrase() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40), size(10, 40)]);
+    let passphrase_hash = session_lock::hash_passphrase("secret").unwrap();
+    session_state.lock_session(LockScreen::new(passphrase_hash));
+    assert!(!session_state.handle_lock_screen_input(1, b"sec"));
+    assert!(!session_state.handle_lock_screen_input(2, b"xyz"));
+    assert!(
+        session_state.handle_lock_screen_input(1, b"ret\r"),
+        "input from another client did not end up in this passphrase"
+    );
+    assert!(!session_state.is_locked());
+}
+
+#[test]
+fn lock_screen_delays_attempts_after_too_many_wrong_passphrases() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let passphrase_hash = session_lock::hash_passphrase("secret").unwrap();
+    session_state.lock_session(LockScreen::new(passphrase_hash));
+    for _ in 0..3 {
+        assert!(!session_state.handle_lock_screen_input(1, b"wrong\r"));
+    }
+    assert!(
+        !session_state.handle_lock_screen_input(1, b"secret\r"),
+        "right passphrase is not accepted before the delay is over"
+    );
+    assert!(session_state.is_locked());
+    assert!(session_state
+        .render_lock_screen(1)
+        .unwrap()
+        .contains("Wrong passphrase, try again in"));
+}
+
+#[test]
+fn lock_screen_is_only_drawn_again_when_needed() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let passphrase_hash = session_lock::hash_passphrase("secret").unwrap();
+    session_state.lock_session(LockScreen::new(passphrase_hash));
+    assert_eq!(session_state.take_lock_screen_redraw(1, false), None);
+    assert!(
+        session_state.take_lock_screen_redraw(1, true).is_some(),
+        "lock screen is drawn again when the display was cleared"
+    );
+    session_state.set_client_size(1, size(20, 80));
+    assert!(
+        session_state.take_lock_screen_redraw(1, false).is_some(),
+        "lock screen is drawn again when the client was resized"
+    );
+    assert_eq!(session_state.take_lock_screen_redraw(1, false), None);
+}
+
+#[test]
+fn idle_clients_are_warned_before_being_detached() {
+    let mut session_state =
+        session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40), size(10, 40)]);
+    let idle_policies = IdlePolicies {
+        detach_idle_clients_after: Some(Duration::from_secs(600)),
+        ..Default::default()
+    };
+    let now = Instant::now();
+    session_state
+        .client_last_input
+        .insert(1, now - Duration::from_secs(570));
+    session_state.client_last_input.insert(2, now);
+    let verdict = session_state.apply_idle_policies(&idle_policies, now);
+    assert_eq!(verdict.clients_to_detach, Vec::<ClientId>::new());
+    assert_eq!(
+        verdict.warnings.get(&1).map(|w| w.as_str()),
+        Some("Detaching in 30s due to inactivity, press any key to stay")
+    );
+    assert!(!verdict.warnings.contains_key(&2));
+    let verdict = session_state.apply_idle_policies(&idle_policies, now + Duration::from_secs(30));
+    assert_eq!(verdict.clients_to_detach, vec![1]);
+    assert!(!verdict.kill_session);
+}
+
+#[test]
+fn session_is_killed_once_detached_for_long_enough() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let idle_policies = IdlePolicies {
+        kill_session_when_detached_after: Some(Duration::from_secs(60)),
+        ..Default::default()
+    };
+    let later = Instant::now() + Duration::from_secs(120);
+    assert!(
+        !session_state
+            .apply_idle_policies(&idle_policies, later)
+            .kill_session
+    );
+    // clients without a terminal (eg. ones sending cli actions) do not count as attached
+    let cli_client = session_state.new_client();
+    session_state.remove_client(1);
+    session_state.remove_client(cli_client);
+    assert!(
+        !session_state
+            .apply_idle_policies(&idle_policies, Instant::now())
+            .kill_session
+    );
+    assert!(
+        session_state
+            .apply_idle_policies(&idle_policies, later)
+            .kill_session
+    );
+}
+
+#[test]
+fn session_is_killed_a_while_after_all_panes_exited() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let idle_policies = IdlePolicies {
+        kill_session_when_all_panes_exited: true,
+        ..Default::default()
+    };
+    session_state.set_all_panes_exited(true);
+    let verdict = session_state.apply_idle_policies(&idle_policies, Instant::now());
+    assert!(!verdict.kill_session);
+    assert!(verdict.warnings.contains_key(&1));
+    let later = Instant::now() + Duration::from_secs(60);
+    assert!(
+        session_state
+            .apply_idle_policies(&idle_policies, later)
+            .kill_session
+    );
+    session_state.set_all_panes_exited(false);
+    assert!(
+        !session_state
+            .apply_idle_policies(&idle_policies, later)
+            .kill_session
+    );
+}
+
+#[test]
+fn sessions_marked_keep_are_excluded_from_idle_policies() {
+    let mut session_state = session_state_with_clients(ClientSizePolicy::Smallest, &[size(10, 40)]);
+    let idle_policies = IdlePolicies {
+        detach_idle_clients_after: Some(Duration::from_secs(1)),
+        kill_session_when_all_panes_exited: true,
+        ..Default::default()
+    };
+    session_state.set_all_panes_exited(true);
+    let later = Instant::now() + Duration::from_secs(600);
+    assert!(session_state.toggle_keep_session());
+    assert_eq!(
+        session_state.apply_idle_policies(&idle_policies, later),
+        IdlePolicyVerdict::default()
+    );
+    assert!(!session_state.toggle_keep_session());
+    assert!(
+        session_state
+            .apply_idle_policies(&idle_policies, later)
+            .kill_session
+    );
+}
+
+#[test]
+fn idle_warning_is_drawn_on_the_first_line() {
+    assert_eq!(
+        idle_warning_banner("Detaching in 5s", size(10, 21)),
+        "\u{1b}7\u{1b}[m\u{1b}[1;30;43m\u{1b}[1;3H Detaching in 5s \u{1b}[m\u{1b}8"
+    );
+}
@@ -347,6 +347,60 @@ pub fn move_focus(direction: Direction) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Stack the focused pane with its neighbour in the specified direction
+pub fn stack_pane(direction: Direction) {
+    let plugin_command = PluginCommand::StackPane(direction);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Break up the stack of the focused pane back into regular splits
+pub fn unstack_panes() {
+    let plugin_command = PluginCommand::UnstackPanes;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Move the focused pane out of its stack and below it
+pub fn remove_pane_from_stack() {
+    let plugin_command = PluginCommand::RemovePaneFromStack;
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Save the session (or only the tab at this position, starting at 1, or with this name) as a
+/// layout called `name` in the layout folder
+pub fn save_layout(
+    name: &str,
+    tab: Option<&str>,
+    strip_contents: bool,
+    relative_to: Option<PathBuf>,
+    force: bool,
+) {
+    let plugin_command = PluginCommand::SaveLayout(
+        name.to_owned(),
+        tab.map(|t| t.to_owned()),
+        strip_contents,
+        relative_to,
+        force,
+    );
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Have new panes in the focused tab start in this folder, or in the cwd of the focused pane if
+/// `None`
+pub fn set_tab_cwd(cwd: Option<PathBuf>) {
+    let plugin_command = PluginCommand::SetTabCwd(cwd);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 /// Change the focused pane in the specified direction, if the pane is on the edge of the screen, the next tab is focused (next if right edge, previous if left edge).
 pub fn move_focus_or_tab(direction: Direction) {
     let plugin_command = PluginCommand::MoveFocusOrTab(direction);
@@ -698,6 +752,24 @@ pub fn delete_dead_session(name: &str) {
     unsafe { host_run_plugin_command() };
 }
 
+/// Request the snapshots a resurrectable session can be restored from, they are sent back to the
+/// plugin as an `Event::SessionSnapshots`
+pub fn list_session_snapshots(session_name: &str) {
+    let plugin_command = PluginCommand::ListSessionSnapshots(session_name.to_owned());
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
+/// Have a resurrectable session be resurrected as it was when this snapshot was taken (the next
+/// time it is resurrected, eg. with `switch_session`)
+pub fn restore_session_snapshot(session_name: &str, timestamp: u64) {
+    let plugin_command = PluginCommand::RestoreSessionSnapshot(session_name.to_owned(), timestamp);
+    let protobuf_plugin_command: ProtobufPluginCommand = plugin_command.try_into().unwrap();
+    object_to_stdout(&protobuf_plugin_command.encode_to_vec());
+    unsafe { host_run_plugin_command() };
+}
+
 /// Permanently delete aall resurrectable sessions on this machine
 pub fn delete_all_dead_sessions() {
     let plugin_command = PluginCommand::DeleteAllDeadSessions;
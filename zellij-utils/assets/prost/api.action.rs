@@ -5,7 +5,7 @@ pub struct Action {
     pub name: i32,
     #[prost(
         oneof = "action::OptionalPayload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52"
     )]
     pub optional_payload: ::core::option::Option<action::OptionalPayload>,
 }
@@ -108,10 +108,34 @@ pub mod action {
         MessagePayload(super::CliPipePayload),
         #[prost(enumeration = "super::MoveTabDirection", tag = "48")]
         MoveTabPayload(i32),
+        #[prost(enumeration = "super::super::resize::ResizeDirection", tag = "49")]
+        StackPanePayload(i32),
+        #[prost(string, tag = "50")]
+        ToggleScratchpadPayload(::prost::alloc::string::String),
+        #[prost(message, tag = "51")]
+        SaveLayoutPayload(super::SaveLayoutPayload),
+        #[prost(string, tag = "52")]
+        SetTabCwdPayload(::prost::alloc::string::String),
+        #[prost(uint32, tag = "53")]
+        FollowClientPayload(u32),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SaveLayoutPayload {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "2")]
+    pub tab: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, tag = "3")]
+    pub strip_contents: bool,
+    #[prost(string, optional, tag = "4")]
+    pub relative_to: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(bool, tag = "5")]
+    pub force: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CliPipePayload {
     #[prost(string, optional, tag = "1")]
     pub name: ::core::option::Option<::prost::alloc::string::String>,
@@ -271,6 +295,12 @@ pub struct RunCommandAction {
     pub hold_on_close: bool,
     #[prost(bool, tag = "7")]
     pub hold_on_start: bool,
+    #[prost(message, repeated, tag = "8")]
+    pub env: ::prost::alloc::vec::Vec<NameAndValue>,
+    #[prost(string, optional, tag = "9")]
+    pub restart: ::core::option::Option<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "10")]
+    pub max_restarts: ::core::option::Option<u32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -455,6 +485,20 @@ pub enum ActionName {
     CliPipe = 82,
     MoveTab = 83,
     KeybindPipe = 84,
+    Undo = 85,
+    Redo = 86,
+    StackPane = 87,
+    UnstackPanes = 88,
+    RemovePaneFromStack = 89,
+    TogglePaneSticky = 90,
+    ToggleScratchpad = 91,
+    SaveLayout = 92,
+    SetTabCwd = 93,
+    StartSuspendedPanes = 94,
+    ResizeToClient = 95,
+    FollowClient = 96,
+    LockSession = 97,
+    ToggleKeepSession = 98,
 }
 impl ActionName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -548,6 +592,20 @@ impl ActionName {
             ActionName::CliPipe => "CliPipe",
             ActionName::MoveTab => "MoveTab",
             ActionName::KeybindPipe => "KeybindPipe",
+            ActionName::Undo => "Undo",
+            ActionName::Redo => "Redo",
+            ActionName::StackPane => "StackPane",
+            ActionName::UnstackPanes => "UnstackPanes",
+            ActionName::RemovePaneFromStack => "RemovePaneFromStack",
+            ActionName::TogglePaneSticky => "TogglePaneSticky",
+            ActionName::ToggleScratchpad => "ToggleScratchpad",
+            ActionName::SaveLayout => "SaveLayout",
+            ActionName::SetTabCwd => "SetTabCwd",
+            ActionName::StartSuspendedPanes => "StartSuspendedPanes",
+            ActionName::ResizeToClient => "ResizeToClient",
+            ActionName::FollowClient => "FollowClient",
+            ActionName::LockSession => "LockSession",
+            ActionName::ToggleKeepSession => "ToggleKeepSession",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -638,6 +696,20 @@ impl ActionName {
             "CliPipe" => Some(Self::CliPipe),
             "MoveTab" => Some(Self::MoveTab),
             "KeybindPipe" => Some(Self::KeybindPipe),
+            "Undo" => Some(Self::Undo),
+            "Redo" => Some(Self::Redo),
+            "StackPane" => Some(Self::StackPane),
+            "UnstackPanes" => Some(Self::UnstackPanes),
+            "RemovePaneFromStack" => Some(Self::RemovePaneFromStack),
+            "TogglePaneSticky" => Some(Self::TogglePaneSticky),
+            "ToggleScratchpad" => Some(Self::ToggleScratchpad),
+            "SaveLayout" => Some(Self::SaveLayout),
+            "SetTabCwd" => Some(Self::SetTabCwd),
+            "StartSuspendedPanes" => Some(Self::StartSuspendedPanes),
+            "ResizeToClient" => Some(Self::ResizeToClient),
+            "FollowClient" => Some(Self::FollowClient),
+            "LockSession" => Some(Self::LockSession),
+            "ToggleKeepSession" => Some(Self::ToggleKeepSession),
             _ => None,
         }
     }
@@ -5,7 +5,7 @@ pub struct PluginCommand {
     pub name: i32,
     #[prost(
         oneof = "plugin_command::Payload",
-        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60, 61"
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 60, 61, 62, 63, 64, 65, 66"
     )]
     pub payload: ::core::option::Option<plugin_command::Payload>,
 }
@@ -116,10 +116,28 @@ pub mod plugin_command {
         KillSessionsPayload(super::KillSessionsPayload),
         #[prost(string, tag = "61")]
         ScanHostFolderPayload(::prost::alloc::string::String),
+        #[prost(message, tag = "62")]
+        StackPanePayload(super::MovePayload),
+        #[prost(message, tag = "63")]
+        SaveLayoutPayload(super::super::action::SaveLayoutPayload),
+        #[prost(string, tag = "64")]
+        SetTabCwdPayload(::prost::alloc::string::String),
+        #[prost(string, tag = "65")]
+        ListSessionSnapshotsPayload(::prost::alloc::string::String),
+        #[prost(message, tag = "66")]
+        RestoreSessionSnapshotPayload(super::RestoreSessionSnapshotPayload),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RestoreSessionSnapshotPayload {
+    #[prost(string, tag = "1")]
+    pub session_name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub timestamp: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct KillSessionsPayload {
     #[prost(string, repeated, tag = "1")]
     pub session_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
@@ -420,6 +438,13 @@ pub enum CommandName {
     KillSessions = 81,
     ScanHostFolder = 82,
     WatchFilesystem = 83,
+    StackPane = 84,
+    UnstackPanes = 85,
+    RemovePaneFromStack = 86,
+    SaveLayout = 87,
+    SetTabCwd = 88,
+    ListSessionSnapshots = 89,
+    RestoreSessionSnapshot = 90,
 }
 impl CommandName {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -512,6 +537,13 @@ impl CommandName {
             CommandName::KillSessions => "KillSessions",
             CommandName::ScanHostFolder => "ScanHostFolder",
             CommandName::WatchFilesystem => "WatchFilesystem",
+            CommandName::StackPane => "StackPane",
+            CommandName::UnstackPanes => "UnstackPanes",
+            CommandName::RemovePaneFromStack => "RemovePaneFromStack",
+            CommandName::SaveLayout => "SaveLayout",
+            CommandName::SetTabCwd => "SetTabCwd",
+            CommandName::ListSessionSnapshots => "ListSessionSnapshots",
+            CommandName::RestoreSessionSnapshot => "RestoreSessionSnapshot",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -601,6 +633,13 @@ impl CommandName {
             "KillSessions" => Some(Self::KillSessions),
             "ScanHostFolder" => Some(Self::ScanHostFolder),
             "WatchFilesystem" => Some(Self::WatchFilesystem),
+            "StackPane" => Some(Self::StackPane),
+            "UnstackPanes" => Some(Self::UnstackPanes),
+            "RemovePaneFromStack" => Some(Self::RemovePaneFromStack),
+            "SaveLayout" => Some(Self::SaveLayout),
+            "SetTabCwd" => Some(Self::SetTabCwd),
+            "ListSessionSnapshots" => Some(Self::ListSessionSnapshots),
+            "RestoreSessionSnapshot" => Some(Self::RestoreSessionSnapshot),
             _ => None,
         }
     }
@@ -2,13 +2,27 @@ use crate::data::{Direction, InputMode, Resize};
 use crate::setup::Setup;
 use crate::{
     consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV},
-    input::{layout::PluginUserConfiguration, options::CliOptions},
+    input::{command::RestartPolicy, layout::PluginUserConfiguration, options::CliOptions},
 };
-use clap::{Parser, Subcommand};
+use clap::{ArgEnum, Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use url::Url;
 
+/// The output format of `zellij list-sessions`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, ArgEnum)]
+pub enum SessionListFormat {
+    Text,
+    Json,
+}
+
+fn parse_key_value(key_value: &str) -> Result<(String, String), String> {
+    match key_value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err(format!("expected KEY=VALUE, got: {}", key_value)),
+    }
+}
+
 fn validate_session(name: &str) -> Result<String, String> {
     #[cfg(unix)]
     {
@@ -56,6 +70,11 @@ pub struct CliArgs {
     #[clap(short, long, value_parser, overrides_with = "layout")]
     pub layout: Option<PathBuf>,
 
+    /// Value for an argument declared in the layout, can be given multiple times
+    /// (eg. --layout-arg project=~/src/api)
+    #[clap(long = "layout-arg", value_parser = parse_key_value)]
+    pub layout_args: Vec<(String, String)>,
+
     /// Change where zellij looks for the configuration file
     #[clap(short, long, overrides_with = "config", env = ZELLIJ_CONFIG_FILE_ENV, value_parser)]
     pub config: Option<PathBuf>,
@@ -110,6 +129,17 @@ pub enum Sessions {
         /// List the sessions in reverse order (default is ascending order)
         #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
         reverse: bool,
+
+        /// The output format, json includes the tabs, panes and clients of each session (the cwd
+        /// of a pane is the one at the last session serialization, null if serialization is off)
+        #[clap(
+            long,
+            arg_enum,
+            value_parser,
+            default_value("text"),
+            conflicts_with_all(&["short", "no-formatting"])
+        )]
+        format: SessionListFormat,
     },
     /// List existing plugin aliases
     #[clap(visible_alias = "la")]
@@ -136,6 +166,72 @@ pub enum Sessions {
         /// If resurrecting a dead session, immediately run all its commands on startup
         #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
         force_run_commands: bool,
+
+        /// Attach as an observer that can look around the session but not change it
+        #[clap(
+            long,
+            value_parser,
+            takes_value(false),
+            default_value("false"),
+            conflicts_with_all(&["create", "force-run-commands"])
+        )]
+        read_only: bool,
+    },
+
+    /// Resurrect a dead session, optionally as it was at an earlier point in time
+    Resurrect {
+        /// Name of the session to resurrect
+        #[clap(value_parser)]
+        session_name: String,
+
+        /// List the snapshots this session can be resurrected from
+        #[clap(long, value_parser, takes_value(false), default_value("false"))]
+        list_snapshots: bool,
+
+        /// Resurrect the session from the latest snapshot taken at or before this time, either a
+        /// unix timestamp, a date (eg. "2024-01-31 18:00:00", UTC) or a duration ago (eg. "2h")
+        #[clap(long, value_parser, conflicts_with("list-snapshots"))]
+        snapshot: Option<String>,
+
+        /// Immediately run all the commands of the session on startup
+        #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
+        force_run_commands: bool,
+    },
+
+    /// Export a session to an archive it can be resurrected from on another machine
+    ExportSession {
+        /// Name of the session to export
+        #[clap(value_parser)]
+        session_name: String,
+
+        /// The archive to write, defaults to <SESSION_NAME>.tar.zst in the current folder
+        #[clap(short, long, value_parser)]
+        output: Option<PathBuf>,
+
+        /// Keep the cwds of the panes relative to the session folder, so that they can be placed
+        /// in another folder when importing the session
+        #[clap(long, value_parser, takes_value(false), default_value("false"))]
+        relative_paths: bool,
+    },
+
+    /// Import a session archive as a resurrectable session, its commands start suspended
+    ImportSession {
+        /// The archive to import, as written by export-session
+        #[clap(value_parser)]
+        archive: PathBuf,
+
+        /// Name of the imported session, defaults to the name it was exported with
+        #[clap(short, long, value_parser)]
+        name: Option<String>,
+
+        /// The folder the cwds of the panes are relative to, defaults to the current folder for
+        /// sessions exported with --relative-paths
+        #[clap(long, value_parser)]
+        cwd: Option<PathBuf>,
+
+        /// Replace a resurrectable session by the same name
+        #[clap(short, long, value_parser, takes_value(false), default_value("false"))]
+        force: bool,
     },
 
     /// Kill a specific session
@@ -223,6 +319,19 @@ pub enum Sessions {
         #[clap(short, long, value_parser, default_value("false"), takes_value(false))]
         start_suspended: bool,
 
+        /// Set an environment variable for the command (eg. --env RUST_LOG=debug), can be
+        /// repeated
+        #[clap(long, value_parser = parse_key_value)]
+        env: Vec<(String, String)>,
+
+        /// Start the command again when it exits: never, on-failure or always
+        #[clap(long, value_parser)]
+        restart: Option<RestartPolicy>,
+
+        /// How many times the command is restarted before its pane is left exited
+        #[clap(long, value_parser, requires("restart"))]
+        max_restarts: Option<usize>,
+
         /// The x coordinates if the pane is floating as a bare integer (eg. 1) or percent (eg. 10%)
         #[clap(short, long, requires("floating"))]
         x: Option<String>,
@@ -418,6 +527,24 @@ pub enum CliAction {
     },
     /// Dump current layout to stdout
     DumpLayout,
+    /// Save the session, or one of its tabs, as a reusable layout in the layout directory
+    SaveLayout {
+        /// The name of the layout (the file is called NAME.kdl)
+        #[clap(short, long, value_parser)]
+        name: String,
+        /// Only save the tab at this position (starting at 1) or with this name
+        #[clap(short, long, value_parser)]
+        tab: Option<String>,
+        /// Leave out the contents of the panes
+        #[clap(long, value_parser)]
+        strip_contents: bool,
+        /// Make the working directories inside this directory relative to it
+        #[clap(long, value_name = "DIR", value_parser)]
+        relative_to: Option<PathBuf>,
+        /// Overwrite an existing layout with the same name
+        #[clap(short, long, value_parser)]
+        force: bool,
+    },
     /// Open the pane scrollback in your default editor
     EditScrollback,
     /// Scroll up in the focused pane
@@ -499,6 +626,16 @@ pub enum CliAction {
             requires("command")
         )]
         start_suspended: bool,
+        /// Set an environment variable for the command (eg. --env RUST_LOG=debug), can be
+        /// repeated
+        #[clap(long, value_parser = parse_key_value, requires("command"))]
+        env: Vec<(String, String)>,
+        /// Start the command again when it exits: never, on-failure or always
+        #[clap(long, value_parser, requires("command"))]
+        restart: Option<RestartPolicy>,
+        /// How many times the command is restarted before its pane is left exited
+        #[clap(long, value_parser, requires("restart"))]
+        max_restarts: Option<usize>,
         #[clap(long, value_parser)]
         configuration: Option<PluginUserConfiguration>,
         #[clap(long, value_parser)]
@@ -599,6 +736,30 @@ pub enum CliAction {
     },
     /// Remove a previously set tab name
     UndoRenameTab,
+    /// Set the working directory new panes in the focused tab start in, wherever its focused
+    /// pane is
+    SetTabCwd {
+        /// Leave out to have new panes start in the working directory of the focused pane again
+        #[clap(value_parser)]
+        cwd: Option<PathBuf>,
+    },
+    /// Start the commands of all the suspended panes in the focused tab, as if Enter was pressed
+    /// in each of them
+    StartSuspendedPanes,
+    /// Resize the session to the size of this client, regardless of the size of the others
+    ResizeToClient,
+    /// Have the active tab and focused pane of this client follow those of another client
+    FollowClient {
+        /// The id of the client to follow, leave out to stop following
+        #[clap(value_parser)]
+        client_id: Option<u16>,
+    },
+    /// Lock the session for all clients until the lock passphrase is entered (see `zellij setup
+    /// --set-lock-passphrase`)
+    LockSession,
+    /// Mark the session as one to keep (or no longer keep), so that it is not detached or killed
+    /// by the idle session policies
+    ToggleKeepSession,
     /// Create a new tab, optionally with a specified tab layout and name
     NewTab {
         /// Layout to use for the new tab
@@ -616,9 +777,34 @@ pub enum CliAction {
         /// Change the working directory of the new tab
         #[clap(short, long, value_parser, requires("layout"))]
         cwd: Option<PathBuf>,
+
+        /// Value for an argument declared in the layout, can be given multiple times
+        /// (eg. --layout-arg project=~/src/api)
+        #[clap(long = "layout-arg", value_parser = parse_key_value, requires("layout"))]
+        layout_args: Vec<(String, String)>,
     },
     PreviousSwapLayout,
     NextSwapLayout,
+    /// Undo the last change to the layout of the current tab (eg. a split, resize or pane close)
+    Undo,
+    /// Redo the last undone change to the layout of the current tab
+    Redo,
+    /// Stack the focused pane with its neighbour in the specified direction
+    /// [right|left|up|down]
+    StackPane {
+        direction: Direction,
+    },
+    /// Break up the stack of the focused pane back into regular splits
+    UnstackPanes,
+    /// Move the focused pane out of its stack and below it
+    RemovePaneFromStack,
+    /// Toggle whether the focused floating pane is shown on all tabs of the session
+    TogglePaneSticky,
+    /// Show or hide the scratchpad pane with the given name from the config, creating it the first
+    /// time it is toggled
+    ToggleScratchpad {
+        name: String,
+    },
     /// Query all tab names
     QueryTabNames,
     StartOrReloadPlugin {
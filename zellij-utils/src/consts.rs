@@ -28,6 +28,10 @@ pub fn session_layout_cache_file_name(session_name: &str) -> PathBuf {
     session_info_folder_for_session(session_name).join("session-layout.kdl")
 }
 
+pub fn session_snapshots_folder_for_session(session_name: &str) -> PathBuf {
+    session_info_folder_for_session(session_name).join("snapshots")
+}
+
 pub fn session_info_folder_for_session(session_name: &str) -> PathBuf {
     ZELLIJ_SESSION_INFO_CACHE_DIR.join(session_name)
 }
@@ -50,6 +54,8 @@ lazy_static! {
         .join(format!("{}", Uuid::new_v4()));
     pub static ref ZELLIJ_PLUGIN_PERMISSIONS_CACHE: PathBuf =
         ZELLIJ_CACHE_DIR.join("permissions.kdl");
+    pub static ref ZELLIJ_TRUSTED_LAYOUTS_CACHE: PathBuf =
+        ZELLIJ_CACHE_DIR.join("trusted_layouts.kdl");
     pub static ref ZELLIJ_SESSION_INFO_CACHE_DIR: PathBuf =
         ZELLIJ_CACHE_DIR.join(VERSION).join("session_info");
     pub static ref ZELLIJ_STDIN_CACHE_FILE: PathBuf =
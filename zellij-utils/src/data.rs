@@ -529,9 +529,16 @@ pub enum Event {
         Vec<u8>,
         BTreeMap<String, String>,
     ), // status,
-       // headers,
-       // body,
-       // context
+    // headers,
+    // body,
+    // context
+    /// A command pane's command exited and is being restarted by its restart policy
+    CommandPaneRestarted(u32, Option<i32>, usize), // terminal pane id, exit status, restart count
+    /// The snapshots a session can be resurrected from, as requested with `list_session_snapshots`
+    SessionSnapshots(
+        String,               // session name
+        Vec<(u64, Duration)>, // snapshot timestamp (seconds since the unix epoch), its age
+    ),
 }
 
 #[derive(
@@ -762,6 +769,8 @@ pub struct ModeInfo {
     pub style: Style,
     pub capabilities: PluginCapabilities,
     pub session_name: Option<String>,
+    /// Whether this client attached with `--read-only` and can only look around the session
+    pub is_read_only: bool,
 }
 
 impl ModeInfo {
@@ -787,6 +796,27 @@ pub struct SessionInfo {
     pub connected_clients: usize,
     pub is_current_session: bool,
     pub available_layouts: Vec<LayoutInfo>,
+    pub clients: Vec<ConnectedClientInfo>,
+}
+
+/// A client connected to a session
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConnectedClientInfo {
+    pub client_id: ClientId,
+    /// The name this client attached with (the `client_name` option), if it has one
+    pub name: Option<String>,
+    /// The client whose active tab and focused pane this client follows, if any
+    pub following: Option<ClientId>,
+    pub is_read_only: bool,
+}
+
+impl ConnectedClientInfo {
+    /// The name of the client, or its id if it has none
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("#{}", self.client_id))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -857,6 +887,8 @@ pub struct TabInfo {
     pub active_swap_layout_name: Option<String>,
     /// Whether the user manually changed the layout, moving out of the swap layout scheme
     pub is_swap_layout_dirty: bool,
+    /// The working directory new panes in this tab start in, if it was set
+    pub cwd: Option<PathBuf>,
 }
 
 /// The `PaneManifest` contains a dictionary of panes, indexed by the tab position (0 indexed).
@@ -922,6 +954,10 @@ pub struct PaneInfo {
     /// Unselectable panes are often used for UI elements that do not have direct user interaction
     /// (eg. the default `status-bar` or `tab-bar`).
     pub is_selectable: bool,
+    /// The working directory of the process running in this terminal pane, as of the last time
+    /// the session was serialized (`None` for plugin panes, for panes opened since then and when
+    /// session serialization is turned off)
+    pub cwd: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
@@ -1374,4 +1410,20 @@ pub enum PluginCommand {
     KillSessions(Vec<String>), // one or more session names
     ScanHostFolder(PathBuf),   // TODO: rename to ScanHostFolder
     WatchFilesystem,
+    StackPane(Direction),
+    UnstackPanes,
+    RemovePaneFromStack,
+    SaveLayout(
+        String,          // layout name
+        Option<String>,  // tab position (starting at 1) or name, the whole session if None
+        bool,            // strip pane contents
+        Option<PathBuf>, // make cwds relative to this folder
+        bool,            // overwrite an existing layout
+    ),
+    SetTabCwd(Option<PathBuf>), // None to have new panes start in the cwd of the focused pane
+    ListSessionSnapshots(String), // session name
+    RestoreSessionSnapshot(
+        String, // session name
+        u64,    // snapshot timestamp
+    ),
 }
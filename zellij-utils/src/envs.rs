@@ -57,6 +57,9 @@ impl EnvironmentVariables {
     pub fn from_data(data: HashMap<String, String>) -> Self {
         EnvironmentVariables { env: data }
     }
+    pub fn inner(&self) -> &HashMap<String, String> {
+        &self.env
+    }
     /// Set all the ENVIRONMENT VARIABLES, that are configured
     /// in the configuration and layout files
     pub fn set_vars(&self) {
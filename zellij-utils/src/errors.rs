@@ -258,6 +258,7 @@ pub enum ScreenContext {
     ClearScreen,
     DumpScreen,
     DumpLayout,
+    SaveLayout,
     EditScrollback,
     ScrollUp,
     ScrollUpAt,
@@ -280,6 +281,7 @@ pub enum ScreenContext {
     SetFixedWidth,
     ClosePane,
     HoldPane,
+    IndicateCommandRestart,
     UpdatePaneName,
     UndoRenamePane,
     NewTab,
@@ -291,6 +293,11 @@ pub enum ScreenContext {
     GoToTabName,
     UpdateTabName,
     UndoRenameTab,
+    SetTabCwd,
+    StartSuspendedPanes,
+    FollowClient,
+    SetClientName,
+    Redraw,
     MoveTabLeft,
     MoveTabRight,
     TerminalResize,
@@ -350,7 +357,21 @@ pub enum ScreenContext {
     ReplacePane,
     NewInPlacePluginPane,
     DumpLayoutToHd,
+    RefreshForegroundCommands,
+    UpdateTerminalCwds,
+    UpdateTerminalCommands,
     RenameSession,
+    UndoLayoutChange,
+    RedoLayoutChange,
+    ExpireClosedPanes,
+    CheckPaneDependencies,
+    StackPane,
+    UnstackPanes,
+    RemovePaneFromStack,
+    TogglePaneSticky,
+    ToggleScratchpad,
+    AddScratchpadPane,
+    CancelScratchpadSpawn,
 }
 
 /// Stack call representations corresponding to the different types of [`PtyInstruction`]s.
@@ -361,15 +382,21 @@ pub enum PtyContext {
     SpawnTerminalVertically,
     SpawnTerminalHorizontally,
     UpdateActivePane,
+    UpdateTabDefaults,
     GoToTab,
     NewTab,
     ClosePane,
     CloseTab,
     ReRunCommandInPane,
+    ScheduleCommandRestart,
+    RestartCommandInPane,
     DropToShellInPane,
     SpawnInPlaceTerminal,
+    SpawnScratchpad,
     DumpLayout,
+    SaveLayout,
     LogLayoutToHd,
+    QueryForegroundCommands,
     FillPluginCwd,
     Exit,
 }
@@ -394,6 +421,7 @@ pub enum PluginContext {
     PluginSubscribedToEvents,
     PermissionRequestResult,
     DumpLayout,
+    SaveLayout,
     LogLayoutToHd,
     CliPipe,
     Message,
@@ -448,6 +476,14 @@ pub enum ServerContext {
     CliPipeOutput,
     AssociatePipeWithClient,
     DisconnectAllClientsExcept,
+    ResizeToClient,
+    LockSession,
+    LockScreenInput,
+    CheckIdleness,
+    EnforceIdlePolicies,
+    AllPanesExited,
+    ToggleKeepSession,
+    RunHooks,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -469,6 +505,11 @@ pub enum BackgroundJobContext {
     ReportLayoutInfo,
     RunCommand,
     WebRequest,
+    ExpireClosedPanes,
+    CheckPaneDependencies,
+    CheckIdleness,
+    EnforceIdlePolicies,
+    RunHookCommand,
     Exit,
 }
 
@@ -6,7 +6,7 @@ use super::layout::{
     SwapFloatingLayout, SwapTiledLayout, TiledPaneLayout,
 };
 use crate::cli::CliAction;
-use crate::data::{Direction, Resize};
+use crate::data::{ClientId, Direction, Resize};
 use crate::data::{FloatingPaneCoordinates, InputMode};
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
@@ -128,6 +128,14 @@ pub enum Action {
     DumpScreen(String, bool),
     /// Dumps
     DumpLayout,
+    /// Save the session, or one of its tabs, as a reusable layout in the layout directory
+    SaveLayout {
+        name: String,
+        tab: Option<String>, // a tab position (starting at 1) or name
+        strip_contents: bool,
+        relative_to: Option<PathBuf>,
+        force: bool,
+    },
     /// Scroll up in focus pane.
     EditScrollback,
     ScrollUp,
@@ -243,6 +251,21 @@ pub enum Action {
     ToggleMouseMode,
     PreviousSwapLayout,
     NextSwapLayout,
+    /// Undo the last change to the layout of the current tab (eg. a split, resize or pane close)
+    Undo,
+    /// Redo the last undone change to the layout of the current tab
+    Redo,
+    /// Stack the focused pane with its neighbour in the specified direction
+    StackPane(Direction),
+    /// Break up the stack of the focused pane back into regular splits
+    UnstackPanes,
+    /// Move the focused pane out of its stack and below it
+    RemovePaneFromStack,
+    /// Toggle whether the focused floating pane is shown on all tabs of the session
+    TogglePaneSticky,
+    /// Show or hide the scratchpad pane with the given name from the config, creating it the first
+    /// time it is toggled
+    ToggleScratchpad(String),
     /// Query all tab names
     QueryTabNames,
     /// Open a new tiled (embedded, non-floating) plugin pane
@@ -266,6 +289,22 @@ pub enum Action {
     RenameTerminalPane(u32, Vec<u8>),
     RenamePluginPane(u32, Vec<u8>),
     RenameTab(u32, Vec<u8>),
+    /// Set the cwd new panes in the focused tab start in (None to have them start in the cwd of
+    /// the focused pane)
+    SetTabCwd(Option<PathBuf>),
+    /// Start the commands of all the suspended panes in the focused tab (eg. those of a
+    /// resurrected session), as if Enter was pressed in each of them
+    StartSuspendedPanes,
+    /// Resize the session to the size of this client, regardless of the `client_size_policy`
+    ResizeToClient,
+    /// Have the active tab and focused pane of this client follow those of another client (None
+    /// to stop following)
+    FollowClient(Option<ClientId>),
+    /// Lock the session for all clients until the lock passphrase is entered
+    LockSession,
+    /// Mark the session as one to keep (or no longer keep), excluding it from the idle session
+    /// policies
+    ToggleKeepSession,
     BreakPane,
     BreakPaneRight,
     BreakPaneLeft,
@@ -310,6 +349,36 @@ impl Action {
         }
     }
 
+    /// Whether a client attached with `--read-only` may perform this action: it can move between
+    /// tabs and scroll, but nothing that changes the session for the other clients. In a mirrored
+    /// session moving between tabs or scrolling would move the view of every client, so only the
+    /// rest is allowed there.
+    pub fn is_allowed_for_read_only_clients(&self, session_is_mirrored: bool) -> bool {
+        match self {
+            Action::NoOp
+            | Action::Detach
+            | Action::SwitchToMode(_)
+            | Action::ToggleMouseMode
+            | Action::FollowClient(_) => true,
+            Action::ScrollUp
+            | Action::ScrollUpAt(_)
+            | Action::ScrollDown
+            | Action::ScrollDownAt(_)
+            | Action::ScrollToBottom
+            | Action::ScrollToTop
+            | Action::PageScrollUp
+            | Action::PageScrollDown
+            | Action::HalfPageScrollUp
+            | Action::HalfPageScrollDown
+            | Action::GoToNextTab
+            | Action::GoToPreviousTab
+            | Action::GoToTab(_)
+            | Action::GoToTabName(_, false)
+            | Action::ToggleTab => !session_is_mirrored,
+            _ => false,
+        }
+    }
+
     pub fn actions_from_cli(
         cli_action: CliAction,
         get_current_dir: Box<dyn Fn() -> PathBuf>,
@@ -331,6 +400,22 @@ impl Action {
                 full,
             )]),
             CliAction::DumpLayout => Ok(vec![Action::DumpLayout]),
+            CliAction::SaveLayout {
+                name,
+                tab,
+                strip_contents,
+                relative_to,
+                force,
+            } => {
+                let current_dir = get_current_dir();
+                Ok(vec![Action::SaveLayout {
+                    name,
+                    tab,
+                    strip_contents,
+                    relative_to: relative_to.map(|relative_to| current_dir.join(relative_to)),
+                    force,
+                }])
+            },
             CliAction::EditScrollback => Ok(vec![Action::EditScrollback]),
             CliAction::ScrollUp => Ok(vec![Action::ScrollUp]),
             CliAction::ScrollDown => Ok(vec![Action::ScrollDown]),
@@ -353,6 +438,9 @@ impl Action {
                 name,
                 close_on_exit,
                 start_suspended,
+                env,
+                restart,
+                max_restarts,
                 configuration,
                 skip_plugin_cache,
                 x,
@@ -432,6 +520,9 @@ impl Action {
                         direction,
                         hold_on_close,
                         hold_on_start,
+                        env: env.into_iter().collect(),
+                        restart: restart.unwrap_or_default(),
+                        max_restarts,
                     };
                     if floating {
                         Ok(vec![Action::NewFloatingPane(
@@ -515,11 +606,23 @@ impl Action {
                 Action::TabNameInput(name.as_bytes().to_vec()),
             ]),
             CliAction::UndoRenameTab => Ok(vec![Action::UndoRenameTab]),
+            CliAction::SetTabCwd { cwd } => {
+                let current_dir = get_current_dir();
+                Ok(vec![Action::SetTabCwd(
+                    cwd.map(|cwd| current_dir.join(cwd)),
+                )])
+            },
+            CliAction::StartSuspendedPanes => Ok(vec![Action::StartSuspendedPanes]),
+            CliAction::ResizeToClient => Ok(vec![Action::ResizeToClient]),
+            CliAction::FollowClient { client_id } => Ok(vec![Action::FollowClient(client_id)]),
+            CliAction::LockSession => Ok(vec![Action::LockSession]),
+            CliAction::ToggleKeepSession => Ok(vec![Action::ToggleKeepSession]),
             CliAction::NewTab {
                 name,
                 layout,
                 layout_dir,
                 cwd,
+                layout_args,
             } => {
                 let current_dir = get_current_dir();
                 let cwd = cwd
@@ -530,12 +633,19 @@ impl Action {
                         .or_else(|| config.and_then(|c| c.options.layout_dir))
                         .or_else(|| get_layout_dir(find_default_config_dir()));
                     let (path_to_raw_layout, raw_layout, swap_layouts) =
-                        Layout::stringified_from_path_or_default(Some(&layout_path), layout_dir)
-                            .map_err(|e| format!("Failed to load layout: {}", e))?;
-                    let layout = Layout::from_str(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd).map_err(|e| {
+                        Layout::stringified_from_path_or_default(
+                            Some(&layout_path),
+                            layout_dir.clone(),
+                        )
+                        .map_err(|e| format!("Failed to load layout: {}", e))?;
+                    let layout = Layout::from_kdl_with_layout_args(&raw_layout, path_to_raw_layout, swap_layouts.as_ref().map(|(f, p)| (f.as_str(), p.as_str())), cwd, layout_args.into_iter().collect(), layout_dir).map_err(|e| {
                         let stringified_error = match e {
                             ConfigError::KdlError(kdl_error) => {
-                                let error = kdl_error.add_src(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout));
+                                let error = if kdl_error.src.is_some() {
+                                    kdl_error
+                                } else {
+                                    kdl_error.add_src(layout_path.as_path().as_os_str().to_string_lossy().to_string(), String::from(raw_layout))
+                                };
                                 let report: Report = error.into();
                                 format!("{:?}", report)
                             }
@@ -598,6 +708,13 @@ impl Action {
             },
             CliAction::PreviousSwapLayout => Ok(vec![Action::PreviousSwapLayout]),
             CliAction::NextSwapLayout => Ok(vec![Action::NextSwapLayout]),
+            CliAction::Undo => Ok(vec![Action::Undo]),
+            CliAction::Redo => Ok(vec![Action::Redo]),
+            CliAction::StackPane { direction } => Ok(vec![Action::StackPane(direction)]),
+            CliAction::UnstackPanes => Ok(vec![Action::UnstackPanes]),
+            CliAction::RemovePaneFromStack => Ok(vec![Action::RemovePaneFromStack]),
+            CliAction::TogglePaneSticky => Ok(vec![Action::TogglePaneSticky]),
+            CliAction::ToggleScratchpad { name } => Ok(vec![Action::ToggleScratchpad(name)]),
             CliAction::QueryTabNames => Ok(vec![Action::QueryTabNames]),
             CliAction::StartOrReloadPlugin { url, configuration } => {
                 let current_dir = get_current_dir();
@@ -1,7 +1,10 @@
 //! Trigger a command
 use crate::data::Direction;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 pub enum TerminalAction {
@@ -35,6 +38,111 @@ pub struct RunCommand {
     pub hold_on_close: bool,
     #[serde(default)]
     pub hold_on_start: bool,
+    /// Environment variables set for the command in addition to those of the session
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Whether the command is started again once it exits
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// How many times the command is restarted before it is treated as exited for good
+    #[serde(default)]
+    pub max_restarts: Option<usize>,
+    /// Panes that should be ready before the command is started
+    #[serde(default)]
+    pub depends_on: Vec<PaneDependency>,
+    /// Whether the command starts right away when its session is resurrected (rather than wait
+    /// for Enter to be pressed in its pane), regardless of the `ResurrectionPolicy`
+    #[serde(default)]
+    pub rerun_on_resurrect: Option<bool>,
+}
+
+/// Which commands start right away when their session is resurrected, the others wait for Enter
+/// to be pressed in their pane.
+///
+/// Patterns are globs (`*` and `?`) matched against the command line of the pane, eg.
+/// `"npm run *"`, commands matching a suspend pattern stay suspended even if they match a run
+/// pattern.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResurrectionPolicy {
+    pub run_commands: Vec<String>,
+    pub suspend_commands: Vec<String>,
+}
+
+impl ResurrectionPolicy {
+    pub fn new(run_commands: Vec<String>, suspend_commands: Vec<String>) -> Self {
+        ResurrectionPolicy {
+            run_commands,
+            suspend_commands,
+        }
+    }
+    pub fn runs_on_resurrection(&self, run_command: &RunCommand) -> bool {
+        if let Some(rerun_on_resurrect) = run_command.rerun_on_resurrect {
+            return rerun_on_resurrect;
+        }
+        let matches_any = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| run_command.command_line_matches(pattern))
+        };
+        !matches_any(&self.suspend_commands) && matches_any(&self.run_commands)
+    }
+}
+
+/// A pane (by name) a command waits for before starting.
+///
+/// The pane is ready once its output matches `output` if it is given, otherwise once its command
+/// exited successfully - or `timeout` seconds after it started, whichever comes first.
+#[derive(Clone, Debug, Deserialize, Default, Serialize, PartialEq, Eq)]
+pub struct PaneDependency {
+    pub pane_name: String,
+    #[serde(default)]
+    pub output: Option<String>,
+    #[serde(default)]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Default, Serialize, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+impl RestartPolicy {
+    pub fn should_restart(&self, exit_status: Option<i32>) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            // a command killed by a signal has no exit status and is considered failed
+            RestartPolicy::OnFailure => exit_status != Some(0),
+            RestartPolicy::Always => true,
+        }
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(RestartPolicy::Never),
+            "on-failure" => Ok(RestartPolicy::OnFailure),
+            "always" => Ok(RestartPolicy::Always),
+            _ => Err(format!(
+                "Unknown restart policy: {}, expected one of: never, on-failure, always",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestartPolicy::Never => write!(f, "never"),
+            RestartPolicy::OnFailure => write!(f, "on-failure"),
+            RestartPolicy::Always => write!(f, "always"),
+        }
+    }
 }
 
 impl std::fmt::Display for RunCommand {
@@ -68,6 +176,12 @@ pub struct RunCommandAction {
     pub hold_on_close: bool,
     #[serde(default)]
     pub hold_on_start: bool,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub max_restarts: Option<usize>,
 }
 
 impl From<RunCommandAction> for RunCommand {
@@ -78,6 +192,11 @@ impl From<RunCommandAction> for RunCommand {
             cwd: action.cwd,
             hold_on_close: action.hold_on_close,
             hold_on_start: action.hold_on_start,
+            env: action.env,
+            restart: action.restart,
+            max_restarts: action.max_restarts,
+            depends_on: vec![],
+            rerun_on_resurrect: None,
         }
     }
 }
@@ -91,6 +210,9 @@ impl From<RunCommand> for RunCommandAction {
             direction: None,
             hold_on_close: run_command.hold_on_close,
             hold_on_start: run_command.hold_on_start,
+            env: run_command.env,
+            restart: run_command.restart,
+            max_restarts: run_command.max_restarts,
         }
     }
 }
@@ -106,4 +228,41 @@ impl RunCommand {
         self.cwd = Some(cwd);
         self
     }
+    /// Whether the glob `pattern` matches the command line, either as it was given or with only
+    /// the file name of the command (eg. both `/usr/bin/npm run dev` and `npm run dev`)
+    pub fn command_line_matches(&self, pattern: &str) -> bool {
+        let glob = pattern
+            .split('*')
+            .map(|part| {
+                part.split('?')
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect::<Vec<_>>()
+            .join(".*");
+        let Ok(glob) = Regex::new(&format!("^{}$", glob)) else {
+            return false;
+        };
+        let with_args = |command: String| {
+            std::iter::once(command)
+                .chain(self.args.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let command_name = self
+            .command
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        glob.is_match(&with_args(self.command.display().to_string()))
+            || command_name.map_or(false, |name| glob.is_match(&with_args(name)))
+    }
+    /// Adds the given environment variables, keeping the values of those already set
+    pub fn inherit_env(&mut self, env: &BTreeMap<String, String>) {
+        for (key, value) in env {
+            self.env
+                .entry(key.to_owned())
+                .or_insert_with(|| value.to_owned());
+        }
+    }
 }
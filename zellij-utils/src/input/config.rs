@@ -7,9 +7,11 @@ use thiserror::Error;
 
 use std::convert::TryFrom;
 
+use super::hooks::Hooks;
 use super::keybinds::Keybinds;
 use super::options::Options;
 use super::plugins::{PluginAliases, PluginsConfigError};
+use super::scratchpads::Scratchpads;
 use super::theme::{Themes, UiConfig};
 use crate::cli::{CliArgs, Command};
 use crate::envs::EnvironmentVariables;
@@ -26,6 +28,8 @@ pub struct Config {
     pub options: Options,
     pub themes: Themes,
     pub plugins: PluginAliases,
+    pub scratchpads: Scratchpads,
+    pub hooks: Hooks,
     pub ui: UiConfig,
     pub env: EnvironmentVariables,
 }
@@ -227,6 +231,8 @@ impl Config {
         self.keybinds.merge(other.keybinds.clone());
         self.themes = self.themes.merge(other.themes);
         self.plugins.merge(other.plugins);
+        self.scratchpads.merge(other.scratchpads);
+        self.hooks.merge(other.hooks);
         self.ui = self.ui.merge(other.ui);
         self.env = self.env.merge(other.env);
         Ok(())
@@ -236,10 +242,15 @@ impl Config {
 #[cfg(test)]
 mod config_test {
     use super::*;
-    use crate::data::{InputMode, Palette, PaletteColor, PluginTag};
+    use crate::data::Direction;
+    use crate::data::{FloatingPaneCoordinates, InputMode, Palette, PaletteColor, PluginTag};
+    use crate::input::actions::Action;
+    use crate::input::command::RunCommand;
+    use crate::input::hooks::{Hook, HookEvent, Hooks};
     use crate::input::layout::{RunPlugin, RunPluginLocation};
     use crate::input::options::{Clipboard, OnForceClose};
     use crate::input::plugins::{PluginConfig, PluginType};
+    use crate::input::scratchpads::Scratchpad;
     use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
     use std::collections::{BTreeMap, HashMap};
     use std::io::Write;
@@ -643,6 +654,110 @@ mod config_test {
         );
     }
 
+    #[test]
+    fn can_define_hooks_in_configfile() {
+        let config_contents = r#"
+            hooks {
+                command_pane_exited "notify-send \"$ZELLIJ_PANE_ID exited\""
+                client_attached "timetracker start"
+                client_attached "echo attached >> /tmp/log"
+                tab_created {
+                    NewPane "Down"
+                }
+            }
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        let mut expected_hooks = BTreeMap::new();
+        expected_hooks.insert(
+            HookEvent::CommandPaneExited,
+            vec![Hook::Command(
+                "notify-send \"$ZELLIJ_PANE_ID exited\"".to_owned(),
+            )],
+        );
+        expected_hooks.insert(
+            HookEvent::ClientAttached,
+            vec![
+                Hook::Command("timetracker start".to_owned()),
+                Hook::Command("echo attached >> /tmp/log".to_owned()),
+            ],
+        );
+        expected_hooks.insert(
+            HookEvent::TabCreated,
+            vec![Hook::Actions(vec![Action::NewPane(
+                Some(Direction::Down),
+                None,
+            )])],
+        );
+        assert_eq!(
+            config.hooks,
+            Hooks::from_data(expected_hooks),
+            "Hooks defined in config"
+        );
+    }
+
+    #[test]
+    fn unknown_hook_event_is_an_error() {
+        let config_contents = r#"
+            hooks {
+                pane_renamed "echo renamed"
+            }
+        "#;
+        assert!(Config::from_kdl(config_contents, None).is_err());
+    }
+
+    #[test]
+    fn can_define_scratchpads_in_configfile() {
+        let config_contents = r#"
+            scratchpads {
+                notes command="vim" {
+                    args "notes.md"
+                    cwd "/tmp"
+                    x "10%"
+                    y 2
+                    width "80%"
+                    height "50%"
+                }
+                monitor command="htop"
+            }
+        "#;
+        let config = Config::from_kdl(config_contents, None).unwrap();
+        let mut expected_scratchpads = BTreeMap::new();
+        expected_scratchpads.insert(
+            "notes".to_owned(),
+            Scratchpad {
+                command: RunCommand {
+                    command: PathBuf::from("vim"),
+                    args: vec!["notes.md".to_owned()],
+                    cwd: Some(PathBuf::from("/tmp")),
+                    hold_on_close: true,
+                    ..Default::default()
+                },
+                coordinates: FloatingPaneCoordinates::new(
+                    Some("10%".to_owned()),
+                    Some("2".to_owned()),
+                    Some("80%".to_owned()),
+                    Some("50%".to_owned()),
+                ),
+            },
+        );
+        expected_scratchpads.insert(
+            "monitor".to_owned(),
+            Scratchpad {
+                command: RunCommand {
+                    command: PathBuf::from("htop"),
+                    hold_on_close: true,
+                    ..Default::default()
+                },
+                coordinates: None,
+            },
+        );
+        assert_eq!(
+            config.scratchpads,
+            Scratchpads::from_data(expected_scratchpads),
+            "Scratchpads defined in config"
+        );
+    }
+
     #[test]
     fn can_define_ui_configuration_in_configfile() {
         let config_contents = r#"
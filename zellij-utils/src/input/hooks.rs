@@ -0,0 +1,116 @@
+//! Lifecycle hooks configuration: commands and actions the server runs on session events
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::actions::Action;
+use crate::data::ClientId;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Deserialize, Serialize)]
+pub enum HookEvent {
+    SessionCreated,
+    ClientAttached,
+    ClientDetached,
+    TabCreated,
+    TabClosed,
+    PaneOpened,
+    CommandPaneExited,
+    SessionSerialized,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::SessionCreated => "session_created",
+            HookEvent::ClientAttached => "client_attached",
+            HookEvent::ClientDetached => "client_detached",
+            HookEvent::TabCreated => "tab_created",
+            HookEvent::TabClosed => "tab_closed",
+            HookEvent::PaneOpened => "pane_opened",
+            HookEvent::CommandPaneExited => "command_pane_exited",
+            HookEvent::SessionSerialized => "session_serialized",
+        }
+    }
+}
+
+impl FromStr for HookEvent {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "session_created" => Ok(HookEvent::SessionCreated),
+            "client_attached" => Ok(HookEvent::ClientAttached),
+            "client_detached" => Ok(HookEvent::ClientDetached),
+            "tab_created" => Ok(HookEvent::TabCreated),
+            "tab_closed" => Ok(HookEvent::TabClosed),
+            "pane_opened" => Ok(HookEvent::PaneOpened),
+            "command_pane_exited" => Ok(HookEvent::CommandPaneExited),
+            "session_serialized" => Ok(HookEvent::SessionSerialized),
+            _ => Err(format!("Unknown hook event: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// What a hook does when its event happens
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum Hook {
+    Command(String), // run with `sh -c`
+    Actions(Vec<Action>),
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Hooks {
+    pub hooks: BTreeMap<HookEvent, Vec<Hook>>,
+}
+
+impl Hooks {
+    pub fn merge(&mut self, other: Self) {
+        self.hooks.extend(other.hooks);
+    }
+    pub fn from_data(hooks: BTreeMap<HookEvent, Vec<Hook>>) -> Self {
+        Hooks { hooks }
+    }
+    pub fn get(&self, event: HookEvent) -> &[Hook] {
+        self.hooks.get(&event).map(|h| h.as_slice()).unwrap_or(&[])
+    }
+    pub fn contains(&self, event: HookEvent) -> bool {
+        !self.get(event).is_empty()
+    }
+}
+
+/// What a hook event happened to, passed to hook commands as environment variables
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct HookContext {
+    pub client_id: Option<ClientId>,
+    pub tab_name: Option<String>,
+    pub pane_id: Option<u32>, // terminal pane id
+    pub exit_code: Option<i32>,
+}
+
+impl HookContext {
+    pub fn env_vars(&self, event: HookEvent, session_name: &str) -> BTreeMap<String, String> {
+        let mut env_vars = BTreeMap::new();
+        env_vars.insert("ZELLIJ_HOOK_EVENT".to_owned(), event.to_string());
+        env_vars.insert("ZELLIJ_SESSION_NAME".to_owned(), session_name.to_owned());
+        if let Some(client_id) = self.client_id {
+            env_vars.insert("ZELLIJ_CLIENT_ID".to_owned(), client_id.to_string());
+        }
+        if let Some(tab_name) = &self.tab_name {
+            env_vars.insert("ZELLIJ_TAB_NAME".to_owned(), tab_name.clone());
+        }
+        if let Some(pane_id) = self.pane_id {
+            env_vars.insert("ZELLIJ_PANE_ID".to_owned(), pane_id.to_string());
+        }
+        if let Some(exit_code) = self.exit_code {
+            env_vars.insert("ZELLIJ_EXIT_CODE".to_owned(), exit_code.to_string());
+        }
+        env_vars
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
     data::{Direction, LayoutInfo},
     home::{default_layout_dir, find_default_config_dir},
     input::{
-        command::RunCommand,
+        command::{PaneDependency, RestartPolicy, ResurrectionPolicy, RunCommand},
         config::{Config, ConfigError},
     },
     pane_size::{Constraint, Dimension, PaneGeom},
@@ -25,7 +25,7 @@ use std::str::FromStr;
 
 use super::plugins::{PluginAliases, PluginTag, PluginsConfigError};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::vec::Vec;
 use std::{
     fmt,
@@ -231,6 +231,7 @@ pub enum Run {
     Command(RunCommand),
     EditFile(PathBuf, Option<usize>, Option<PathBuf>), // TODO: merge this with TerminalAction::OpenFile
     Cwd(PathBuf),
+    Shell(Option<PathBuf>, BTreeMap<String, String>), // the default shell, with a cwd and environment variables
 }
 
 impl Run {
@@ -248,6 +249,7 @@ impl Run {
                 if merged.args.is_empty() && !base_run_command.args.is_empty() {
                     merged.args = base_run_command.args.clone();
                 }
+                merged.inherit_env(&base_run_command.env);
                 Some(Run::Command(merged))
             },
             (Some(Run::Command(base_run_command)), Some(Run::Cwd(other_cwd))) => {
@@ -262,6 +264,34 @@ impl Run {
                 }
                 Some(Run::Command(merged))
             },
+            (Some(Run::Command(base_run_command)), Some(Run::Shell(other_cwd, other_env))) => {
+                let mut merged = base_run_command.clone();
+                if other_cwd.is_some() {
+                    merged.cwd = other_cwd.clone();
+                }
+                merged.env.extend(other_env.clone());
+                Some(Run::Command(merged))
+            },
+            (Some(Run::Shell(base_cwd, base_env)), Some(Run::Command(other_command))) => {
+                let mut merged = other_command.clone();
+                if merged.cwd.is_none() {
+                    merged.cwd = base_cwd.clone();
+                }
+                merged.inherit_env(base_env);
+                Some(Run::Command(merged))
+            },
+            (Some(Run::Shell(base_cwd, base_env)), Some(Run::Shell(other_cwd, other_env))) => {
+                let mut env = base_env.clone();
+                env.extend(other_env.clone());
+                Some(Run::Shell(other_cwd.clone().or(base_cwd.clone()), env))
+            },
+            (Some(Run::Shell(_base_cwd, base_env)), Some(Run::Cwd(other_cwd))) => {
+                Some(Run::Shell(Some(other_cwd.clone()), base_env.clone()))
+            },
+            (Some(Run::Cwd(base_cwd)), Some(Run::Shell(other_cwd, other_env))) => Some(Run::Shell(
+                other_cwd.clone().or(Some(base_cwd.clone())),
+                other_env.clone(),
+            )),
             (
                 Some(Run::Command(base_run_command)),
                 Some(Run::EditFile(file_to_edit, line_number, edit_cwd)),
@@ -315,6 +345,14 @@ impl Run {
             Run::Cwd(path) => {
                 *path = cwd.join(&path);
             },
+            Run::Shell(shell_cwd, _env) => match shell_cwd.as_mut() {
+                Some(shell_cwd) => {
+                    *shell_cwd = cwd.join(&shell_cwd);
+                },
+                None => {
+                    let _ = shell_cwd.insert(cwd.clone());
+                },
+            },
             Run::Plugin(run_plugin_or_alias) => {
                 run_plugin_or_alias.add_initial_cwd(&cwd);
             },
@@ -349,18 +387,88 @@ impl Run {
             }
         }
     }
+    pub fn add_rerun_on_resurrect(&mut self, rerun_on_resurrect: Option<bool>) {
+        // overrides the rerun_on_resurrect of a Run::Command if it is Some
+        if let Some(rerun_on_resurrect) = rerun_on_resurrect {
+            if let Run::Command(run_command) = self {
+                run_command.rerun_on_resurrect = Some(rerun_on_resurrect);
+            }
+        }
+    }
+    pub fn start_on_resurrection(&mut self, resurrection_policy: &ResurrectionPolicy) {
+        // un-suspends a Run::Command if the policy has it run when its session is resurrected
+        if let Run::Command(run_command) = self {
+            if resurrection_policy.runs_on_resurrection(run_command) {
+                run_command.hold_on_start = false;
+            }
+        }
+    }
+    pub fn add_restart_policy(
+        &mut self,
+        restart: Option<RestartPolicy>,
+        max_restarts: Option<usize>,
+    ) {
+        // overrides the restart policy of a Run::Command with those that are Some
+        if let Run::Command(run_command) = self {
+            if let Some(restart) = restart {
+                run_command.restart = restart;
+            }
+            if let Some(max_restarts) = max_restarts {
+                run_command.max_restarts = Some(max_restarts);
+            }
+        }
+    }
+    pub fn add_depends_on(&mut self, depends_on: Vec<PaneDependency>) {
+        // adds to the dependencies of a Run::Command
+        if let Run::Command(run_command) = self {
+            run_command.depends_on.extend(depends_on);
+        }
+    }
+    pub fn add_env(&mut self, env: Option<BTreeMap<String, String>>) {
+        // overrides the matching environment variables of a Run::Command if they are Some
+        if let Some(env) = env {
+            match self {
+                Run::Command(run_command) => run_command.env.extend(env),
+                Run::Shell(_cwd, shell_env) => shell_env.extend(env),
+                _ => {},
+            }
+        }
+    }
+    pub fn inherit_env(&mut self, env: &BTreeMap<String, String>) {
+        // unlike add_env, this only adds the environment variables the Run::Command does not
+        // already define (eg. those of the tab it is in)
+        match self {
+            Run::Command(run_command) => run_command.inherit_env(env),
+            Run::Shell(_cwd, shell_env) => {
+                for (key, value) in env {
+                    shell_env
+                        .entry(key.to_owned())
+                        .or_insert_with(|| value.to_owned());
+                }
+            },
+            Run::Cwd(cwd) if !env.is_empty() => {
+                *self = Run::Shell(Some(cwd.clone()), env.clone());
+            },
+            _ => {},
+        }
+    }
     pub fn is_same_category(first: &Option<Run>, second: &Option<Run>) -> bool {
         match (first, second) {
             (Some(Run::Plugin(..)), Some(Run::Plugin(..))) => true,
             (Some(Run::Command(..)), Some(Run::Command(..))) => true,
             (Some(Run::EditFile(..)), Some(Run::EditFile(..))) => true,
             (Some(Run::Cwd(..)), Some(Run::Cwd(..))) => true,
+            (Some(Run::Shell(..)), Some(Run::Shell(..))) => true,
             _ => false,
         }
     }
     pub fn is_terminal(run: &Option<Run>) -> bool {
         match run {
-            Some(Run::Command(..)) | Some(Run::EditFile(..)) | Some(Run::Cwd(..)) | None => true,
+            Some(Run::Command(..))
+            | Some(Run::EditFile(..))
+            | Some(Run::Cwd(..))
+            | Some(Run::Shell(..))
+            | None => true,
             _ => false,
         }
     }
@@ -370,6 +478,7 @@ impl Run {
             Run::Command(run_command) => run_command.cwd.clone(),
             Run::EditFile(_file, _line_num, cwd) => cwd.clone(),
             Run::Cwd(cwd) => Some(cwd.clone()),
+            Run::Shell(cwd, _env) => cwd.clone(),
         }
     }
     pub fn get_run_plugin(&self) -> Option<RunPlugin> {
@@ -609,6 +718,13 @@ pub enum LayoutConstraint {
     MaxPanes(usize),
     MinPanes(usize),
     ExactPanes(usize),
+    HasCommand(String), // a pane runs this command (its name or full path) in its foreground
+    HasPlugin(String),  // a plugin pane with this url or alias is open
+    MinColumns(usize),
+    MaxColumns(usize),
+    MinRows(usize),
+    MaxRows(usize),
+    All(Vec<LayoutConstraint>), // several of the above, all of which should be met
     NoConstraint,
 }
 
@@ -618,6 +734,16 @@ impl Display for LayoutConstraint {
             LayoutConstraint::MaxPanes(max_panes) => write!(f, "max_panes={}", max_panes),
             LayoutConstraint::MinPanes(min_panes) => write!(f, "min_panes={}", min_panes),
             LayoutConstraint::ExactPanes(exact_panes) => write!(f, "exact_panes={}", exact_panes),
+            LayoutConstraint::HasCommand(command) => write!(f, "has_command={:?}", command),
+            LayoutConstraint::HasPlugin(plugin) => write!(f, "has_plugin={:?}", plugin),
+            LayoutConstraint::MinColumns(min_columns) => write!(f, "min_columns={}", min_columns),
+            LayoutConstraint::MaxColumns(max_columns) => write!(f, "max_columns={}", max_columns),
+            LayoutConstraint::MinRows(min_rows) => write!(f, "min_rows={}", min_rows),
+            LayoutConstraint::MaxRows(max_rows) => write!(f, "max_rows={}", max_rows),
+            LayoutConstraint::All(constraints) => {
+                let constraints: Vec<String> = constraints.iter().map(|c| c.to_string()).collect();
+                write!(f, "{}", constraints.join(" "))
+            },
             LayoutConstraint::NoConstraint => write!(f, ""),
         }
     }
@@ -709,6 +835,7 @@ pub struct FloatingPaneLayout {
     pub focus: Option<bool>,
     pub already_running: bool,
     pub pane_initial_contents: Option<String>,
+    pub is_sticky: bool,
 }
 
 impl FloatingPaneLayout {
@@ -725,6 +852,20 @@ impl FloatingPaneLayout {
             run.add_start_suspended(start_suspended);
         }
     }
+    pub fn start_on_resurrection(&mut self, resurrection_policy: &ResurrectionPolicy) {
+        if let Some(run) = self.run.as_mut() {
+            run.start_on_resurrection(resurrection_policy);
+        }
+    }
+    pub fn inherit_env_in_layout(&mut self, env: &BTreeMap<String, String>) {
+        match self.run.as_mut() {
+            Some(run) => run.inherit_env(env),
+            None if !env.is_empty() => {
+                self.run = Some(Run::Shell(None, env.clone()));
+            },
+            None => {},
+        }
+    }
 }
 
 impl From<&TiledPaneLayout> for FloatingPaneLayout {
@@ -744,6 +885,8 @@ pub struct TiledPaneLayout {
     pub name: Option<String>,
     pub children: Vec<TiledPaneLayout>,
     pub split_size: Option<SplitSize>,
+    pub min_size: Option<usize>, // in rows or columns, along the parent's split direction
+    pub max_size: Option<usize>, // in rows or columns, along the parent's split direction
     pub run: Option<Run>,
     pub borderless: bool,
     pub focus: Option<bool>,
@@ -754,9 +897,19 @@ pub struct TiledPaneLayout {
     pub run_instructions_to_ignore: Vec<Option<Run>>,
     pub hide_floating_panes: bool, // only relevant if this is the base layout
     pub pane_initial_contents: Option<String>,
+    pub default_cwd: Option<PathBuf>, // only relevant if this is the base layout of a tab
+    pub default_env: BTreeMap<String, String>, // only relevant if this is the base layout of a tab
 }
 
 impl TiledPaneLayout {
+    pub fn all_panes(&self) -> Vec<&TiledPaneLayout> {
+        // this pane and all of its descendants
+        let mut all_panes = vec![self];
+        for child in &self.children {
+            all_panes.append(&mut child.all_panes());
+        }
+        all_panes
+    }
     pub fn insert_children_layout(
         &mut self,
         children_layout: &mut TiledPaneLayout,
@@ -921,6 +1074,19 @@ impl TiledPaneLayout {
             child.add_cwd_to_layout(cwd);
         }
     }
+    pub fn inherit_env_in_layout(&mut self, env: &BTreeMap<String, String>) {
+        match self.run.as_mut() {
+            Some(run) => run.inherit_env(env),
+            // only panes without children run a shell
+            None if self.children.is_empty() && !env.is_empty() => {
+                self.run = Some(Run::Shell(None, env.clone()));
+            },
+            None => {},
+        }
+        for child in self.children.iter_mut() {
+            child.inherit_env_in_layout(env);
+        }
+    }
     pub fn populate_plugin_aliases_in_layout(&mut self, plugin_aliases: &PluginAliases) {
         match self.run.as_mut() {
             Some(run) => run.populate_run_plugin_if_needed(plugin_aliases),
@@ -1018,6 +1184,14 @@ impl TiledPaneLayout {
             child.recursively_add_start_suspended(start_suspended);
         }
     }
+    pub fn recursively_start_on_resurrection(&mut self, resurrection_policy: &ResurrectionPolicy) {
+        if let Some(run) = self.run.as_mut() {
+            run.start_on_resurrection(resurrection_policy);
+        }
+        for child in self.children.iter_mut() {
+            child.recursively_start_on_resurrection(resurrection_policy);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -1055,6 +1229,64 @@ impl Default for LayoutParts {
 }
 
 impl Layout {
+    fn tiled_and_floating_panes(&self) -> Vec<(&TiledPaneLayout, &Vec<FloatingPaneLayout>)> {
+        self.tabs
+            .iter()
+            .map(|(_tab_name, tiled_panes, floating_panes)| (tiled_panes, floating_panes))
+            .chain(
+                self.template
+                    .iter()
+                    .map(|(tiled_panes, floating_panes)| (tiled_panes, floating_panes)),
+            )
+            .collect()
+    }
+    pub fn pane_names(&self) -> HashSet<String> {
+        let mut pane_names = HashSet::new();
+        for (tiled_panes, floating_panes) in self.tiled_and_floating_panes() {
+            let tiled_pane_names = tiled_panes.all_panes().into_iter().map(|p| &p.name);
+            let floating_pane_names = floating_panes.iter().map(|p| &p.name);
+            pane_names.extend(
+                tiled_pane_names
+                    .chain(floating_pane_names)
+                    .flatten()
+                    .cloned(),
+            );
+        }
+        pane_names
+    }
+    /// The panes of this layout as their (optional) names along with what they run
+    pub fn pane_names_and_runs(&self) -> Vec<(Option<&String>, Option<&Run>)> {
+        let mut pane_names_and_runs = vec![];
+        for (tiled_panes, floating_panes) in self.tiled_and_floating_panes() {
+            let tiled_panes = tiled_panes
+                .all_panes()
+                .into_iter()
+                .map(|p| (p.name.as_ref(), p.run.as_ref()));
+            let floating_panes = floating_panes
+                .iter()
+                .map(|p| (p.name.as_ref(), p.run.as_ref()));
+            pane_names_and_runs.extend(tiled_panes.chain(floating_panes));
+        }
+        pane_names_and_runs
+    }
+    pub fn runs(&self) -> Vec<&Run> {
+        let mut runs = vec![];
+        for (tiled_panes, floating_panes) in self.tiled_and_floating_panes() {
+            let tiled_pane_runs = tiled_panes.all_panes().into_iter().map(|p| &p.run);
+            let floating_pane_runs = floating_panes.iter().map(|p| &p.run);
+            runs.extend(tiled_pane_runs.chain(floating_pane_runs).flatten());
+        }
+        runs
+    }
+    pub fn pane_dependencies(&self) -> Vec<PaneDependency> {
+        let mut pane_dependencies = vec![];
+        for run in self.runs() {
+            if let Run::Command(run_command) = run {
+                pane_dependencies.extend(run_command.depends_on.iter().cloned());
+            }
+        }
+        pane_dependencies
+    }
     // the first layout will either be the default one
     pub fn list_available_layouts(
         layout_dir: Option<PathBuf>,
@@ -1139,16 +1371,19 @@ impl Layout {
         layout_path: Option<&PathBuf>,
         layout_dir: Option<PathBuf>,
         config: Config,
+        layout_args: BTreeMap<String, String>,
     ) -> Result<(Layout, Config), ConfigError> {
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
-            Layout::stringified_from_path_or_default(layout_path, layout_dir)?;
-        let layout = Layout::from_kdl(
+            Layout::stringified_from_path_or_default(layout_path, layout_dir.clone())?;
+        let layout = Layout::from_kdl_with_layout_args(
             &raw_layout,
             path_to_raw_layout,
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            layout_args,
+            layout_dir,
         )?;
         let config = Config::from_kdl(&raw_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
@@ -1171,18 +1406,21 @@ impl Layout {
     }
     pub fn from_default_assets(
         layout_name: &Path,
-        _layout_dir: Option<PathBuf>,
+        layout_dir: Option<PathBuf>,
         config: Config,
+        layout_args: BTreeMap<String, String>,
     ) -> Result<(Layout, Config), ConfigError> {
         let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
             Layout::stringified_from_default_assets(layout_name)?;
-        let layout = Layout::from_kdl(
+        let layout = Layout::from_kdl_with_layout_args(
             &raw_layout,
             path_to_raw_layout,
             raw_swap_layouts
                 .as_ref()
                 .map(|(r, f)| (r.as_str(), f.as_str())),
             None,
+            layout_args,
+            layout_dir,
         )?;
         let config = Config::from_kdl(&raw_layout, Some(config))?; // this merges the two config, with
         Ok((layout, config))
@@ -1347,6 +1585,17 @@ impl Layout {
         }
     }
 
+    /// Starts the suspended commands of a resurrected session that `resurrection_policy` has run
+    /// right away
+    pub fn start_on_resurrection(&mut self, resurrection_policy: &ResurrectionPolicy) {
+        for (_tab_name, tiled_panes, floating_panes) in self.tabs.iter_mut() {
+            tiled_panes.recursively_start_on_resurrection(resurrection_policy);
+            for floating_pane in floating_panes.iter_mut() {
+                floating_pane.start_on_resurrection(resurrection_policy);
+            }
+        }
+    }
+
     fn swap_layout_and_path(path: &Path) -> Option<(String, String)> {
         // Option<path, stringified_swap_layout>
         let mut swap_layout_path = PathBuf::from(path);
@@ -1461,8 +1710,8 @@ fn split_space(
         }
     });
 
-    let mut total_pane_size = 0;
-    for (&size, _part) in sizes.iter().zip(&*layout.children) {
+    let mut split_dimensions = Vec::new();
+    for (&size, part) in sizes.iter().zip(&*layout.children) {
         let mut split_dimension = match size {
             Some(SplitSize::Percent(percent)) => Dimension::percent(percent as f64),
             Some(SplitSize::Fixed(size)) => Dimension::fixed(size),
@@ -1487,6 +1736,15 @@ fn split_space(
                 .as_usize()
                 .saturating_sub(total_fixed_size),
         );
+        if !layout.children_are_stacked {
+            split_dimension.set_size_bounds(part.min_size, part.max_size);
+        }
+        split_dimensions.push(split_dimension);
+    }
+    clamp_dimensions_to_size_bounds(&mut split_dimensions, split_dimension_space.as_usize());
+
+    let mut total_pane_size = 0;
+    for split_dimension in split_dimensions {
         total_pane_size += split_dimension.as_usize();
 
         let geom = match layout.children_split_direction {
@@ -1533,6 +1791,34 @@ fn split_space(
     Ok(pane_positions)
 }
 
+fn clamp_dimensions_to_size_bounds(dimensions: &mut Vec<Dimension>, available_space: usize) {
+    // brings flexible dimensions within their min_size/max_size, taking the difference from (or
+    // giving it to) their unbounded flexible siblings as long as they stay at least 1 wide
+    let mut space_to_redistribute: isize = 0;
+    for dimension in dimensions.iter_mut() {
+        if dimension.is_percent() && dimension.has_size_bounds() {
+            let current_size = dimension.as_usize();
+            let clamped_size = dimension
+                .clamp_to_size_bounds(current_size)
+                .min(available_space);
+            space_to_redistribute += current_size as isize - clamped_size as isize;
+            dimension.set_inner(clamped_size);
+        }
+    }
+    let mut flexible_siblings: Vec<&mut Dimension> = dimensions
+        .iter_mut()
+        .filter(|d| d.is_percent() && !d.has_size_bounds())
+        .collect();
+    let flexible_sibling_count = flexible_siblings.len();
+    for (i, sibling) in flexible_siblings.iter_mut().enumerate() {
+        let siblings_left = (flexible_sibling_count - i) as isize;
+        let share = space_to_redistribute / siblings_left;
+        let new_size = (sibling.as_usize() as isize + share).max(1);
+        space_to_redistribute -= new_size - sibling.as_usize() as isize;
+        sibling.set_inner(new_size as usize);
+    }
+}
+
 fn adjust_geoms_for_rounding_errors(
     total_pane_size: usize,
     split_geoms: &mut Vec<PaneGeom>,
@@ -0,0 +1,485 @@
+//! Checks a layout file without starting a session (`zellij setup --check-layout`).
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use kdl::{KdlDocument, KdlNode};
+use miette::{Diagnostic, LabeledSpan, NamedSource, Report, Severity, SourceCode};
+use thiserror::Error;
+
+use crate::input::config::{Config, ConfigError};
+use crate::input::layout::{
+    FloatingPaneLayout, Layout, LayoutConstraint, PercentOrFixed, Run, RunPluginOrAlias,
+    TiledPaneLayout,
+};
+use crate::pane_size::{PaneGeom, Size};
+
+/// The terminal size a layout is checked against if none is given.
+pub const DEFAULT_LAYOUT_CHECK_SIZE: Size = Size { rows: 24, cols: 80 };
+
+/// A problem found in a layout that did parse, pointing at the node it was found in (or at the
+/// first place its subject appears in the layout file).
+#[derive(Error, Debug)]
+#[error("{message}")]
+pub struct LayoutProblem {
+    message: String,
+    severity: Severity,
+    help_message: Option<String>,
+    src: NamedSource,
+    span: Option<(usize, usize)>,
+}
+
+impl Diagnostic for LayoutProblem {
+    fn severity(&self) -> Option<Severity> {
+        Some(self.severity)
+    }
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.src)
+    }
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.help_message
+            .as_ref()
+            .map(|help_message| Box::new(help_message) as Box<dyn Display>)
+    }
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.span.map(|(offset, len)| {
+            let label = LabeledSpan::new(Some(self.message.clone()), offset, len);
+            Box::new(std::iter::once(label)) as Box<dyn Iterator<Item = LabeledSpan>>
+        })
+    }
+}
+
+/// A node in the layout file, or in the swap layout file next to it
+#[derive(Clone, Copy)]
+struct NodeLocation {
+    in_swap_layouts: bool,
+    span: (usize, usize),
+}
+
+impl NodeLocation {
+    fn new(node: &KdlNode, in_swap_layouts: bool) -> Self {
+        NodeLocation {
+            in_swap_layouts,
+            span: (node.span().offset(), node.span().len()),
+        }
+    }
+}
+
+struct LayoutChecker<'a> {
+    raw_layout: &'a str,
+    path_to_raw_layout: &'a str,
+    raw_swap_layouts: Option<(&'a str, &'a str)>, // (path_to_swap_layouts, raw_swap_layouts)
+    // the layouts already parsed, these are only used to find where their nodes are
+    layout_document: Option<KdlDocument>,
+    swap_layouts_document: Option<KdlDocument>,
+    problems: Vec<Report>,
+}
+
+impl<'a> LayoutChecker<'a> {
+    fn report(&mut self, severity: Severity, message: String, help: Option<String>, subject: &str) {
+        // we don't keep track of where things were defined after parsing, so we point at the
+        // first place the subject is mentioned in the layout
+        let location = self
+            .raw_layout
+            .find(&format!("\"{}\"", subject))
+            .map(|offset| NodeLocation {
+                in_swap_layouts: false,
+                span: (offset, subject.len() + 2),
+            });
+        self.report_at(severity, message, help, location);
+    }
+    fn report_at(
+        &mut self,
+        severity: Severity,
+        message: String,
+        help: Option<String>,
+        location: Option<NodeLocation>,
+    ) {
+        let src = match (location, self.raw_swap_layouts) {
+            (Some(location), Some((path_to_swap_layouts, raw_swap_layouts)))
+                if location.in_swap_layouts =>
+            {
+                NamedSource::new(path_to_swap_layouts, raw_swap_layouts.to_owned())
+            },
+            _ => NamedSource::new(self.path_to_raw_layout, self.raw_layout.to_owned()),
+        };
+        self.problems.push(Report::new(LayoutProblem {
+            message,
+            severity,
+            help_message: help,
+            src,
+            span: location.map(|location| location.span),
+        }));
+    }
+    fn layout_node(&self) -> Option<&KdlNode> {
+        self.layout_document
+            .as_ref()
+            .and_then(|document| document.get("layout"))
+    }
+    /// The nodes of the tabs of the layout, in order: either `tab` nodes or nodes named after one
+    /// of the tab templates of the layout file
+    fn tab_nodes(&self) -> Vec<&KdlNode> {
+        let Some(layout_children) = self.layout_node().and_then(|node| node.children()) else {
+            return vec![];
+        };
+        let tab_template_names: Vec<&str> = layout_children
+            .nodes()
+            .iter()
+            .filter(|node| node.name().value() == "tab_template")
+            .filter_map(|node| node.get("name"))
+            .filter_map(|entry| entry.value().as_string())
+            .collect();
+        layout_children
+            .nodes()
+            .iter()
+            .filter(|node| {
+                let node_name = node.name().value();
+                node_name == "tab" || tab_template_names.contains(&node_name)
+            })
+            .collect()
+    }
+    /// The nodes of the swap layouts with this name (`swap_tiled_layout` or
+    /// `swap_floating_layout`) in order: those of the layout file first, then those of the swap
+    /// layout file
+    fn swap_layout_nodes(&self, node_name: &str) -> Vec<NodeLocation> {
+        let in_layout = self
+            .layout_node()
+            .and_then(|node| node.children())
+            .into_iter()
+            .flat_map(|children| children.nodes())
+            .map(|node| (node, false));
+        let in_swap_layouts = self
+            .swap_layouts_document
+            .iter()
+            .flat_map(|document| document.nodes())
+            .map(|node| (node, true));
+        in_layout
+            .chain(in_swap_layouts)
+            .filter(|(node, _in_swap_layouts)| node.name().value() == node_name)
+            .map(|(node, in_swap_layouts)| NodeLocation::new(node, in_swap_layouts))
+            .collect()
+    }
+    fn check_runs(&mut self, layout: &Layout) {
+        for run in layout.runs() {
+            match run {
+                Run::Command(run_command) => {
+                    let cwd = run_command.cwd.as_deref();
+                    if !command_exists(&run_command.command, cwd) {
+                        self.report(
+                            Severity::Warning,
+                            format!("Command not found: {}", run_command.command.display()),
+                            Some("Make sure it is installed and in the PATH".to_owned()),
+                            &run_command.command.display().to_string(),
+                        );
+                    }
+                    if let Some(cwd) = cwd {
+                        self.check_cwd(cwd);
+                    }
+                },
+                Run::Cwd(cwd) | Run::Shell(Some(cwd), _) => self.check_cwd(cwd),
+                Run::Plugin(RunPluginOrAlias::Alias(plugin_alias))
+                    if plugin_alias.run_plugin.is_none() =>
+                {
+                    self.report(
+                        Severity::Error,
+                        format!("No plugin alias named \"{}\"", plugin_alias.name),
+                        Some(
+                            "Plugin aliases are defined in the plugins block of the configuration"
+                                .to_owned(),
+                        ),
+                        &plugin_alias.name,
+                    );
+                },
+                _ => {},
+            }
+        }
+    }
+    fn check_cwd(&mut self, cwd: &Path) {
+        if !cwd.is_dir() {
+            self.report(
+                Severity::Warning,
+                format!("Directory not found: {}", cwd.display()),
+                None,
+                &cwd.display().to_string(),
+            );
+        }
+    }
+    fn check_fit(
+        &mut self,
+        pane_layout: &TiledPaneLayout,
+        max_panes: Option<usize>,
+        description: String,
+        location: Option<NodeLocation>,
+        space: &PaneGeom,
+    ) {
+        if let Err(e) = pane_layout.position_panes_in_space(space, max_panes) {
+            self.report_at(
+                Severity::Error,
+                format!(
+                    "{} does not fit in a {}x{} terminal: {}",
+                    description,
+                    space.cols.as_usize(),
+                    space.rows.as_usize(),
+                    e
+                ),
+                Some("Use --size to check against a different terminal size".to_owned()),
+                location,
+            );
+        }
+    }
+    fn check_floating_fit(
+        &mut self,
+        floating_panes: &[FloatingPaneLayout],
+        description: &str,
+        locations: &[NodeLocation],
+        fallback_location: Option<NodeLocation>,
+        space: &PaneGeom,
+    ) {
+        let (cols, rows) = (space.cols.as_usize(), space.rows.as_usize());
+        for (i, floating_pane) in floating_panes.iter().enumerate() {
+            // the size and position of floating panes that do not fit are adjusted when they are
+            // opened, so they still open - just not where they were placed
+            let fits = |position: Option<usize>, size: Option<usize>, space: usize| {
+                let position = position.unwrap_or(0);
+                position < space && position + size.unwrap_or(1) <= space
+            };
+            let fits_horizontally = fits(
+                floating_pane
+                    .x
+                    .as_ref()
+                    .map(|x| requested_position(x, cols)),
+                floating_pane
+                    .width
+                    .as_ref()
+                    .map(|width| requested_position(width, cols)),
+                cols,
+            );
+            let fits_vertically = fits(
+                floating_pane
+                    .y
+                    .as_ref()
+                    .map(|y| requested_position(y, rows)),
+                floating_pane
+                    .height
+                    .as_ref()
+                    .map(|height| requested_position(height, rows)),
+                rows,
+            );
+            if !fits_horizontally || !fits_vertically {
+                let pane_description = match &floating_pane.name {
+                    Some(name) => format!("Floating pane \"{}\"", name),
+                    None => format!("Floating pane #{}", i + 1),
+                };
+                // floating panes can also come from templates, in which case we point at the tab
+                let location = if locations.len() == floating_panes.len() {
+                    locations.get(i).copied()
+                } else {
+                    fallback_location
+                };
+                self.report_at(
+                    Severity::Warning,
+                    format!(
+                        "{} in {} does not fit in a {}x{} terminal",
+                        pane_description, description, cols, rows,
+                    ),
+                    Some("It will be moved and shrunk to fit when it is opened".to_owned()),
+                    location,
+                );
+            }
+        }
+    }
+    fn check_fits_in(&mut self, layout: &Layout, size: Size) {
+        let mut space = PaneGeom::default();
+        space.cols.set_inner(size.cols);
+        space.rows.set_inner(size.rows);
+        if layout.tabs.is_empty() {
+            let (tiled_panes, floating_panes) = layout.template.clone().unwrap_or_default();
+            let location = self
+                .layout_node()
+                .map(|node| NodeLocation::new(node, false));
+            let floating_pane_locations = self
+                .layout_node()
+                .map(floating_pane_nodes)
+                .unwrap_or_default();
+            self.check_fit(
+                &tiled_panes,
+                None,
+                "The layout".to_owned(),
+                location,
+                &space,
+            );
+            self.check_floating_fit(
+                &floating_panes,
+                "The layout",
+                &floating_pane_locations,
+                location,
+                &space,
+            );
+        }
+        let tab_nodes: Vec<(NodeLocation, Vec<NodeLocation>)> = self
+            .tab_nodes()
+            .into_iter()
+            .map(|node| (NodeLocation::new(node, false), floating_pane_nodes(node)))
+            .collect();
+        for (i, (tab_name, tiled_panes, floating_panes)) in layout.tabs.iter().enumerate() {
+            let description = match tab_name {
+                Some(tab_name) => format!("Tab \"{}\"", tab_name),
+                None => format!("Tab #{}", i + 1),
+            };
+            // tabs can also come from included layout files, which we do not point into
+            let (location, floating_pane_locations) = match tab_nodes.get(i) {
+                Some((location, floating_pane_locations)) => {
+                    (Some(*location), floating_pane_locations.as_slice())
+                },
+                None => (None, [].as_slice()),
+            };
+            self.check_fit(tiled_panes, None, description.clone(), location, &space);
+            self.check_floating_fit(
+                floating_panes,
+                &description,
+                floating_pane_locations,
+                location,
+                &space,
+            );
+        }
+        let swap_tiled_layout_nodes = self.swap_layout_nodes("swap_tiled_layout");
+        for (i, (swap_layouts, swap_layout_name)) in layout.swap_tiled_layouts.iter().enumerate() {
+            for (constraint, tiled_panes) in swap_layouts {
+                let max_panes = max_panes_of(constraint);
+                let description = swap_layout_description(swap_layout_name, constraint);
+                let location = swap_tiled_layout_nodes.get(i).copied();
+                self.check_fit(tiled_panes, max_panes, description, location, &space);
+            }
+        }
+        let swap_floating_layout_nodes = self.swap_layout_nodes("swap_floating_layout");
+        for (i, (swap_layouts, swap_layout_name)) in layout.swap_floating_layouts.iter().enumerate()
+        {
+            for (constraint, floating_panes) in swap_layouts {
+                let description = swap_layout_description(swap_layout_name, constraint);
+                let location = swap_floating_layout_nodes.get(i).copied();
+                self.check_floating_fit(floating_panes, &description, &[], location, &space);
+            }
+        }
+    }
+}
+
+/// The pane nodes in the `floating_panes` block of a tab (or layout) node
+fn floating_pane_nodes(node: &KdlNode) -> Vec<NodeLocation> {
+    node.children()
+        .and_then(|children| children.get("floating_panes"))
+        .and_then(|floating_panes| floating_panes.children())
+        .map(|floating_panes| {
+            floating_panes
+                .nodes()
+                .iter()
+                .map(|node| NodeLocation::new(node, false))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The position (or size) a floating pane asks for, before it is adjusted to fit
+fn requested_position(position: &PercentOrFixed, whole: usize) -> usize {
+    match position {
+        PercentOrFixed::Fixed(fixed) => *fixed,
+        PercentOrFixed::Percent(_) => position.to_position(whole),
+    }
+}
+
+fn max_panes_of(constraint: &LayoutConstraint) -> Option<usize> {
+    match constraint {
+        LayoutConstraint::MaxPanes(pane_count)
+        | LayoutConstraint::MinPanes(pane_count)
+        | LayoutConstraint::ExactPanes(pane_count) => Some(*pane_count),
+        LayoutConstraint::All(constraints) => constraints.iter().find_map(max_panes_of),
+        _ => None,
+    }
+}
+
+fn swap_layout_description(
+    swap_layout_name: &Option<String>,
+    constraint: &LayoutConstraint,
+) -> String {
+    let mut description = match swap_layout_name {
+        Some(name) => format!("Swap layout \"{}\"", name),
+        None => "Swap layout".to_owned(),
+    };
+    if constraint != &LayoutConstraint::NoConstraint {
+        description.push_str(&format!(" ({})", constraint));
+    }
+    description
+}
+
+/// Parses the layout the way a new session would (resolving its templates, swap layouts,
+/// includes and plugin aliases) and simulates applying it to a terminal of the given size.
+/// Returns all the errors and warnings found, an empty list means the layout is fine.
+pub fn check_layout(
+    raw_layout: &str,
+    path_to_raw_layout: &str,
+    raw_swap_layouts: Option<(&str, &str)>,
+    layout_dir: Option<PathBuf>,
+    layout_args: BTreeMap<String, String>,
+    config: &Config,
+    size: Size,
+) -> Vec<Report> {
+    let parsed = Layout::from_kdl_with_layout_args(
+        raw_layout,
+        path_to_raw_layout.to_owned(),
+        raw_swap_layouts,
+        None,
+        layout_args,
+        layout_dir,
+    )
+    .and_then(|layout| {
+        // layouts can also contain configuration, which should be valid as well
+        Config::from_kdl(raw_layout, Some(config.clone())).map(|config| (layout, config))
+    });
+    let (mut layout, config) = match parsed {
+        Ok(parsed) => parsed,
+        Err(ConfigError::KdlError(error)) => return vec![error.into()],
+        Err(e) => return vec![miette::miette!("{}", e)],
+    };
+    layout.populate_plugin_aliases_in_layout(&config.plugins);
+    let mut checker = LayoutChecker {
+        raw_layout,
+        path_to_raw_layout,
+        raw_swap_layouts,
+        layout_document: raw_layout.parse().ok(),
+        swap_layouts_document: raw_swap_layouts.and_then(|(_, raw)| raw.parse().ok()),
+        problems: vec![],
+    };
+    checker.check_runs(&layout);
+    checker.check_fits_in(&layout, size);
+    checker.problems
+}
+
+fn command_exists(command: &Path, cwd: Option<&Path>) -> bool {
+    if command.components().count() > 1 {
+        // a path rather than a name to look up in the PATH
+        return match cwd {
+            Some(cwd) => cwd.join(command).is_file(),
+            None => command.is_file(),
+        };
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(command).is_file()))
+        .unwrap_or(false)
+}
+
+/// Parses a COLUMNSxROWS terminal size, as given to `--size`
+pub fn parse_layout_check_size(size: &str) -> Result<Size, String> {
+    let error = || {
+        format!(
+            "Invalid size \"{}\", expected COLUMNSxROWS (eg. 80x24)",
+            size
+        )
+    };
+    let (cols, rows) = size.split_once('x').ok_or_else(error)?;
+    let cols = cols.parse().map_err(|_| error())?;
+    let rows = rows.parse().map_err(|_| error())?;
+    Ok(Size { rows, cols })
+}
+
+#[cfg(test)]
+#[path = "./unit/layout_check_test.rs"]
+mod layout_check_test;
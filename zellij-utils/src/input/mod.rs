@@ -1,11 +1,16 @@
 pub mod actions;
 pub mod command;
 pub mod config;
+pub mod hooks;
 pub mod keybinds;
 pub mod layout;
+pub mod layout_check;
 pub mod options;
 pub mod permission;
 pub mod plugins;
+#[cfg(not(target_family = "wasm"))]
+pub mod project_layout;
+pub mod scratchpads;
 pub mod theme;
 
 // Can't use this in wasm due to dependency on the `termwiz` crate.
@@ -40,6 +45,7 @@ mod not_wasm {
             style: attributes.style,
             capabilities,
             session_name,
+            is_read_only: false,
         }
     }
 
@@ -32,6 +32,43 @@ impl FromStr for OnForceClose {
     }
 }
 
+/// How the size of a session is chosen when several clients of different sizes are attached to it
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize, ArgEnum)]
+pub enum ClientSizePolicy {
+    /// The smallest width and height of all clients
+    #[serde(alias = "smallest")]
+    Smallest,
+    /// The largest width and height of all clients
+    #[serde(alias = "largest")]
+    Largest,
+    /// The size of the client that was most recently active
+    #[serde(alias = "latest")]
+    Latest,
+    /// The size of the first client, until it is changed with the `ResizeToClient` action
+    #[serde(alias = "manual")]
+    Manual,
+}
+
+impl Default for ClientSizePolicy {
+    fn default() -> Self {
+        Self::Smallest
+    }
+}
+
+impl FromStr for ClientSizePolicy {
+    type Err = Box<dyn std::error::Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "smallest" => Ok(Self::Smallest),
+            "largest" => Ok(Self::Largest),
+            "latest" => Ok(Self::Latest),
+            "manual" => Ok(Self::Manual),
+            e => Err(e.to_string().into()),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Deserialize, Serialize, Args)]
 /// Options that can be set either through the config file,
 /// or cli flags - cli flags should take precedence over the config file
@@ -151,6 +188,80 @@ pub struct Options {
     /// The interval at which to serialize sessions for resurrection (in seconds)
     #[clap(long, value_parser)]
     pub serialization_interval: Option<u64>,
+
+    /// How many snapshots of each session to keep around so that it can be resurrected as it was
+    /// at an earlier point, 0 disables them, default is 20
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub session_snapshot_count: Option<usize>,
+
+    /// How long to keep session snapshots around for (in seconds), default is to keep them until
+    /// they are pushed out by newer ones
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub session_snapshot_max_age: Option<u64>,
+
+    /// Commands (as globs, eg. "npm run *") that start right away when their session is
+    /// resurrected rather than wait for Enter to be pressed in their pane
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub resurrect_run_commands: Option<Vec<String>>,
+
+    /// Commands (as globs) that stay suspended when their session is resurrected, even if they
+    /// match `resurrect_run_commands`
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub resurrect_suspend_commands: Option<Vec<String>>,
+
+    /// Whether to start new sessions with the layout of the project they are started in
+    /// (`.zellij/layout.kdl` or `zellij.kdl` in the current folder or one of its parents)
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub project_layouts: Option<bool>,
+
+    /// How to size the session when clients of different sizes are attached to it (smallest,
+    /// largest, latest or manual), default is smallest
+    #[clap(long, arg_enum, hide_possible_values = true, value_parser)]
+    #[serde(default)]
+    pub client_size_policy: Option<ClientSizePolicy>,
+
+    /// A name for this client, shown to the other clients attached to the same session
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub client_name: Option<String>,
+
+    /// The argon2 hash of the passphrase that unlocks a locked session, if not set the one stored
+    /// in the data dir by `zellij setup --set-lock-passphrase` is used
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub lock_session_passphrase_hash: Option<String>,
+
+    /// Lock the session after it received no input for this many seconds
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub auto_lock_session_after: Option<u64>,
+
+    /// Detach clients that sent no input for this many seconds
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub detach_idle_clients_after: Option<u64>,
+
+    /// Kill the session once it has been detached (no clients attached) for this many seconds
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub kill_session_when_detached_after: Option<u64>,
+
+    /// Kill the session once the commands in all of its terminal panes exited,
+    /// default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub kill_session_when_all_panes_exited: Option<bool>,
+
+    /// Mark the session as one to keep, excluding it from the idle session policies above (can
+    /// be toggled with the `ToggleKeepSession` action), default is false
+    #[clap(long, value_parser)]
+    #[serde(default)]
+    pub keep_session: Option<bool>,
 }
 
 #[derive(ArgEnum, Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
@@ -223,6 +334,35 @@ impl Options {
             .or(self.scrollback_lines_to_serialize);
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let session_snapshot_count = other.session_snapshot_count.or(self.session_snapshot_count);
+        let session_snapshot_max_age = other
+            .session_snapshot_max_age
+            .or(self.session_snapshot_max_age);
+        let resurrect_run_commands = other
+            .resurrect_run_commands
+            .or_else(|| self.resurrect_run_commands.clone());
+        let resurrect_suspend_commands = other
+            .resurrect_suspend_commands
+            .or_else(|| self.resurrect_suspend_commands.clone());
+        let project_layouts = other.project_layouts.or(self.project_layouts);
+        let client_size_policy = other.client_size_policy.or(self.client_size_policy);
+        let client_name = other.client_name.or_else(|| self.client_name.clone());
+        let lock_session_passphrase_hash = other
+            .lock_session_passphrase_hash
+            .or_else(|| self.lock_session_passphrase_hash.clone());
+        let auto_lock_session_after = other
+            .auto_lock_session_after
+            .or(self.auto_lock_session_after);
+        let detach_idle_clients_after = other
+            .detach_idle_clients_after
+            .or(self.detach_idle_clients_after);
+        let kill_session_when_detached_after = other
+            .kill_session_when_detached_after
+            .or(self.kill_session_when_detached_after);
+        let kill_session_when_all_panes_exited = other
+            .kill_session_when_all_panes_exited
+            .or(self.kill_session_when_all_panes_exited);
+        let keep_session = other.keep_session.or(self.keep_session);
 
         Options {
             simplified_ui,
@@ -250,6 +390,19 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            session_snapshot_count,
+            session_snapshot_max_age,
+            resurrect_run_commands,
+            resurrect_suspend_commands,
+            project_layouts,
+            client_size_policy,
+            client_name,
+            lock_session_passphrase_hash,
+            auto_lock_session_after,
+            detach_idle_clients_after,
+            kill_session_when_detached_after,
+            kill_session_when_all_panes_exited,
+            keep_session,
         }
     }
 
@@ -302,6 +455,36 @@ impl Options {
             .or_else(|| self.scrollback_lines_to_serialize.clone());
         let styled_underlines = other.styled_underlines.or(self.styled_underlines);
         let serialization_interval = other.serialization_interval.or(self.serialization_interval);
+        let session_snapshot_count = other.session_snapshot_count.or(self.session_snapshot_count);
+        let session_snapshot_max_age = other
+            .session_snapshot_max_age
+            .or(self.session_snapshot_max_age);
+        let resurrect_run_commands = other
+            .resurrect_run_commands
+            .or_else(|| self.resurrect_run_commands.clone());
+        let resurrect_suspend_commands = other
+            .resurrect_suspend_commands
+            .or_else(|| self.resurrect_suspend_commands.clone());
+        let project_layouts = other.project_layouts.or(self.project_layouts);
+        let client_size_policy = other.client_size_policy.or(self.client_size_policy);
+        let client_name = other.client_name.or_else(|| self.client_name.clone());
+        let lock_session_passphrase_hash = other
+            .lock_session_passphrase_hash
+            .or_else(|| self.lock_session_passphrase_hash.clone());
+        let auto_lock_session_after = other
+            .auto_lock_session_after
+            .or(self.auto_lock_session_after);
+        let detach_idle_clients_after = other
+            .detach_idle_clients_after
+            .or(self.detach_idle_clients_after);
+        let kill_session_when_detached_after = other
+            .kill_session_when_detached_after
+            .or(self.kill_session_when_detached_after);
+        let kill_session_when_all_panes_exited = merge_bool(
+            other.kill_session_when_all_panes_exited,
+            self.kill_session_when_all_panes_exited,
+        );
+        let keep_session = merge_bool(other.keep_session, self.keep_session);
 
         Options {
             simplified_ui,
@@ -329,6 +512,19 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            session_snapshot_count,
+            session_snapshot_max_age,
+            resurrect_run_commands,
+            resurrect_suspend_commands,
+            project_layouts,
+            client_size_policy,
+            client_name,
+            lock_session_passphrase_hash,
+            auto_lock_session_after,
+            detach_idle_clients_after,
+            kill_session_when_detached_after,
+            kill_session_when_all_panes_exited,
+            keep_session,
         }
     }
 
@@ -393,6 +589,19 @@ impl From<CliOptions> for Options {
             scrollback_lines_to_serialize: opts.scrollback_lines_to_serialize,
             styled_underlines: opts.styled_underlines,
             serialization_interval: opts.serialization_interval,
+            session_snapshot_count: opts.session_snapshot_count,
+            session_snapshot_max_age: opts.session_snapshot_max_age,
+            resurrect_run_commands: opts.resurrect_run_commands,
+            resurrect_suspend_commands: opts.resurrect_suspend_commands,
+            project_layouts: opts.project_layouts,
+            client_size_policy: opts.client_size_policy,
+            client_name: opts.client_name,
+            lock_session_passphrase_hash: opts.lock_session_passphrase_hash,
+            auto_lock_session_after: opts.auto_lock_session_after,
+            detach_idle_clients_after: opts.detach_idle_clients_after,
+            kill_session_when_detached_after: opts.kill_session_when_detached_after,
+            kill_session_when_all_panes_exited: opts.kill_session_when_all_panes_exited,
+            keep_session: opts.keep_session,
             ..Default::default()
         }
     }
@@ -0,0 +1,101 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    consts::ZELLIJ_TRUSTED_LAYOUTS_CACHE,
+    input::{config::ConfigError, layout::Layout},
+};
+
+/// The places (relative to a project folder) in which a project can ship its own layout
+pub const PROJECT_LAYOUT_FILE_NAMES: &[&str] = &[".zellij/layout.kdl", "zellij.kdl"];
+
+/// Looks for a project layout in this folder or in the nearest of its parents that has one
+pub fn find_project_layout(start_dir: &Path) -> Option<PathBuf> {
+    start_dir.ancestors().find_map(|dir| {
+        PROJECT_LAYOUT_FILE_NAMES
+            .iter()
+            .map(|file_name| dir.join(file_name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// What we trust when trusting a layout: its own contents and those of the files it includes and
+/// of its swap layout file, since any of them can add commands to the session
+pub fn layout_trust_contents(
+    layout_path: &Path,
+    layout_dir: Option<PathBuf>,
+) -> Result<String, ConfigError> {
+    let (file_name, raw_layout, swap_layout_and_path) = Layout::stringified_from_path(layout_path)?;
+    let mut contents = raw_layout.clone();
+    for (included_file_name, raw_included_layout) in
+        Layout::included_layout_files(&raw_layout, file_name, layout_dir)?
+    {
+        contents.push_str(&format!(
+            "\n// include: {}\n{}",
+            included_file_name, raw_included_layout
+        ));
+    }
+    if let Some((swap_layout_file_name, raw_swap_layout)) = swap_layout_and_path {
+        contents.push_str(&format!(
+            "\n// swap layouts: {}\n{}",
+            swap_layout_file_name, raw_swap_layout
+        ));
+    }
+    Ok(contents)
+}
+
+pub type TrustedLayouts = BTreeMap<PathBuf, String>; // layout path -> its contents when trusted
+
+/// The project layouts the user agreed to start sessions with, since they can run arbitrary
+/// commands. We keep the trusted contents (see `layout_trust_contents`) rather than just the
+/// path, so that a layout that changed since it was trusted (eg. after a `git pull`) has to be
+/// trusted again.
+#[derive(Default, Debug)]
+pub struct LayoutTrustCache {
+    path: PathBuf,
+    trusted: TrustedLayouts,
+}
+
+impl LayoutTrustCache {
+    pub fn trust(&mut self, layout_path: PathBuf, contents: String) {
+        self.trusted.insert(layout_path, contents);
+    }
+
+    pub fn is_trusted(&self, layout_path: &Path, contents: &str) -> bool {
+        self.trusted.get(layout_path).map(|c| c.as_str()) == Some(contents)
+    }
+
+    pub fn from_path_or_default(cache_path: Option<PathBuf>) -> Self {
+        let cache_path = cache_path.unwrap_or(ZELLIJ_TRUSTED_LAYOUTS_CACHE.to_path_buf());
+
+        let trusted = match fs::read_to_string(cache_path.clone()) {
+            Ok(raw_string) => LayoutTrustCache::from_string(raw_string).unwrap_or_default(),
+            Err(e) => {
+                log::error!("Failed to read trusted layouts file: {}", e);
+                TrustedLayouts::default()
+            },
+        };
+
+        LayoutTrustCache {
+            path: cache_path,
+            trusted,
+        }
+    }
+
+    pub fn write_to_file(&self) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut f = File::create(&self.path)?;
+        write!(f, "{}", LayoutTrustCache::to_string(&self.trusted))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/project_layout_test.rs"]
+mod project_layout_test;
@@ -0,0 +1,32 @@
+//! Named scratchpad panes configuration
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::command::RunCommand;
+use crate::data::FloatingPaneCoordinates;
+
+/// A floating command pane that is created the first time it is toggled and then shown or hidden
+/// by name
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Scratchpad {
+    pub command: RunCommand,
+    pub coordinates: Option<FloatingPaneCoordinates>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Scratchpads {
+    pub scratchpads: BTreeMap<String, Scratchpad>,
+}
+
+impl Scratchpads {
+    pub fn merge(&mut self, other: Self) {
+        self.scratchpads.extend(other.scratchpads);
+    }
+    pub fn from_data(scratchpads: BTreeMap<String, Scratchpad>) -> Self {
+        Scratchpads { scratchpads }
+    }
+    pub fn get(&self, name: &str) -> Option<&Scratchpad> {
+        self.scratchpads.get(name)
+    }
+}
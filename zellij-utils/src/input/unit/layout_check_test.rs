@@ -0,0 +1,206 @@
+use super::*;
+use miette::Severity;
+
+fn check(raw_layout: &str, size: Size) -> Vec<Report> {
+    check_layout(
+        raw_layout,
+        "layout_file_name",
+        None,
+        None,
+        BTreeMap::new(),
+        &Config::default(),
+        size,
+    )
+}
+
+#[test]
+fn valid_layout_has_no_problems() {
+    let kdl_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane command="sh"
+                pane cwd="/"
+            }
+            pane size=1 borderless=true {
+                plugin location="zellij:status-bar"
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    assert!(problems.is_empty(), "no problems found: {:?}", problems);
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    let kdl_layout = r#"
+        layout {
+            pane command="sh" restart="sometimes"
+        }
+    "#;
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    assert_eq!(problems.len(), 1, "one problem found");
+}
+
+#[test]
+fn missing_commands_and_directories_are_warnings() {
+    let kdl_layout = r#"
+        layout {
+            pane command="this-command-does-not-exist-anywhere"
+            pane cwd="/this/directory/does/not/exist"
+        }
+    "#;
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    let messages: Vec<String> = problems.iter().map(|p| p.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Command not found: this-command-does-not-exist-anywhere",
+            "Directory not found: /this/directory/does/not/exist",
+        ]
+    );
+    assert!(problems
+        .iter()
+        .all(|p| p.severity() == Some(Severity::Warning)));
+}
+
+#[test]
+fn unknown_plugin_alias_is_an_error() {
+    let kdl_layout = r#"
+        layout {
+            pane {
+                plugin location="no-such-alias"
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    assert_eq!(problems.len(), 1, "one problem found");
+    assert_eq!(
+        problems[0].to_string(),
+        "No plugin alias named \"no-such-alias\""
+    );
+    assert_eq!(problems[0].severity(), Some(Severity::Error));
+}
+
+#[test]
+fn layout_that_does_not_fit_is_an_error() {
+    let kdl_layout = r#"
+        layout {
+            tab name="crowded" split_direction="vertical" {
+                pane
+                pane
+                pane
+                pane
+                pane
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, Size { rows: 24, cols: 3 });
+    assert_eq!(problems.len(), 1, "one problem found");
+    assert!(problems[0]
+        .to_string()
+        .starts_with("Tab \"crowded\" does not fit in a 3x24 terminal"));
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    assert!(problems.is_empty(), "fits in a bigger terminal");
+}
+
+#[test]
+fn layout_that_does_not_fit_points_at_its_tab() {
+    let kdl_layout = r#"
+        layout {
+            tab name="editor" {
+                pane name="crowded"
+            }
+            tab split_direction="vertical" {
+                pane
+                pane
+                pane
+                pane
+                pane
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, Size { rows: 24, cols: 3 });
+    assert_eq!(problems.len(), 1, "one problem found");
+    assert!(problems[0]
+        .to_string()
+        .starts_with("Tab #2 does not fit in a 3x24 terminal"));
+    let label = problems[0].labels().unwrap().next().unwrap();
+    assert!(
+        kdl_layout[label.offset()..].starts_with("tab split_direction=\"vertical\""),
+        "problem points at the tab that does not fit"
+    );
+}
+
+#[test]
+fn floating_pane_that_does_not_fit_is_a_warning() {
+    let kdl_layout = r#"
+        layout {
+            tab name="floating" {
+                pane
+                floating_panes {
+                    pane x=10 y=2 width=40 height=10
+                    pane name="wide" x=60 y=2 width=40 height=10
+                }
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, DEFAULT_LAYOUT_CHECK_SIZE);
+    assert_eq!(problems.len(), 1, "one problem found");
+    assert_eq!(
+        problems[0].to_string(),
+        "Floating pane \"wide\" in Tab \"floating\" does not fit in a 80x24 terminal"
+    );
+    assert_eq!(problems[0].severity(), Some(Severity::Warning));
+    let label = problems[0].labels().unwrap().next().unwrap();
+    assert!(
+        kdl_layout[label.offset()..].starts_with("pane name=\"wide\""),
+        "problem points at the floating pane that does not fit"
+    );
+    let problems = check(
+        kdl_layout,
+        Size {
+            rows: 24,
+            cols: 120,
+        },
+    );
+    assert!(problems.is_empty(), "fits in a wider terminal");
+}
+
+#[test]
+fn swap_layouts_are_checked_with_their_constraints() {
+    let kdl_layout = r#"
+        layout {
+            pane
+            swap_tiled_layout name="columns" {
+                tab max_panes=6 {
+                    pane split_direction="vertical" {
+                        pane
+                        pane
+                        pane
+                        pane
+                        pane
+                        pane
+                    }
+                }
+            }
+        }
+    "#;
+    let problems = check(kdl_layout, Size { rows: 24, cols: 4 });
+    assert_eq!(problems.len(), 1, "one problem found");
+    assert!(problems[0]
+        .to_string()
+        .starts_with("Swap layout \"columns\" (max_panes=6) does not fit"));
+}
+
+#[test]
+fn parse_size() {
+    assert_eq!(
+        parse_layout_check_size("120x40"),
+        Ok(Size {
+            rows: 40,
+            cols: 120
+        })
+    );
+    assert!(parse_layout_check_size("120").is_err());
+    assert!(parse_layout_check_size("wide x tall").is_err());
+}
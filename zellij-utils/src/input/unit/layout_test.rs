@@ -1,3 +1,4 @@
+use super::super::command::ResurrectionPolicy;
 use super::super::layout::*;
 use insta::assert_snapshot;
 
@@ -148,6 +149,42 @@ fn layout_with_floating_panes() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_sticky_floating_panes() {
+    let kdl_layout = r#"
+        layout {
+            floating_panes {
+                pane sticky=true
+                pane {
+                    sticky true
+                    x 10
+                }
+                pane
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout::default(),
+            vec![
+                FloatingPaneLayout {
+                    is_sticky: true,
+                    ..Default::default()
+                },
+                FloatingPaneLayout {
+                    x: Some(PercentOrFixed::Fixed(10)),
+                    is_sticky: true,
+                    ..Default::default()
+                },
+                FloatingPaneLayout::default(),
+            ],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
 #[test]
 fn layout_with_mixed_panes_and_floating_panes() {
     let kdl_layout = r#"
@@ -409,6 +446,91 @@ fn layout_with_panes_in_different_mixed_split_sizes() {
     assert_eq!(layout, expected_layout);
 }
 
+#[test]
+fn layout_with_pane_size_bounds() {
+    let kdl_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane size="20%" min_size=30
+                pane
+            }
+            pane max_size=15
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let expected_layout = Layout {
+        template: Some((
+            TiledPaneLayout {
+                children: vec![
+                    TiledPaneLayout {
+                        children_split_direction: SplitDirection::Vertical,
+                        children: vec![
+                            TiledPaneLayout {
+                                split_size: Some(SplitSize::Percent(20)),
+                                min_size: Some(30),
+                                ..Default::default()
+                            },
+                            TiledPaneLayout::default(),
+                        ],
+                        ..Default::default()
+                    },
+                    TiledPaneLayout {
+                        max_size: Some(15),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            vec![],
+        )),
+        ..Default::default()
+    };
+    assert_eq!(layout, expected_layout);
+}
+
+#[test]
+fn error_on_pane_min_size_greater_than_max_size() {
+    let kdl_layout = r#"
+        layout {
+            pane min_size=20 max_size=10
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(
+        layout.is_err(),
+        "error provided for min_size greater than max_size"
+    );
+}
+
+#[test]
+fn pane_size_bounds_are_honoured_when_positioning_panes() {
+    let kdl_layout = r#"
+        layout {
+            pane split_direction="vertical" {
+                pane size="20%" min_size=30
+                pane
+            }
+            pane max_size=15
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let (tiled_layout, _floating_layouts) = layout.template.unwrap();
+    let space = PaneGeom::from(&crate::pane_size::Size {
+        rows: 50,
+        cols: 100,
+    });
+    let positions = tiled_layout.position_panes_in_space(&space, None).unwrap();
+    let sizes: Vec<(usize, usize, usize, usize)> = positions
+        .iter()
+        .map(|(_, geom)| (geom.x, geom.y, geom.cols.as_usize(), geom.rows.as_usize()))
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![(0, 0, 30, 35), (30, 0, 70, 35), (0, 35, 100, 15)],
+        "sidebar grown to its min_size and bottom pane shrunk to its max_size"
+    );
+}
+
 #[test]
 fn layout_with_command_panes() {
     let kdl_layout = r#"
@@ -524,6 +646,87 @@ fn layout_with_command_panes_and_start_suspended() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn resurrected_commands_start_according_to_the_resurrection_policy() {
+    let kdl_layout = r#"
+        layout {
+            tab {
+                pane command="npm" start_suspended=true {
+                    args "run" "dev"
+                }
+                pane command="/usr/bin/cargo" start_suspended=true {
+                    args "build" "--release"
+                }
+                pane command="htop" start_suspended=true
+                pane command="make" start_suspended=true rerun_on_resurrect=true
+                floating_panes {
+                    pane command="npm" start_suspended=true rerun_on_resurrect=false {
+                        args "run" "dev"
+                    }
+                }
+            }
+        }
+    "#;
+    let mut layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    layout.start_on_resurrection(&ResurrectionPolicy::new(
+        vec!["npm run *".to_owned(), "cargo *".to_owned()],
+        vec!["* --release".to_owned()],
+    ));
+    let (_tab_name, tiled_panes, floating_panes) = layout.tabs().remove(0);
+    let held_commands = |runs: Vec<Option<Run>>| -> Vec<bool> {
+        runs.into_iter()
+            .filter_map(|run| match run {
+                Some(Run::Command(run_command)) => Some(run_command.hold_on_start),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(
+        held_commands(tiled_panes.extract_run_instructions()),
+        vec![false, true, true, false],
+        "matching run pattern, matching suspend pattern, no match, layout override"
+    );
+    assert_eq!(
+        held_commands(floating_panes.into_iter().map(|f| f.run).collect()),
+        vec![true],
+        "layout override of a matching run pattern"
+    );
+}
+
+#[test]
+fn layout_with_command_panes_and_restart_policy() {
+    let kdl_layout = r#"
+        layout {
+            pane command="npm" restart="on-failure" max_restarts=5
+            pane command="htop" {
+                restart "always"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
+#[test]
+fn layout_with_command_panes_and_dependencies() {
+    let kdl_layout = r#"
+        layout {
+            pane name="db" command="postgres"
+            pane name="migrations" command="npm" {
+                args "run" "migrate"
+                depends_on "db" output="ready to accept connections" timeout=30
+            }
+            pane command="npm" {
+                args "start"
+                depends_on "migrations"
+                depends_on "db" timeout=10
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
 #[test]
 fn layout_with_plugin_panes() {
     let kdl_layout = r#"
@@ -1427,6 +1630,24 @@ fn close_on_exit_added_to_close_on_exit_in_template() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn restart_policy_added_to_command_in_template() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="server" {
+                command "npm"
+                restart "on-failure"
+            }
+            server
+            server {
+                max_restarts 3
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
 #[test]
 fn cwd_override_cwd_in_template() {
     let kdl_layout = r#"
@@ -1515,6 +1736,123 @@ fn error_on_bare_close_on_exit_without_command() {
     assert!(layout.is_err(), "error provided");
 }
 
+#[test]
+fn error_on_bare_restart_without_command() {
+    let kdl_layout = r#"
+        layout {
+            pane {
+                restart "always"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_unknown_restart_policy() {
+    let kdl_layout = r#"
+        layout {
+            pane command="htop" restart="sometimes"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_dependency_on_unknown_pane() {
+    let kdl_layout = r#"
+        layout {
+            pane name="db" command="postgres"
+            pane command="npm" {
+                depends_on "database"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_panes_depending_on_each_other() {
+    let kdl_layout = r#"
+        layout {
+            pane name="server" command="npm" {
+                depends_on "worker" output="connected"
+            }
+            pane name="worker" command="npm" {
+                depends_on "server" output="listening"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    let error = layout.expect_err("error provided");
+    assert!(
+        format!("{:?}", error).contains("server -> worker -> server"),
+        "error names the panes waiting for each other: {:?}",
+        error
+    );
+}
+
+#[test]
+fn error_on_pane_depending_on_itself() {
+    let kdl_layout = r#"
+        layout {
+            pane name="server" command="npm" {
+                depends_on "server" output="listening"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_waiting_for_a_pane_without_a_command_to_exit() {
+    let kdl_layout = r#"
+        layout {
+            pane name="shell"
+            pane command="npm" {
+                depends_on "shell"
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn dependency_on_a_pane_without_a_command_can_wait_for_its_output_or_a_timeout() {
+    let kdl_layout = r#"
+        layout {
+            pane name="shell"
+            pane command="npm" {
+                depends_on "shell" output="\\$ "
+            }
+            pane command="npm" {
+                depends_on "shell" timeout=5
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_ok(), "no error: {:?}", layout.err());
+}
+
+#[test]
+fn error_on_dependency_with_invalid_output_pattern() {
+    let kdl_layout = r#"
+        layout {
+            pane name="db" command="postgres"
+            pane command="npm" {
+                depends_on "db" output="ready ("
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
 #[test]
 fn error_on_bare_args_in_template_without_command() {
     let kdl_layout = r#"
@@ -1832,6 +2170,288 @@ fn tab_cwd_prepended_to_panes_with_cwd() {
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn tab_and_pane_env_inherited_by_command_panes() {
+    let kdl_layout = r#"
+        layout {
+            tab {
+                env {
+                    RUST_LOG "info"
+                    PORT 8080
+                }
+                pane command="tail"
+                pane split_direction="vertical" {
+                    env {
+                        RUST_LOG "debug"
+                    }
+                    pane command="htop" {
+                        env {
+                            PORT 9090
+                        }
+                    }
+                    pane
+                }
+                floating_panes {
+                    pane command="top"
+                }
+            }
+            // tail should have RUST_LOG=info and PORT=8080, htop RUST_LOG=debug and PORT=9090,
+            // top RUST_LOG=info and PORT=8080 and the shell pane RUST_LOG=debug and PORT=8080
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
+#[test]
+fn env_in_pane_template_extended_by_its_consumer() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="server" command="cargo" {
+                args "run"
+                env {
+                    RUST_LOG "info"
+                    PORT 8080
+                }
+            }
+            server {
+                env {
+                    PORT 9090
+                }
+            }
+            // should have RUST_LOG=info and PORT=9090
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
+#[test]
+fn env_without_command_or_child_panes_is_given_to_the_default_shell() {
+    let kdl_layout = r#"
+        layout {
+            pane cwd="/tmp" {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let mut env = BTreeMap::new();
+    env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+    let (tiled_panes, _floating_panes) = layout.new_tab();
+    assert_eq!(
+        tiled_panes.children[0].run,
+        Some(Run::Shell(Some(PathBuf::from("/tmp")), env)),
+        "env given to the default shell"
+    );
+}
+
+#[test]
+fn env_of_pane_template_consumer_with_children_is_given_to_the_children() {
+    let kdl_layout = r#"
+        layout {
+            pane_template name="two-panes" {
+                pane
+                pane
+            }
+            two-panes {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let mut env = BTreeMap::new();
+    env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+    let (tiled_panes, _floating_panes) = layout.new_tab();
+    let pane_template = &tiled_panes.children[0];
+    assert_eq!(
+        pane_template.run, None,
+        "no shell for the pane with children"
+    );
+    for child in &pane_template.children {
+        assert_eq!(
+            child.run,
+            Some(Run::Shell(None, env.clone())),
+            "env given to the default shell of the children"
+        );
+    }
+}
+
+#[test]
+fn error_on_env_in_edit_or_plugin_panes() {
+    let edit_pane = r#"
+        layout {
+            pane edit="file.rs" {
+                env {
+                    RUST_LOG "debug"
+                }
+            }
+        }
+    "#;
+    let plugin_pane = r#"
+        layout {
+            pane {
+                env {
+                    RUST_LOG "debug"
+                }
+                plugin location="zellij:tab-bar"
+            }
+        }
+    "#;
+    for kdl_layout in [edit_pane, plugin_pane] {
+        let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+        assert!(layout.is_err(), "error provided");
+    }
+}
+
+#[test]
+fn layout_args_replaced_with_given_values_or_defaults() {
+    let kdl_layout = r#"
+        layout {
+            args {
+                project "/tmp"
+                port 8080
+                server_name
+            }
+            tab name="{{server_name}}" cwd="{{project}}" {
+                pane command="cargo" {
+                    args "run" "--" "--port" "{{port}}"
+                }
+                pane cwd="./{{server_name}}"
+            }
+            // the tab should be called api and have the /src/api cwd, cargo should get the 8080 port
+            // and the second pane the /src/api/api cwd
+        }
+    "#;
+    let layout_args = vec![
+        ("project".to_owned(), "/src/api".to_owned()),
+        ("server_name".to_owned(), "api".to_owned()),
+    ]
+    .into_iter()
+    .collect();
+    let layout = Layout::from_kdl_with_layout_args(
+        kdl_layout,
+        "layout_file_name".into(),
+        None,
+        None,
+        layout_args,
+        None,
+    )
+    .unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
+#[test]
+fn error_on_undeclared_layout_arg() {
+    let kdl_layout = r#"
+        layout {
+            args {
+                project "/tmp"
+            }
+            pane cwd="{{project}}"
+        }
+    "#;
+    let layout_args = vec![("port".to_owned(), "8080".to_owned())]
+        .into_iter()
+        .collect();
+    let layout = Layout::from_kdl_with_layout_args(
+        kdl_layout,
+        "layout_file_name".into(),
+        None,
+        None,
+        layout_args,
+        None,
+    );
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_on_missing_layout_arg_without_default() {
+    let kdl_layout = r#"
+        layout {
+            args {
+                project
+            }
+            pane cwd="{{project}}"
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn layout_with_included_templates() {
+    let layout_path = PathBuf::from(format!(
+        "{}/src/input/unit/fixtures/layouts/layout-with-include.kdl",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let layout = Layout::from_path_or_default_without_config(Some(&layout_path), None).unwrap();
+    assert_snapshot!(format!("{:#?}", layout));
+}
+
+#[test]
+fn error_on_cyclic_include() {
+    let layout_path = PathBuf::from(format!(
+        "{}/src/input/unit/fixtures/layouts/cyclic-include-a.kdl",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let layout = Layout::from_path_or_default_without_config(Some(&layout_path), None);
+    assert!(layout.is_err(), "error provided");
+}
+
+#[test]
+fn error_in_included_layout_points_to_the_included_file() {
+    let layout_path = PathBuf::from(format!(
+        "{}/src/input/unit/fixtures/layouts/layout-with-invalid-include.kdl",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let layout = Layout::from_path_or_default_without_config(Some(&layout_path), None);
+    match layout {
+        Err(ConfigError::KdlError(kdl_error)) => {
+            let src_name = kdl_error.src.map(|src| src.name().to_owned());
+            assert!(
+                src_name
+                    .map(|name| name.ends_with("invalid-templates.kdl"))
+                    .unwrap_or(false),
+                "error reported against the included file"
+            );
+        },
+        e => panic!("expected a kdl error, got: {:?}", e),
+    }
+}
+
+#[test]
+fn error_on_included_node_points_to_the_included_file() {
+    let layout_path = PathBuf::from(format!(
+        "{}/src/input/unit/fixtures/layouts/layout-with-duplicate-included-template.kdl",
+        env!("CARGO_MANIFEST_DIR")
+    ));
+    let layout = Layout::from_path_or_default_without_config(Some(&layout_path), None);
+    match layout {
+        Err(ConfigError::KdlError(kdl_error)) => {
+            let src_name = kdl_error.src.as_ref().map(|src| src.name().to_owned());
+            assert!(
+                src_name
+                    .map(|name| name.ends_with("templates.kdl"))
+                    .unwrap_or(false),
+                "error reported against the included file"
+            );
+            let included_layout =
+                std::fs::read_to_string(layout_path.with_file_name("templates.kdl")).unwrap();
+            let error_offset = kdl_error.offset.unwrap();
+            assert!(
+                included_layout[error_offset..].starts_with("pane_template name=\"editor\""),
+                "error points to the duplicate template in the included file"
+            );
+        },
+        e => panic!("expected a kdl error, got: {:?}", e),
+    }
+}
+
 #[test]
 fn global_cwd_and_tab_cwd_prepended_to_panes_with_and_without_cwd() {
     let kdl_layout = r#"
@@ -1887,6 +2507,64 @@ fn global_cwd_and_tab_cwd_prepended_to_panes_with_and_without_cwd_in_tab_templat
     assert_snapshot!(format!("{:#?}", layout));
 }
 
+#[test]
+fn swap_layout_constraints_on_pane_contents_and_terminal_size() {
+    let kdl_layout = r#"
+        layout {
+            swap_tiled_layout {
+                tab has_command="nvim" {
+                    pane
+                }
+                tab has_plugin="zellij:strider" {
+                    pane
+                }
+                tab min_columns=120 {
+                    pane
+                }
+                tab max_rows=20 {
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let constraints: Vec<LayoutConstraint> =
+        layout.swap_tiled_layouts[0].0.keys().cloned().collect();
+    assert_eq!(
+        constraints,
+        vec![
+            LayoutConstraint::HasCommand("nvim".to_owned()),
+            LayoutConstraint::HasPlugin("zellij:strider".to_owned()),
+            LayoutConstraint::MinColumns(120),
+            LayoutConstraint::MaxRows(20),
+        ]
+    );
+}
+
+#[test]
+fn swap_layout_with_more_than_one_constraint() {
+    let kdl_layout = r#"
+        layout {
+            swap_tiled_layout {
+                tab has_command="nvim" min_columns=120 {
+                    pane
+                }
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let constraints: Vec<LayoutConstraint> =
+        layout.swap_tiled_layouts[0].0.keys().cloned().collect();
+    assert_eq!(
+        constraints,
+        vec![LayoutConstraint::All(vec![
+            LayoutConstraint::HasCommand("nvim".to_owned()),
+            LayoutConstraint::MinColumns(120),
+        ])],
+        "constraints are combined"
+    );
+}
+
 #[test]
 fn can_load_swap_layouts_from_a_different_file() {
     let kdl_layout = r#"
@@ -2228,3 +2906,36 @@ fn env_var_missing() {
     let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None);
     assert!(layout.is_err(), "invalid env var lookup should fail");
 }
+
+#[test]
+fn tab_cwd_and_env_are_kept_as_the_tab_defaults() {
+    let kdl_layout = r#"
+        layout {
+            cwd "/projects"
+            tab name="backend" cwd="backend" {
+                env {
+                    RUST_LOG "debug"
+                }
+                pane
+            }
+            tab name="scratch" {
+                pane
+            }
+        }
+    "#;
+    let layout = Layout::from_kdl(kdl_layout, "layout_file_name".into(), None, None).unwrap();
+    let (_, backend_tab, _) = &layout.tabs[0];
+    let (_, scratch_tab, _) = &layout.tabs[1];
+    let mut expected_env = BTreeMap::new();
+    expected_env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+    assert_eq!(
+        backend_tab.default_cwd,
+        Some(PathBuf::from("/projects/backend"))
+    );
+    assert_eq!(backend_tab.default_env, expected_env);
+    assert_eq!(
+        scratch_tab.default_cwd, None,
+        "the global cwd is not a tab default"
+    );
+    assert!(scratch_tab.default_env.is_empty());
+}
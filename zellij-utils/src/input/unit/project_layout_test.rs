@@ -0,0 +1,94 @@
+use super::*;
+use tempfile::tempdir;
+
+#[test]
+fn finds_project_layout_in_a_parent_folder() {
+    let project_dir = tempdir().unwrap();
+    let sub_dir = project_dir.path().join("src").join("bin");
+    fs::create_dir_all(&sub_dir).unwrap();
+    fs::create_dir_all(project_dir.path().join(".zellij")).unwrap();
+    let layout_path = project_dir.path().join(".zellij").join("layout.kdl");
+    fs::write(&layout_path, "layout {}").unwrap();
+    assert_eq!(find_project_layout(&sub_dir), Some(layout_path));
+}
+
+#[test]
+fn prefers_dot_zellij_folder_over_zellij_kdl() {
+    let project_dir = tempdir().unwrap();
+    fs::create_dir_all(project_dir.path().join(".zellij")).unwrap();
+    let layout_path = project_dir.path().join(".zellij").join("layout.kdl");
+    fs::write(&layout_path, "layout {}").unwrap();
+    fs::write(project_dir.path().join("zellij.kdl"), "layout {}").unwrap();
+    assert_eq!(find_project_layout(project_dir.path()), Some(layout_path));
+}
+
+#[test]
+fn trusted_layouts_are_persisted() {
+    let cache_dir = tempdir().unwrap();
+    let cache_path = cache_dir.path().join("trusted_layouts.kdl");
+    let layout_path = PathBuf::from("/path/to/project/.zellij/layout.kdl");
+    let contents = "layout {\n    pane command=\"cargo\" {\n        args \"run\"\n    }\n}\n";
+    let mut trust_cache = LayoutTrustCache::from_path_or_default(Some(cache_path.clone()));
+    assert!(!trust_cache.is_trusted(&layout_path, contents));
+    trust_cache.trust(layout_path.clone(), contents.to_owned());
+    trust_cache.write_to_file().unwrap();
+
+    let trust_cache = LayoutTrustCache::from_path_or_default(Some(cache_path));
+    assert!(
+        trust_cache.is_trusted(&layout_path, contents),
+        "layout trusted after reloading the cache"
+    );
+    assert!(
+        !trust_cache.is_trusted(&layout_path, "layout { pane command=\"rm\"; }"),
+        "layout not trusted once its contents changed"
+    );
+}
+
+#[test]
+fn editing_an_included_or_swap_layout_file_invalidates_trust() {
+    let project_dir = tempdir().unwrap();
+    let layout_path = project_dir.path().join("zellij.kdl");
+    let included_path = project_dir.path().join("templates.kdl");
+    let swap_layout_path = project_dir.path().join("zellij.swap.kdl");
+    fs::write(&layout_path, "layout {\n    include \"templates.kdl\"\n}\n").unwrap();
+    fs::write(&included_path, "layout {\n    pane command=\"cargo\"\n}\n").unwrap();
+    fs::write(
+        &swap_layout_path,
+        "swap_tiled_layout {\n    tab {\n        pane\n    }\n}\n",
+    )
+    .unwrap();
+
+    let cache_dir = tempdir().unwrap();
+    let mut trust_cache =
+        LayoutTrustCache::from_path_or_default(Some(cache_dir.path().join("trusted_layouts.kdl")));
+    let contents = layout_trust_contents(&layout_path, None).unwrap();
+    trust_cache.trust(layout_path.clone(), contents);
+    assert!(trust_cache.is_trusted(
+        &layout_path,
+        &layout_trust_contents(&layout_path, None).unwrap()
+    ));
+
+    fs::write(&included_path, "layout {\n    pane command=\"rm\"\n}\n").unwrap();
+    assert!(
+        !trust_cache.is_trusted(
+            &layout_path,
+            &layout_trust_contents(&layout_path, None).unwrap()
+        ),
+        "layout not trusted once an included file changed"
+    );
+
+    let contents = layout_trust_contents(&layout_path, None).unwrap();
+    trust_cache.trust(layout_path.clone(), contents);
+    fs::write(
+        &swap_layout_path,
+        "swap_tiled_layout {\n    tab {\n        pane command=\"rm\"\n    }\n}\n",
+    )
+    .unwrap();
+    assert!(
+        !trust_cache.is_trusted(
+            &layout_path,
+            &layout_trust_contents(&layout_path, None).unwrap()
+        ),
+        "layout not trusted once its swap layout file changed"
+    );
+}
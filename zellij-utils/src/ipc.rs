@@ -4,7 +4,10 @@ use crate::{
     data::{ClientId, ConnectToSession, InputMode, Style},
     errors::{get_current_ctx, prelude::*, ErrorContext},
     input::keybinds::Keybinds,
-    input::{actions::Action, layout::Layout, options::Options, plugins::PluginAliases},
+    input::{
+        actions::Action, hooks::Hooks, layout::Layout, options::Options, plugins::PluginAliases,
+        scratchpads::Scratchpads,
+    },
     pane_size::{Size, SizeInPixels},
 };
 use interprocess::local_socket::LocalSocketStream;
@@ -77,12 +80,15 @@ pub enum ClientToServerMsg {
         Box<Options>,
         Box<Layout>,
         Box<PluginAliases>,
+        Box<Scratchpads>,
+        Box<Hooks>,
     ),
     AttachClient(
         ClientAttributes,
         Options,
         Option<usize>,       // tab position to focus
         Option<(u32, bool)>, // (pane_id, is_plugin) => pane id to focus
+        bool,                // is_read_only
     ),
     Action(Action, Option<u32>, Option<ClientId>), // u32 is the terminal id
     ClientExited,
@@ -1,5 +1,6 @@
+use crate::envs::EnvironmentVariables;
 use crate::input::{
-    command::RunCommand,
+    command::{PaneDependency, RestartPolicy, RunCommand},
     config::ConfigError,
     layout::{
         FloatingPaneLayout, Layout, LayoutConstraint, PercentOrFixed, PluginUserConfiguration, Run,
@@ -7,8 +8,10 @@ use crate::input::{
         TiledPaneLayout,
     },
 };
+use crate::kdl::layout_error_with_src;
 
 use kdl::*;
+use miette::SourceSpan;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
@@ -33,6 +36,15 @@ pub enum PaneOrFloatingPane {
     Either(TiledPaneLayout),
 }
 
+/// A layout file whose nodes were spliced into this one by an include. The spans of these nodes
+/// are moved past the end of this file (to `offset`), so that errors raised on them can be
+/// reported against the included file.
+struct IncludedSource {
+    offset: usize,
+    file_name: String,
+    raw_layout: String,
+}
+
 pub struct KdlLayoutParser<'a> {
     global_cwd: Option<PathBuf>,
     raw_layout: &'a str,
@@ -41,10 +53,22 @@ pub struct KdlLayoutParser<'a> {
     default_tab_template: Option<(TiledPaneLayout, Vec<FloatingPaneLayout>, KdlNode)>,
     new_tab_template: Option<(TiledPaneLayout, Vec<FloatingPaneLayout>)>,
     file_name: PathBuf,
+    layout_args: BTreeMap<String, String>,
+    layout_dir: Option<PathBuf>,
+    included_from: Vec<PathBuf>, // the layout files currently being included, to detect cycles
+    included_sources: Vec<IncludedSource>,
 }
 
 impl<'a> KdlLayoutParser<'a> {
-    pub fn new(raw_layout: &'a str, global_cwd: Option<PathBuf>, file_name: String) -> Self {
+    pub fn new(
+        raw_layout: &'a str,
+        global_cwd: Option<PathBuf>,
+        file_name: String,
+        layout_args: BTreeMap<String, String>,
+        layout_dir: Option<PathBuf>,
+    ) -> Self {
+        let file_name = PathBuf::from(file_name);
+        let included_from = file_name.canonicalize().ok().into_iter().collect();
         KdlLayoutParser {
             raw_layout,
             tab_templates: HashMap::new(),
@@ -52,7 +76,11 @@ impl<'a> KdlLayoutParser<'a> {
             default_tab_template: None,
             new_tab_template: None,
             global_cwd,
-            file_name: PathBuf::from(file_name),
+            file_name,
+            layout_args,
+            layout_dir,
+            included_from,
+            included_sources: vec![],
         }
     }
     fn is_a_reserved_word(&self, word: &str) -> bool {
@@ -72,22 +100,31 @@ impl<'a> KdlLayoutParser<'a> {
             || word == "args"
             || word == "close_on_exit"
             || word == "start_suspended"
+            || word == "rerun_on_resurrect"
+            || word == "restart"
+            || word == "max_restarts"
+            || word == "depends_on"
             || word == "borderless"
             || word == "focus"
             || word == "name"
             || word == "size"
+            || word == "min_size"
+            || word == "max_size"
             || word == "cwd"
             || word == "split_direction"
             || word == "swap_tiled_layout"
             || word == "swap_floating_layout"
             || word == "hide_floating_panes"
             || word == "contents_file"
+            || word == "sticky"
     }
     fn is_a_valid_pane_property(&self, property_name: &str) -> bool {
         property_name == "borderless"
             || property_name == "focus"
             || property_name == "name"
             || property_name == "size"
+            || property_name == "min_size"
+            || property_name == "max_size"
             || property_name == "plugin"
             || property_name == "command"
             || property_name == "edit"
@@ -95,6 +132,10 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "args"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "rerun_on_resurrect"
+            || property_name == "restart"
+            || property_name == "max_restarts"
+            || property_name == "depends_on"
             || property_name == "split_direction"
             || property_name == "pane"
             || property_name == "children"
@@ -114,11 +155,16 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "args"
             || property_name == "close_on_exit"
             || property_name == "start_suspended"
+            || property_name == "rerun_on_resurrect"
+            || property_name == "restart"
+            || property_name == "max_restarts"
+            || property_name == "depends_on"
             || property_name == "x"
             || property_name == "y"
             || property_name == "width"
             || property_name == "height"
             || property_name == "contents_file"
+            || property_name == "sticky"
     }
     fn is_a_valid_tab_property(&self, property_name: &str) -> bool {
         property_name == "focus"
@@ -130,6 +176,12 @@ impl<'a> KdlLayoutParser<'a> {
             || property_name == "max_panes"
             || property_name == "min_panes"
             || property_name == "exact_panes"
+            || property_name == "has_command"
+            || property_name == "has_plugin"
+            || property_name == "min_columns"
+            || property_name == "max_columns"
+            || property_name == "min_rows"
+            || property_name == "max_rows"
             || property_name == "hide_floating_panes"
     }
     pub fn is_a_reserved_plugin_property(property_name: &str) -> bool {
@@ -240,6 +292,57 @@ impl<'a> KdlLayoutParser<'a> {
             Ok(None)
         }
     }
+    fn parse_size_bound(
+        &self,
+        kdl_node: &KdlNode,
+        bound_name: &str,
+    ) -> Result<Option<usize>, ConfigError> {
+        if let Some(size) = kdl_get_int_property_or_child_value!(kdl_node, bound_name) {
+            if size <= 0 {
+                return Err(kdl_parsing_error!(
+                    format!("{} should be greater than 0", bound_name),
+                    kdl_node
+                ));
+            }
+            Ok(Some(size as usize))
+        } else if let Some(node) = kdl_property_or_child_value_node!(kdl_node, bound_name) {
+            Err(kdl_parsing_error!(
+                format!(
+                    "{} should be a fixed number of rows or columns (eg. 30)",
+                    bound_name
+                ),
+                node
+            ))
+        } else if let Some(node) = kdl_child_with_name!(kdl_node, bound_name) {
+            Err(kdl_parsing_error!(
+                format!(
+                    "{} cannot be bare, it should have a value (eg. '{} 30')",
+                    bound_name, bound_name
+                ),
+                node
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+    fn parse_size_bounds(
+        &self,
+        kdl_node: &KdlNode,
+    ) -> Result<(Option<usize>, Option<usize>), ConfigError> {
+        // (min_size, max_size)
+        let min_size = self.parse_size_bound(kdl_node, "min_size")?;
+        let max_size = self.parse_size_bound(kdl_node, "max_size")?;
+        match (min_size, max_size) {
+            (Some(min_size), Some(max_size)) if min_size > max_size => Err(kdl_parsing_error!(
+                format!(
+                    "min_size ({}) cannot be greater than max_size ({})",
+                    min_size, max_size
+                ),
+                kdl_node
+            )),
+            _ => Ok((min_size, max_size)),
+        }
+    }
     fn parse_percent_or_fixed(
         &self,
         kdl_node: &KdlNode,
@@ -398,6 +501,98 @@ impl<'a> KdlLayoutParser<'a> {
             None => Ok(None),
         }
     }
+    fn parse_env(
+        &self,
+        kdl_node: &KdlNode,
+    ) -> Result<Option<BTreeMap<String, String>>, ConfigError> {
+        match kdl_get_child!(kdl_node, "env") {
+            Some(kdl_env) => Ok(Some(
+                EnvironmentVariables::from_kdl(kdl_env)?
+                    .inner()
+                    .clone()
+                    .into_iter()
+                    .collect(),
+            )),
+            None => Ok(None),
+        }
+    }
+    fn parse_restart_policy(
+        &self,
+        kdl_node: &KdlNode,
+    ) -> Result<Option<RestartPolicy>, ConfigError> {
+        match kdl_get_string_property_or_child_value_with_error!(kdl_node, "restart") {
+            Some(restart) => RestartPolicy::from_str(restart)
+                .map(Some)
+                .map_err(|e| kdl_parsing_error!(e, kdl_node)),
+            None => Ok(None),
+        }
+    }
+    fn parse_max_restarts(&self, kdl_node: &KdlNode) -> Result<Option<usize>, ConfigError> {
+        if let Some(max_restarts) = kdl_get_int_property_or_child_value!(kdl_node, "max_restarts") {
+            if max_restarts < 0 {
+                return Err(kdl_parsing_error!(
+                    "max_restarts should not be negative".into(),
+                    kdl_node
+                ));
+            }
+            Ok(Some(max_restarts as usize))
+        } else if let Some(node) = kdl_property_or_child_value_node!(kdl_node, "max_restarts") {
+            Err(kdl_parsing_error!(
+                "max_restarts should be a number (eg. 5)".into(),
+                node
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+    fn parse_depends_on(&self, kdl_node: &KdlNode) -> Result<Vec<PaneDependency>, ConfigError> {
+        let mut depends_on = vec![];
+        let dependency_nodes = kdl_children_nodes!(kdl_node)
+            .into_iter()
+            .flatten()
+            .filter(|child| kdl_name!(child) == "depends_on");
+        for dependency_node in dependency_nodes {
+            let pane_name =
+                kdl_first_entry_as_string!(dependency_node).ok_or(kdl_parsing_error!(
+                    "depends_on should be given the name of a pane (eg. depends_on \"db\")".into(),
+                    dependency_node
+                ))?;
+            let output = match dependency_node.get("output") {
+                Some(output) => {
+                    let output = output.value().as_string().ok_or(kdl_parsing_error!(
+                        "output should be a regular expression string".into(),
+                        dependency_node
+                    ))?;
+                    regex::Regex::new(output).map_err(|e| {
+                        kdl_parsing_error!(
+                            format!("output is not a valid regular expression: {}", e),
+                            dependency_node
+                        )
+                    })?;
+                    Some(output.to_owned())
+                },
+                None => None,
+            };
+            let timeout = match dependency_node.get("timeout") {
+                Some(timeout) => match timeout.value().as_i64() {
+                    Some(timeout) if timeout >= 0 => Some(timeout as u64),
+                    _ => {
+                        return Err(kdl_parsing_error!(
+                            "timeout should be a number of seconds (eg. 10)".into(),
+                            dependency_node
+                        ))
+                    },
+                },
+                None => None,
+            };
+            depends_on.push(PaneDependency {
+                pane_name: pane_name.to_owned(),
+                output,
+                timeout,
+            });
+        }
+        Ok(depends_on)
+    }
     fn cwd_prefix(&self, tab_cwd: Option<&PathBuf>) -> Result<Option<PathBuf>, ConfigError> {
         Ok(match (&self.global_cwd, tab_cwd) {
             (Some(global_cwd), Some(tab_cwd)) => Some(global_cwd.join(tab_cwd)),
@@ -428,22 +623,47 @@ impl<'a> KdlLayoutParser<'a> {
         let edit = self.parse_path(pane_node, "edit")?;
         let cwd = self.parse_path(pane_node, "cwd")?;
         let args = self.parse_args(pane_node)?;
+        let env = self.parse_env(pane_node)?;
         let close_on_exit =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "close_on_exit");
         let start_suspended =
             kdl_get_bool_property_or_child_value_with_error!(pane_node, "start_suspended");
+        let rerun_on_resurrect =
+            kdl_get_bool_property_or_child_value_with_error!(pane_node, "rerun_on_resurrect");
+        let restart = self.parse_restart_policy(pane_node)?;
+        let max_restarts = self.parse_max_restarts(pane_node)?;
+        let depends_on = self.parse_depends_on(pane_node)?;
         if !is_template {
             self.assert_no_bare_attributes_in_pane_node(
                 &command,
                 &args,
                 &close_on_exit,
                 &start_suspended,
+                &rerun_on_resurrect,
+                &restart,
+                &max_restarts,
+                &depends_on,
                 pane_node,
             )?;
+            if edit.is_some() && env.is_some() && !self.has_child_nodes(pane_node) {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "env cannot be set for a pane that edits a file".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
         }
         let hold_on_close = close_on_exit.map(|c| !c).unwrap_or(true);
         let hold_on_start = start_suspended.map(|c| c).unwrap_or(false);
+        // without a command, the env of a pane is given to its default shell - or to its child
+        // panes if it has any
+        let shell_env = env
+            .clone()
+            .filter(|env| !env.is_empty() && !self.has_child_nodes(pane_node));
         match (command, edit, cwd) {
+            (None, None, cwd) if shell_env.is_some() => {
+                Ok(Some(Run::Shell(cwd, shell_env.unwrap_or_default())))
+            },
             (None, None, Some(cwd)) => Ok(Some(Run::Cwd(cwd))),
             (Some(command), None, cwd) => Ok(Some(Run::Command(RunCommand {
                 command,
@@ -451,6 +671,11 @@ impl<'a> KdlLayoutParser<'a> {
                 cwd,
                 hold_on_close,
                 hold_on_start,
+                env: env.unwrap_or_default(),
+                restart: restart.unwrap_or_default(),
+                max_restarts,
+                depends_on,
+                rerun_on_resurrect,
             }))),
             (None, Some(edit), Some(cwd)) => {
                 Ok(Some(Run::EditFile(cwd.join(edit), None, Some(cwd))))
@@ -470,6 +695,13 @@ impl<'a> KdlLayoutParser<'a> {
     ) -> Result<Option<Run>, ConfigError> {
         let mut run = self.parse_pane_command(kdl_node, false)?;
         if let Some(plugin_block) = kdl_get_child!(kdl_node, "plugin") {
+            if let Some(Run::Shell(..)) = run {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "env cannot be set for a plugin pane".into(),
+                    plugin_block.span().offset(),
+                    plugin_block.span().len(),
+                ));
+            }
             let has_non_cwd_run_prop = run
                 .map(|r| match r {
                     Run::Cwd(_) => false,
@@ -493,6 +725,13 @@ impl<'a> KdlLayoutParser<'a> {
     ) -> Result<Option<Run>, ConfigError> {
         let mut run = self.parse_pane_command(kdl_node, true)?;
         if let Some(plugin_block) = kdl_get_child!(kdl_node, "plugin") {
+            if let Some(Run::Shell(..)) = run {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "env cannot be set for a plugin pane".into(),
+                    plugin_block.span().offset(),
+                    plugin_block.span().len(),
+                ));
+            }
             let has_non_cwd_run_prop = run
                 .map(|r| match r {
                     Run::Cwd(_) => false,
@@ -529,15 +768,21 @@ impl<'a> KdlLayoutParser<'a> {
         let contents_file =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "contents_file");
         let split_size = self.parse_split_size(kdl_node)?;
+        let (min_size, max_size) = self.parse_size_bounds(kdl_node)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let children_split_direction = self.parse_split_direction(kdl_node)?;
-        let (external_children_index, children) = match kdl_children_nodes!(kdl_node) {
+        let (external_children_index, mut children) = match kdl_children_nodes!(kdl_node) {
             Some(children) => {
                 self.assert_no_grandchildren_in_stack(&children, is_part_of_stack)?;
                 self.parse_child_pane_nodes_for_pane(&children, children_are_stacked)?
             },
             None => (None, vec![]),
         };
+        if let Some(env) = self.parse_env(kdl_node)? {
+            for child in children.iter_mut() {
+                child.inherit_env_in_layout(&env);
+            }
+        }
         if children_are_stacked && external_children_index.is_none() && children.is_empty() {
             return Err(ConfigError::new_layout_kdl_error(
                 format!("A stacked pane must have children nodes or possibly a \"children\" node if in a swap_layout"),
@@ -568,6 +813,8 @@ impl<'a> KdlLayoutParser<'a> {
             focus,
             name,
             split_size,
+            min_size,
+            max_size,
             run,
             children_split_direction,
             external_children_index,
@@ -590,6 +837,8 @@ impl<'a> KdlLayoutParser<'a> {
         let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
         let run = self.parse_command_plugin_or_edit_block(kdl_node)?;
         let focus = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "focus");
+        let is_sticky =
+            kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky").unwrap_or(false);
         let name = kdl_get_string_property_or_child_value_with_error!(kdl_node, "name")
             .map(|name| name.to_string());
         let contents_file =
@@ -609,6 +858,7 @@ impl<'a> KdlLayoutParser<'a> {
             run,
             focus,
             pane_initial_contents,
+            is_sticky,
             ..Default::default()
         })
     }
@@ -655,7 +905,11 @@ impl<'a> KdlLayoutParser<'a> {
     ) -> Result<Option<usize>, ConfigError> {
         // Option<external_children_index>
         if let Some(pane_child_nodes) = kdl_children_nodes!(kdl_node) {
-            for (i, child) in pane_child_nodes.iter().enumerate() {
+            for (i, child) in pane_child_nodes
+                .iter()
+                .filter(|child| kdl_name!(child) != "env")
+                .enumerate()
+            {
                 if kdl_name!(child) == "children" {
                     if let Some(grand_children) = kdl_children_nodes!(child) {
                         let grand_children: Vec<&str> =
@@ -701,10 +955,26 @@ impl<'a> KdlLayoutParser<'a> {
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let rerun_on_resurrect = kdl_get_bool_property_or_child_value_with_error!(
+                    kdl_node,
+                    "rerun_on_resurrect"
+                );
+                let restart = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
+                let depends_on = self.parse_depends_on(kdl_node)?;
                 let split_size = self.parse_split_size(kdl_node)?;
+                let (min_size, max_size) = self.parse_size_bounds(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 let exclude_from_sync =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "exclude_from_sync");
+                let has_child_panes =
+                    self.has_child_nodes(kdl_node) || !pane_template.children.is_empty();
+                // the env of a pane with children is inherited by them rather than its own shell
+                let run = match run {
+                    Some(Run::Shell(cwd, _env)) if has_child_panes => cwd.map(Run::Cwd),
+                    run => run,
+                };
 
                 let external_children_index = if should_mark_external_children_index {
                     self.populate_external_children_index(kdl_node)?
@@ -717,6 +987,11 @@ impl<'a> KdlLayoutParser<'a> {
                     &args,
                     &close_on_exit,
                     &start_suspended,
+                    &rerun_on_resurrect,
+                    &restart,
+                    &max_restarts,
+                    &depends_on,
+                    if has_child_panes { &None } else { &env },
                     kdl_node,
                 )?;
                 self.insert_children_to_pane_template(
@@ -731,7 +1006,16 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_template_run_command.add_args(args);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_rerun_on_resurrect(rerun_on_resurrect);
+                    pane_template_run_command.add_restart_policy(restart, max_restarts);
+                    pane_template_run_command.add_depends_on(depends_on);
+                    pane_template_run_command.add_env(env.clone());
                 };
+                if let Some(env) = &env {
+                    for child in pane_template.children.iter_mut() {
+                        child.inherit_env_in_layout(env);
+                    }
+                }
                 if let Some(borderless) = borderless {
                     pane_template.borderless = borderless;
                 }
@@ -747,6 +1031,12 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(split_size) = split_size {
                     pane_template.split_size = Some(split_size);
                 }
+                if let Some(min_size) = min_size {
+                    pane_template.min_size = Some(min_size);
+                }
+                if let Some(max_size) = max_size {
+                    pane_template.max_size = Some(max_size);
+                }
                 if let Some(index_of_children) = pane_template.external_children_index {
                     pane_template.children.insert(
                         index_of_children,
@@ -807,13 +1097,26 @@ impl<'a> KdlLayoutParser<'a> {
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let rerun_on_resurrect = kdl_get_bool_property_or_child_value_with_error!(
+                    kdl_node,
+                    "rerun_on_resurrect"
+                );
+                let restart = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
+                let depends_on = self.parse_depends_on(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
                     &args,
                     &close_on_exit,
                     &start_suspended,
+                    &rerun_on_resurrect,
+                    &restart,
+                    &max_restarts,
+                    &depends_on,
+                    &env,
                     kdl_node,
                 )?;
                 pane_template.run = Run::merge(&pane_template.run, &run);
@@ -823,6 +1126,10 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_template_run_command.add_args(args);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_rerun_on_resurrect(rerun_on_resurrect);
+                    pane_template_run_command.add_restart_policy(restart, max_restarts);
+                    pane_template_run_command.add_depends_on(depends_on);
+                    pane_template_run_command.add_env(env);
                 };
                 if let Some(focus) = focus {
                     pane_template.focus = Some(focus);
@@ -847,6 +1154,11 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(x) = x {
                     pane_template.x = Some(x);
                 }
+                if let Some(is_sticky) =
+                    kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky")
+                {
+                    pane_template.is_sticky = is_sticky;
+                }
                 Ok(pane_template)
             },
             PaneOrFloatingPane::Either(mut pane_template) => {
@@ -858,13 +1170,26 @@ impl<'a> KdlLayoutParser<'a> {
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "close_on_exit");
                 let start_suspended =
                     kdl_get_bool_property_or_child_value_with_error!(kdl_node, "start_suspended");
+                let rerun_on_resurrect = kdl_get_bool_property_or_child_value_with_error!(
+                    kdl_node,
+                    "rerun_on_resurrect"
+                );
+                let restart = self.parse_restart_policy(kdl_node)?;
+                let max_restarts = self.parse_max_restarts(kdl_node)?;
+                let depends_on = self.parse_depends_on(kdl_node)?;
                 let run = self.parse_command_plugin_or_edit_block_for_template(kdl_node)?;
+                let env = self.parse_env(kdl_node)?;
                 self.assert_no_bare_attributes_in_pane_node_with_template(
                     &run,
                     &pane_template.run,
                     &args,
                     &close_on_exit,
                     &start_suspended,
+                    &rerun_on_resurrect,
+                    &restart,
+                    &max_restarts,
+                    &depends_on,
+                    &env,
                     kdl_node,
                 )?;
                 pane_template.run = Run::merge(&pane_template.run, &run);
@@ -874,6 +1199,10 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_template_run_command.add_args(args);
                     pane_template_run_command.add_close_on_exit(close_on_exit);
                     pane_template_run_command.add_start_suspended(start_suspended);
+                    pane_template_run_command.add_rerun_on_resurrect(rerun_on_resurrect);
+                    pane_template_run_command.add_restart_policy(restart, max_restarts);
+                    pane_template_run_command.add_depends_on(depends_on);
+                    pane_template_run_command.add_env(env);
                 };
                 if let Some(focus) = focus {
                     pane_template.focus = Some(focus);
@@ -898,6 +1227,11 @@ impl<'a> KdlLayoutParser<'a> {
                 if let Some(x) = x {
                     floating_pane.x = Some(x);
                 }
+                if let Some(is_sticky) =
+                    kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky")
+                {
+                    floating_pane.is_sticky = is_sticky;
+                }
                 Ok(floating_pane)
             },
         }
@@ -928,6 +1262,7 @@ impl<'a> KdlLayoutParser<'a> {
         let is_expanded_in_stack =
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "expanded");
         let split_size = self.parse_split_size(kdl_node)?;
+        let (min_size, max_size) = self.parse_size_bounds(kdl_node)?;
         let split_direction =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "split_direction");
         let has_children_nodes = self.has_child_nodes(kdl_node);
@@ -937,15 +1272,21 @@ impl<'a> KdlLayoutParser<'a> {
         let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
         let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
         let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
+        let is_sticky = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky");
 
         let has_pane_properties = borderless.is_some()
             || split_size.is_some()
+            || min_size.is_some()
+            || max_size.is_some()
             || split_direction.is_some()
             || children_are_stacked.is_some()
             || is_expanded_in_stack.is_some()
             || has_children_nodes;
-        let has_floating_pane_properties =
-            height.is_some() || width.is_some() || x.is_some() || y.is_some();
+        let has_floating_pane_properties = height.is_some()
+            || width.is_some()
+            || x.is_some()
+            || y.is_some()
+            || is_sticky.is_some();
         if has_pane_properties || has_floating_pane_properties {
             Ok(false)
         } else {
@@ -965,6 +1306,7 @@ impl<'a> KdlLayoutParser<'a> {
         let is_expanded_in_stack =
             kdl_get_bool_property_or_child_value_with_error!(kdl_node, "expanded");
         let split_size = self.parse_split_size(kdl_node)?;
+        let (min_size, max_size) = self.parse_size_bounds(kdl_node)?;
         let split_direction =
             kdl_get_string_property_or_child_value_with_error!(kdl_node, "split_direction");
         let has_children_nodes = self.has_child_nodes(kdl_node);
@@ -974,15 +1316,21 @@ impl<'a> KdlLayoutParser<'a> {
         let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
         let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
         let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
+        let is_sticky = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky");
 
         let has_pane_properties = borderless.is_some()
             || split_size.is_some()
+            || min_size.is_some()
+            || max_size.is_some()
             || split_direction.is_some()
             || children_are_stacked.is_some()
             || is_expanded_in_stack.is_some()
             || has_children_nodes;
-        let has_floating_pane_properties =
-            height.is_some() || width.is_some() || x.is_some() || y.is_some();
+        let has_floating_pane_properties = height.is_some()
+            || width.is_some()
+            || x.is_some()
+            || y.is_some()
+            || is_sticky.is_some();
 
         if has_pane_properties && has_floating_pane_properties {
             let mut pane_properties = vec![];
@@ -998,6 +1346,12 @@ impl<'a> KdlLayoutParser<'a> {
             if split_size.is_some() {
                 pane_properties.push("split_size");
             }
+            if min_size.is_some() {
+                pane_properties.push("min_size");
+            }
+            if max_size.is_some() {
+                pane_properties.push("max_size");
+            }
             if split_direction.is_some() {
                 pane_properties.push("split_direction");
             }
@@ -1017,6 +1371,9 @@ impl<'a> KdlLayoutParser<'a> {
             if y.is_some() {
                 floating_pane_properties.push("y");
             }
+            if is_sticky.is_some() {
+                floating_pane_properties.push("sticky");
+            }
             Err(ConfigError::new_layout_kdl_error(
                 format!(
                     "A pane_template cannot have both pane ({}) and floating pane ({}) properties",
@@ -1068,6 +1425,8 @@ impl<'a> KdlLayoutParser<'a> {
             let width = self.parse_percent_or_fixed(kdl_node, "width", false)?;
             let x = self.parse_percent_or_fixed(kdl_node, "x", true)?;
             let y = self.parse_percent_or_fixed(kdl_node, "y", true)?;
+            let is_sticky = kdl_get_bool_property_or_child_value_with_error!(kdl_node, "sticky")
+                .unwrap_or(false);
             self.pane_templates.insert(
                 template_name,
                 (
@@ -1078,6 +1437,7 @@ impl<'a> KdlLayoutParser<'a> {
                         width,
                         x,
                         y,
+                        is_sticky,
                         ..Default::default()
                     }),
                     kdl_node.clone(),
@@ -1095,13 +1455,19 @@ impl<'a> KdlLayoutParser<'a> {
                 kdl_get_bool_property_or_child_value_with_error!(kdl_node, "expanded")
                     .unwrap_or(false);
             let split_size = self.parse_split_size(kdl_node)?;
+            let (min_size, max_size) = self.parse_size_bounds(kdl_node)?;
             let children_split_direction = self.parse_split_direction(kdl_node)?;
-            let (external_children_index, pane_parts) = match kdl_children_nodes!(kdl_node) {
+            let (external_children_index, mut pane_parts) = match kdl_children_nodes!(kdl_node) {
                 Some(children) => {
                     self.parse_child_pane_nodes_for_pane(&children, children_are_stacked)?
                 },
                 None => (None, vec![]),
             };
+            if let Some(env) = self.parse_env(kdl_node)? {
+                for pane_part in pane_parts.iter_mut() {
+                    pane_part.inherit_env_in_layout(&env);
+                }
+            }
             self.assert_no_mixed_children_and_properties(kdl_node)?;
             self.pane_templates.insert(
                 template_name,
@@ -1110,6 +1476,8 @@ impl<'a> KdlLayoutParser<'a> {
                         borderless: borderless.unwrap_or_default(),
                         focus,
                         split_size,
+                        min_size,
+                        max_size,
                         run,
                         children_split_direction,
                         external_children_index,
@@ -1166,6 +1534,16 @@ impl<'a> KdlLayoutParser<'a> {
         };
         if let Some(cwd_prefix) = &self.cwd_prefix(tab_cwd.as_ref())? {
             pane_layout.add_cwd_to_layout(&cwd_prefix);
+            if tab_cwd.is_some() {
+                pane_layout.default_cwd = Some(cwd_prefix.clone());
+            }
+        }
+        if let Some(tab_env) = self.parse_env(kdl_node)? {
+            pane_layout.inherit_env_in_layout(&tab_env);
+            for floating_pane in child_floating_panes.iter_mut() {
+                floating_pane.inherit_env_in_layout(&tab_env);
+            }
+            pane_layout.default_env = tab_env;
         }
         Ok((is_focused, tab_name, pane_layout, child_floating_panes))
     }
@@ -1191,6 +1569,8 @@ impl<'a> KdlLayoutParser<'a> {
                 )?);
             } else if kdl_name!(child) == "floating_panes" {
                 self.populate_floating_pane_children(child, child_floating_panes)?;
+            } else if kdl_name!(child) == "env" {
+                // parsed along with the rest of the tab properties
             } else if self.is_a_valid_tab_property(kdl_name!(child)) {
                 return Err(ConfigError::new_layout_kdl_error(
                     format!("Tab property '{}' must be placed on the tab title line and not in the child braces", kdl_name!(child)),
@@ -1218,6 +1598,7 @@ impl<'a> KdlLayoutParser<'a> {
         // usize is external_children_index
         let mut external_children_index = None;
         let mut nodes = vec![];
+        let mut children_index_offset = 0;
         for (i, child) in children.iter().enumerate() {
             if kdl_name!(child) == "pane" {
                 nodes.push(self.parse_pane_node(child, is_part_of_stack)?);
@@ -1236,7 +1617,9 @@ impl<'a> KdlLayoutParser<'a> {
                         ));
                     }
                 }
-                external_children_index = Some(i);
+                external_children_index = Some(i.saturating_sub(children_index_offset));
+            } else if kdl_name!(child) == "env" {
+                children_index_offset += 1;
             } else if let Some((pane_template, pane_template_kdl_node)) =
                 self.pane_templates.get(kdl_name!(child)).cloned()
             {
@@ -1296,6 +1679,11 @@ impl<'a> KdlLayoutParser<'a> {
         args: &Option<Vec<String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
+        rerun_on_resurrect: &Option<bool>,
+        restart: &Option<RestartPolicy>,
+        max_restarts: &Option<usize>,
+        depends_on: &[PaneDependency],
+        env: &Option<BTreeMap<String, String>>,
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if let (None, None, true) = (pane_run, pane_template_run, args.is_some()) {
@@ -1316,6 +1704,34 @@ impl<'a> KdlLayoutParser<'a> {
                 pane_node
             ));
         }
+        if let (None, None, true) = (pane_run, pane_template_run, rerun_on_resurrect.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("rerun_on_resurrect can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
+        if let (None, None, true) = (
+            pane_run,
+            pane_template_run,
+            restart.is_some() || max_restarts.is_some(),
+        ) {
+            return Err(kdl_parsing_error!(
+                format!("restart and max_restarts can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
+        if let (None, None, true) = (pane_run, pane_template_run, !depends_on.is_empty()) {
+            return Err(kdl_parsing_error!(
+                format!("depends_on can only be specified if a command was specified either in the pane_template or in the pane"),
+                pane_node
+            ));
+        }
+        if let (None, None, true) = (pane_run, pane_template_run, env.is_some()) {
+            return Err(kdl_parsing_error!(
+                format!("env can only be specified if a command was specified either in the pane_template or in the pane, or if the pane has child panes"),
+                pane_node
+            ));
+        }
         Ok(())
     }
     fn assert_no_bare_attributes_in_pane_node(
@@ -1324,9 +1740,27 @@ impl<'a> KdlLayoutParser<'a> {
         args: &Option<Vec<String>>,
         close_on_exit: &Option<bool>,
         start_suspended: &Option<bool>,
+        rerun_on_resurrect: &Option<bool>,
+        restart: &Option<RestartPolicy>,
+        max_restarts: &Option<usize>,
+        depends_on: &[PaneDependency],
         pane_node: &KdlNode,
     ) -> Result<(), ConfigError> {
         if command.is_none() {
+            if !depends_on.is_empty() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "depends_on can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
+            if restart.is_some() || max_restarts.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "restart and max_restarts can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
             if close_on_exit.is_some() {
                 return Err(ConfigError::new_layout_kdl_error(
                     "close_on_exit can only be set if a command was specified".into(),
@@ -1341,6 +1775,13 @@ impl<'a> KdlLayoutParser<'a> {
                     pane_node.span().len(),
                 ));
             }
+            if rerun_on_resurrect.is_some() {
+                return Err(ConfigError::new_layout_kdl_error(
+                    "rerun_on_resurrect can only be set if a command was specified".into(),
+                    pane_node.span().offset(),
+                    pane_node.span().len(),
+                ));
+            }
             if args.is_some() {
                 return Err(ConfigError::new_layout_kdl_error(
                     "args can only be set if a command was specified".into(),
@@ -1572,6 +2013,16 @@ impl<'a> KdlLayoutParser<'a> {
         }
         if let Some(cwd_prefix) = self.cwd_prefix(tab_cwd.as_ref())? {
             tab_layout.add_cwd_to_layout(&cwd_prefix);
+            if tab_cwd.is_some() {
+                tab_layout.default_cwd = Some(cwd_prefix);
+            }
+        }
+        if let Some(tab_env) = self.parse_env(kdl_node)? {
+            tab_layout.inherit_env_in_layout(&tab_env);
+            for floating_pane in tab_template_floating_panes.iter_mut() {
+                floating_pane.inherit_env_in_layout(&tab_env);
+            }
+            tab_layout.default_env.extend(tab_env);
         }
         tab_layout.external_children_index = None;
         Ok((
@@ -1667,6 +2118,8 @@ impl<'a> KdlLayoutParser<'a> {
                 } else if kdl_name!(child) == "floating_panes" {
                     children_index_offset += 1;
                     self.populate_floating_pane_children(child, &mut tab_floating_children)?;
+                } else if kdl_name!(child) == "env" {
+                    children_index_offset += 1;
                 } else if self.is_a_valid_tab_property(kdl_name!(child)) {
                     return Err(ConfigError::new_layout_kdl_error(
                         format!("Tab property '{}' must be placed on the tab_template title line and not in the child braces", kdl_name!(child)),
@@ -1682,6 +2135,14 @@ impl<'a> KdlLayoutParser<'a> {
                 }
             }
         }
+        if let Some(tab_env) = self.parse_env(kdl_node)? {
+            for child in tab_children.iter_mut() {
+                child.inherit_env_in_layout(&tab_env);
+            }
+            for floating_pane in tab_floating_children.iter_mut() {
+                floating_pane.inherit_env_in_layout(&tab_env);
+            }
+        }
         Ok((
             TiledPaneLayout {
                 children_split_direction,
@@ -1776,6 +2237,70 @@ impl<'a> KdlLayoutParser<'a> {
         }
         Ok(())
     }
+    fn apply_layout_args(&self, kdl_layout: &mut KdlDocument) -> Result<(), ConfigError> {
+        // the layout declares its arguments (and their optional defaults) in an "args" node,
+        // which we remove here after replacing any references to them (eg. "{{project}}") with
+        // the values we were given or the defaults
+        let Some(layout_node) = kdl_layout
+            .nodes_mut()
+            .iter_mut()
+            .find(|n| kdl_name!(n) == "layout")
+        else {
+            // this will be reported when parsing the layout
+            return Ok(());
+        };
+        let layout_node_span = (layout_node.span().offset(), layout_node.span().len());
+        let args_node = layout_node.children_mut().as_mut().and_then(|children| {
+            let args_node_index = children
+                .nodes()
+                .iter()
+                .position(|n| kdl_name!(n) == "args")?;
+            Some(children.nodes_mut().remove(args_node_index))
+        });
+        let mut declared_args: BTreeMap<String, Option<String>> = BTreeMap::new();
+        if let Some(args_node) = &args_node {
+            for arg in args_node.children().map(|c| c.nodes()).unwrap_or(&[]) {
+                let default_value = kdl_first_entry_as_string!(arg)
+                    .map(|s| s.to_string())
+                    .or_else(|| kdl_first_entry_as_i64!(arg).map(|i| i.to_string()));
+                declared_args.insert(kdl_name!(arg).to_owned(), default_value);
+            }
+        }
+        let (error_offset, error_len) = args_node
+            .as_ref()
+            .map(|a| (a.span().offset(), a.span().len()))
+            .unwrap_or(layout_node_span);
+        let mut layout_args = BTreeMap::new();
+        for (arg_name, arg_value) in &self.layout_args {
+            if !declared_args.contains_key(arg_name) {
+                return Err(ConfigError::new_layout_kdl_error(
+                    format!("This layout does not declare the argument \"{}\"", arg_name),
+                    error_offset,
+                    error_len,
+                ));
+            }
+            layout_args.insert(arg_name.clone(), arg_value.clone());
+        }
+        for (arg_name, default_value) in declared_args {
+            if !layout_args.contains_key(&arg_name) {
+                let default_value = default_value.ok_or_else(|| {
+                    ConfigError::new_layout_kdl_error(
+                        format!(
+                            "The layout argument \"{}\" has no default and must be provided (eg. --layout-arg {}=<value>)",
+                            arg_name, arg_name
+                        ),
+                        error_offset,
+                        error_len,
+                    )
+                })?;
+                layout_args.insert(arg_name, default_value);
+            }
+        }
+        if let Some(layout_children) = layout_node.children_mut().as_mut() {
+            substitute_layout_args(layout_children, &layout_args);
+        }
+        Ok(())
+    }
     fn populate_global_cwd(&mut self, layout_node: &KdlNode) -> Result<(), ConfigError> {
         // we only populate global cwd from the layout file if another wasn't explicitly passed to us
         if self.global_cwd.is_none() {
@@ -1945,59 +2470,55 @@ impl<'a> KdlLayoutParser<'a> {
         Ok(())
     }
     fn parse_constraint(&mut self, layout_node: &KdlNode) -> Result<LayoutConstraint, ConfigError> {
-        if let Some(max_panes) = kdl_get_string_property_or_child_value!(layout_node, "max_panes") {
-            return Err(kdl_parsing_error!(
-                format!(
-                    "max_panes should be a fixed number (eg. 1) and not a quoted string (\"{}\")",
-                    max_panes
-                ),
-                layout_node
-            ));
-        };
-        if let Some(min_panes) = kdl_get_string_property_or_child_value!(layout_node, "min_panes") {
-            return Err(kdl_parsing_error!(
-                format!(
-                    "min_panes should be a fixed number (eg. 1) and not a quoted string (\"{}\")",
-                    min_panes
-                ),
-                layout_node
-            ));
+        let numeric_constraints = [
+            "max_panes",
+            "min_panes",
+            "exact_panes",
+            "min_columns",
+            "max_columns",
+            "min_rows",
+            "max_rows",
+        ];
+        for constraint_name in numeric_constraints {
+            if let Some(value) =
+                kdl_get_string_property_or_child_value!(layout_node, constraint_name)
+            {
+                return Err(kdl_parsing_error!(
+                    format!(
+                        "{} should be a fixed number (eg. 1) and not a quoted string (\"{}\")",
+                        constraint_name, value
+                    ),
+                    layout_node
+                ));
+            };
+        }
+        let numeric_constraint = |constraint_name: &str| {
+            kdl_get_int_property_or_child_value!(layout_node, constraint_name).map(|v| v as usize)
         };
-        if let Some(exact_panes) =
-            kdl_get_string_property_or_child_value!(layout_node, "exact_panes")
-        {
-            return Err(kdl_parsing_error!(
-                format!(
-                    "exact_panes should be a fixed number (eg. 1) and not a quoted string (\"{}\")",
-                    exact_panes,
-                ),
-                layout_node
-            ));
+        let string_constraint = |constraint_name: &str| {
+            kdl_get_string_property_or_child_value!(layout_node, constraint_name)
+                .map(|v| v.to_owned())
         };
-        let max_panes = kdl_get_int_property_or_child_value!(layout_node, "max_panes");
-        let min_panes = kdl_get_int_property_or_child_value!(layout_node, "min_panes");
-        let exact_panes = kdl_get_int_property_or_child_value!(layout_node, "exact_panes");
-        let mut constraint_count = 0;
-        let mut constraint = None;
-        if let Some(max_panes) = max_panes {
-            constraint_count += 1;
-            constraint = Some(LayoutConstraint::MaxPanes(max_panes as usize));
-        }
-        if let Some(min_panes) = min_panes {
-            constraint_count += 1;
-            constraint = Some(LayoutConstraint::MinPanes(min_panes as usize));
-        }
-        if let Some(exact_panes) = exact_panes {
-            constraint_count += 1;
-            constraint = Some(LayoutConstraint::ExactPanes(exact_panes as usize));
-        }
-        if constraint_count > 1 {
-            return Err(kdl_parsing_error!(
-                format!("cannot have more than one constraint (eg. max_panes + min_panes)'"),
-                layout_node
-            ));
+        let mut constraints: Vec<LayoutConstraint> = [
+            numeric_constraint("max_panes").map(LayoutConstraint::MaxPanes),
+            numeric_constraint("min_panes").map(LayoutConstraint::MinPanes),
+            numeric_constraint("exact_panes").map(LayoutConstraint::ExactPanes),
+            string_constraint("has_command").map(LayoutConstraint::HasCommand),
+            string_constraint("has_plugin").map(LayoutConstraint::HasPlugin),
+            numeric_constraint("min_columns").map(LayoutConstraint::MinColumns),
+            numeric_constraint("max_columns").map(LayoutConstraint::MaxColumns),
+            numeric_constraint("min_rows").map(LayoutConstraint::MinRows),
+            numeric_constraint("max_rows").map(LayoutConstraint::MaxRows),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        // several constraints (eg. min_panes + min_columns) should all be met
+        match constraints.len() {
+            0 => Ok(LayoutConstraint::NoConstraint),
+            1 => Ok(constraints.remove(0)),
+            _ => Ok(LayoutConstraint::All(constraints)),
         }
-        Ok(constraint.unwrap_or(LayoutConstraint::NoConstraint))
     }
     fn populate_one_swap_tiled_layout(
         &self,
@@ -2334,7 +2855,208 @@ impl<'a> KdlLayoutParser<'a> {
         Ok(existing_layout)
     }
     pub fn parse(&mut self) -> Result<Layout, ConfigError> {
-        let kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        let mut kdl_layout = self.parse_document_with_includes()?;
+        self.apply_layout_args(&mut kdl_layout)?;
+        self.parse_kdl_layout(&kdl_layout)
+    }
+    pub fn included_layout_files(&mut self) -> Result<Vec<(String, String)>, ConfigError> {
+        self.parse_document_with_includes()?;
+        Ok(self
+            .included_sources
+            .iter()
+            .map(|included_source| {
+                (
+                    included_source.file_name.clone(),
+                    included_source.raw_layout.clone(),
+                )
+            })
+            .collect())
+    }
+    fn parse_document_with_includes(&mut self) -> Result<KdlDocument, ConfigError> {
+        let mut kdl_layout: KdlDocument = self.raw_layout.parse()?;
+        let Some(layout_children) = kdl_layout
+            .nodes_mut()
+            .iter_mut()
+            .find(|n| kdl_name!(n) == "layout")
+            .and_then(|layout_node| layout_node.children_mut().as_mut())
+        else {
+            return Ok(kdl_layout);
+        };
+        let mut nodes = vec![];
+        for node in layout_children.nodes_mut().drain(..) {
+            if kdl_name!(node) == "include" {
+                nodes.append(&mut self.parse_included_layout_nodes(&node)?);
+            } else {
+                nodes.push(node);
+            }
+        }
+        *layout_children.nodes_mut() = nodes;
+        Ok(kdl_layout)
+    }
+    fn parse_included_layout_nodes(
+        &mut self,
+        include_node: &KdlNode,
+    ) -> Result<Vec<KdlNode>, ConfigError> {
+        // the nodes inside the "layout" node of the included file are placed instead of the
+        // include node, so that its templates, tabs and swap layouts can be used as if they were
+        // declared in this file
+        let included_path =
+            kdl_first_entry_as_string!(include_node).ok_or(ConfigError::new_layout_kdl_error(
+                "include should be given the path to a layout file (eg. include \"templates.kdl\")"
+                    .into(),
+                include_node.span().offset(),
+                include_node.span().len(),
+            ))?;
+        let included_path =
+            self.find_included_layout(included_path)
+                .ok_or(ConfigError::new_layout_kdl_error(
+                    format!(
+                        "Could not find the layout file \"{}\" to include",
+                        included_path
+                    ),
+                    include_node.span().offset(),
+                    include_node.span().len(),
+                ))?;
+        let canonical_included_path = included_path
+            .canonicalize()
+            .unwrap_or_else(|_| included_path.clone());
+        if self.included_from.contains(&canonical_included_path) {
+            return Err(ConfigError::new_layout_kdl_error(
+                format!(
+                    "Cannot include \"{}\", it is already being included (include cycle)",
+                    included_path.display()
+                ),
+                include_node.span().offset(),
+                include_node.span().len(),
+            ));
+        }
+        let raw_included_layout = std::fs::read_to_string(&included_path)
+            .map_err(|e| ConfigError::IoPath(e, included_path.clone()))?;
+        let included_file_name = included_path.display().to_string();
+        let mut included_layout_parser = KdlLayoutParser::new(
+            &raw_included_layout,
+            None,
+            included_file_name.clone(),
+            BTreeMap::new(),
+            self.layout_dir.clone(),
+        );
+        included_layout_parser
+            .included_from
+            .extend(self.included_from.iter().cloned());
+        included_layout_parser
+            .included_from
+            .push(canonical_included_path);
+        // we parse the included file on its own first so that errors in it are reported against
+        // its own source
+        let included_layout = included_layout_parser
+            .parse_document_with_includes()
+            .and_then(|included_layout| {
+                included_layout_parser.assert_no_args_in_included_layout(&included_layout)?;
+                included_layout_parser.parse_kdl_layout(&included_layout)?;
+                Ok(included_layout)
+            })
+            .map_err(|e| included_layout_parser.layout_error_with_src(e))?;
+        let nested_included_sources = std::mem::take(&mut included_layout_parser.included_sources);
+        let offset = self.next_included_source_offset();
+        let mut included_nodes = included_layout
+            .nodes()
+            .iter()
+            .find(|n| kdl_name!(n) == "layout")
+            .and_then(|layout_node| layout_node.children())
+            .map(|children| children.nodes().to_vec())
+            .unwrap_or_default();
+        for node in included_nodes.iter_mut() {
+            offset_spans(node, offset);
+        }
+        self.included_sources.push(IncludedSource {
+            offset,
+            file_name: included_file_name,
+            raw_layout: raw_included_layout,
+        });
+        for mut nested_included_source in nested_included_sources {
+            nested_included_source.offset += offset;
+            self.included_sources.push(nested_included_source);
+        }
+        Ok(included_nodes)
+    }
+    fn next_included_source_offset(&self) -> usize {
+        let end_of_sources = self
+            .included_sources
+            .last()
+            .map(|included_source| included_source.offset + included_source.raw_layout.len())
+            .unwrap_or(0);
+        end_of_sources.max(self.raw_layout.len()) + 1
+    }
+    /// Adds the source of the file the error was raised in to it, which is an included file if
+    /// it was raised on one of the nodes spliced in by an include
+    pub fn layout_error_with_src(&self, e: ConfigError) -> ConfigError {
+        let included_source = match &e {
+            ConfigError::KdlError(kdl_error) if kdl_error.src.is_none() => {
+                kdl_error.offset.and_then(|error_offset| {
+                    self.included_sources
+                        .iter()
+                        .rev()
+                        .find(|included_source| included_source.offset <= error_offset)
+                })
+            },
+            _ => None,
+        };
+        match (e, included_source) {
+            (ConfigError::KdlError(mut kdl_error), Some(included_source)) => {
+                kdl_error.offset = kdl_error
+                    .offset
+                    .map(|error_offset| error_offset - included_source.offset);
+                layout_error_with_src(
+                    ConfigError::KdlError(kdl_error),
+                    included_source.file_name.clone(),
+                    &included_source.raw_layout,
+                )
+            },
+            (e, _) => {
+                layout_error_with_src(e, self.file_name.display().to_string(), self.raw_layout)
+            },
+        }
+    }
+    fn find_included_layout(&self, included_path: &str) -> Option<PathBuf> {
+        // relative paths are looked up next to the including file first and then in the layout
+        // dir, with or without the .kdl extension
+        let included_path = PathBuf::from(included_path);
+        let mut candidates = vec![];
+        if included_path.is_absolute() {
+            candidates.push(included_path);
+        } else {
+            if let Some(including_file_dir) = self.file_name.parent() {
+                candidates.push(including_file_dir.join(&included_path));
+            }
+            if let Some(layout_dir) = &self.layout_dir {
+                candidates.push(layout_dir.join(&included_path));
+            }
+        }
+        candidates
+            .into_iter()
+            .flat_map(|candidate| [candidate.clone(), candidate.with_extension("kdl")])
+            .find(|candidate| candidate.is_file())
+    }
+    fn assert_no_args_in_included_layout(
+        &self,
+        kdl_layout: &KdlDocument,
+    ) -> Result<(), ConfigError> {
+        let args_node = kdl_layout
+            .nodes()
+            .iter()
+            .find(|n| kdl_name!(n) == "layout")
+            .and_then(|layout_node| kdl_get_child!(layout_node, "args"));
+        match args_node {
+            Some(args_node) => Err(ConfigError::new_layout_kdl_error(
+                "Layout arguments can only be declared in the main layout file, not in included ones"
+                    .into(),
+                args_node.span().offset(),
+                args_node.span().len(),
+            )),
+            None => Ok(()),
+        }
+    }
+    fn parse_kdl_layout(&mut self, kdl_layout: &KdlDocument) -> Result<Layout, ConfigError> {
         let layout_node = kdl_layout
             .nodes()
             .iter()
@@ -2375,7 +3097,7 @@ impl<'a> KdlLayoutParser<'a> {
         let mut swap_floating_layouts = vec![];
         if let Some(children) = kdl_children_nodes!(layout_node) {
             self.populate_global_cwd(layout_node)?;
-            self.populate_pane_templates(children, &kdl_layout)?;
+            self.populate_pane_templates(children, kdl_layout)?;
             self.populate_tab_templates(children)?;
             self.populate_swap_tiled_layouts(children, &mut swap_tiled_layouts)?;
             self.populate_swap_floating_layouts(children, &mut swap_floating_layouts)?;
@@ -2388,7 +3110,7 @@ impl<'a> KdlLayoutParser<'a> {
                 )?;
             }
         }
-        if !child_tabs.is_empty() {
+        let layout = if !child_tabs.is_empty() {
             let has_more_than_one_focused_tab = child_tabs
                 .iter()
                 .filter(|(is_focused, _, _, _)| *is_focused)
@@ -2432,6 +3154,198 @@ impl<'a> KdlLayoutParser<'a> {
                 swap_tiled_layouts,
                 swap_floating_layouts,
             )
+        }?;
+        self.assert_pane_dependencies_can_be_met(&layout, kdl_layout)?;
+        Ok(layout)
+    }
+    fn assert_pane_dependencies_can_be_met(
+        &self,
+        layout: &Layout,
+        kdl_layout: &KdlDocument,
+    ) -> Result<(), ConfigError> {
+        let depends_on_error =
+            |error_message: String, pane_name: Option<&str>, dependency: &str| {
+                let depends_on_node = pane_name
+                    .and_then(|pane_name| find_named_pane_node(kdl_layout, pane_name))
+                    .and_then(|pane_node| pane_node.children())
+                    .and_then(|children| find_depends_on_node(children, dependency))
+                    .or_else(|| find_depends_on_node(kdl_layout, dependency));
+                match depends_on_node {
+                    Some(depends_on_node) => kdl_parsing_error!(error_message, depends_on_node),
+                    None => ConfigError::new_layout_kdl_error(
+                        error_message,
+                        kdl_layout.span().offset(),
+                        kdl_layout.span().len(),
+                    ),
+                }
+            };
+        let pane_names_and_runs = layout.pane_names_and_runs();
+        let pane_names = layout.pane_names();
+        let command_pane_names: HashSet<&String> = pane_names_and_runs
+            .iter()
+            .filter_map(|(pane_name, run)| match run {
+                Some(Run::Command(_)) => *pane_name,
+                _ => None,
+            })
+            .collect();
+        let mut pane_dependencies: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (pane_name, run) in &pane_names_and_runs {
+            let Some(Run::Command(run_command)) = run else {
+                continue;
+            };
+            for dependency in &run_command.depends_on {
+                if !pane_names.contains(&dependency.pane_name) {
+                    return Err(depends_on_error(
+                        format!(
+                            "depends_on refers to a pane named \"{}\", but there is no such pane in this layout",
+                            dependency.pane_name
+                        ),
+                        pane_name.map(|p| p.as_str()),
+                        &dependency.pane_name,
+                    ));
+                }
+                // without an output to wait for, the pane is ready once its command exits
+                // successfully, so one that does not run a command would never be
+                if dependency.output.is_none()
+                    && dependency.timeout.is_none()
+                    && !command_pane_names.contains(&dependency.pane_name)
+                {
+                    return Err(depends_on_error(
+                        format!(
+                            "depends_on waits for the pane named \"{}\" to exit successfully, but it does not run a command (use output or timeout to wait for something else)",
+                            dependency.pane_name
+                        ),
+                        pane_name.map(|p| p.as_str()),
+                        &dependency.pane_name,
+                    ));
+                }
+                if let Some(pane_name) = pane_name {
+                    pane_dependencies
+                        .entry(pane_name.as_str())
+                        .or_default()
+                        .push(dependency.pane_name.as_str());
+                }
+            }
+        }
+        if let Some(cycle) = find_pane_dependency_cycle(&pane_dependencies) {
+            let error_message = format!(
+                "depends_on makes these panes wait for each other: {}",
+                cycle.join(" -> ")
+            );
+            let (pane_name, dependency) = (cycle[cycle.len() - 2], cycle[cycle.len() - 1]);
+            return Err(depends_on_error(error_message, Some(pane_name), dependency));
+        }
+        Ok(())
+    }
+}
+
+/// A chain of panes (starting and ending with the same one) that wait for each other, if any
+fn find_pane_dependency_cycle<'a>(
+    pane_dependencies: &BTreeMap<&'a str, Vec<&'a str>>,
+) -> Option<Vec<&'a str>> {
+    fn visit<'a>(
+        pane_name: &'a str,
+        pane_dependencies: &BTreeMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        visited: &mut HashSet<&'a str>,
+    ) -> Option<Vec<&'a str>> {
+        if let Some(position) = path.iter().position(|p| *p == pane_name) {
+            let mut cycle = path[position..].to_vec();
+            cycle.push(pane_name);
+            return Some(cycle);
+        }
+        if !visited.insert(pane_name) {
+            return None;
+        }
+        path.push(pane_name);
+        for dependency in pane_dependencies.get(pane_name).into_iter().flatten() {
+            if let Some(cycle) = visit(dependency, pane_dependencies, path, visited) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        None
+    }
+    let mut visited = HashSet::new();
+    pane_dependencies
+        .keys()
+        .find_map(|pane_name| visit(pane_name, pane_dependencies, &mut vec![], &mut visited))
+}
+
+fn find_named_pane_node<'a>(kdl_document: &'a KdlDocument, pane_name: &str) -> Option<&'a KdlNode> {
+    kdl_document.nodes().iter().find_map(|kdl_node| {
+        let is_named_pane = kdl_node
+            .get("name")
+            .and_then(|name| name.value().as_string())
+            == Some(pane_name);
+        if is_named_pane {
+            Some(kdl_node)
+        } else {
+            kdl_node
+                .children()
+                .and_then(|children| find_named_pane_node(children, pane_name))
+        }
+    })
+}
+
+fn find_depends_on_node<'a>(kdl_document: &'a KdlDocument, pane_name: &str) -> Option<&'a KdlNode> {
+    kdl_document.nodes().iter().find_map(|kdl_node| {
+        if kdl_name!(kdl_node) == "depends_on"
+            && kdl_first_entry_as_string!(kdl_node) == Some(pane_name)
+        {
+            Some(kdl_node)
+        } else {
+            kdl_node
+                .children()
+                .and_then(|children| find_depends_on_node(children, pane_name))
+        }
+    })
+}
+
+fn substitute_layout_args(kdl_document: &mut KdlDocument, layout_args: &BTreeMap<String, String>) {
+    // layout arguments can be referenced in the cwd, name and command properties and in the
+    // arguments of the cwd, name, command and args nodes
+    let is_substitutable =
+        |name: &str| name == "cwd" || name == "name" || name == "command" || name == "args";
+    for kdl_node in kdl_document.nodes_mut() {
+        let node_is_substitutable = is_substitutable(kdl_name!(kdl_node));
+        for entry in kdl_node.entries_mut() {
+            let entry_is_substitutable = match entry.name() {
+                Some(property_name) => is_substitutable(property_name.value()),
+                None => node_is_substitutable,
+            };
+            if !entry_is_substitutable {
+                continue;
+            }
+            if let Some(value) = entry.value().as_string() {
+                let mut substituted = value.to_owned();
+                for (arg_name, arg_value) in layout_args {
+                    substituted = substituted.replace(&format!("{{{{{}}}}}", arg_name), arg_value);
+                }
+                if substituted != value {
+                    entry.set_value(substituted);
+                }
+            }
+        }
+        if let Some(children) = kdl_node.children_mut().as_mut() {
+            substitute_layout_args(children, layout_args);
+        }
+    }
+}
+
+fn offset_spans(node: &mut KdlNode, offset: usize) {
+    let offset_span =
+        |span: &SourceSpan| SourceSpan::new((span.offset() + offset).into(), span.len().into());
+    node.set_span(offset_span(node.span()));
+    let name_span = offset_span(node.name().span());
+    node.name_mut().set_span(name_span);
+    for entry in node.entries_mut() {
+        entry.set_span(offset_span(entry.span()));
+    }
+    if let Some(children) = node.children_mut() {
+        children.set_span(offset_span(children.span()));
+        for child in children.nodes_mut() {
+            offset_spans(child, offset);
         }
     }
 }
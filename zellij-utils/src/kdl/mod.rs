@@ -1,16 +1,20 @@
 mod kdl_layout_parser;
 use crate::data::{
-    Direction, FloatingPaneCoordinates, InputMode, Key, LayoutInfo, Palette, PaletteColor,
-    PaneInfo, PaneManifest, PermissionType, Resize, SessionInfo, TabInfo,
+    ClientId, ConnectedClientInfo, Direction, FloatingPaneCoordinates, InputMode, Key, LayoutInfo,
+    Palette, PaletteColor, PaneInfo, PaneManifest, PermissionType, Resize, SessionInfo, TabInfo,
 };
 use crate::envs::EnvironmentVariables;
 use crate::home::{find_default_config_dir, get_layout_dir};
 use crate::input::config::{Config, ConfigError, KdlError};
+use crate::input::hooks::{Hook, HookEvent, Hooks};
 use crate::input::keybinds::Keybinds;
 use crate::input::layout::{Layout, RunPlugin, RunPluginOrAlias};
-use crate::input::options::{Clipboard, OnForceClose, Options};
+use crate::input::options::{ClientSizePolicy, Clipboard, OnForceClose, Options};
 use crate::input::permission::{GrantedPermission, PermissionCache};
 use crate::input::plugins::PluginAliases;
+#[cfg(not(target_family = "wasm"))]
+use crate::input::project_layout::{LayoutTrustCache, TrustedLayouts};
+use crate::input::scratchpads::{Scratchpad, Scratchpads};
 use crate::input::theme::{FrameConfig, Theme, Themes, UiConfig};
 use kdl_layout_parser::KdlLayoutParser;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -25,7 +29,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use crate::input::actions::{Action, SearchDirection, SearchOption};
-use crate::input::command::RunCommandAction;
+use crate::input::command::{RestartPolicy, RunCommand, RunCommandAction};
 
 #[macro_export]
 macro_rules! parse_kdl_action_arguments {
@@ -64,6 +68,10 @@ macro_rules! parse_kdl_action_arguments {
                 "CloseTab" => Ok(Action::CloseTab),
                 "ToggleTab" => Ok(Action::ToggleTab),
                 "UndoRenameTab" => Ok(Action::UndoRenameTab),
+                "StartSuspendedPanes" => Ok(Action::StartSuspendedPanes),
+                "ResizeToClient" => Ok(Action::ResizeToClient),
+                "LockSession" => Ok(Action::LockSession),
+                "ToggleKeepSession" => Ok(Action::ToggleKeepSession),
                 "Detach" => Ok(Action::Detach),
                 "Copy" => Ok(Action::Copy),
                 "Confirm" => Ok(Action::Confirm),
@@ -71,6 +79,11 @@ macro_rules! parse_kdl_action_arguments {
                 "ToggleMouseMode" => Ok(Action::ToggleMouseMode),
                 "PreviousSwapLayout" => Ok(Action::PreviousSwapLayout),
                 "NextSwapLayout" => Ok(Action::NextSwapLayout),
+                "Undo" => Ok(Action::Undo),
+                "Redo" => Ok(Action::Redo),
+                "UnstackPanes" => Ok(Action::UnstackPanes),
+                "RemovePaneFromStack" => Ok(Action::RemovePaneFromStack),
+                "TogglePaneSticky" => Ok(Action::TogglePaneSticky),
                 "Clear" => Ok(Action::ClearScreen),
                 _ => Err(ConfigError::new_kdl_error(
                     format!("Unsupported action: {:?}", $action_name),
@@ -460,6 +473,16 @@ impl Action {
                 })?;
                 Ok(Action::MoveFocus(direction))
             },
+            "StackPane" => {
+                let direction = Direction::from_str(string.as_str()).map_err(|_| {
+                    ConfigError::new_kdl_error(
+                        format!("Invalid direction: '{}'", string),
+                        action_node.span().offset(),
+                        action_node.span().len(),
+                    )
+                })?;
+                Ok(Action::StackPane(direction))
+            },
             "MoveFocusOrTab" => {
                 let direction = Direction::from_str(string.as_str()).map_err(|_| {
                     ConfigError::new_kdl_error(
@@ -541,6 +564,7 @@ impl Action {
                 Ok(Action::Search(search_direction))
             },
             "RenameSession" => Ok(Action::RenameSession(string)),
+            "ToggleScratchpad" => Ok(Action::ToggleScratchpad(string)),
             _ => Err(ConfigError::new_kdl_error(
                 format!("Unsupported action: {}", action_name),
                 action_node.span().offset(),
@@ -734,6 +758,18 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             "UndoRenameTab" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
+            "StartSuspendedPanes" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ResizeToClient" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "LockSession" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
+            "ToggleKeepSession" => {
+                parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
+            },
             "ToggleMouseMode" => {
                 parse_kdl_action_arguments!(action_name, action_arguments, kdl_action)
             },
@@ -773,6 +809,11 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "StackPane" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
             "MoveTab" => parse_kdl_action_char_or_string_arguments!(
                 action_name,
                 action_arguments,
@@ -936,6 +977,31 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 let in_place = command_metadata
                     .and_then(|c_m| kdl_child_bool_value_for_entry(c_m, "in_place"))
                     .unwrap_or(false);
+                let env = match command_metadata.and_then(|c_m| c_m.get("env")) {
+                    Some(env_block) => EnvironmentVariables::from_kdl(env_block)?
+                        .inner()
+                        .clone()
+                        .into_iter()
+                        .collect(),
+                    None => BTreeMap::new(),
+                };
+                let restart = match command_metadata
+                    .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "restart"))
+                {
+                    Some(restart) => RestartPolicy::from_str(restart).map_err(|e| {
+                        ConfigError::new_kdl_error(
+                            e,
+                            kdl_action.span().offset(),
+                            kdl_action.span().len(),
+                        )
+                    })?,
+                    None => RestartPolicy::default(),
+                };
+                let max_restarts = command_metadata
+                    .and_then(|c_m| c_m.get("max_restarts"))
+                    .and_then(|max_restarts| max_restarts.entries().iter().next())
+                    .and_then(|max_restarts| max_restarts.value().as_i64())
+                    .and_then(|max_restarts| usize::try_from(max_restarts).ok());
                 let run_command_action = RunCommandAction {
                     command: PathBuf::from(command),
                     args,
@@ -943,6 +1009,9 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                     direction,
                     hold_on_close,
                     hold_on_start,
+                    env,
+                    restart,
+                    max_restarts,
                 };
                 let x = command_metadata
                     .and_then(|c_m| kdl_child_string_value_for_entry(c_m, "x"))
@@ -972,6 +1041,38 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                     ))
                 }
             },
+            "SaveLayout" => {
+                let arguments = action_arguments.iter().copied();
+                let mut args = kdl_arguments_that_are_strings(arguments)?;
+                if args.is_empty() {
+                    return Err(ConfigError::new_kdl_error(
+                        "SaveLayout needs the name of the layout to save".into(),
+                        kdl_action.span().offset(),
+                        kdl_action.span().len(),
+                    ));
+                }
+                let name = args.remove(0);
+                let layout_metadata = action_children.iter().next();
+                let tab = layout_metadata
+                    .and_then(|l_m| kdl_child_string_value_for_entry(l_m, "tab"))
+                    .map(|tab| tab.to_owned());
+                let strip_contents = layout_metadata
+                    .and_then(|l_m| kdl_child_bool_value_for_entry(l_m, "strip_contents"))
+                    .unwrap_or(false);
+                let relative_to = layout_metadata
+                    .and_then(|l_m| kdl_child_string_value_for_entry(l_m, "relative_to"))
+                    .map(PathBuf::from);
+                let force = layout_metadata
+                    .and_then(|l_m| kdl_child_bool_value_for_entry(l_m, "force"))
+                    .unwrap_or(false);
+                Ok(Action::SaveLayout {
+                    name,
+                    tab,
+                    strip_contents,
+                    relative_to,
+                    force,
+                })
+            },
             "LaunchOrFocusPlugin" => {
                 let arguments = action_arguments.iter().copied();
                 let mut args = kdl_arguments_that_are_strings(arguments)?;
@@ -1071,6 +1172,11 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
             },
             "PreviousSwapLayout" => Ok(Action::PreviousSwapLayout),
             "NextSwapLayout" => Ok(Action::NextSwapLayout),
+            "Undo" => Ok(Action::Undo),
+            "Redo" => Ok(Action::Redo),
+            "UnstackPanes" => Ok(Action::UnstackPanes),
+            "RemovePaneFromStack" => Ok(Action::RemovePaneFromStack),
+            "TogglePaneSticky" => Ok(Action::TogglePaneSticky),
             "BreakPane" => Ok(Action::BreakPane),
             "BreakPaneRight" => Ok(Action::BreakPaneRight),
             "BreakPaneLeft" => Ok(Action::BreakPaneLeft),
@@ -1079,6 +1185,40 @@ impl TryFrom<(&KdlNode, &Options)> for Action {
                 action_arguments,
                 kdl_action
             ),
+            "ToggleScratchpad" => parse_kdl_action_char_or_string_arguments!(
+                action_name,
+                action_arguments,
+                kdl_action
+            ),
+            "FollowClient" => {
+                let client_id = match action_arguments.get(0) {
+                    Some(argument) => Some(
+                        argument
+                            .value()
+                            .as_i64()
+                            .and_then(|client_id| ClientId::try_from(client_id).ok())
+                            .ok_or_else(|| {
+                                ConfigError::new_kdl_error(
+                                    "FollowClient expects a client id".into(),
+                                    kdl_action.span().offset(),
+                                    kdl_action.span().len(),
+                                )
+                            })?,
+                    ),
+                    None => None,
+                };
+                Ok(Action::FollowClient(client_id))
+            },
+            "SetTabCwd" => {
+                let arguments = action_arguments.iter().copied();
+                let mut args = kdl_arguments_that_are_strings(arguments)?;
+                let cwd = if args.is_empty() {
+                    None
+                } else {
+                    Some(PathBuf::from(args.remove(0)))
+                };
+                Ok(Action::SetTabCwd(cwd))
+            },
             "MessagePlugin" => {
                 let arguments = action_arguments.iter().copied();
                 let mut args = kdl_arguments_that_are_strings(arguments)?;
@@ -1188,6 +1328,35 @@ macro_rules! kdl_property_first_arg_as_string_or_error {
     }};
 }
 
+#[macro_export]
+macro_rules! kdl_property_args_as_strings_or_error {
+    ( $kdl_node:expr, $property_name:expr ) => {{
+        match $kdl_node.get($property_name) {
+            Some(property) => {
+                let mut strings = vec![];
+                for entry in property.entries() {
+                    match entry.value().as_string() {
+                        Some(string_entry) => strings.push(string_entry.to_owned()),
+                        None => {
+                            return Err(ConfigError::new_kdl_error(
+                                format!(
+                                    "Property {} must be a list of strings, found: {}",
+                                    $property_name,
+                                    entry.value()
+                                ),
+                                property.span().offset(),
+                                property.span().len(),
+                            ));
+                        },
+                    }
+                }
+                Some(strings)
+            },
+            None => None,
+        }
+    }};
+}
+
 #[macro_export]
 macro_rules! kdl_property_first_arg_as_bool_or_error {
     ( $kdl_node:expr, $property_name:expr ) => {{
@@ -1608,6 +1777,54 @@ impl Options {
         let serialization_interval =
             kdl_property_first_arg_as_i64_or_error!(kdl_options, "serialization_interval")
                 .map(|(scroll_buffer_size, _entry)| scroll_buffer_size as u64);
+        let session_snapshot_count =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "session_snapshot_count")
+                .map(|(v, _)| v as usize);
+        let session_snapshot_max_age =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "session_snapshot_max_age")
+                .map(|(v, _)| v as u64);
+        let resurrect_run_commands =
+            kdl_property_args_as_strings_or_error!(kdl_options, "resurrect_run_commands");
+        let resurrect_suspend_commands =
+            kdl_property_args_as_strings_or_error!(kdl_options, "resurrect_suspend_commands");
+        let project_layouts =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "project_layouts")
+                .map(|(v, _)| v);
+        let client_size_policy =
+            match kdl_property_first_arg_as_string_or_error!(kdl_options, "client_size_policy") {
+                Some((string, entry)) => {
+                    Some(ClientSizePolicy::from_str(string).map_err(|_| {
+                        kdl_parsing_error!(
+                            format!("Invalid value for client_size_policy: '{}'", string),
+                            entry
+                        )
+                    })?)
+                },
+                None => None,
+            };
+        let client_name = kdl_property_first_arg_as_string_or_error!(kdl_options, "client_name")
+            .map(|(client_name, _entry)| client_name.to_string());
+        let lock_session_passphrase_hash =
+            kdl_property_first_arg_as_string_or_error!(kdl_options, "lock_session_passphrase_hash")
+                .map(|(hash, _entry)| hash.to_string());
+        let auto_lock_session_after =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "auto_lock_session_after")
+                .map(|(seconds, _entry)| seconds as u64);
+        let detach_idle_clients_after =
+            kdl_property_first_arg_as_i64_or_error!(kdl_options, "detach_idle_clients_after")
+                .map(|(seconds, _entry)| seconds as u64);
+        let kill_session_when_detached_after = kdl_property_first_arg_as_i64_or_error!(
+            kdl_options,
+            "kill_session_when_detached_after"
+        )
+        .map(|(seconds, _entry)| seconds as u64);
+        let kill_session_when_all_panes_exited = kdl_property_first_arg_as_bool_or_error!(
+            kdl_options,
+            "kill_session_when_all_panes_exited"
+        )
+        .map(|(v, _)| v);
+        let keep_session =
+            kdl_property_first_arg_as_bool_or_error!(kdl_options, "keep_session").map(|(v, _)| v);
         Ok(Options {
             simplified_ui,
             theme,
@@ -1634,6 +1851,19 @@ impl Options {
             scrollback_lines_to_serialize,
             styled_underlines,
             serialization_interval,
+            session_snapshot_count,
+            session_snapshot_max_age,
+            resurrect_run_commands,
+            resurrect_suspend_commands,
+            project_layouts,
+            client_size_policy,
+            client_name,
+            lock_session_passphrase_hash,
+            auto_lock_session_after,
+            detach_idle_clients_after,
+            kill_session_when_detached_after,
+            kill_session_when_all_panes_exited,
+            keep_session,
         })
     }
 }
@@ -1645,40 +1875,71 @@ impl Layout {
         raw_swap_layouts: Option<(&str, &str)>, // raw_swap_layouts swap_layouts_file_name
         cwd: Option<PathBuf>,
     ) -> Result<Self, ConfigError> {
-        let mut kdl_layout_parser = KdlLayoutParser::new(raw_layout, cwd, file_name.clone());
-        let layout = kdl_layout_parser.parse().map_err(|e| match e {
-            ConfigError::KdlError(kdl_error) => {
-                ConfigError::KdlError(kdl_error.add_src(file_name, String::from(raw_layout)))
-            },
-            ConfigError::KdlDeserializationError(kdl_error) => {
-                kdl_layout_error(kdl_error, file_name, raw_layout)
-            },
-            e => e,
-        })?;
+        Layout::from_kdl_with_layout_args(
+            raw_layout,
+            file_name,
+            raw_swap_layouts,
+            cwd,
+            BTreeMap::new(),
+            None,
+        )
+    }
+    pub fn from_kdl_with_layout_args(
+        raw_layout: &str,
+        file_name: String,
+        raw_swap_layouts: Option<(&str, &str)>, // raw_swap_layouts swap_layouts_file_name
+        cwd: Option<PathBuf>,
+        layout_args: BTreeMap<String, String>,
+        layout_dir: Option<PathBuf>,
+    ) -> Result<Self, ConfigError> {
+        let mut kdl_layout_parser =
+            KdlLayoutParser::new(raw_layout, cwd, file_name.clone(), layout_args, layout_dir);
+        let layout = kdl_layout_parser
+            .parse()
+            .map_err(|e| kdl_layout_parser.layout_error_with_src(e))?;
         match raw_swap_layouts {
             Some((raw_swap_layout_filename, raw_swap_layout)) => {
                 // here we use the same parser to parse the swap layout so that we can reuse assets
                 // (eg. pane and tab templates)
                 kdl_layout_parser
                     .parse_external_swap_layouts(raw_swap_layout, layout)
-                    .map_err(|e| match e {
-                        ConfigError::KdlError(kdl_error) => {
-                            ConfigError::KdlError(kdl_error.add_src(
-                                String::from(raw_swap_layout_filename),
-                                String::from(raw_swap_layout),
-                            ))
-                        },
-                        ConfigError::KdlDeserializationError(kdl_error) => kdl_layout_error(
-                            kdl_error,
-                            raw_swap_layout_filename.into(),
-                            raw_swap_layout,
-                        ),
-                        e => e,
+                    .map_err(|e| {
+                        layout_error_with_src(e, raw_swap_layout_filename.into(), raw_swap_layout)
                     })
             },
             None => Ok(layout),
         }
     }
+    /// The names and contents of the layout files included by this layout, including those
+    /// included by them in turn
+    pub fn included_layout_files(
+        raw_layout: &str,
+        file_name: String,
+        layout_dir: Option<PathBuf>,
+    ) -> Result<Vec<(String, String)>, ConfigError> {
+        let mut kdl_layout_parser =
+            KdlLayoutParser::new(raw_layout, None, file_name, BTreeMap::new(), layout_dir);
+        kdl_layout_parser
+            .included_layout_files()
+            .map_err(|e| kdl_layout_parser.layout_error_with_src(e))
+    }
+}
+
+pub(crate) fn layout_error_with_src(
+    e: ConfigError,
+    file_name: String,
+    raw_layout: &str,
+) -> ConfigError {
+    match e {
+        // errors that already have a source come from an included layout file
+        ConfigError::KdlError(kdl_error) if kdl_error.src.is_none() => {
+            ConfigError::KdlError(kdl_error.add_src(file_name, String::from(raw_layout)))
+        },
+        ConfigError::KdlDeserializationError(kdl_error) => {
+            kdl_layout_error(kdl_error, file_name, raw_layout)
+        },
+        e => e,
+    }
 }
 
 fn kdl_layout_error(kdl_error: kdl::KdlError, file_name: String, raw_layout: &str) -> ConfigError {
@@ -1890,6 +2151,14 @@ impl Config {
             let config_plugins = PluginAliases::from_kdl(kdl_plugin_aliases)?;
             config.plugins.merge(config_plugins);
         }
+        if let Some(kdl_scratchpads) = kdl_config.get("scratchpads") {
+            let config_scratchpads = Scratchpads::from_kdl(kdl_scratchpads)?;
+            config.scratchpads.merge(config_scratchpads);
+        }
+        if let Some(kdl_hooks) = kdl_config.get("hooks") {
+            let config_hooks = Hooks::from_kdl(kdl_hooks, &config.options)?;
+            config.hooks.merge(config_hooks);
+        }
         if let Some(kdl_ui_config) = kdl_config.get("ui") {
             let config_ui = UiConfig::from_kdl(&kdl_ui_config)?;
             config.ui = config.ui.merge(config_ui);
@@ -1927,6 +2196,94 @@ impl PluginAliases {
     }
 }
 
+impl Scratchpads {
+    pub fn from_kdl(kdl_scratchpads: &KdlNode) -> Result<Scratchpads, ConfigError> {
+        let mut scratchpads: BTreeMap<String, Scratchpad> = BTreeMap::new();
+        if let Some(kdl_scratchpads) = kdl_children_nodes!(kdl_scratchpads) {
+            for scratchpad_definition in kdl_scratchpads {
+                let scratchpad_name = kdl_name!(scratchpad_definition);
+                let command =
+                    kdl_get_string_property_or_child_value!(scratchpad_definition, "command")
+                        .ok_or(ConfigError::new_kdl_error(
+                            format!("Scratchpad {} must have a command", scratchpad_name),
+                            scratchpad_definition.span().offset(),
+                            scratchpad_definition.span().len(),
+                        ))?;
+                let args = match kdl_get_child!(scratchpad_definition, "args") {
+                    Some(kdl_args) => kdl_string_arguments!(kdl_args)
+                        .iter()
+                        .map(|s| String::from(*s))
+                        .collect(),
+                    None => vec![],
+                };
+                let cwd = kdl_get_string_property_or_child_value!(scratchpad_definition, "cwd")
+                    .map(|s| PathBuf::from(s));
+                // coordinates can be either fixed (eg. 10) or a quoted percent (eg. "10%")
+                let coordinate = |name: &str| {
+                    kdl_get_string_property_or_child_value!(scratchpad_definition, name)
+                        .map(|s| s.to_owned())
+                        .or_else(|| {
+                            kdl_get_int_property_or_child_value!(scratchpad_definition, name)
+                                .map(|i| i.to_string())
+                        })
+                };
+                let coordinates = FloatingPaneCoordinates::new(
+                    coordinate("x"),
+                    coordinate("y"),
+                    coordinate("width"),
+                    coordinate("height"),
+                );
+                let scratchpad = Scratchpad {
+                    command: RunCommand {
+                        command: PathBuf::from(command),
+                        args,
+                        cwd,
+                        hold_on_close: true,
+                        ..Default::default()
+                    },
+                    coordinates,
+                };
+                scratchpads.insert(scratchpad_name.to_owned(), scratchpad);
+            }
+        }
+        Ok(Scratchpads { scratchpads })
+    }
+}
+
+impl Hooks {
+    pub fn from_kdl(kdl_hooks: &KdlNode, config_options: &Options) -> Result<Hooks, ConfigError> {
+        let mut hooks: BTreeMap<HookEvent, Vec<Hook>> = BTreeMap::new();
+        if let Some(kdl_hooks) = kdl_children_nodes!(kdl_hooks) {
+            for hook_definition in kdl_hooks {
+                let event_name = kdl_name!(hook_definition);
+                let event = HookEvent::from_str(event_name).map_err(|e| {
+                    ConfigError::new_kdl_error(
+                        e,
+                        hook_definition.span().offset(),
+                        hook_definition.span().len(),
+                    )
+                })?;
+                // either a shell command (eg. tab_created "notify-send hi") or a block of actions
+                let hook = match kdl_first_entry_as_string!(hook_definition) {
+                    Some(command) => Hook::Command(command.to_owned()),
+                    None => {
+                        let actions = kdl_children_nodes_or_error!(
+                            hook_definition,
+                            format!("Hook {} must have a command or actions", event_name)
+                        )
+                        .iter()
+                        .map(|kdl_action| Action::try_from((kdl_action, config_options)))
+                        .collect::<Result<_, _>>()?;
+                        Hook::Actions(actions)
+                    },
+                };
+                hooks.entry(event).or_insert_with(Vec::new).push(hook);
+            }
+        }
+        Ok(Hooks { hooks })
+    }
+}
+
 impl UiConfig {
     pub fn from_kdl(kdl_ui_config: &KdlNode) -> Result<UiConfig, ConfigError> {
         let mut ui_config = UiConfig::default();
@@ -2061,6 +2418,41 @@ impl PermissionCache {
     }
 }
 
+#[cfg(not(target_family = "wasm"))]
+impl LayoutTrustCache {
+    pub fn from_string(raw_string: String) -> Result<TrustedLayouts, ConfigError> {
+        let kdl_document: KdlDocument = raw_string.parse()?;
+
+        let mut trusted_layouts = TrustedLayouts::default();
+
+        for node in kdl_document.nodes() {
+            let layout_path = kdl_name!(node);
+            if let Some(contents) = kdl_get_string_property_or_child_value!(node, "contents") {
+                trusted_layouts.insert(PathBuf::from(layout_path), contents.to_owned());
+            }
+        }
+
+        Ok(trusted_layouts)
+    }
+
+    pub fn to_string(trusted_layouts: &TrustedLayouts) -> String {
+        let mut kdl_document = KdlDocument::new();
+
+        trusted_layouts.iter().for_each(|(layout_path, contents)| {
+            let mut node = KdlNode::new(layout_path.display().to_string().as_str());
+            let mut children = KdlDocument::new();
+            let mut contents_node = KdlNode::new("contents");
+            contents_node.push(contents.as_str());
+            children.nodes_mut().push(contents_node);
+            node.set_children(children);
+            kdl_document.nodes_mut().push(node);
+        });
+
+        kdl_document.fmt();
+        kdl_document.to_string()
+    }
+}
+
 impl SessionInfo {
     pub fn from_string(raw_session_info: &str, current_session_name: &str) -> Result<Self, String> {
         let kdl_document: KdlDocument = raw_session_info
@@ -2121,6 +2513,17 @@ impl SessionInfo {
                     .collect()
             })
             .ok_or("Failed to parse available_layouts")?;
+        // sessions written before clients were listed do not have this node
+        let clients: Vec<ConnectedClientInfo> = kdl_document
+            .get("clients")
+            .and_then(|c| c.children())
+            .map(|c| {
+                c.nodes()
+                    .iter()
+                    .filter_map(|n| ConnectedClientInfo::decode_from_kdl(n).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
         let is_current_session = name == current_session_name;
         Ok(SessionInfo {
             name,
@@ -2129,6 +2532,7 @@ impl SessionInfo {
             connected_clients,
             is_current_session,
             available_layouts,
+            clients,
         })
     }
     pub fn to_string(&self) -> String {
@@ -2167,16 +2571,75 @@ impl SessionInfo {
         }
         available_layouts.set_children(available_layouts_children);
 
+        let mut clients = KdlNode::new("clients");
+        let mut clients_children = KdlDocument::new();
+        for client in &self.clients {
+            clients_children.nodes_mut().push(client.encode_to_kdl());
+        }
+        clients.set_children(clients_children);
+
         kdl_document.nodes_mut().push(name);
         kdl_document.nodes_mut().push(tabs);
         kdl_document.nodes_mut().push(panes);
         kdl_document.nodes_mut().push(connected_clients);
         kdl_document.nodes_mut().push(available_layouts);
+        kdl_document.nodes_mut().push(clients);
         kdl_document.fmt();
         kdl_document.to_string()
     }
 }
 
+impl ConnectedClientInfo {
+    pub fn decode_from_kdl(kdl_node: &KdlNode) -> Result<Self, String> {
+        let client_id = kdl_node
+            .entries()
+            .iter()
+            .find(|e| e.name().is_none())
+            .and_then(|e| e.value().as_i64())
+            .map(|c| c as ClientId)
+            .ok_or("Failed to parse client id")?;
+        let property = |name: &str| {
+            kdl_node
+                .entries()
+                .iter()
+                .find(|e| e.name().map(|n| n.value()) == Some(name))
+                .map(|e| e.value())
+        };
+        Ok(ConnectedClientInfo {
+            client_id,
+            name: property("name")
+                .and_then(|v| v.as_string())
+                .map(|s| s.to_owned()),
+            following: property("following")
+                .and_then(|v| v.as_i64())
+                .map(|c| c as ClientId),
+            is_read_only: property("read_only")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        })
+    }
+    pub fn encode_to_kdl(&self) -> KdlNode {
+        let mut kdl_node = KdlNode::new("client");
+        kdl_node.push(self.client_id as i64);
+        if let Some(name) = &self.name {
+            kdl_node
+                .entries_mut()
+                .push(KdlEntry::new_prop("name", name.clone()));
+        }
+        if let Some(following) = self.following {
+            kdl_node
+                .entries_mut()
+                .push(KdlEntry::new_prop("following", following as i64));
+        }
+        if self.is_read_only {
+            kdl_node
+                .entries_mut()
+                .push(KdlEntry::new_prop("read_only", true));
+        }
+        kdl_node
+    }
+}
+
 impl TabInfo {
     pub fn decode_from_kdl(kdl_document: &KdlDocument) -> Result<Self, String> {
         macro_rules! int_node {
@@ -2238,6 +2701,7 @@ impl TabInfo {
         }
         let active_swap_layout_name = optional_string_node!("active_swap_layout_name");
         let is_swap_layout_dirty = bool_node!("is_swap_layout_dirty");
+        let cwd = optional_string_node!("cwd").map(PathBuf::from);
         Ok(TabInfo {
             position,
             name,
@@ -2249,6 +2713,7 @@ impl TabInfo {
             other_focused_clients,
             active_swap_layout_name,
             is_swap_layout_dirty,
+            cwd,
         })
     }
     pub fn encode_to_kdl(&self) -> KdlDocument {
@@ -2300,6 +2765,12 @@ impl TabInfo {
         is_swap_layout_dirty.push(self.is_swap_layout_dirty);
         kdl_doucment.nodes_mut().push(is_swap_layout_dirty);
 
+        if let Some(cwd) = self.cwd.as_ref() {
+            let mut cwd_node = KdlNode::new("cwd");
+            cwd_node.push(cwd.display().to_string());
+            kdl_doucment.nodes_mut().push(cwd_node);
+        }
+
         kdl_doucment
     }
 }
@@ -2427,6 +2898,7 @@ impl PaneInfo {
         let terminal_command = optional_string_node!("terminal_command");
         let plugin_url = optional_string_node!("plugin_url");
         let is_selectable = bool_node!("is_selectable");
+        let cwd = optional_string_node!("cwd").map(PathBuf::from);
 
         let pane_info = PaneInfo {
             id,
@@ -2451,6 +2923,7 @@ impl PaneInfo {
             terminal_command,
             plugin_url,
             is_selectable,
+            cwd,
         };
         Ok((tab_position, pane_info))
     }
@@ -2511,6 +2984,9 @@ impl PaneInfo {
             string_node!("plugin_url", plugin_url.to_string());
         }
         bool_node!("is_selectable", self.is_selectable);
+        if let Some(cwd) = &self.cwd {
+            string_node!("cwd", cwd.display().to_string());
+        }
         kdl_doucment
     }
 }
@@ -2598,6 +3074,7 @@ fn serialize_and_deserialize_session_info_with_data() {
             terminal_command: Some("foo".to_owned()),
             plugin_url: None,
             is_selectable: true,
+            cwd: Some(PathBuf::from("/path/to/backend")),
         },
         PaneInfo {
             id: 1,
@@ -2622,6 +3099,7 @@ fn serialize_and_deserialize_session_info_with_data() {
             terminal_command: None,
             plugin_url: Some("i_am_a_fake_plugin".to_owned()),
             is_selectable: true,
+            cwd: None,
         },
     ];
     let mut panes = HashMap::new();
@@ -2640,6 +3118,7 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: Some("BASE".to_owned()),
                 is_swap_layout_dirty: true,
+                cwd: Some(PathBuf::from("/path/to/backend")),
             },
             TabInfo {
                 position: 1,
@@ -2652,6 +3131,7 @@ fn serialize_and_deserialize_session_info_with_data() {
                 other_focused_clients: vec![2, 3],
                 active_swap_layout_name: None,
                 is_swap_layout_dirty: false,
+                cwd: None,
             },
         ],
         panes: PaneManifest { panes },
@@ -2662,6 +3142,20 @@ fn serialize_and_deserialize_session_info_with_data() {
             LayoutInfo::BuiltIn("layout2".to_owned()),
             LayoutInfo::File("layout3".to_owned()),
         ],
+        clients: vec![
+            ConnectedClientInfo {
+                client_id: 1,
+                name: Some("presenter".to_owned()),
+                following: None,
+                is_read_only: false,
+            },
+            ConnectedClientInfo {
+                client_id: 2,
+                name: None,
+                following: Some(1),
+                is_read_only: true,
+            },
+        ],
     };
     let serialized = session_info.to_string();
     let deserealized = SessionInfo::from_string(&serialized, "not this session").unwrap();
@@ -22,6 +22,12 @@ pub mod downloader; // Requires async_std
 pub mod ipc; // Requires interprocess
 #[cfg(not(target_family = "wasm"))]
 pub mod logging; // Requires log4rs
+#[cfg(not(target_family = "wasm"))]
+pub mod session_archive; // Requires tar and zstd
+#[cfg(not(target_family = "wasm"))]
+pub mod session_lock; // Requires argon2
+#[cfg(not(target_family = "wasm"))]
+pub mod session_snapshots; // Requires humantime
 
 #[cfg(not(target_family = "wasm"))]
 pub use ::{
@@ -57,6 +57,10 @@ pub struct SizeInPixels {
 pub struct Dimension {
     pub constraint: Constraint,
     pub(crate) inner: usize,
+    #[serde(default)]
+    pub(crate) min_size: Option<usize>,
+    #[serde(default)]
+    pub(crate) max_size: Option<usize>,
 }
 
 impl Default for Dimension {
@@ -70,6 +74,8 @@ impl Dimension {
         Self {
             constraint: Constraint::Fixed(size),
             inner: size,
+            min_size: None,
+            max_size: None,
         }
     }
 
@@ -77,6 +83,8 @@ impl Dimension {
         Self {
             constraint: Constraint::Percent(percent),
             inner: 1,
+            min_size: None,
+            max_size: None,
         }
     }
 
@@ -125,6 +133,29 @@ impl Dimension {
         self.inner -= by;
     }
 
+    pub fn min_size(&self) -> Option<usize> {
+        self.min_size
+    }
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+    /// Set the bounds (in rows or columns) this dimension should be kept within when it is
+    /// flexible, eg. when the terminal is resized or a neighbouring pane grows
+    pub fn set_size_bounds(&mut self, min_size: Option<usize>, max_size: Option<usize>) {
+        self.min_size = min_size;
+        self.max_size = max_size;
+    }
+    pub fn has_size_bounds(&self) -> bool {
+        self.min_size.is_some() || self.max_size.is_some()
+    }
+    pub fn clamp_to_size_bounds(&self, size: usize) -> usize {
+        let size = self.max_size.map(|max| size.min(max)).unwrap_or(size);
+        self.min_size.map(|min| size.max(min)).unwrap_or(size)
+    }
+    pub fn is_within_size_bounds(&self, size: usize) -> bool {
+        self.clamp_to_size_bounds(size) == size
+    }
+
     pub fn is_fixed(&self) -> bool {
         matches!(self.constraint, Constraint::Fixed(_))
     }
@@ -133,13 +164,11 @@ impl Dimension {
     }
     pub fn from_split_size(split_size: SplitSize, full_size: usize) -> Self {
         match split_size {
-            SplitSize::Fixed(fixed) => Dimension {
-                constraint: Constraint::Fixed(fixed),
-                inner: fixed,
-            },
-            SplitSize::Percent(percent) => Dimension {
-                constraint: Constraint::Percent(percent as f64),
-                inner: ((percent as f64 / 100.0) * full_size as f64).floor() as usize,
+            SplitSize::Fixed(fixed) => Dimension::fixed(fixed),
+            SplitSize::Percent(percent) => {
+                let mut dimension = Dimension::percent(percent as f64);
+                dimension.set_inner(((percent as f64 / 100.0) * full_size as f64).floor() as usize);
+                dimension
             },
         }
     }
@@ -6,18 +6,18 @@ pub use super::generated_api::api::{
         NameAndValue as ProtobufNameAndValue, NewFloatingPanePayload, NewPanePayload,
         NewPluginPanePayload, NewTiledPanePayload, PaneIdAndShouldFloat,
         PluginConfiguration as ProtobufPluginConfiguration, Position as ProtobufPosition,
-        RunCommandAction as ProtobufRunCommandAction, ScrollAtPayload,
+        RunCommandAction as ProtobufRunCommandAction, SaveLayoutPayload, ScrollAtPayload,
         SearchDirection as ProtobufSearchDirection, SearchOption as ProtobufSearchOption,
         SwitchToModePayload, WriteCharsPayload, WritePayload,
     },
     input_mode::InputMode as ProtobufInputMode,
     resize::{Resize as ProtobufResize, ResizeDirection as ProtobufResizeDirection},
 };
-use crate::data::{Direction, InputMode, ResizeStrategy};
+use crate::data::{ClientId, Direction, InputMode, ResizeStrategy};
 use crate::errors::prelude::*;
 use crate::input::actions::Action;
 use crate::input::actions::{SearchDirection, SearchOption};
-use crate::input::command::RunCommandAction;
+use crate::input::command::{RestartPolicy, RunCommandAction};
 use crate::input::layout::{
     PluginUserConfiguration, RunPlugin, RunPluginLocation, RunPluginOrAlias,
 };
@@ -26,6 +26,7 @@ use crate::position::Position;
 use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 impl TryFrom<ProtobufAction> for Action {
     type Error = &'static str;
@@ -542,6 +543,86 @@ impl TryFrom<ProtobufAction> for Action {
                 Some(_) => Err("NextSwapLayout should not have a payload"),
                 None => Ok(Action::NextSwapLayout),
             },
+            Some(ProtobufActionName::Undo) => match protobuf_action.optional_payload {
+                Some(_) => Err("Undo should not have a payload"),
+                None => Ok(Action::Undo),
+            },
+            Some(ProtobufActionName::Redo) => match protobuf_action.optional_payload {
+                Some(_) => Err("Redo should not have a payload"),
+                None => Ok(Action::Redo),
+            },
+            Some(ProtobufActionName::StackPane) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::StackPanePayload(stack_pane_payload)) => {
+                    let direction: Direction =
+                        ProtobufResizeDirection::from_i32(stack_pane_payload)
+                            .ok_or("Malformed resize direction for Action::StackPane")?
+                            .try_into()?;
+                    Ok(Action::StackPane(direction))
+                },
+                _ => Err("Wrong payload for Action::StackPane"),
+            },
+            Some(ProtobufActionName::UnstackPanes) => match protobuf_action.optional_payload {
+                Some(_) => Err("UnstackPanes should not have a payload"),
+                None => Ok(Action::UnstackPanes),
+            },
+            Some(ProtobufActionName::RemovePaneFromStack) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("RemovePaneFromStack should not have a payload"),
+                    None => Ok(Action::RemovePaneFromStack),
+                }
+            },
+            Some(ProtobufActionName::TogglePaneSticky) => match protobuf_action.optional_payload {
+                Some(_) => Err("TogglePaneSticky should not have a payload"),
+                None => Ok(Action::TogglePaneSticky),
+            },
+            Some(ProtobufActionName::ToggleScratchpad) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::ToggleScratchpadPayload(name)) => {
+                    Ok(Action::ToggleScratchpad(name))
+                },
+                _ => Err("Wrong payload for Action::ToggleScratchpad"),
+            },
+            Some(ProtobufActionName::SaveLayout) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::SaveLayoutPayload(payload)) => Ok(Action::SaveLayout {
+                    name: payload.name,
+                    tab: payload.tab,
+                    strip_contents: payload.strip_contents,
+                    relative_to: payload.relative_to.map(PathBuf::from),
+                    force: payload.force,
+                }),
+                _ => Err("Wrong payload for Action::SaveLayout"),
+            },
+            Some(ProtobufActionName::SetTabCwd) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::SetTabCwdPayload(cwd)) => {
+                    Ok(Action::SetTabCwd(Some(PathBuf::from(cwd))))
+                },
+                None => Ok(Action::SetTabCwd(None)),
+                _ => Err("Wrong payload for Action::SetTabCwd"),
+            },
+            Some(ProtobufActionName::StartSuspendedPanes) => {
+                match protobuf_action.optional_payload {
+                    Some(_) => Err("StartSuspendedPanes should not have a payload"),
+                    None => Ok(Action::StartSuspendedPanes),
+                }
+            },
+            Some(ProtobufActionName::ResizeToClient) => match protobuf_action.optional_payload {
+                Some(_) => Err("ResizeToClient should not have a payload"),
+                None => Ok(Action::ResizeToClient),
+            },
+            Some(ProtobufActionName::FollowClient) => match protobuf_action.optional_payload {
+                Some(OptionalPayload::FollowClientPayload(client_id)) => {
+                    Ok(Action::FollowClient(Some(client_id as ClientId)))
+                },
+                None => Ok(Action::FollowClient(None)),
+                _ => Err("Wrong payload for Action::FollowClient"),
+            },
+            Some(ProtobufActionName::LockSession) => match protobuf_action.optional_payload {
+                Some(_) => Err("LockSession should not have a payload"),
+                None => Ok(Action::LockSession),
+            },
+            Some(ProtobufActionName::ToggleKeepSession) => match protobuf_action.optional_payload {
+                Some(_) => Err("ToggleKeepSession should not have a payload"),
+                None => Ok(Action::ToggleKeepSession),
+            },
             Some(ProtobufActionName::QueryTabNames) => match protobuf_action.optional_payload {
                 Some(_) => Err("QueryTabNames should not have a payload"),
                 None => Ok(Action::QueryTabNames),
@@ -1176,6 +1257,79 @@ impl TryFrom<Action> for ProtobufAction {
                 name: ProtobufActionName::NextSwapLayout as i32,
                 optional_payload: None,
             }),
+            Action::Undo => Ok(ProtobufAction {
+                name: ProtobufActionName::Undo as i32,
+                optional_payload: None,
+            }),
+            Action::Redo => Ok(ProtobufAction {
+                name: ProtobufActionName::Redo as i32,
+                optional_payload: None,
+            }),
+            Action::StackPane(direction) => {
+                let direction: ProtobufResizeDirection = direction.try_into()?;
+                Ok(ProtobufAction {
+                    name: ProtobufActionName::StackPane as i32,
+                    optional_payload: Some(OptionalPayload::StackPanePayload(direction as i32)),
+                })
+            },
+            Action::UnstackPanes => Ok(ProtobufAction {
+                name: ProtobufActionName::UnstackPanes as i32,
+                optional_payload: None,
+            }),
+            Action::RemovePaneFromStack => Ok(ProtobufAction {
+                name: ProtobufActionName::RemovePaneFromStack as i32,
+                optional_payload: None,
+            }),
+            Action::TogglePaneSticky => Ok(ProtobufAction {
+                name: ProtobufActionName::TogglePaneSticky as i32,
+                optional_payload: None,
+            }),
+            Action::ToggleScratchpad(name) => Ok(ProtobufAction {
+                name: ProtobufActionName::ToggleScratchpad as i32,
+                optional_payload: Some(OptionalPayload::ToggleScratchpadPayload(name)),
+            }),
+            Action::SaveLayout {
+                name,
+                tab,
+                strip_contents,
+                relative_to,
+                force,
+            } => Ok(ProtobufAction {
+                name: ProtobufActionName::SaveLayout as i32,
+                optional_payload: Some(OptionalPayload::SaveLayoutPayload(SaveLayoutPayload {
+                    name,
+                    tab,
+                    strip_contents,
+                    relative_to: relative_to.map(|r| r.display().to_string()),
+                    force,
+                })),
+            }),
+            Action::SetTabCwd(cwd) => Ok(ProtobufAction {
+                name: ProtobufActionName::SetTabCwd as i32,
+                optional_payload: cwd
+                    .map(|cwd| OptionalPayload::SetTabCwdPayload(cwd.display().to_string())),
+            }),
+            Action::StartSuspendedPanes => Ok(ProtobufAction {
+                name: ProtobufActionName::StartSuspendedPanes as i32,
+                optional_payload: None,
+            }),
+            Action::ResizeToClient => Ok(ProtobufAction {
+                name: ProtobufActionName::ResizeToClient as i32,
+                optional_payload: None,
+            }),
+            Action::FollowClient(client_id) => Ok(ProtobufAction {
+                name: ProtobufActionName::FollowClient as i32,
+                optional_payload: client_id
+                    .map(|client_id| OptionalPayload::FollowClientPayload(client_id as u32)),
+            }),
+            Action::LockSession => Ok(ProtobufAction {
+                name: ProtobufActionName::LockSession as i32,
+                optional_payload: None,
+            }),
+            Action::ToggleKeepSession => Ok(ProtobufAction {
+                name: ProtobufActionName::ToggleKeepSession as i32,
+                optional_payload: None,
+            }),
             Action::QueryTabNames => Ok(ProtobufAction {
                 name: ProtobufActionName::QueryTabNames as i32,
                 optional_payload: None,
@@ -1377,6 +1531,18 @@ impl TryFrom<ProtobufRunCommandAction> for RunCommandAction {
             .and_then(|d| d.try_into().ok());
         let hold_on_close = protobuf_run_command_action.hold_on_close;
         let hold_on_start = protobuf_run_command_action.hold_on_start;
+        let env = protobuf_run_command_action
+            .env
+            .into_iter()
+            .map(|e| (e.name, e.value))
+            .collect();
+        let restart = match protobuf_run_command_action.restart {
+            Some(restart) => {
+                RestartPolicy::from_str(&restart).map_err(|_| "Unknown restart policy")?
+            },
+            None => RestartPolicy::default(),
+        };
+        let max_restarts = protobuf_run_command_action.max_restarts.map(|m| m as usize);
         Ok(RunCommandAction {
             command,
             args,
@@ -1384,6 +1550,9 @@ impl TryFrom<ProtobufRunCommandAction> for RunCommandAction {
             direction,
             hold_on_close,
             hold_on_start,
+            env,
+            restart,
+            max_restarts,
         })
     }
 }
@@ -1400,6 +1569,13 @@ impl TryFrom<RunCommandAction> for ProtobufRunCommandAction {
         });
         let hold_on_close = run_command_action.hold_on_close;
         let hold_on_start = run_command_action.hold_on_start;
+        let env = run_command_action
+            .env
+            .into_iter()
+            .map(|(name, value)| ProtobufNameAndValue { name, value })
+            .collect();
+        let restart = Some(run_command_action.restart.to_string());
+        let max_restarts = run_command_action.max_restarts.map(|m| m as u32);
         Ok(ProtobufRunCommandAction {
             command,
             args,
@@ -1408,6 +1584,9 @@ impl TryFrom<RunCommandAction> for ProtobufRunCommandAction {
             hold_on_close,
             hold_on_start,
             pane_name: None,
+            env,
+            restart,
+            max_restarts,
         })
     }
 }
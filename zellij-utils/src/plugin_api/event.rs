@@ -1,14 +1,15 @@
 pub use super::generated_api::api::{
     action::{Action as ProtobufAction, Position as ProtobufPosition},
     event::{
-        event::Payload as ProtobufEventPayload, CopyDestination as ProtobufCopyDestination,
-        Event as ProtobufEvent, EventNameList as ProtobufEventNameList,
-        EventType as ProtobufEventType, FileMetadata as ProtobufFileMetadata,
-        InputModeKeybinds as ProtobufInputModeKeybinds, KeyBind as ProtobufKeyBind,
-        LayoutInfo as ProtobufLayoutInfo, ModeUpdatePayload as ProtobufModeUpdatePayload,
-        PaneInfo as ProtobufPaneInfo, PaneManifest as ProtobufPaneManifest,
-        ResurrectableSession as ProtobufResurrectableSession,
-        SessionManifest as ProtobufSessionManifest, TabInfo as ProtobufTabInfo, *,
+        event::Payload as ProtobufEventPayload, ConnectedClientInfo as ProtobufConnectedClientInfo,
+        CopyDestination as ProtobufCopyDestination, Event as ProtobufEvent,
+        EventNameList as ProtobufEventNameList, EventType as ProtobufEventType,
+        FileMetadata as ProtobufFileMetadata, InputModeKeybinds as ProtobufInputModeKeybinds,
+        KeyBind as ProtobufKeyBind, LayoutInfo as ProtobufLayoutInfo,
+        ModeUpdatePayload as ProtobufModeUpdatePayload, PaneInfo as ProtobufPaneInfo,
+        PaneManifest as ProtobufPaneManifest, ResurrectableSession as ProtobufResurrectableSession,
+        SessionManifest as ProtobufSessionManifest, SessionSnapshot as ProtobufSessionSnapshot,
+        TabInfo as ProtobufTabInfo, *,
     },
     input_mode::InputMode as ProtobufInputMode,
     key::Key as ProtobufKey,
@@ -16,8 +17,9 @@ pub use super::generated_api::api::{
 };
 #[allow(hidden_glob_reexports)]
 use crate::data::{
-    CopyDestination, Event, EventType, FileMetadata, InputMode, Key, LayoutInfo, ModeInfo, Mouse,
-    PaneInfo, PaneManifest, PermissionStatus, PluginCapabilities, SessionInfo, Style, TabInfo,
+    ConnectedClientInfo, CopyDestination, Event, EventType, FileMetadata, InputMode, Key,
+    LayoutInfo, ModeInfo, Mouse, PaneInfo, PaneManifest, PermissionStatus, PluginCapabilities,
+    SessionInfo, Style, TabInfo,
 };
 
 use crate::errors::prelude::*;
@@ -234,6 +236,29 @@ impl TryFrom<ProtobufEvent> for Event {
                 },
                 _ => Err("Malformed payload for the WebRequestResult Event"),
             },
+            Some(ProtobufEventType::CommandPaneRestarted) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::CommandPaneRestartedPayload(
+                    command_pane_restarted_payload,
+                )) => Ok(Event::CommandPaneRestarted(
+                    command_pane_restarted_payload.terminal_pane_id,
+                    command_pane_restarted_payload.exit_code,
+                    command_pane_restarted_payload.restart_count as usize,
+                )),
+                _ => Err("Malformed payload for the CommandPaneRestarted Event"),
+            },
+            Some(ProtobufEventType::SessionSnapshots) => match protobuf_event.payload {
+                Some(ProtobufEventPayload::SessionSnapshotsPayload(session_snapshots_payload)) => {
+                    Ok(Event::SessionSnapshots(
+                        session_snapshots_payload.session_name,
+                        session_snapshots_payload
+                            .snapshots
+                            .iter()
+                            .map(|s| (s.timestamp, Duration::from_secs(s.age)))
+                            .collect(),
+                    ))
+                },
+                _ => Err("Malformed payload for the SessionSnapshots Event"),
+            },
             None => Err("Unknown Protobuf Event"),
         }
     }
@@ -459,6 +484,37 @@ impl TryFrom<Event> for ProtobufEvent {
                     )),
                 })
             },
+            Event::CommandPaneRestarted(terminal_pane_id, exit_code, restart_count) => {
+                let command_pane_restarted_payload = CommandPaneRestartedPayload {
+                    terminal_pane_id,
+                    exit_code,
+                    restart_count: restart_count as u32,
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::CommandPaneRestarted as i32,
+                    payload: Some(event::Payload::CommandPaneRestartedPayload(
+                        command_pane_restarted_payload,
+                    )),
+                })
+            },
+            Event::SessionSnapshots(session_name, snapshots) => {
+                let session_snapshots_payload = SessionSnapshotsPayload {
+                    session_name,
+                    snapshots: snapshots
+                        .iter()
+                        .map(|(timestamp, age)| ProtobufSessionSnapshot {
+                            timestamp: *timestamp,
+                            age: age.as_secs(),
+                        })
+                        .collect(),
+                };
+                Ok(ProtobufEvent {
+                    name: ProtobufEventType::SessionSnapshots as i32,
+                    payload: Some(event::Payload::SessionSnapshotsPayload(
+                        session_snapshots_payload,
+                    )),
+                })
+            },
         }
     }
 }
@@ -492,10 +548,33 @@ impl TryFrom<SessionInfo> for ProtobufSessionManifest {
                 .into_iter()
                 .filter_map(|l| ProtobufLayoutInfo::try_from(l).ok())
                 .collect(),
+            clients: session_info.clients.into_iter().map(|c| c.into()).collect(),
         })
     }
 }
 
+impl From<ConnectedClientInfo> for ProtobufConnectedClientInfo {
+    fn from(client_info: ConnectedClientInfo) -> Self {
+        ProtobufConnectedClientInfo {
+            client_id: client_info.client_id as u32,
+            name: client_info.name,
+            following: client_info.following.map(|c| c as u32),
+            is_read_only: client_info.is_read_only,
+        }
+    }
+}
+
+impl From<ProtobufConnectedClientInfo> for ConnectedClientInfo {
+    fn from(protobuf_client_info: ProtobufConnectedClientInfo) -> Self {
+        ConnectedClientInfo {
+            client_id: protobuf_client_info.client_id as u16,
+            name: protobuf_client_info.name,
+            following: protobuf_client_info.following.map(|c| c as u16),
+            is_read_only: protobuf_client_info.is_read_only,
+        }
+    }
+}
+
 impl TryFrom<ProtobufSessionManifest> for SessionInfo {
     type Error = &'static str;
     fn try_from(protobuf_session_manifest: ProtobufSessionManifest) -> Result<Self, &'static str> {
@@ -529,6 +608,11 @@ impl TryFrom<ProtobufSessionManifest> for SessionInfo {
                 .into_iter()
                 .filter_map(|l| LayoutInfo::try_from(l).ok())
                 .collect(),
+            clients: protobuf_session_manifest
+                .clients
+                .into_iter()
+                .map(|c| c.into())
+                .collect(),
         })
     }
 }
@@ -715,6 +799,7 @@ impl TryFrom<ProtobufPaneInfo> for PaneInfo {
             terminal_command: protobuf_pane_info.terminal_command,
             plugin_url: protobuf_pane_info.plugin_url,
             is_selectable: protobuf_pane_info.is_selectable,
+            cwd: protobuf_pane_info.cwd.map(PathBuf::from),
         })
     }
 }
@@ -750,6 +835,7 @@ impl TryFrom<PaneInfo> for ProtobufPaneInfo {
             terminal_command: pane_info.terminal_command,
             plugin_url: pane_info.plugin_url,
             is_selectable: pane_info.is_selectable,
+            cwd: pane_info.cwd.map(|cwd| cwd.display().to_string()),
         })
     }
 }
@@ -772,6 +858,7 @@ impl TryFrom<ProtobufTabInfo> for TabInfo {
                 .collect(),
             active_swap_layout_name: protobuf_tab_info.active_swap_layout_name,
             is_swap_layout_dirty: protobuf_tab_info.is_swap_layout_dirty,
+            cwd: protobuf_tab_info.cwd.map(PathBuf::from),
         })
     }
 }
@@ -794,6 +881,7 @@ impl TryFrom<TabInfo> for ProtobufTabInfo {
                 .collect(),
             active_swap_layout_name: tab_info.active_swap_layout_name,
             is_swap_layout_dirty: tab_info.is_swap_layout_dirty,
+            cwd: tab_info.cwd.map(|cwd| cwd.display().to_string()),
         })
     }
 }
@@ -844,6 +932,7 @@ impl TryFrom<ProtobufModeUpdatePayload> for ModeInfo {
             style,
             capabilities,
             session_name,
+            is_read_only: protobuf_mode_update_payload.is_read_only,
         };
         Ok(mode_info)
     }
@@ -856,6 +945,7 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
         let style: ProtobufStyle = mode_info.style.try_into()?;
         let arrow_fonts_support: bool = mode_info.capabilities.arrow_fonts;
         let session_name = mode_info.session_name;
+        let is_read_only = mode_info.is_read_only;
         let mut protobuf_input_mode_keybinds: Vec<ProtobufInputModeKeybinds> = vec![];
         for (input_mode, input_mode_keybinds) in mode_info.keybinds {
             let mode: ProtobufInputMode = input_mode.try_into()?;
@@ -886,6 +976,7 @@ impl TryFrom<ModeInfo> for ProtobufModeUpdatePayload {
             keybinds: protobuf_input_mode_keybinds,
             arrow_fonts_support,
             session_name,
+            is_read_only,
         })
     }
 }
@@ -943,6 +1034,8 @@ impl TryFrom<ProtobufEventType> for EventType {
             ProtobufEventType::SessionUpdate => EventType::SessionUpdate,
             ProtobufEventType::RunCommandResult => EventType::RunCommandResult,
             ProtobufEventType::WebRequestResult => EventType::WebRequestResult,
+            ProtobufEventType::CommandPaneRestarted => EventType::CommandPaneRestarted,
+            ProtobufEventType::SessionSnapshots => EventType::SessionSnapshots,
         })
     }
 }
@@ -970,6 +1063,8 @@ impl TryFrom<EventType> for ProtobufEventType {
             EventType::SessionUpdate => ProtobufEventType::SessionUpdate,
             EventType::RunCommandResult => ProtobufEventType::RunCommandResult,
             EventType::WebRequestResult => ProtobufEventType::WebRequestResult,
+            EventType::CommandPaneRestarted => ProtobufEventType::CommandPaneRestarted,
+            EventType::SessionSnapshots => ProtobufEventType::SessionSnapshots,
         })
     }
 }
@@ -1102,6 +1197,7 @@ fn serialize_mode_update_event_with_non_default_values() {
         },
         capabilities: PluginCapabilities { arrow_fonts: false },
         session_name: Some("my awesome test session".to_owned()),
+        is_read_only: true,
     });
     let protobuf_event: ProtobufEvent = mode_update_event.clone().try_into().unwrap();
     let serialized_protobuf_event = protobuf_event.encode_to_vec();
@@ -1144,6 +1240,7 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            cwd: Some(PathBuf::from("/path/to/backend")),
         },
         TabInfo {
             position: 1,
@@ -1156,6 +1253,7 @@ fn serialize_tab_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            cwd: None,
         },
         TabInfo::default(),
     ]);
@@ -1422,6 +1520,7 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![2, 3, 4],
             active_swap_layout_name: Some("my cool swap layout".to_owned()),
             is_swap_layout_dirty: false,
+            cwd: Some(PathBuf::from("/path/to/backend")),
         },
         TabInfo {
             position: 1,
@@ -1434,6 +1533,7 @@ fn serialize_session_update_event_with_non_default_values() {
             other_focused_clients: vec![1, 5, 111],
             active_swap_layout_name: None,
             is_swap_layout_dirty: true,
+            cwd: None,
         },
         TabInfo::default(),
     ];
@@ -1462,6 +1562,7 @@ fn serialize_session_update_event_with_non_default_values() {
             terminal_command: Some("foo".to_owned()),
             plugin_url: None,
             is_selectable: true,
+            cwd: Some(PathBuf::from("/path/to/backend")),
         },
         PaneInfo {
             id: 1,
@@ -1486,6 +1587,7 @@ fn serialize_session_update_event_with_non_default_values() {
             terminal_command: None,
             plugin_url: Some("i_am_a_fake_plugin".to_owned()),
             is_selectable: true,
+            cwd: None,
         },
     ];
     panes.insert(0, panes_list);
@@ -1500,6 +1602,20 @@ fn serialize_session_update_event_with_non_default_values() {
             LayoutInfo::BuiltIn("layout2".to_owned()),
             LayoutInfo::File("layout3".to_owned()),
         ],
+        clients: vec![
+            ConnectedClientInfo {
+                client_id: 1,
+                name: Some("presenter".to_owned()),
+                following: None,
+                is_read_only: false,
+            },
+            ConnectedClientInfo {
+                client_id: 2,
+                name: None,
+                following: Some(1),
+                is_read_only: true,
+            },
+        ],
     };
     let session_info_2 = SessionInfo {
         name: "session 2".to_owned(),
@@ -1514,6 +1630,7 @@ fn serialize_session_update_event_with_non_default_values() {
             LayoutInfo::BuiltIn("layout2".to_owned()),
             LayoutInfo::File("layout3".to_owned()),
         ],
+        clients: vec![],
     };
     let session_infos = vec![session_info_1, session_info_2];
     let resurrectable_sessions = vec![];
@@ -1529,3 +1646,39 @@ fn serialize_session_update_event_with_non_default_values() {
         "Event properly serialized/deserialized without change"
     );
 }
+
+#[test]
+fn serialize_command_pane_restarted_event() {
+    use prost::Message;
+    let command_pane_restarted_event = Event::CommandPaneRestarted(1, Some(2), 3);
+    let protobuf_event: ProtobufEvent = command_pane_restarted_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        command_pane_restarted_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
+
+#[test]
+fn serialize_session_snapshots_event() {
+    use prost::Message;
+    let session_snapshots_event = Event::SessionSnapshots(
+        "my-session".to_owned(),
+        vec![
+            (1700000000, Duration::from_secs(60)),
+            (1700003600, Duration::from_secs(0)),
+        ],
+    );
+    let protobuf_event: ProtobufEvent = session_snapshots_event.clone().try_into().unwrap();
+    let serialized_protobuf_event = protobuf_event.encode_to_vec();
+    let deserialized_protobuf_event: ProtobufEvent =
+        Message::decode(serialized_protobuf_event.as_slice()).unwrap();
+    let deserialized_event: Event = deserialized_protobuf_event.try_into().unwrap();
+    assert_eq!(
+        session_snapshots_event, deserialized_event,
+        "Event properly serialized/deserialized without change"
+    );
+}
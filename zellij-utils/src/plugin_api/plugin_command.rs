@@ -1,5 +1,5 @@
 pub use super::generated_api::api::{
-    action::{PaneIdAndShouldFloat, SwitchToModePayload},
+    action::{PaneIdAndShouldFloat, SaveLayoutPayload, SwitchToModePayload},
     event::{EventNameList as ProtobufEventNameList, Header},
     input_mode::InputMode as ProtobufInputMode,
     plugin_command::{
@@ -11,9 +11,9 @@ pub use super::generated_api::api::{
         NewPluginArgs as ProtobufNewPluginArgs, OpenCommandPanePayload, OpenFilePayload,
         PaneId as ProtobufPaneId, PaneType as ProtobufPaneType,
         PluginCommand as ProtobufPluginCommand, PluginMessagePayload,
-        RequestPluginPermissionPayload, ResizePayload, RunCommandPayload, SetTimeoutPayload,
-        SubscribePayload, SwitchSessionPayload, SwitchTabToPayload, UnsubscribePayload,
-        WebRequestPayload,
+        RequestPluginPermissionPayload, ResizePayload, RestoreSessionSnapshotPayload,
+        RunCommandPayload, SetTimeoutPayload, SubscribePayload, SwitchSessionPayload,
+        SwitchTabToPayload, UnsubscribePayload, WebRequestPayload,
     },
     plugin_permission::PermissionType as ProtobufPermissionType,
     resize::ResizeAction as ProtobufResizeAction,
@@ -867,6 +867,55 @@ impl TryFrom<ProtobufPluginCommand> for PluginCommand {
                 Some(_) => Err("WatchFilesystem should have no payload, found a payload"),
                 None => Ok(PluginCommand::WatchFilesystem),
             },
+            Some(CommandName::StackPane) => match protobuf_plugin_command.payload {
+                Some(Payload::StackPanePayload(move_payload)) => match move_payload.direction {
+                    Some(direction) => Ok(PluginCommand::StackPane(direction.try_into()?)),
+                    None => Err("Malformed stack pane payload"),
+                },
+                _ => Err("Mismatched payload for StackPane"),
+            },
+            Some(CommandName::UnstackPanes) => match protobuf_plugin_command.payload {
+                Some(_) => Err("UnstackPanes should have no payload, found a payload"),
+                None => Ok(PluginCommand::UnstackPanes),
+            },
+            Some(CommandName::RemovePaneFromStack) => match protobuf_plugin_command.payload {
+                Some(_) => Err("RemovePaneFromStack should have no payload, found a payload"),
+                None => Ok(PluginCommand::RemovePaneFromStack),
+            },
+            Some(CommandName::SaveLayout) => match protobuf_plugin_command.payload {
+                Some(Payload::SaveLayoutPayload(save_layout_payload)) => {
+                    Ok(PluginCommand::SaveLayout(
+                        save_layout_payload.name,
+                        save_layout_payload.tab,
+                        save_layout_payload.strip_contents,
+                        save_layout_payload.relative_to.map(PathBuf::from),
+                        save_layout_payload.force,
+                    ))
+                },
+                _ => Err("Mismatched payload for SaveLayout"),
+            },
+            Some(CommandName::SetTabCwd) => match protobuf_plugin_command.payload {
+                Some(Payload::SetTabCwdPayload(cwd)) => {
+                    Ok(PluginCommand::SetTabCwd(Some(PathBuf::from(cwd))))
+                },
+                None => Ok(PluginCommand::SetTabCwd(None)),
+                _ => Err("Mismatched payload for SetTabCwd"),
+            },
+            Some(CommandName::ListSessionSnapshots) => match protobuf_plugin_command.payload {
+                Some(Payload::ListSessionSnapshotsPayload(session_name)) => {
+                    Ok(PluginCommand::ListSessionSnapshots(session_name))
+                },
+                _ => Err("Mismatched payload for ListSessionSnapshots"),
+            },
+            Some(CommandName::RestoreSessionSnapshot) => match protobuf_plugin_command.payload {
+                Some(Payload::RestoreSessionSnapshotPayload(restore_session_snapshot_payload)) => {
+                    Ok(PluginCommand::RestoreSessionSnapshot(
+                        restore_session_snapshot_payload.session_name,
+                        restore_session_snapshot_payload.timestamp,
+                    ))
+                },
+                _ => Err("Mismatched payload for RestoreSessionSnapshot"),
+            },
             None => Err("Unrecognized plugin command"),
         }
     }
@@ -1381,6 +1430,51 @@ impl TryFrom<PluginCommand> for ProtobufPluginCommand {
                 name: CommandName::WatchFilesystem as i32,
                 payload: None,
             }),
+            PluginCommand::StackPane(direction) => Ok(ProtobufPluginCommand {
+                name: CommandName::StackPane as i32,
+                payload: Some(Payload::StackPanePayload(MovePayload {
+                    direction: Some(direction.try_into()?),
+                })),
+            }),
+            PluginCommand::UnstackPanes => Ok(ProtobufPluginCommand {
+                name: CommandName::UnstackPanes as i32,
+                payload: None,
+            }),
+            PluginCommand::RemovePaneFromStack => Ok(ProtobufPluginCommand {
+                name: CommandName::RemovePaneFromStack as i32,
+                payload: None,
+            }),
+            PluginCommand::SaveLayout(name, tab, strip_contents, relative_to, force) => {
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::SaveLayout as i32,
+                    payload: Some(Payload::SaveLayoutPayload(SaveLayoutPayload {
+                        name,
+                        tab,
+                        strip_contents,
+                        relative_to: relative_to.map(|r| r.display().to_string()),
+                        force,
+                    })),
+                })
+            },
+            PluginCommand::SetTabCwd(cwd) => Ok(ProtobufPluginCommand {
+                name: CommandName::SetTabCwd as i32,
+                payload: cwd.map(|cwd| Payload::SetTabCwdPayload(cwd.display().to_string())),
+            }),
+            PluginCommand::ListSessionSnapshots(session_name) => Ok(ProtobufPluginCommand {
+                name: CommandName::ListSessionSnapshots as i32,
+                payload: Some(Payload::ListSessionSnapshotsPayload(session_name)),
+            }),
+            PluginCommand::RestoreSessionSnapshot(session_name, timestamp) => {
+                Ok(ProtobufPluginCommand {
+                    name: CommandName::RestoreSessionSnapshot as i32,
+                    payload: Some(Payload::RestoreSessionSnapshotPayload(
+                        RestoreSessionSnapshotPayload {
+                            session_name,
+                            timestamp,
+                        },
+                    )),
+                })
+            },
         }
     }
 }
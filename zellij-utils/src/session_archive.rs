@@ -0,0 +1,346 @@
+//! Portable archives of resurrectable sessions (`zellij export-session` / `zellij import-session`),
+//! so that a session can be handed off to and resurrected on another machine.
+//!
+//! An archive is a zstd compressed tarball holding a single folder named after the session, with
+//! the serialized layout of the session, the pane contents files it refers to and its metadata.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use kdl::{KdlDocument, KdlNode};
+
+const LAYOUT_FILE_NAME: &str = "session-layout.kdl";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionArchive {
+    pub session_name: String,
+    pub files: BTreeMap<String, Vec<u8>>, // file name -> contents
+}
+
+impl SessionArchive {
+    /// Reads the files of a session from its session info folder, leaving out its snapshots
+    pub fn from_session_folder(session_name: &str, session_folder: &Path) -> io::Result<Self> {
+        let session_not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Session {:?} not found", session_name),
+            )
+        };
+        let mut files = BTreeMap::new();
+        for entry in fs::read_dir(session_folder).map_err(|_| session_not_found())? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                // pane contents can hold anything the panes printed, so they are kept as bytes
+                files.insert(file_name, fs::read(entry.path())?);
+            }
+        }
+        if !files.contains_key(LAYOUT_FILE_NAME) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Session {:?} has no serialized layout", session_name),
+            ));
+        }
+        Ok(SessionArchive {
+            session_name: session_name.to_owned(),
+            files,
+        })
+    }
+    pub fn write(&self, writer: impl Write) -> io::Result<()> {
+        let encoder = zstd::Encoder::new(writer, 0)?;
+        let mut tarball = tar::Builder::new(encoder);
+        for (file_name, contents) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tarball.append_data(
+                &mut header,
+                Path::new(&self.session_name).join(file_name),
+                contents.as_slice(),
+            )?;
+        }
+        tarball.into_inner()?.finish()?;
+        Ok(())
+    }
+    pub fn read(reader: impl Read) -> io::Result<Self> {
+        let invalid_archive = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let decoder = zstd::Decoder::new(reader)?;
+        let mut tarball = tar::Archive::new(decoder);
+        let mut session_name = None;
+        let mut files = BTreeMap::new();
+        for entry in tarball.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_path_buf();
+            let mut components = path.components();
+            // archives are passed around between machines, so we only accept files directly
+            // inside the session folder (eg. no `..` or absolute paths)
+            let (Some(Component::Normal(folder)), Some(Component::Normal(file_name)), None) =
+                (components.next(), components.next(), components.next())
+            else {
+                return Err(invalid_archive("Unexpected file in session archive"));
+            };
+            let folder = folder.to_string_lossy().to_string();
+            if session_name.get_or_insert_with(|| folder.clone()) != &folder {
+                return Err(invalid_archive(
+                    "Session archive holds more than one session",
+                ));
+            }
+            let mut contents = vec![];
+            entry.read_to_end(&mut contents)?;
+            files.insert(file_name.to_string_lossy().to_string(), contents);
+        }
+        if !files.contains_key(LAYOUT_FILE_NAME) {
+            return Err(invalid_archive("Session archive has no serialized layout"));
+        }
+        Ok(SessionArchive {
+            session_name: session_name.unwrap_or_default(),
+            files,
+        })
+    }
+    /// Writes the session files to its (new) session info folder, making it resurrectable.
+    ///
+    /// An archive can come from anyone, so all of its commands are made to start suspended
+    /// when the session is resurrected, whatever the archive asks for.
+    pub fn install(&self, session_folder: &Path) -> io::Result<()> {
+        let mut layout = self.parsed_layout()?;
+        for node in layout.nodes_mut() {
+            suspend_commands(node);
+        }
+        layout.fmt();
+        fs::create_dir_all(session_folder)?;
+        for (file_name, contents) in &self.files {
+            if file_name == LAYOUT_FILE_NAME {
+                fs::write(session_folder.join(file_name), layout.to_string())?;
+            } else {
+                fs::write(session_folder.join(file_name), contents)?;
+            }
+        }
+        Ok(())
+    }
+    fn parsed_layout(&self) -> io::Result<KdlDocument> {
+        let invalid_layout = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Failed to parse the serialized session layout",
+            )
+        };
+        let layout = self
+            .files
+            .get(LAYOUT_FILE_NAME)
+            .ok_or_else(invalid_layout)?;
+        std::str::from_utf8(layout)
+            .map_err(|_| invalid_layout())?
+            .parse()
+            .map_err(|_| invalid_layout())
+    }
+    /// The folder the cwds of the panes in the session are relative to
+    pub fn global_cwd(&self) -> Option<PathBuf> {
+        let layout = self.parsed_layout().ok()?;
+        layout
+            .get("layout")?
+            .children()?
+            .get_arg("cwd")?
+            .as_string()
+            .map(PathBuf::from)
+    }
+    /// Replaces the folder the cwds of the panes are relative to, removing it if `None` so that
+    /// they can later be made relative to another folder (eg. on another machine)
+    pub fn set_global_cwd(&mut self, cwd: Option<&Path>) -> io::Result<()> {
+        let invalid_layout = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Failed to parse the serialized session layout",
+            )
+        };
+        let mut layout = self.parsed_layout()?;
+        let layout_children = layout
+            .get_mut("layout")
+            .and_then(|l| l.children_mut().as_mut())
+            .ok_or_else(invalid_layout)?;
+        layout_children
+            .nodes_mut()
+            .retain(|node| node.name().value() != "cwd");
+        if let Some(cwd) = cwd {
+            let mut cwd_node = KdlNode::new("cwd");
+            cwd_node.push(cwd.display().to_string());
+            layout_children.nodes_mut().insert(0, cwd_node);
+        }
+        layout.fmt();
+        self.files
+            .insert(LAYOUT_FILE_NAME.to_owned(), layout.to_string().into_bytes());
+        Ok(())
+    }
+}
+
+/// Makes the command of this pane (and of the panes nested in it) start suspended, dropping
+/// anything that would run it right away on resurrection. Layouts can give each of these as a
+/// property or as a child node, so both are handled.
+fn suspend_commands(node: &mut KdlNode) {
+    const RUNS_ON_RESURRECTION: [&str; 2] = ["start_suspended", "rerun_on_resurrect"];
+    let is_command_pane = node.get("command").is_some()
+        || node
+            .children()
+            .map(|children| children.get("command").is_some())
+            .unwrap_or(false);
+    if is_command_pane {
+        node.entries_mut().retain(|entry| {
+            entry
+                .name()
+                .map(|name| !RUNS_ON_RESURRECTION.contains(&name.value()))
+                .unwrap_or(true)
+        });
+        if node.children().is_none() {
+            node.set_children(KdlDocument::new());
+        }
+    }
+    if let Some(children) = node.children_mut() {
+        if is_command_pane {
+            children
+                .nodes_mut()
+                .retain(|child| !RUNS_ON_RESURRECTION.contains(&child.name().value()));
+            let mut start_suspended = KdlNode::new("start_suspended");
+            start_suspended.push(true);
+            children.nodes_mut().push(start_suspended);
+        }
+        for child in children.nodes_mut() {
+            suspend_commands(child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn session_folder_with_files(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let folder = tempdir().unwrap();
+        for (file_name, contents) in files {
+            fs::write(folder.path().join(file_name), contents).unwrap();
+        }
+        fs::create_dir_all(folder.path().join("snapshots").join("1700000000")).unwrap();
+        folder
+    }
+
+    #[test]
+    fn session_archive_round_trip() {
+        let session_folder = session_folder_with_files(&[
+            (
+                "session-layout.kdl",
+                "layout {\n    cwd \"/home/me/project\"\n    tab {\n        pane cwd=\"src\" contents_file=\"initial_contents_1\"\n    }\n}\n",
+            ),
+            ("initial_contents_1", "$ cargo test\n"),
+        ]);
+        let archive =
+            SessionArchive::from_session_folder("debugging", session_folder.path()).unwrap();
+        assert_eq!(
+            archive.files.len(),
+            2,
+            "snapshots are left out of the archive"
+        );
+        let mut tarball = vec![];
+        archive.write(&mut tarball).unwrap();
+        let read_archive = SessionArchive::read(tarball.as_slice()).unwrap();
+        assert_eq!(archive, read_archive);
+
+        let imported_folder = tempdir().unwrap();
+        read_archive.install(imported_folder.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(imported_folder.path().join("initial_contents_1")).unwrap(),
+            "$ cargo test\n"
+        );
+    }
+
+    #[test]
+    fn session_archive_global_cwd_can_be_removed_and_replaced() {
+        let session_folder = session_folder_with_files(&[(
+            "session-layout.kdl",
+            "layout {\n    cwd \"/home/me/project\"\n    tab {\n        pane cwd=\"src\"\n    }\n}\n",
+        )]);
+        let mut archive =
+            SessionArchive::from_session_folder("debugging", session_folder.path()).unwrap();
+        assert_eq!(
+            archive.global_cwd(),
+            Some(PathBuf::from("/home/me/project"))
+        );
+        archive.set_global_cwd(None).unwrap();
+        assert_eq!(archive.global_cwd(), None);
+        assert_eq!(
+            archive.files.get("session-layout.kdl").unwrap(),
+            b"layout {\n    tab {\n        pane cwd=\"src\"\n    }\n}\n"
+        );
+        archive
+            .set_global_cwd(Some(Path::new("/home/teammate/project")))
+            .unwrap();
+        assert_eq!(
+            archive.files.get("session-layout.kdl").unwrap(),
+            b"layout {\n    cwd \"/home/teammate/project\"\n    tab {\n        pane cwd=\"src\"\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn installed_session_archive_starts_all_commands_suspended() {
+        let session_folder = session_folder_with_files(&[(
+            "session-layout.kdl",
+            "layout {\n    tab {\n        pane command=\"htop\"\n        pane command=\"rm\" {\n            args \"-rf\" \"~\"\n            start_suspended false\n            rerun_on_resurrect true\n        }\n        pane\n    }\n}\n",
+        )]);
+        let archive =
+            SessionArchive::from_session_folder("debugging", session_folder.path()).unwrap();
+        let imported_folder = tempdir().unwrap();
+        archive.install(imported_folder.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(imported_folder.path().join("session-layout.kdl")).unwrap(),
+            "layout {\n    tab {\n        pane command=\"htop\" {\n            start_suspended true\n        }\n        pane command=\"rm\" {\n            args \"-rf\" \"~\"\n            start_suspended true\n        }\n        pane\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn installed_session_archive_suspends_commands_given_as_properties_or_children() {
+        let session_folder = session_folder_with_files(&[(
+            "session-layout.kdl",
+            "layout {\n    tab {\n        pane command=\"rm\" start_suspended=false rerun_on_resurrect=true\n        pane {\n            command \"rm\"\n            start_suspended false\n        }\n    }\n}\n",
+        )]);
+        let archive =
+            SessionArchive::from_session_folder("debugging", session_folder.path()).unwrap();
+        let imported_folder = tempdir().unwrap();
+        archive.install(imported_folder.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(imported_folder.path().join("session-layout.kdl")).unwrap(),
+            "layout {\n    tab {\n        pane command=\"rm\" {\n            start_suspended true\n        }\n        pane {\n            command \"rm\"\n            start_suspended true\n        }\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn session_archive_keeps_pane_contents_that_are_not_utf8() {
+        let session_folder = session_folder_with_files(&[("session-layout.kdl", "layout {\n}\n")]);
+        fs::write(
+            session_folder.path().join("initial_contents_1"),
+            b"caf\xe9\n".as_slice(),
+        )
+        .unwrap();
+        let archive =
+            SessionArchive::from_session_folder("debugging", session_folder.path()).unwrap();
+        let mut tarball = vec![];
+        archive.write(&mut tarball).unwrap();
+        let imported_folder = tempdir().unwrap();
+        SessionArchive::read(tarball.as_slice())
+            .unwrap()
+            .install(imported_folder.path())
+            .unwrap();
+        assert_eq!(
+            fs::read(imported_folder.path().join("initial_contents_1")).unwrap(),
+            b"caf\xe9\n"
+        );
+    }
+
+    #[test]
+    fn session_archive_needs_a_layout() {
+        let session_folder = session_folder_with_files(&[("session-metadata.kdl", "")]);
+        assert!(SessionArchive::from_session_folder("debugging", session_folder.path()).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! The passphrase that unlocks sessions locked with the `LockSession` action.
+//!
+//! Only an argon2 hash of the passphrase is ever stored, either in the config
+//! (`lock_session_passphrase_hash`) or in a file in the data dir (written by
+//! `zellij setup --set-lock-passphrase`).
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+
+const PASSPHRASE_HASH_FILE_NAME: &str = "session-lock-passphrase";
+
+pub fn passphrase_hash_file(data_dir: &Path) -> PathBuf {
+    data_dir.join(PASSPHRASE_HASH_FILE_NAME)
+}
+
+pub fn hash_passphrase(passphrase: &str) -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Failed to hash passphrase: {}", e))
+}
+
+/// False if the passphrase does not match or the hash is not a valid argon2 hash
+pub fn verify_passphrase(passphrase: &str, passphrase_hash: &str) -> bool {
+    match PasswordHash::new(passphrase_hash) {
+        Ok(passphrase_hash) => Argon2::default()
+            .verify_password(passphrase.as_bytes(), &passphrase_hash)
+            .is_ok(),
+        Err(e) => {
+            log::error!("Invalid session lock passphrase hash: {}", e);
+            false
+        },
+    }
+}
+
+/// The hash from the config if there is one, otherwise the one in the data dir
+pub fn read_passphrase_hash(config_hash: Option<&String>, data_dir: &Path) -> Option<String> {
+    config_hash.cloned().or_else(|| {
+        fs::read_to_string(passphrase_hash_file(data_dir))
+            .ok()
+            .map(|hash| hash.trim().to_owned())
+            .filter(|hash| !hash.is_empty())
+    })
+}
+
+pub fn write_passphrase_hash(passphrase_hash: &str, data_dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(data_dir)?;
+    let path = passphrase_hash_file(data_dir);
+    fs::write(&path, passphrase_hash)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passphrase_is_verified_against_its_hash() {
+        let passphrase_hash = hash_passphrase("correct horse").unwrap();
+        assert!(verify_passphrase("correct horse", &passphrase_hash));
+        assert!(!verify_passphrase("wrong horse", &passphrase_hash));
+    }
+
+    #[test]
+    fn invalid_hash_never_verifies() {
+        assert!(!verify_passphrase("", "not a hash"));
+    }
+
+    #[test]
+    fn config_hash_takes_precedence_over_data_dir() {
+        let data_dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_passphrase_hash(None, data_dir.path()), None);
+        write_passphrase_hash("hash-from-data-dir\n", data_dir.path()).unwrap();
+        assert_eq!(
+            read_passphrase_hash(None, data_dir.path()),
+            Some("hash-from-data-dir".to_owned())
+        );
+        assert_eq!(
+            read_passphrase_hash(Some(&"hash-from-config".to_owned()), data_dir.path()),
+            Some("hash-from-config".to_owned())
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use kdl::{KdlDocument, KdlNode};
 
 use crate::{
+    input::command::RestartPolicy,
     input::layout::PluginUserConfiguration,
     input::layout::{
         FloatingPaneLayout, Layout, PercentOrFixed, Run, RunPluginOrAlias, SplitDirection,
@@ -32,6 +35,9 @@ pub struct GlobalLayoutManifest {
     pub default_shell: Option<PathBuf>,
     pub default_layout: Box<Layout>,
     pub tabs: Vec<(String, TabLayoutManifest)>,
+    /// Serialize as a layout to be reused by new sessions rather than to resurrect this one, so
+    /// command panes only start suspended if they were originally meant to
+    pub portable: bool,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -40,6 +46,11 @@ pub struct TabLayoutManifest {
     pub floating_panes: Vec<PaneLayoutManifest>,
     pub is_focused: bool,
     pub hide_floating_panes: bool,
+    /// The cwd new panes in this tab start in, the cwds of its panes are relative to the global
+    /// cwd regardless
+    pub cwd: Option<PathBuf>,
+    /// Environment variables given to new panes in this tab
+    pub env: BTreeMap<String, String>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -51,6 +62,7 @@ pub struct PaneLayoutManifest {
     pub title: Option<String>,
     pub is_focused: bool,
     pub pane_contents: Option<String>,
+    pub is_sticky: bool,
 }
 
 pub fn serialize_session_layout(
@@ -59,9 +71,12 @@ pub fn serialize_session_layout(
     // BTreeMap is the pane contents and their file names
     let mut kdl_string = String::from("layout {\n");
     let mut pane_contents = BTreeMap::new();
+    let suspend_commands = !global_layout_manifest.portable;
     stringify_global_cwd(&global_layout_manifest.global_cwd, &mut kdl_string);
     if let Err(e) = stringify_multiple_tabs(
         global_layout_manifest.tabs,
+        &global_layout_manifest.global_cwd,
+        suspend_commands,
         &mut pane_contents,
         &mut kdl_string,
     ) {
@@ -69,16 +84,19 @@ pub fn serialize_session_layout(
     }
     stringify_new_tab_template(
         global_layout_manifest.default_layout.template,
+        suspend_commands,
         &mut pane_contents,
         &mut kdl_string,
     );
     stringify_swap_tiled_layouts(
         global_layout_manifest.default_layout.swap_tiled_layouts,
+        suspend_commands,
         &mut pane_contents,
         &mut kdl_string,
     );
     stringify_swap_floating_layouts(
         global_layout_manifest.default_layout.swap_floating_layouts,
+        suspend_commands,
         &mut pane_contents,
         &mut kdl_string,
     );
@@ -90,8 +108,11 @@ fn stringify_tab(
     tab_name: String,
     is_focused: bool,
     hide_floating_panes: bool,
+    cwd: &Option<PathBuf>,
+    env: &BTreeMap<String, String>,
     tiled_panes: &Vec<PaneLayoutManifest>,
     floating_panes: &Vec<PaneLayoutManifest>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
 ) -> Option<String> {
     let mut kdl_string = String::new();
@@ -113,11 +134,16 @@ fn stringify_tab(
             if hide_floating_panes {
                 tab_attributes.push(format!("hide_floating_panes=true"));
             }
+            if let Some(cwd) = cwd {
+                tab_attributes.push(format!("cwd=\"{}\"", cwd.display()));
+            }
             kdl_string.push_str(&kdl_string_from_tab(
                 &tiled_panes,
                 &floating_panes_layout,
                 tab_attributes,
                 None,
+                env,
+                suspend_commands,
                 pane_contents,
             ));
             Some(kdl_string)
@@ -134,6 +160,8 @@ fn kdl_string_from_tab(
     floating_panes: &Vec<FloatingPaneLayout>,
     node_attributes: Vec<String>,
     node_name: Option<String>,
+    env: &BTreeMap<String, String>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
 ) -> String {
     let mut kdl_string = if node_attributes.is_empty() {
@@ -145,17 +173,25 @@ fn kdl_string_from_tab(
             node_attributes.join(" ")
         )
     };
+    stringify_env_variables(env, &mut kdl_string);
     for tiled_pane_layout in tiled_panes {
         let ignore_size = false;
-        let sub_kdl_string =
-            kdl_string_from_tiled_pane(&tiled_pane_layout, ignore_size, pane_contents);
+        let sub_kdl_string = kdl_string_from_tiled_pane(
+            &tiled_pane_layout,
+            ignore_size,
+            suspend_commands,
+            pane_contents,
+        );
         kdl_string.push_str(&indent(&sub_kdl_string, INDENT));
     }
     if !floating_panes.is_empty() {
         kdl_string.push_str(&indent("floating_panes {\n", INDENT));
         for floating_pane_layout in floating_panes {
-            let sub_kdl_string =
-                kdl_string_from_floating_pane(&floating_pane_layout, pane_contents);
+            let sub_kdl_string = kdl_string_from_floating_pane(
+                &floating_pane_layout,
+                suspend_commands,
+                pane_contents,
+            );
             kdl_string.push_str(&indent(&sub_kdl_string, DOUBLE_INDENT));
         }
         kdl_string.push_str(&indent("}\n", INDENT));
@@ -168,6 +204,7 @@ fn kdl_string_from_tab(
 fn kdl_string_from_tiled_pane(
     layout: &TiledPaneLayout,
     ignore_size: bool,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
 ) -> String {
     let (command, args) = extract_command_and_args(&layout.run);
@@ -191,11 +228,16 @@ fn kdl_string_from_tiled_pane(
         || layout.external_children_index.is_some()
         || !args.is_empty()
         || plugin.is_some()
-        || command.is_some();
+        || command.is_some()
+        || matches!(&layout.run, Some(Run::Shell(_cwd, env)) if !env.is_empty());
     if has_child_attributes {
         kdl_string.push_str(" {\n");
         stringify_args(args, &mut kdl_string);
-        stringify_start_suspended(&command, &mut kdl_string);
+        stringify_env(&layout.run, &mut kdl_string);
+        stringify_restart_policy(&layout.run, &mut kdl_string);
+        stringify_depends_on(&layout.run, &mut kdl_string);
+        stringify_start_suspended(&layout.run, suspend_commands, &mut kdl_string);
+        stringify_rerun_on_resurrect(&layout.run, &mut kdl_string);
         stringify_plugin(plugin, plugin_config, &mut kdl_string);
         if layout.children.is_empty() && layout.external_children_index.is_some() {
             kdl_string.push_str(&indent(&"children\n", INDENT));
@@ -205,7 +247,8 @@ fn kdl_string_from_tiled_pane(
                 kdl_string.push_str(&indent(&"children\n", INDENT));
             } else {
                 let ignore_size = layout.children_are_stacked;
-                let sub_kdl_string = kdl_string_from_tiled_pane(&pane, ignore_size, pane_contents);
+                let sub_kdl_string =
+                    kdl_string_from_tiled_pane(&pane, ignore_size, suspend_commands, pane_contents);
                 kdl_string.push_str(&indent(&sub_kdl_string, INDENT));
             }
         }
@@ -304,6 +347,61 @@ fn stringify_args(args: Vec<String>, kdl_string: &mut String) {
     }
 }
 
+fn stringify_env(layout_run: &Option<Run>, kdl_string: &mut String) {
+    match layout_run {
+        Some(Run::Command(run_command)) => stringify_env_variables(&run_command.env, kdl_string),
+        Some(Run::Shell(_cwd, env)) => stringify_env_variables(env, kdl_string),
+        _ => {},
+    }
+}
+
+fn stringify_env_variables(env: &BTreeMap<String, String>, kdl_string: &mut String) {
+    if !env.is_empty() {
+        // built as a node so that the values are escaped (they can contain eg. quotes or newlines)
+        let mut env_node = KdlNode::new("env");
+        let mut env_variables = KdlDocument::new();
+        for (key, value) in env {
+            let mut env_variable = KdlNode::new(key.as_str());
+            env_variable.push(value.as_str());
+            env_variables.nodes_mut().push(env_variable);
+        }
+        env_node.set_children(env_variables);
+        let mut kdl_document = KdlDocument::new();
+        kdl_document.nodes_mut().push(env_node);
+        kdl_document.fmt();
+        kdl_string.push_str(&indent(&kdl_document.to_string(), INDENT));
+    }
+}
+
+fn stringify_restart_policy(layout_run: &Option<Run>, kdl_string: &mut String) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        if run_command.restart != RestartPolicy::Never {
+            kdl_string.push_str(&indent(
+                &format!("restart \"{}\"\n", run_command.restart),
+                INDENT,
+            ));
+        }
+        if let Some(max_restarts) = run_command.max_restarts {
+            kdl_string.push_str(&indent(&format!("max_restarts {}\n", max_restarts), INDENT));
+        }
+    }
+}
+
+fn stringify_depends_on(layout_run: &Option<Run>, kdl_string: &mut String) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        for dependency in &run_command.depends_on {
+            let mut dependency_line = format!("depends_on {:?}", dependency.pane_name);
+            if let Some(output) = &dependency.output {
+                dependency_line.push_str(&format!(" output={:?}", output));
+            }
+            if let Some(timeout) = dependency.timeout {
+                dependency_line.push_str(&format!(" timeout={}", timeout));
+            }
+            kdl_string.push_str(&indent(&format!("{}\n", dependency_line), INDENT));
+        }
+    }
+}
+
 fn stringify_plugin(
     plugin: Option<String>,
     plugin_config: Option<PluginUserConfiguration>,
@@ -345,6 +443,12 @@ fn stringify_tiled_layout_attributes(
             None => (),
         };
     }
+    if let Some(min_size) = layout.min_size {
+        kdl_string.push_str(&format!(" min_size={min_size}"));
+    }
+    if let Some(max_size) = layout.max_size {
+        kdl_string.push_str(&format!(" max_size={max_size}"));
+    }
     if layout.borderless {
         kdl_string.push_str(&" borderless=true");
     }
@@ -400,11 +504,31 @@ fn stringify_floating_layout_attributes(layout: &FloatingPaneLayout, kdl_string:
         },
         None => {},
     }
+    if layout.is_sticky {
+        kdl_string.push_str(&indent("sticky true\n", INDENT));
+    }
+}
+
+fn stringify_start_suspended(
+    layout_run: &Option<Run>,
+    suspend_commands: bool,
+    kdl_string: &mut String,
+) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        if suspend_commands || run_command.hold_on_start {
+            kdl_string.push_str(&indent(&"start_suspended true\n", INDENT));
+        }
+    }
 }
 
-fn stringify_start_suspended(command: &Option<String>, kdl_string: &mut String) {
-    if command.is_some() {
-        kdl_string.push_str(&indent(&"start_suspended true\n", INDENT));
+fn stringify_rerun_on_resurrect(layout_run: &Option<Run>, kdl_string: &mut String) {
+    if let Some(Run::Command(run_command)) = layout_run {
+        if let Some(rerun_on_resurrect) = run_command.rerun_on_resurrect {
+            kdl_string.push_str(&indent(
+                &format!("rerun_on_resurrect {}\n", rerun_on_resurrect),
+                INDENT,
+            ));
+        }
     }
 }
 
@@ -419,6 +543,7 @@ fn stringify_global_cwd(global_cwd: &Option<PathBuf>, kdl_string: &mut String) {
 
 fn stringify_new_tab_template(
     new_tab_template: Option<(TiledPaneLayout, Vec<FloatingPaneLayout>)>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
     kdl_string: &mut String,
 ) {
@@ -434,6 +559,8 @@ fn stringify_new_tab_template(
                 &floating_panes,
                 vec![],
                 Some(String::from("new_tab_template")),
+                &BTreeMap::new(),
+                suspend_commands,
                 pane_contents,
             ),
             INDENT,
@@ -443,6 +570,7 @@ fn stringify_new_tab_template(
 
 fn stringify_swap_tiled_layouts(
     swap_tiled_layouts: Vec<SwapTiledLayout>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
     kdl_string: &mut String,
 ) {
@@ -468,6 +596,8 @@ fn stringify_swap_tiled_layouts(
                     &vec![],
                     vec![layout_constraint.to_string()],
                     None,
+                    &BTreeMap::new(),
+                    suspend_commands,
                     pane_contents,
                 ),
                 DOUBLE_INDENT,
@@ -479,6 +609,7 @@ fn stringify_swap_tiled_layouts(
 
 fn stringify_swap_floating_layouts(
     swap_floating_layouts: Vec<SwapFloatingLayout>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
     kdl_string: &mut String,
 ) {
@@ -505,8 +636,11 @@ fn stringify_swap_floating_layouts(
                 ));
             }
             for floating_pane_layout in floating_panes_layout {
-                let sub_kdl_string =
-                    kdl_string_from_floating_pane(&floating_pane_layout, pane_contents);
+                let sub_kdl_string = kdl_string_from_floating_pane(
+                    &floating_pane_layout,
+                    suspend_commands,
+                    pane_contents,
+                );
                 kdl_string.push_str(&indent(&sub_kdl_string, TRIPLE_INDENT));
             }
             if has_floating_panes {
@@ -519,19 +653,31 @@ fn stringify_swap_floating_layouts(
 
 fn stringify_multiple_tabs(
     tabs: Vec<(String, TabLayoutManifest)>,
+    global_cwd: &Option<PathBuf>,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
     kdl_string: &mut String,
 ) -> Result<(), &'static str> {
     for (tab_name, tab_layout_manifest) in tabs {
-        let tiled_panes = tab_layout_manifest.tiled_panes;
-        let floating_panes = tab_layout_manifest.floating_panes;
+        let mut tiled_panes = tab_layout_manifest.tiled_panes;
+        let mut floating_panes = tab_layout_manifest.floating_panes;
         let hide_floating_panes = tab_layout_manifest.hide_floating_panes;
+        if let Some(tab_cwd) = &tab_layout_manifest.cwd {
+            rebase_pane_cwds_on_tab_cwd(
+                tiled_panes.iter_mut().chain(floating_panes.iter_mut()),
+                global_cwd,
+                tab_cwd,
+            );
+        }
         let stringified = stringify_tab(
             tab_name.clone(),
             tab_layout_manifest.is_focused,
             hide_floating_panes,
+            &tab_layout_manifest.cwd,
+            &tab_layout_manifest.env,
             &tiled_panes,
             &floating_panes,
+            suspend_commands,
             pane_contents,
         );
         match stringified {
@@ -546,8 +692,32 @@ fn stringify_multiple_tabs(
     Ok(())
 }
 
+/// A tab's cwd is prepended to the cwds of its panes when the layout is loaded, so these are made
+/// relative to it rather than to the global cwd (or absolute if they are outside of it)
+fn rebase_pane_cwds_on_tab_cwd<'a>(
+    panes: impl Iterator<Item = &'a mut PaneLayoutManifest>,
+    global_cwd: &Option<PathBuf>,
+    tab_cwd: &Path,
+) {
+    let full_path = |path: &Path| match global_cwd {
+        Some(global_cwd) => global_cwd.join(path),
+        None => path.to_path_buf(),
+    };
+    let tab_cwd = full_path(tab_cwd);
+    for pane in panes {
+        if let Some(cwd) = pane.cwd.as_mut() {
+            let pane_cwd = full_path(cwd);
+            *cwd = match pane_cwd.strip_prefix(&tab_cwd) {
+                Ok(relative_cwd) => relative_cwd.to_path_buf(),
+                Err(_) => pane_cwd,
+            };
+        }
+    }
+}
+
 fn kdl_string_from_floating_pane(
     layout: &FloatingPaneLayout,
+    suspend_commands: bool,
     pane_contents: &mut BTreeMap<String, String>,
 ) -> String {
     let (command, args) = extract_command_and_args(&layout.run);
@@ -566,9 +736,13 @@ fn kdl_string_from_floating_pane(
         has_children,
     );
     kdl_string.push_str(" {\n");
-    stringify_start_suspended(&command, &mut kdl_string);
+    stringify_start_suspended(&layout.run, suspend_commands, &mut kdl_string);
+    stringify_rerun_on_resurrect(&layout.run, &mut kdl_string);
     stringify_floating_layout_attributes(&layout, &mut kdl_string);
     stringify_args(args, &mut kdl_string);
+    stringify_env(&layout.run, &mut kdl_string);
+    stringify_restart_policy(&layout.run, &mut kdl_string);
+    stringify_depends_on(&layout.run, &mut kdl_string);
     stringify_plugin(plugin, plugin_config, &mut kdl_string);
     kdl_string.push_str("}\n");
     kdl_string
@@ -654,7 +828,15 @@ fn get_tiled_panes_layout_from_panegeoms(
     let new_split_sizes = get_split_sizes(&new_constraints);
     for (subgeoms, subsplit_size) in new_geoms.iter().zip(new_split_sizes) {
         match get_tiled_panes_layout_from_panegeoms(&subgeoms, subsplit_size) {
-            Some(child) => {
+            Some(mut child) => {
+                if let Some(manifest) = subgeoms.iter().next().filter(|_| subgeoms.len() == 1) {
+                    let split_dimension = match children_split_direction {
+                        SplitDirection::Horizontal => manifest.geom.rows,
+                        SplitDirection::Vertical => manifest.geom.cols,
+                    };
+                    child.min_size = split_dimension.min_size();
+                    child.max_size = split_dimension.max_size();
+                }
                 children.push(child);
             },
             None => {
@@ -695,6 +877,7 @@ fn get_floating_panes_layout_from_panegeoms(
                 focus: Some(m.is_focused),
                 already_running: false,
                 pane_initial_contents: m.pane_contents.clone(),
+                is_sticky: m.is_sticky,
             }
         })
         .collect()
@@ -921,6 +1104,7 @@ fn get_split_sizes(constraints: &Vec<Constraint>) -> Vec<Option<SplitSize>> {
 mod tests {
 
     use super::*;
+    use crate::input::command::{PaneDependency, RunCommand};
     use crate::pane_size::Dimension;
     use expect_test::expect;
     use serde_json::Value;
@@ -1125,6 +1309,348 @@ mod tests {
         .assert_eq(&kdl.0);
     }
     // utility functions
+    #[test]
+    fn sticky_floating_panes() {
+        let tiled_panes = vec![PaneLayoutManifest {
+            geom: parse_panegeom_from_json(PANEGEOMS_JSON[0][0]),
+            ..Default::default()
+        }];
+        let floating_panes = vec![PaneLayoutManifest {
+            geom: parse_panegeom_from_json(PANEGEOMS_JSON[0][1]),
+            is_sticky: true,
+            ..Default::default()
+        }];
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes,
+            floating_panes,
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    floating_panes {
+                        pane {
+                            height 1
+                            width "100%"
+                            x 0
+                            y 0
+                            sticky true
+                        }
+                    }
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn command_pane_environment_variables() {
+        let mut env = BTreeMap::new();
+        env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+        env.insert("PORT".to_owned(), "8080".to_owned());
+        let tab_layout_manifest = tab_running_commands(vec![RunCommand {
+            command: PathBuf::from("cargo"),
+            args: vec!["run".to_owned()],
+            env: env.clone(),
+            ..Default::default()
+        }]);
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane size=1
+                    pane command="cargo" {
+                        args "run"
+                        env {
+                            PORT "8080"
+                            RUST_LOG "debug"
+                        }
+                        start_suspended true
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn shell_pane_environment_variables() {
+        let mut env = BTreeMap::new();
+        env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+        let mut tab_layout_manifest = tab_running_commands(vec![]);
+        tab_layout_manifest.tiled_panes[0].run = Some(Run::Shell(None, env.clone()));
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane size=1
+                    pane {
+                        env {
+                            RUST_LOG "debug"
+                        }
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+        let layout = Layout::from_str(&kdl.0, "layout.kdl".into(), None, None).unwrap();
+        let (_, tiled_panes, _) = &layout.tabs[0];
+        assert_eq!(
+            tiled_panes.children[1].run,
+            Some(Run::Shell(None, env)),
+            "Shell pane environment variables survived the round trip"
+        );
+    }
+
+    #[test]
+    fn environment_variables_are_escaped() {
+        let mut env = BTreeMap::new();
+        env.insert("GREETING".to_owned(), "say \"hi\"\nthen leave".to_owned());
+        env.insert("PATTERN".to_owned(), "C:\\Users".to_owned());
+        let tab_layout_manifest = TabLayoutManifest {
+            env: env.clone(),
+            ..tab_running_commands(vec![RunCommand {
+                command: PathBuf::from("sh"),
+                env: env.clone(),
+                ..Default::default()
+            }])
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        let layout = Layout::from_str(&kdl.0, "layout.kdl".into(), None, None).unwrap();
+        let (_, tiled_panes, _) = &layout.tabs[0];
+        let Some(run_command) = tiled_panes
+            .children
+            .iter()
+            .find_map(|pane| match &pane.run {
+                Some(Run::Command(run_command)) => Some(run_command),
+                _ => None,
+            })
+        else {
+            panic!("Pane was not parsed as a command pane");
+        };
+        assert_eq!(
+            run_command.env, env,
+            "Pane environment variables survived the round trip"
+        );
+        assert_eq!(
+            tiled_panes.default_env, env,
+            "Tab environment variables survived the round trip"
+        );
+    }
+
+    #[test]
+    fn tab_cwd_and_env() {
+        let mut env = BTreeMap::new();
+        env.insert("RUST_LOG".to_owned(), "debug".to_owned());
+        let pane_cwds = ["backend", "backend/src", "frontend"];
+        let tiled_panes = PANEGEOMS_JSON[0]
+            .iter()
+            .map(|pg| parse_panegeom_from_json(pg))
+            .zip(pane_cwds.iter())
+            .map(|(geom, cwd)| PaneLayoutManifest {
+                geom,
+                cwd: Some(PathBuf::from(cwd)),
+                ..Default::default()
+            })
+            .collect();
+        let tab_layout_manifest = TabLayoutManifest {
+            tiled_panes,
+            cwd: Some(PathBuf::from("/projects/backend")),
+            env,
+            ..Default::default()
+        };
+        let global_layout_manifest = GlobalLayoutManifest {
+            global_cwd: Some(PathBuf::from("/projects")),
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                cwd "/projects"
+                tab name="Tab #1" cwd="/projects/backend" {
+                    env {
+                        RUST_LOG "debug"
+                    }
+                    pane cwd="src" size=1
+                    pane
+                    pane cwd="/projects/frontend" size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn command_pane_restart_policy() {
+        let tab_layout_manifest = tab_running_commands(vec![RunCommand {
+            command: PathBuf::from("npm"),
+            args: vec!["start".to_owned()],
+            restart: RestartPolicy::OnFailure,
+            max_restarts: Some(5),
+            ..Default::default()
+        }]);
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane size=1
+                    pane command="npm" {
+                        args "start"
+                        restart "on-failure"
+                        max_restarts 5
+                        start_suspended true
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn command_pane_depends_on() {
+        let tab_layout_manifest = tab_running_commands(vec![RunCommand {
+            command: PathBuf::from("npm"),
+            args: vec!["start".to_owned()],
+            depends_on: vec![
+                PaneDependency {
+                    pane_name: "db".to_owned(),
+                    output: Some("listening on \"\\d+\"".to_owned()),
+                    timeout: Some(30),
+                },
+                PaneDependency {
+                    pane_name: "migrations".to_owned(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }]);
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane size=1
+                    pane command="npm" {
+                        args "start"
+                        depends_on "db" output="listening on \"\\d+\"" timeout=30
+                        depends_on "migrations"
+                        start_suspended true
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn command_pane_rerun_on_resurrect() {
+        let tab_layout_manifest = tab_running_commands(vec![
+            RunCommand {
+                command: PathBuf::from("cargo"),
+                rerun_on_resurrect: Some(true),
+                ..Default::default()
+            },
+            RunCommand {
+                command: PathBuf::from("vim"),
+                rerun_on_resurrect: Some(false),
+                ..Default::default()
+            },
+        ]);
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane command="vim" size=1 {
+                        start_suspended true
+                        rerun_on_resurrect false
+                    }
+                    pane command="cargo" {
+                        start_suspended true
+                        rerun_on_resurrect true
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    #[test]
+    fn portable_layout_only_suspends_commands_meant_to_start_suspended() {
+        let tab_layout_manifest = tab_running_commands(vec![
+            RunCommand {
+                command: PathBuf::from("htop"),
+                ..Default::default()
+            },
+            RunCommand {
+                command: PathBuf::from("make"),
+                hold_on_start: true,
+                ..Default::default()
+            },
+        ]);
+        let global_layout_manifest = GlobalLayoutManifest {
+            tabs: vec![("Tab #1".to_owned(), tab_layout_manifest)],
+            portable: true,
+            ..Default::default()
+        };
+        let kdl = serialize_session_layout(global_layout_manifest).unwrap();
+        expect![[r#"
+            layout {
+                tab name="Tab #1" {
+                    pane command="make" size=1 {
+                        start_suspended true
+                    }
+                    pane command="htop" {
+                    }
+                    pane size=2
+                }
+            }"#]]
+        .assert_eq(&kdl.0);
+    }
+
+    /// A tab laid out like the first one of `PANEGEOMS_JSON`, whose first panes run these commands
+    fn tab_running_commands(commands: Vec<RunCommand>) -> TabLayoutManifest {
+        let mut commands = commands.into_iter();
+        let tiled_panes = PANEGEOMS_JSON[0]
+            .iter()
+            .map(|pg| PaneLayoutManifest {
+                geom: parse_panegeom_from_json(pg),
+                run: commands.next().map(Run::Command),
+                ..Default::default()
+            })
+            .collect();
+        TabLayoutManifest {
+            tiled_panes,
+            ..Default::default()
+        }
+    }
+
     fn parse_panegeom_from_json(data_str: &str) -> PaneGeom {
         //
         // Expects this input
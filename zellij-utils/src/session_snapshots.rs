@@ -0,0 +1,277 @@
+//! A bounded history of timestamped session snapshots, so that a session can be resurrected as
+//! it was at some earlier point rather than only as it was last serialized.
+//!
+//! Each snapshot is a folder named after the unix time (in seconds) at which it was taken,
+//! holding the serialized layout of the session and the pane contents files it refers to.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const SNAPSHOT_LAYOUT_FILE_NAME: &str = "session-layout.kdl";
+const SESSION_METADATA_FILE_NAME: &str = "session-metadata.kdl";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SessionSnapshot {
+    pub timestamp: u64, // seconds since the unix epoch
+    pub path: PathBuf,
+}
+
+impl SessionSnapshot {
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.timestamp)
+    }
+    pub fn layout_file(&self) -> PathBuf {
+        self.path.join(SNAPSHOT_LAYOUT_FILE_NAME)
+    }
+    fn read_contents(&self) -> io::Result<(String, BTreeMap<String, String>)> {
+        let mut layout = String::new();
+        let mut files = BTreeMap::new();
+        for entry in fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let contents = fs::read_to_string(entry.path())?;
+            if file_name == SNAPSHOT_LAYOUT_FILE_NAME {
+                layout = contents;
+            } else {
+                files.insert(file_name, contents);
+            }
+        }
+        Ok((layout, files))
+    }
+}
+
+/// All the snapshots in this folder, oldest first
+pub fn list_snapshots(snapshots_folder: &Path) -> Vec<SessionSnapshot> {
+    let mut snapshots: Vec<SessionSnapshot> = match fs::read_dir(snapshots_folder) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .filter_map(|e| {
+                let timestamp = e.file_name().to_str()?.parse::<u64>().ok()?;
+                Some(SessionSnapshot {
+                    timestamp,
+                    path: e.path(),
+                })
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+    snapshots.sort();
+    snapshots
+}
+
+/// Writes a new snapshot of the session unless it is identical to the latest one, returns whether
+/// a snapshot was written
+pub fn take_snapshot(
+    snapshots_folder: &Path,
+    layout: &str,
+    layout_files: &BTreeMap<String, String>,
+    now: SystemTime,
+) -> io::Result<bool> {
+    if let Some(latest_snapshot) = list_snapshots(snapshots_folder).last() {
+        if let Ok((latest_layout, latest_files)) = latest_snapshot.read_contents() {
+            if latest_layout == layout && &latest_files == layout_files {
+                return Ok(false);
+            }
+        }
+    }
+    let timestamp = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let snapshot_folder = snapshots_folder.join(timestamp.to_string());
+    fs::create_dir_all(&snapshot_folder)?;
+    fs::write(snapshot_folder.join(SNAPSHOT_LAYOUT_FILE_NAME), layout)?;
+    for (file_name, contents) in layout_files {
+        fs::write(snapshot_folder.join(file_name), contents)?;
+    }
+    Ok(true)
+}
+
+/// Removes the oldest snapshots so that at most `max_count` remain, as well as any snapshot older
+/// than `max_age` (if there is one)
+pub fn prune_snapshots(
+    snapshots_folder: &Path,
+    max_count: usize,
+    max_age: Option<Duration>,
+    now: SystemTime,
+) -> io::Result<()> {
+    let snapshots = list_snapshots(snapshots_folder);
+    let excess_count = snapshots.len().saturating_sub(max_count);
+    for (i, snapshot) in snapshots.iter().enumerate() {
+        let is_expired = max_age
+            .map(|max_age| {
+                now.duration_since(snapshot.time())
+                    .map(|age| age > max_age)
+                    .unwrap_or(false)
+            })
+            .unwrap_or(false);
+        if i < excess_count || is_expired {
+            fs::remove_dir_all(&snapshot.path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The latest snapshot taken at or before `time`
+pub fn find_snapshot_at(
+    snapshots: &[SessionSnapshot],
+    time: SystemTime,
+) -> Option<&SessionSnapshot> {
+    snapshots.iter().rev().find(|s| s.time() <= time)
+}
+
+/// Places the snapshot in the session folder, so that the session will be resurrected from it
+pub fn restore_snapshot(snapshot: &SessionSnapshot, session_folder: &Path) -> io::Result<()> {
+    let (layout, files) = snapshot.read_contents()?;
+    fs::create_dir_all(session_folder)?;
+    // the pane contents files of the current layout would otherwise be mixed with the ones of the
+    // snapshot
+    for entry in fs::read_dir(session_folder)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && entry.file_name() != SESSION_METADATA_FILE_NAME {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    fs::write(session_folder.join(SNAPSHOT_LAYOUT_FILE_NAME), layout)?;
+    for (file_name, contents) in files {
+        fs::write(session_folder.join(file_name), contents)?;
+    }
+    Ok(())
+}
+
+/// Parses a point in time given either as a unix timestamp (eg. `1700000000`), as an RFC3339 date
+/// (eg. `2023-11-14 22:13:20`) or as a duration before `now` (eg. `2h 30m`)
+pub fn parse_snapshot_time(time: &str, now: SystemTime) -> Result<SystemTime, String> {
+    let time = time.trim();
+    let out_of_range = || format!("\"{}\" is too far from now", time);
+    if let Ok(timestamp) = time.parse::<u64>() {
+        return UNIX_EPOCH
+            .checked_add(Duration::from_secs(timestamp))
+            .ok_or_else(out_of_range);
+    }
+    if let Ok(date) = humantime::parse_rfc3339_weak(time) {
+        return Ok(date);
+    }
+    let ago = time.strip_suffix("ago").unwrap_or(time).trim();
+    let duration = humantime::parse_duration(ago).map_err(|_| {
+        format!(
+            "Could not parse \"{}\" as a timestamp, a date or a duration (eg. 2h)",
+            time
+        )
+    })?;
+    // no snapshot can be older than the unix epoch (and dates before it can't be displayed)
+    now.checked_sub(duration)
+        .filter(|time| *time >= UNIX_EPOCH)
+        .ok_or_else(out_of_range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn at(timestamp: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(timestamp)
+    }
+
+    #[test]
+    fn identical_snapshots_are_not_repeated() {
+        let folder = tempdir().unwrap();
+        let files = BTreeMap::from([("initial_contents_1".to_owned(), "$ ls".to_owned())]);
+        assert!(take_snapshot(folder.path(), "layout {}", &files, at(100)).unwrap());
+        assert!(!take_snapshot(folder.path(), "layout {}", &files, at(200)).unwrap());
+        assert!(take_snapshot(folder.path(), "layout { pane; }", &files, at(300)).unwrap());
+        let timestamps: Vec<u64> = list_snapshots(folder.path())
+            .iter()
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![100, 300]);
+    }
+
+    #[test]
+    fn snapshots_are_pruned_by_count_and_age() {
+        let folder = tempdir().unwrap();
+        for i in 1..=5 {
+            take_snapshot(folder.path(), &i.to_string(), &BTreeMap::new(), at(i * 100)).unwrap();
+        }
+        prune_snapshots(folder.path(), 4, Some(Duration::from_secs(250)), at(600)).unwrap();
+        let timestamps: Vec<u64> = list_snapshots(folder.path())
+            .iter()
+            .map(|s| s.timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![400, 500]);
+    }
+
+    #[test]
+    fn restoring_a_snapshot_from_a_point_in_time() {
+        let folder = tempdir().unwrap();
+        let session_folder = folder.path().join("session");
+        let snapshots_folder = session_folder.join("snapshots");
+        take_snapshot(
+            &snapshots_folder,
+            "layout { pane; }",
+            &BTreeMap::new(),
+            at(100),
+        )
+        .unwrap();
+        take_snapshot(&snapshots_folder, "layout {}", &BTreeMap::new(), at(200)).unwrap();
+        let snapshots = list_snapshots(&snapshots_folder);
+        assert_eq!(find_snapshot_at(&snapshots, at(50)), None);
+        let snapshot = find_snapshot_at(&snapshots, at(199)).unwrap();
+        restore_snapshot(snapshot, &session_folder).unwrap();
+        assert_eq!(
+            fs::read_to_string(session_folder.join("session-layout.kdl")).unwrap(),
+            "layout { pane; }"
+        );
+    }
+
+    #[test]
+    fn restoring_a_snapshot_replaces_the_pane_contents_files() {
+        let folder = tempdir().unwrap();
+        let session_folder = folder.path().join("session");
+        let snapshots_folder = session_folder.join("snapshots");
+        let files = BTreeMap::from([("initial_contents_1".to_owned(), "$ ls".to_owned())]);
+        take_snapshot(&snapshots_folder, "layout {}", &files, at(100)).unwrap();
+        fs::write(
+            session_folder.join("session-layout.kdl"),
+            "layout { pane; }",
+        )
+        .unwrap();
+        fs::write(session_folder.join("initial_contents_1"), "$ top").unwrap();
+        fs::write(session_folder.join("initial_contents_2"), "$ vim").unwrap();
+        fs::write(session_folder.join("session-metadata.kdl"), "name \"s\"").unwrap();
+        let snapshots = list_snapshots(&snapshots_folder);
+        restore_snapshot(&snapshots[0], &session_folder).unwrap();
+        assert_eq!(
+            fs::read_to_string(session_folder.join("initial_contents_1")).unwrap(),
+            "$ ls"
+        );
+        assert!(
+            !session_folder.join("initial_contents_2").exists(),
+            "contents files of the replaced layout are removed"
+        );
+        assert!(session_folder.join("session-metadata.kdl").exists());
+        assert_eq!(list_snapshots(&snapshots_folder).len(), 1);
+    }
+
+    #[test]
+    fn snapshot_times_can_be_timestamps_dates_or_durations() {
+        let now = at(10_000);
+        assert_eq!(
+            parse_snapshot_time("1700000000", now),
+            Ok(at(1_700_000_000))
+        );
+        assert_eq!(
+            parse_snapshot_time("2023-11-14 22:13:20", now),
+            Ok(at(1_700_000_000))
+        );
+        assert_eq!(parse_snapshot_time("1h 30m", now), Ok(at(4_600)));
+        assert_eq!(parse_snapshot_time("2h ago", now), Ok(at(2_800)));
+        assert!(parse_snapshot_time("yesterday-ish", now).is_err());
+        assert!(parse_snapshot_time("1000000y", now).is_err());
+        assert!(parse_snapshot_time(&format!("{}s", u64::MAX), now).is_err());
+        assert!(parse_snapshot_time(&u64::MAX.to_string(), now).is_err());
+    }
+}
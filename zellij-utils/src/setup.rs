@@ -12,13 +12,16 @@ use crate::{
     input::{
         config::{Config, ConfigError},
         layout::Layout,
+        layout_check::{check_layout, parse_layout_check_size, DEFAULT_LAYOUT_CHECK_SIZE},
         options::Options,
     },
+    pane_size::Size,
 };
 use clap::{Args, IntoApp};
 use clap_complete::Shell;
 use directories::BaseDirs;
 use log::info;
+use miette::Severity;
 use serde::{Deserialize, Serialize};
 use std::{
     convert::TryFrom,
@@ -298,6 +301,44 @@ pub fn dump_builtin_plugins(_path: &PathBuf) -> Result<()> {
     Ok(())
 }
 
+/// Reads a passphrase from stdin (without echoing it if stdin is a terminal) and stores its hash
+/// in the data dir, returns the path of the file it was stored in
+#[cfg(not(target_family = "wasm"))]
+fn set_lock_passphrase(data_dir: &Path) -> std::result::Result<PathBuf, String> {
+    use crate::session_lock::{hash_passphrase, write_passphrase_hash};
+    use nix::sys::termios;
+    use std::io::BufRead;
+
+    let stdin = std::io::stdin();
+    let stdin_fd = libc::STDIN_FILENO;
+    let orig_termios = termios::tcgetattr(stdin_fd).ok();
+    if let Some(orig_termios) = &orig_termios {
+        let mut no_echo = orig_termios.clone();
+        no_echo.local_flags.remove(termios::LocalFlags::ECHO);
+        let _ = termios::tcsetattr(stdin_fd, termios::SetArg::TCSANOW, &no_echo);
+        eprint!("Passphrase to unlock locked sessions: ");
+    }
+    let mut passphrase = String::new();
+    let read_result = stdin.lock().read_line(&mut passphrase);
+    if let Some(orig_termios) = &orig_termios {
+        let _ = termios::tcsetattr(stdin_fd, termios::SetArg::TCSANOW, orig_termios);
+        eprintln!();
+    }
+    read_result.map_err(|e| format!("Failed to read passphrase: {}", e))?;
+    let passphrase = passphrase.trim_end_matches(&['\r', '\n'][..]);
+    if passphrase.is_empty() {
+        return Err("The passphrase cannot be empty".to_owned());
+    }
+    let passphrase_hash = hash_passphrase(passphrase)?;
+    write_passphrase_hash(&passphrase_hash, data_dir)
+        .map_err(|e| format!("Failed to store passphrase: {}", e))
+}
+
+#[cfg(target_family = "wasm")]
+fn set_lock_passphrase(_data_dir: &Path) -> std::result::Result<PathBuf, String> {
+    Err("Not supported".to_owned())
+}
+
 #[derive(Debug, Default, Clone, Args, Serialize, Deserialize)]
 pub struct Setup {
     /// Dump the default configuration file to stdout
@@ -322,6 +363,20 @@ pub struct Setup {
     #[clap(long, value_parser)]
     pub dump_swap_layout: Option<String>,
 
+    /// Check the specified layout for errors without starting a session,
+    /// exits with a non-zero code if any are found
+    #[clap(long, value_name = "LAYOUT", value_parser)]
+    pub check_layout: Option<PathBuf>,
+
+    /// The terminal size to check the layout against [default: 80x24]
+    #[clap(
+        long,
+        value_name = "COLUMNSxROWS",
+        value_parser = parse_layout_check_size,
+        requires("check-layout")
+    )]
+    pub size: Option<Size>,
+
     /// Dump the builtin plugins to DIR or "DATA DIR" if unspecified
     #[clap(
         long,
@@ -340,6 +395,11 @@ pub struct Setup {
     /// Generates auto-start script for the specified shell
     #[clap(long, value_name = "SHELL", value_parser)]
     pub generate_auto_start: Option<String>,
+
+    /// Set the passphrase that unlocks locked sessions, it is read from stdin and stored hashed in
+    /// the "DATA DIR"
+    #[clap(long, value_parser, exclusive = true)]
+    pub set_lock_passphrase: bool,
 }
 
 impl Setup {
@@ -395,7 +455,7 @@ impl Setup {
 
         if let Some(Command::Setup(ref setup)) = &cli_args.command {
             setup
-                .from_cli_with_options(cli_args, &config_options)
+                .from_cli_with_options(cli_args, &config, &config_options)
                 .map_or_else(
                     |e| {
                         eprintln!("{:?}", e);
@@ -448,12 +508,24 @@ impl Setup {
     }
 
     /// Checks the merged configuration
-    pub fn from_cli_with_options(&self, opts: &CliArgs, config_options: &Options) -> Result<()> {
+    pub fn from_cli_with_options(
+        &self,
+        opts: &CliArgs,
+        config: &Config,
+        config_options: &Options,
+    ) -> Result<()> {
         if self.check {
             Setup::check_defaults_config(opts, config_options)?;
             std::process::exit(0);
         }
 
+        if let Some(layout) = &self.check_layout {
+            let size = self.size.unwrap_or(DEFAULT_LAYOUT_CHECK_SIZE);
+            let is_valid =
+                Setup::check_specified_layout(layout, size, opts, config, config_options);
+            std::process::exit(if is_valid { 0 } else { 1 });
+        }
+
         if let Some(maybe_path) = &self.dump_plugins {
             let data_dir = &opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
             let dir = match maybe_path {
@@ -466,9 +538,73 @@ impl Setup {
             std::process::exit(0);
         }
 
+        if self.set_lock_passphrase {
+            let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
+            match set_lock_passphrase(&data_dir) {
+                Ok(path) => {
+                    println!("Stored the hashed passphrase in '{}'", path.display());
+                    std::process::exit(0);
+                },
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                },
+            }
+        }
+
         Ok(())
     }
 
+    /// Prints the problems found in the layout, returns false if any of them are errors
+    pub fn check_specified_layout(
+        layout: &PathBuf,
+        size: Size,
+        opts: &CliArgs,
+        config: &Config,
+        config_options: &Options,
+    ) -> bool {
+        let layout_dir = config_options
+            .layout_dir
+            .clone()
+            .or_else(|| get_layout_dir(opts.config_dir.clone().or_else(find_default_config_dir)));
+        let (path_to_raw_layout, raw_layout, raw_swap_layouts) =
+            match Layout::stringified_from_path_or_default(Some(layout), layout_dir.clone()) {
+                Ok(stringified_layout) => stringified_layout,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return false;
+                },
+            };
+        let problems = check_layout(
+            &raw_layout,
+            &path_to_raw_layout,
+            raw_swap_layouts
+                .as_ref()
+                .map(|(r, f)| (r.as_str(), f.as_str())),
+            layout_dir,
+            opts.layout_args.iter().cloned().collect(),
+            config,
+            size,
+        );
+        let mut error_count = 0;
+        for problem in &problems {
+            if problem.severity().unwrap_or(Severity::Error) == Severity::Error {
+                error_count += 1;
+            }
+            eprintln!("{:?}", problem);
+        }
+        let warning_count = problems.len() - error_count;
+        if problems.is_empty() {
+            println!("{}: OK", path_to_raw_layout);
+        } else {
+            eprintln!(
+                "{}: {} error(s), {} warning(s)",
+                path_to_raw_layout, error_count, warning_count
+            );
+        }
+        error_count == 0
+    }
+
     pub fn check_defaults_config(opts: &CliArgs, config_options: &Options) -> std::io::Result<()> {
         let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
         let config_dir = opts.config_dir.clone().or_else(find_default_config_dir);
@@ -668,7 +804,12 @@ impl Setup {
             .or_else(|| config.options.default_layout.clone());
         // we merge-override the config here because the layout might contain configuration
         // that needs to take precedence
-        Layout::from_path_or_default(chosen_layout.as_ref(), layout_dir.clone(), config)
+        Layout::from_path_or_default(
+            chosen_layout.as_ref(),
+            layout_dir.clone(),
+            config,
+            cli_args.layout_args.iter().cloned().collect(),
+        )
     }
     fn handle_setup_commands(cli_args: &CliArgs) {
         if let Some(Command::Setup(ref setup)) = &cli_args.command {